@@ -71,6 +71,7 @@ pub struct Args {
 
     /// Set output datatype(s) of U256 integers
     /// [default: binary, string, f64]
+    /// d128 stores exact decimal128(38, 0), null beyond 38 digits
     #[arg(long, num_args(1..), help_heading = "Content Options", verbatim_doc_comment)]
     pub u256_types: Option<Vec<String>>,
 
@@ -78,6 +79,10 @@ pub struct Args {
     #[arg(long, help_heading = "Content Options")]
     pub hex: bool,
 
+    /// Store trace action types as categoricals instead of plain strings
+    #[arg(long, help_heading = "Content Options")]
+    pub categorical: bool,
+
     /// Columns(s) to sort by, `none` for unordered
     #[arg(short, long, num_args(0..), help_heading="Content Options")]
     pub sort: Option<Vec<String>>,
@@ -86,6 +91,10 @@ pub struct Args {
     #[arg(long, help_heading = "Content Options")]
     pub exclude_failed: bool,
 
+    /// Representation of missing gas values (null, zero, or sentinel)
+    #[arg(long, value_name = "POLICY", default_value = "null", help_heading = "Content Options")]
+    pub gas_nulls: String,
+
     /// RPC url [default: 1. MESC 2. ETH_RPC_URL]
     #[arg(short, long, help_heading = "Source Options")]
     pub rpc: Option<String>,
@@ -94,10 +103,32 @@ pub struct Args {
     #[arg(long, help_heading = "Source Options")]
     pub network_name: Option<String>,
 
+    /// Record every rpc response into a fixture file
+    #[arg(long, value_name = "PATH", help_heading = "Source Options")]
+    pub record_fixtures: Option<String>,
+
+    /// Answer rpc requests from a recorded fixture file instead of a node
+    #[arg(
+        long,
+        value_name = "PATH",
+        help_heading = "Source Options",
+        conflicts_with = "record_fixtures"
+    )]
+    pub replay_fixtures: Option<String>,
+
     /// Ratelimit on requests per second
     #[arg(short('l'), long, value_name = "limit", help_heading = "Acquisition Options")]
     pub requests_per_second: Option<u32>,
 
+    /// Share --requests-per-second budget with other cryo processes using the same rpc,
+    /// requires --requests-per-second
+    #[arg(long, help_heading = "Acquisition Options")]
+    pub shared_rate_limit: bool,
+
+    /// Directory used to coordinate --shared-rate-limit [default: system temp dir]
+    #[arg(long, value_name = "DIR", help_heading = "Acquisition Options")]
+    pub rate_limit_dir: Option<String>,
+
     /// Max retries for provider errors
     #[arg(long, default_value_t = 5, value_name = "R", help_heading = "Acquisition Options")]
     pub max_retries: u32,
@@ -118,14 +149,34 @@ pub struct Args {
     #[arg(long, value_name = "M", help_heading = "Acquisition Options")]
     pub max_concurrent_chunks: Option<u64>,
 
+    /// Request profile, use polite for free public rpc endpoints
+    ///   polite: max 4 concurrent requests, max 1 concurrent chunk,
+    ///           random delay between requests, cryo User-Agent
+    #[arg(
+        long,
+        value_name = "PROFILE",
+        verbatim_doc_comment,
+        help_heading = "Acquisition Options"
+    )]
+    pub profile: Option<String>,
+
     /// Chunk collection order (normal, reverse, random)
     #[arg(long, help_heading = "Acquisition Options")]
     pub chunk_order: Option<String>,
 
+    /// Batch eth_calls of erc20_balances, erc20_supplies, balances, and call_results into
+    /// Multicall3 calls of up to CALLS calls per block
+    #[arg(long, value_name = "CALLS", help_heading = "Acquisition Options")]
+    pub multicall: Option<usize>,
+
     /// Dry run, collect no data
     #[arg(short, long, help_heading = "Acquisition Options")]
     pub dry: bool,
 
+    /// Enqueue chunks in a work queue directory for `cryo worker` instead of collecting them
+    #[arg(long, value_name = "QUEUE_DIR", help_heading = "Acquisition Options")]
+    pub enqueue: Option<String>,
+
     /// Remember current command for future use
     #[arg(long)]
     pub remember: bool,
@@ -167,6 +218,23 @@ pub struct Args {
     #[arg(long, help_heading = "Output Options")]
     pub overwrite: bool,
 
+    /// Write rewritten files as numbered versions instead of replacing them
+    #[arg(long, help_heading = "Output Options")]
+    pub version_outputs: bool,
+
+    /// Directory for staging files before moving them to output dir
+    /// [default: output dir]
+    #[arg(long, value_name = "DIR", help_heading = "Output Options", verbatim_doc_comment)]
+    pub tmp_dir: Option<String>,
+
+    /// Max bytes staged in --tmp-dir, larger files are staged in output dir
+    #[arg(long, value_name = "BYTES", help_heading = "Output Options")]
+    pub max_tmp_bytes: Option<u64>,
+
+    /// Write blocks larger than this many bytes to their own files
+    #[arg(long, value_name = "BYTES", help_heading = "Output Options")]
+    pub max_block_bytes: Option<u64>,
+
     /// Save as csv instead of parquet
     #[arg(long, help_heading = "Output Options")]
     pub csv: bool,
@@ -187,6 +255,19 @@ pub struct Args {
     #[arg(long, help_heading = "Output Options")]
     pub no_stats: bool,
 
+    /// Columns to pseudonymize with a keyed hash,
+    /// key is read from CRYO_HASH_KEY
+    #[arg(long, value_name="COLS", num_args(1..), verbatim_doc_comment, help_heading="Output Options")]
+    pub hash_columns: Option<Vec<String>>,
+
+    /// Columns to drop from every dataset before writing
+    #[arg(long, value_name="COLS", num_args(1..), help_heading="Output Options")]
+    pub drop_columns: Option<Vec<String>>,
+
+    /// Columns to replace with nulls in every dataset before writing
+    #[arg(long, value_name="COLS", num_args(1..), help_heading="Output Options")]
+    pub mask_columns: Option<Vec<String>>,
+
     /// Compression algorithm and level
     #[arg(long, help_heading="Output Options", value_name="NAME [#]", num_args(1..=2), default_value = "lz4")]
     pub compression: Vec<String>,
@@ -200,6 +281,15 @@ pub struct Args {
     #[arg(long, help_heading = "Output Options")]
     pub no_report: bool,
 
+    /// Expected rows per block, as DATASET:MIN:MAX[:FROM_BLOCK],
+    /// e.g. transactions:0:2000 or logs:1::15000000
+    #[arg(long, value_name = "CHECK", num_args(1..), help_heading = "Output Options", verbatim_doc_comment)]
+    pub check: Option<Vec<String>>,
+
+    /// Fail chunks that violate a --check instead of reporting them
+    #[arg(long, help_heading = "Output Options")]
+    pub strict_checks: bool,
+
     /// Address(es)
     #[arg(long, help_heading = "Dataset-specific Options", num_args(1..))]
     pub address: Option<Vec<String>>,
@@ -224,7 +314,29 @@ pub struct Args {
     #[arg(long, help_heading = "Dataset-specific Options", num_args(1..))]
     pub inputs: Option<Vec<String>>,
 
-    /// Slot(s)
+    /// Function called by call_results, ex: "function latestAnswer() view returns (int256)"
+    #[arg(long, value_name = "SIG", help_heading = "Dataset-specific Options")]
+    pub call_signature: Option<String>,
+
+    /// Comma-separated arguments of --call-signature, one call per value
+    #[arg(long, value_name = "ARGS", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub call_args: Option<Vec<String>>,
+
+    /// Seconds of pending transactions written to each mempool file
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 60,
+        help_heading = "Dataset-specific Options"
+    )]
+    pub mempool_window: u64,
+
+    /// Stop streaming the mempool after SECONDS [default: stream until interrupted]
+    #[arg(long, value_name = "SECONDS", help_heading = "Dataset-specific Options")]
+    pub mempool_duration: Option<u64>,
+
+    /// Slot(s), also eip1967.implementation, eip1967.admin, eip1967.beacon, or
+    /// mapping:KEY[:KEY...]:INDEX for the slot of a (nested) mapping entry
     #[arg(long, help_heading = "Dataset-specific Options", num_args(1..))]
     pub slot: Option<Vec<String>>,
 
@@ -252,6 +364,10 @@ pub struct Args {
     #[arg(long, value_name = "SIG", help_heading = "Dataset-specific Options", num_args(1..))]
     pub event_signature: Option<String>,
 
+    /// ABI json files whose functions and events are decoded by decoded_calls and decoded_logs
+    #[arg(long, value_name = "PATHS", help_heading = "Dataset-specific Options", num_args(1..))]
+    pub abi: Option<Vec<String>>,
+
     /// Blocks per request (eth_getLogs)
     #[arg(
         long,
@@ -261,9 +377,62 @@ pub struct Args {
     )]
     pub inner_request_size: u64,
 
+    /// Number of trace_address_N integer columns to add to traces, up to 8
+    #[arg(long, value_name = "K", help_heading = "Dataset-specific Options")]
+    pub trace_address_columns: Option<usize>,
+
     /// Event signature for log decoding
     #[arg(long, value_name = "tracer", help_heading = "Dataset-specific Options")]
     pub js_tracer: Option<String>,
+
+    /// Tracer of custom_traces, a built-in geth tracer name, javascript tracer code, or a
+    /// path to a javascript tracer file
+    #[arg(long, value_name = "TRACER", help_heading = "Dataset-specific Options")]
+    pub tracer: Option<String>,
+
+    /// Config of --tracer, as json or a path to a json file
+    #[arg(long, value_name = "JSON", help_heading = "Dataset-specific Options")]
+    pub tracer_config: Option<String>,
+
+    /// Mapping of --tracer output to custom_traces columns, as json or a path to a json file,
+    /// ex: {"rows": "/calls", "columns": {"to": "/to", "gas": {"path": "/gas", "type":
+    /// "uint64"}}}
+    #[arg(long, value_name = "JSON", help_heading = "Dataset-specific Options")]
+    pub trace_schema: Option<String>,
+
+    /// Use debug_traceCall instead of trace_call for trace_calls, for nodes without the
+    /// trace namespace
+    #[arg(long, help_heading = "Dataset-specific Options")]
+    pub debug_trace_calls: bool,
+
+    /// Include addresses whose state changed in address_appearances, which replays the
+    /// state diffs of every transaction
+    #[arg(long, help_heading = "Dataset-specific Options")]
+    pub state_diff_appearances: bool,
+
+    /// Genesis spec file with an alloc section, used by genesis_balances
+    #[arg(long, value_name = "PATH", help_heading = "Dataset-specific Options")]
+    pub genesis_file: Option<String>,
+
+    /// Beacon node api url, used by beacon datasets and by blob_transactions to fetch
+    /// blob_data
+    #[arg(long, value_name = "URL", help_heading = "Dataset-specific Options")]
+    pub beacon_url: Option<String>,
+
+    /// Validator indices or pubkeys of validator_balances, required by validator_balances
+    #[arg(long, value_name = "IDS", num_args(1..), help_heading = "Dataset-specific Options")]
+    pub validators: Option<Vec<String>>,
+
+    /// Percentiles of gas prices computed by gas_percentiles
+    /// [default: 10 25 50 75 90]
+    #[arg(
+        long,
+        value_name = "PERCENTILES",
+        help_heading = "Dataset-specific Options",
+        num_args(1..),
+        verbatim_doc_comment
+    )]
+    pub gas_percentiles: Option<Vec<f64>>,
 }
 
 impl Args {
@@ -319,6 +488,12 @@ fn get_after_str() -> String {
       <white><bold>cryo help</bold></white>                      display help message
       <white><bold>cryo help syntax</bold></white>               display block + tx specification syntax
       <white><bold>cryo help datasets</bold></white>             display list of all datasets
+      <white><bold>cryo query</bold></white> <SQL>               run sql over datasets in --output-dir
+      <white><bold>cryo schema</bold></white> <FORMAT> <DATASET(S)> print ddl, arrow, or json schema
+      <white><bold>cryo wizard</bold></white>                    interactively build and run a command
+      <white><bold>cryo compare-providers</bold></white> <RPC(S)>   compare latency and methods of rpcs
+      <white><bold>cryo diff</bold></white> <DIR_A> <DIR_B>          write rows added, removed, or changed
+      <white><bold>cryo worker</bold></white> <QUEUE_DIR>           freeze chunks enqueued with --enqueue
       <white><bold>cryo help</bold></white>"#
     );
     let post_subcommands = " <DATASET(S)>         display info about a dataset";
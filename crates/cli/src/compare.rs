@@ -0,0 +1,162 @@
+use crate::{args, parse};
+use alloy::{
+    providers::{Provider, ProviderBuilder, RootProvider},
+    transports::BoxTransport,
+};
+use colored::Colorize;
+use cryo_freeze::{err, percentile, redact_rpc_url, CollectError, FreezeSummary};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// number of times the timed workload is repeated against each provider
+const N_ROUNDS: usize = 5;
+
+/// blocks behind the chain head used by the workload, so that every provider has the block
+const HEAD_DISTANCE: u64 = 8;
+
+/// methods probed for support, in addition to those of the timed workload
+const PROBED_METHODS: [&str; 3] =
+    ["eth_getBlockReceipts", "trace_block", "debug_traceBlockByNumber"];
+
+/// results of running the workload against one provider
+struct ProviderComparison {
+    endpoint: String,
+    latencies: Vec<Duration>,
+    n_errors: usize,
+    supported_methods: Vec<&'static str>,
+}
+
+/// Run a small fixed workload against each provider and print a comparison table.
+///
+/// Providers are given as urls or MESC endpoint names, e.g. `cryo compare-providers <A> <B>`.
+pub(crate) async fn run_compare_providers(
+    args: args::Args,
+) -> Result<Option<FreezeSummary>, CollectError> {
+    let endpoints = &args.datatype[1..];
+    if endpoints.is_empty() {
+        return Err(err("usage: cryo compare-providers <RPC_URL> [<RPC_URL> ...]"))
+    }
+
+    let mut comparisons = Vec::new();
+    for endpoint in endpoints.iter() {
+        // endpoints are printed without the paths and queries that often contain api keys
+        let name = redact_rpc_url(endpoint);
+        let rpc_args = args::Args { rpc: Some(endpoint.clone()), ..args.clone() };
+        let rpc_url = parse::source::parse_rpc_url(&rpc_args)
+            .map_err(|e| err(format!("invalid rpc {}: {}", name, e).as_str()))?;
+        println!("running workload against {}", name.bold());
+        comparisons.push(compare_provider(&name, &rpc_url).await);
+    }
+    println!();
+    print_comparisons(&comparisons);
+    Ok(None)
+}
+
+async fn compare_provider(endpoint: &str, rpc_url: &str) -> ProviderComparison {
+    let mut comparison = ProviderComparison {
+        endpoint: endpoint.to_string(),
+        latencies: Vec::new(),
+        n_errors: 0,
+        supported_methods: Vec::new(),
+    };
+    let provider: RootProvider<BoxTransport> =
+        match ProviderBuilder::default().on_builtin(rpc_url).await {
+            Ok(provider) => provider,
+            Err(_) => {
+                comparison.n_errors += 1;
+                return comparison
+            }
+        };
+    let block = match timed_request(&provider, "eth_blockNumber", json!([]), &mut comparison)
+        .await
+        .and_then(|head| {
+            head.as_str()?.strip_prefix("0x").and_then(|x| u64::from_str_radix(x, 16).ok())
+        }) {
+        Some(head) => format!("0x{:x}", head.saturating_sub(HEAD_DISTANCE)),
+        None => return comparison,
+    };
+
+    for _ in 0..N_ROUNDS {
+        let requests = [
+            ("eth_blockNumber", json!([])),
+            ("eth_getBlockByNumber", json!([&block, false])),
+            ("eth_getLogs", json!([{"fromBlock": &block, "toBlock": &block}])),
+        ];
+        for (method, params) in requests.into_iter() {
+            timed_request(&provider, method, params, &mut comparison).await;
+        }
+    }
+
+    for method in PROBED_METHODS.into_iter() {
+        let params = match method {
+            "debug_traceBlockByNumber" => json!([&block, {"tracer": "callTracer"}]),
+            _ => json!([&block]),
+        };
+        let result: Result<Value, _> = provider.raw_request(method.into(), params).await;
+        if result.is_ok() {
+            comparison.supported_methods.push(method);
+        }
+    }
+    comparison
+}
+
+/// send request, recording its latency if it succeeds and counting it as an error otherwise
+async fn timed_request(
+    provider: &RootProvider<BoxTransport>,
+    method: &'static str,
+    params: Value,
+    comparison: &mut ProviderComparison,
+) -> Option<Value> {
+    let start = Instant::now();
+    match provider.raw_request::<_, Value>(method.into(), params).await {
+        Ok(result) => {
+            comparison.latencies.push(start.elapsed());
+            Some(result)
+        }
+        Err(_) => {
+            comparison.n_errors += 1;
+            None
+        }
+    }
+}
+
+fn print_comparisons(comparisons: &[ProviderComparison]) {
+    let width = comparisons.iter().map(|c| c.endpoint.len()).max().unwrap_or(0).max(8);
+    println!(
+        "{:<width$}  {:>8}  {:>8}  {:>8}  {:>6}  supported methods",
+        "provider",
+        "p50",
+        "p90",
+        "max",
+        "errors",
+        width = width
+    );
+    println!("{}", "─".repeat(width + 60));
+    for comparison in comparisons.iter() {
+        let mut latencies = comparison.latencies.clone();
+        latencies.sort();
+        let format_latency = |latency: Option<Duration>| match latency {
+            Some(latency) => format!("{}ms", latency.as_millis()),
+            None => "-".to_string(),
+        };
+        let n_requests = comparison.latencies.len() + comparison.n_errors;
+        let error_rate = match n_requests {
+            0 => "-".to_string(),
+            n => format!("{:.0}%", 100.0 * comparison.n_errors as f64 / n as f64),
+        };
+        let supported_methods = match comparison.supported_methods.is_empty() {
+            true => "[none]".to_string(),
+            false => comparison.supported_methods.join(", "),
+        };
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {:>8}  {:>6}  {}",
+            comparison.endpoint,
+            format_latency(percentile(&latencies, 0.5)),
+            format_latency(percentile(&latencies, 0.9)),
+            format_latency(latencies.last().copied()),
+            error_rate,
+            supported_methods,
+            width = width
+        );
+    }
+}
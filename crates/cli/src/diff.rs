@@ -0,0 +1,171 @@
+use crate::args;
+use colored::Colorize;
+use cryo_freeze::{err, CollectError, Datatype, FreezeSummary};
+use polars::prelude::*;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// column marking rows whose key was found in the other output directory
+const MATCHED_COLUMN: &str = "__matched";
+
+/// rows of a dataset that differ between two output directories
+struct DatasetDiff {
+    added: DataFrame,
+    removed: DataFrame,
+    changed: DataFrame,
+}
+
+/// Compare the datasets of two output directories and write the rows that differ.
+///
+/// Rows are matched by the unique key columns of each dataset, or by `--sort` columns if
+/// given, e.g. `cryo diff <DIR_A> <DIR_B> -o <DIFF_DIR>`. Keys must be unique in both
+/// directories. For each dataset, rows only in B are written to `{datatype}_diff_added.parquet`,
+/// rows only in A to `{datatype}_diff_removed.parquet` and rows whose other columns differ to
+/// `{datatype}_diff_changed.parquet`
+pub(crate) fn run_diff(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
+    let (dir_a, dir_b) = match &args.datatype[1..] {
+        [dir_a, dir_b] => (Path::new(dir_a), Path::new(dir_b)),
+        _ => return Err(err("usage: cryo diff <DIR_A> <DIR_B> [-o OUTPUT_DIR] [--sort KEYS]")),
+    };
+    let files_a = cryo_freeze::find_dataset_files(dir_a)?;
+    let files_b = cryo_freeze::find_dataset_files(dir_b)?;
+    let datatypes: BTreeSet<&String> = files_a.keys().chain(files_b.keys()).collect();
+    if datatypes.is_empty() {
+        return Err(err("no parquet datasets found in either directory"))
+    }
+
+    let output_dir = Path::new(&args.output_dir);
+    std::fs::create_dir_all(output_dir).map_err(|_| err("could not create output directory"))?;
+    for datatype in datatypes.into_iter() {
+        let (paths_a, paths_b) = match (files_a.get(datatype), files_b.get(datatype)) {
+            (Some(paths_a), Some(paths_b)) => (paths_a, paths_b),
+            (Some(_), None) => {
+                println!("{}: only in {}", datatype.bold(), dir_a.display());
+                continue
+            }
+            _ => {
+                println!("{}: only in {}", datatype.bold(), dir_b.display());
+                continue
+            }
+        };
+        let keys = match &args.sort {
+            Some(sort) if !sort.is_empty() => sort.clone(),
+            _ => Datatype::from_str(datatype)?.unique_key(),
+        };
+        let mut diff = diff_dataset(scan(paths_a)?, scan(paths_b)?, &keys)?;
+        println!(
+            "{}: {} added, {} removed, {} changed",
+            datatype.bold(),
+            diff.added.height(),
+            diff.removed.height(),
+            diff.changed.height()
+        );
+        for (kind, df) in [
+            ("added", &mut diff.added),
+            ("removed", &mut diff.removed),
+            ("changed", &mut diff.changed),
+        ] {
+            if df.height() > 0 {
+                let path = output_dir.join(format!("{}_diff_{}.parquet", datatype, kind));
+                let file = std::fs::File::create(&path)
+                    .map_err(|_| err(format!("could not create {}", path.display()).as_str()))?;
+                ParquetWriter::new(file).finish(df)?;
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn scan(paths: &[PathBuf]) -> Result<LazyFrame, CollectError> {
+    Ok(LazyFrame::scan_parquet_files(paths.to_vec().into(), ScanArgsParquet::default())?)
+}
+
+/// match rows of a and b by key columns and collect the rows that differ
+fn diff_dataset(a: LazyFrame, b: LazyFrame, keys: &[String]) -> Result<DatasetDiff, CollectError> {
+    let schema_a = a.schema()?;
+    let schema_b = b.schema()?;
+    for key in keys.iter() {
+        if schema_a.get(key).is_none() || schema_b.get(key).is_none() {
+            return Err(err(format!("key column {} missing, choose keys with --sort", key).as_str()))
+        }
+    }
+    let key_exprs: Vec<Expr> = keys.iter().map(|key| col(key)).collect();
+    for (lf, name) in [(&a, "A"), (&b, "B")] {
+        let n_rows = lf.clone().select(key_exprs.clone()).collect()?.height();
+        let n_keys = lf
+            .clone()
+            .select(key_exprs.clone())
+            .unique(None, UniqueKeepStrategy::Any)
+            .collect()?
+            .height();
+        if n_keys < n_rows {
+            let message = format!(
+                "key columns {} are not unique in {}, choose unique keys with --sort",
+                keys.join(", "),
+                name
+            );
+            return Err(err(&message))
+        }
+    }
+    let join_args = |how: JoinType| JoinArgs { join_nulls: true, ..JoinArgs::new(how) };
+    let matched_keys = |lf: LazyFrame| {
+        lf.select(key_exprs.clone())
+            .unique(None, UniqueKeepStrategy::Any)
+            .with_column(lit(true).alias(MATCHED_COLUMN))
+    };
+    let unmatched = |lf: LazyFrame, other: LazyFrame, schema: &Schema| {
+        let columns: Vec<Expr> = schema.iter_names().map(|name| col(name)).collect();
+        lf.join(
+            matched_keys(other),
+            key_exprs.clone(),
+            key_exprs.clone(),
+            join_args(JoinType::Left),
+        )
+        .filter(col(MATCHED_COLUMN).is_null())
+        .select(columns)
+    };
+    let added = unmatched(b.clone(), a.clone(), schema_b.as_ref()).collect()?;
+    let removed = unmatched(a.clone(), b.clone(), schema_a.as_ref()).collect()?;
+
+    // compare the non-key columns present in both datasets
+    let values: Vec<&str> = schema_a
+        .iter_names()
+        .filter(|name| schema_b.get(name).is_some() && !keys.contains(&name.to_string()))
+        .map(|name| name.as_str())
+        .collect();
+    let is_changed = values.iter().fold(lit(false), |is_changed, name| {
+        is_changed.or(col(name).neq_missing(col(&format!("{}_right", name))))
+    });
+    let changed = a
+        .join(b, key_exprs.clone(), key_exprs.clone(), join_args(JoinType::Inner))
+        .filter(is_changed)
+        .collect()?;
+
+    Ok(DatasetDiff { added, removed, changed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_dataset() {
+        let a = df!("block_number" => [1u32, 2, 3], "n_logs" => [10u32, 20, 30]).unwrap();
+        let b = df!("block_number" => [2u32, 3, 4], "n_logs" => [20u32, 31, 40]).unwrap();
+        let keys = vec!["block_number".to_string()];
+        let diff = diff_dataset(a.lazy(), b.lazy(), &keys).unwrap();
+        let blocks = |df: &DataFrame| -> Vec<Option<u32>> {
+            df.column("block_number").unwrap().u32().unwrap().into_iter().collect()
+        };
+        assert_eq!(blocks(&diff.added), vec![Some(4)]);
+        assert_eq!(blocks(&diff.removed), vec![Some(1)]);
+        assert_eq!(blocks(&diff.changed), vec![Some(3)]);
+
+        let a = df!("block_number" => [1u32, 1], "n_logs" => [10u32, 11]).unwrap();
+        let b = df!("block_number" => [1u32], "n_logs" => [10u32]).unwrap();
+        assert!(diff_dataset(a.lazy(), b.lazy(), &keys).is_err());
+    }
+}
@@ -8,9 +8,15 @@
 ))]
 
 mod args;
+mod compare;
+mod diff;
 mod parse;
+pub mod plan;
+mod queue;
 mod remember;
 mod run;
+mod sql;
+mod wizard;
 
 // used in main.rs but not lib.rs
 use eyre as _;
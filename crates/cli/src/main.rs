@@ -1,13 +1,7 @@
 //! cryo_cli is a cli for cryo_freeze
 
 use clap_cryo::Parser;
-
-mod args;
-mod parse;
-mod remember;
-mod run;
-
-pub use args::Args;
+use cryo_cli::Args;
 use eyre::Result;
 
 #[tokio::main]
@@ -15,7 +9,7 @@ use eyre::Result;
 #[allow(clippy::needless_return)]
 async fn main() -> Result<()> {
     let args = Args::parse();
-    match run::run(args).await {
+    match cryo_cli::run(args).await {
         Ok(Some(freeze_summary)) if freeze_summary.errored.is_empty() => Ok(()),
         Ok(Some(_freeze_summary)) => std::process::exit(1),
         Ok(None) => Ok(()),
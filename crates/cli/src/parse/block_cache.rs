@@ -0,0 +1,243 @@
+// block metadata cache shared across runs
+// - maps block_number -> (hash, timestamp), stored per chain in {output_dir}/.cryo/block_cache
+// - used by timestamp parsing so that date-based ranges do not refetch headers on every run
+// - blocks within REORG_SAFETY_DEPTH of the chain head are never cached, since they may change
+// - the file stores a hash of its entries, a file whose entries do not match it is discarded
+// - on load, the hash of the highest cached block is compared with the canonical chain, and the
+//   cache is discarded on mismatch. any reorg of a cached block also replaces every later block, so
+//   checking the highest one is enough
+
+use alloy::{primitives::keccak256, rpc::types::BlockTransactionsKind};
+use cryo_freeze::{ParseError, Source};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+const BLOCK_CACHE_DIRNAME: &str = "block_cache";
+
+/// blocks closer than this to the head are not cached
+const REORG_SAFETY_DEPTH: u64 = 64;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct CachedBlock {
+    pub(crate) hash: String,
+    pub(crate) timestamp: u64,
+}
+
+/// on-disk format of cache, content_hash is the keccak of the serialized blocks
+#[derive(Serialize, Deserialize)]
+struct BlockCacheFile {
+    content_hash: String,
+    blocks: BTreeMap<u64, CachedBlock>,
+}
+
+fn content_hash(blocks: &BTreeMap<u64, CachedBlock>) -> Result<String, serde_json::Error> {
+    let json = serde_json::to_string(blocks)?;
+    Ok(keccak256(json.as_bytes()).to_string())
+}
+
+#[derive(Default)]
+pub(crate) struct BlockCache {
+    path: Option<PathBuf>,
+    entries: Mutex<BTreeMap<u64, CachedBlock>>,
+}
+
+impl BlockCache {
+    /// load cache of chain, an unreadable or corrupted cache file is treated as empty
+    pub(crate) fn load(cryo_dir: &Path, chain_id: u64) -> BlockCache {
+        let path = cryo_dir.join(BLOCK_CACHE_DIRNAME).join(format!("{}.json", chain_id));
+        let file: Option<BlockCacheFile> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+        let entries = match file {
+            Some(file) if content_hash(&file.blocks).ok().as_ref() == Some(&file.content_hash) => {
+                file.blocks
+            }
+            Some(_) => {
+                eprintln!(
+                    "block cache {} failed its content hash check, ignoring it",
+                    path.display()
+                );
+                BTreeMap::new()
+            }
+            None => BTreeMap::new(),
+        };
+        BlockCache { path: Some(path), entries: Mutex::new(entries) }
+    }
+
+    /// discard the cache if its highest block is no longer part of the canonical chain
+    pub(crate) async fn verify(&self, source: &Source) -> Result<(), ParseError> {
+        let highest =
+            self.entries.lock().ok().and_then(|e| e.last_key_value().map(|(n, b)| (*n, b.clone())));
+        let (block_number, cached) = match highest {
+            Some(highest) => highest,
+            None => return Ok(()),
+        };
+        let block =
+            source.get_block(block_number, BlockTransactionsKind::Hashes).await.map_err(|_e| {
+                ParseError::ParseError("Error fetching block for block cache".to_string())
+            })?;
+        if block.map(|block| block.header.hash.to_string()) != Some(cached.hash) {
+            eprintln!(
+                "block {} of the block cache was reorged, discarding the cache",
+                block_number
+            );
+            if let Ok(mut entries) = self.entries.lock() {
+                entries.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// get timestamp of block, fetching and caching it if missing
+    pub(crate) async fn get_timestamp(
+        &self,
+        block_number: u64,
+        latest_block_number: u64,
+        source: &Source,
+    ) -> Result<u64, ParseError> {
+        if let Some(block) = self.entries.lock().ok().and_then(|e| e.get(&block_number).cloned()) {
+            return Ok(block.timestamp)
+        }
+
+        let block = source
+            .get_block(block_number, BlockTransactionsKind::Hashes)
+            .await
+            .map_err(|_e| ParseError::ParseError("Error fetching block for timestamp".to_string()))?
+            .ok_or_else(|| ParseError::ParseError("block not found".to_string()))?;
+
+        if block_number + REORG_SAFETY_DEPTH <= latest_block_number {
+            if let Ok(mut entries) = self.entries.lock() {
+                let cached = CachedBlock {
+                    hash: block.header.hash.to_string(),
+                    timestamp: block.header.timestamp,
+                };
+                entries.insert(block_number, cached);
+            }
+        }
+        Ok(block.header.timestamp)
+    }
+
+    /// write cache to disk so that later runs can reuse it
+    pub(crate) fn save(&self) -> Result<(), ParseError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let entries = self
+            .entries
+            .lock()
+            .map_err(|_| ParseError::ParseError("could not lock block cache".to_string()))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|_| {
+                ParseError::ParseError("could not create block cache directory".to_string())
+            })?;
+        }
+        let serialize_error =
+            |_| ParseError::ParseError("could not serialize block cache".to_string());
+        let file = BlockCacheFile {
+            content_hash: content_hash(&entries).map_err(serialize_error)?,
+            blocks: entries.clone(),
+        };
+        let json = serde_json::to_string(&file).map_err(serialize_error)?;
+        std::fs::write(path, json)
+            .map_err(|_| ParseError::ParseError("could not write block cache".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cryo_freeze::RpcFixtures;
+    use serde_json::json;
+
+    fn cached_block(hash: u64, timestamp: u64) -> CachedBlock {
+        CachedBlock { hash: format!("0x{:064x}", hash), timestamp }
+    }
+
+    fn cache_with_blocks(cryo_dir: &Path, blocks: &[(u64, CachedBlock)]) -> BlockCache {
+        let cache = BlockCache::load(cryo_dir, 1);
+        cache.entries.lock().unwrap().extend(blocks.iter().cloned());
+        cache
+    }
+
+    fn block_json(number: u64, hash: u64) -> serde_json::Value {
+        json!({
+            "hash": format!("0x{:064x}", hash),
+            "parentHash": format!("0x{:064x}", 0),
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0x05a56e2d52c817161883f50c441c3228cfe54d9f",
+            "stateRoot": "0xd67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3",
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "difficulty": "0x0",
+            "number": format!("0x{:x}", number),
+            "gasLimit": "0x1388",
+            "gasUsed": "0x0",
+            "timestamp": "0x55ba4224",
+            "extraData": "0x",
+            "mixHash": format!("0x{:064x}", 0),
+            "nonce": "0x0000000000000000",
+            "size": "0x219",
+            "uncles": [],
+            "transactions": [],
+        })
+    }
+
+    #[test]
+    fn test_block_cache_round_trip() {
+        let cryo_dir = tempfile::tempdir().unwrap();
+        let blocks = [(10, cached_block(10, 1000)), (20, cached_block(20, 2000))];
+        cache_with_blocks(cryo_dir.path(), &blocks).save().unwrap();
+
+        let loaded = BlockCache::load(cryo_dir.path(), 1);
+        let entries = loaded.entries.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[&20].timestamp, 2000);
+        assert_eq!(entries[&20].hash, format!("0x{:064x}", 20));
+
+        // caches are stored per chain
+        assert!(BlockCache::load(cryo_dir.path(), 10).entries.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_block_cache_discards_tampered_file() {
+        let cryo_dir = tempfile::tempdir().unwrap();
+        cache_with_blocks(cryo_dir.path(), &[(10, cached_block(10, 1000))]).save().unwrap();
+        let path = cryo_dir.path().join(BLOCK_CACHE_DIRNAME).join("1.json");
+        let contents = std::fs::read_to_string(&path).unwrap().replace("1000", "1001");
+        std::fs::write(&path, contents).unwrap();
+        assert!(BlockCache::load(cryo_dir.path(), 1).entries.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_block_cache_discarded_after_reorg() {
+        let cryo_dir = tempfile::tempdir().unwrap();
+        let blocks = [(10, cached_block(10, 1000)), (20, cached_block(20, 2000))];
+        let mut fixtures = RpcFixtures::default();
+        fixtures.insert(
+            "eth_getBlockByNumber".to_string(),
+            json!(["0x14", false]),
+            block_json(20, 20),
+        );
+        let source = fixtures.into_source(1);
+        let cache = cache_with_blocks(cryo_dir.path(), &blocks);
+        cache.verify(&source).await.unwrap();
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+
+        // the canonical block 20 now has another hash
+        let mut fixtures = RpcFixtures::default();
+        fixtures.insert(
+            "eth_getBlockByNumber".to_string(),
+            json!(["0x14", false]),
+            block_json(20, 21),
+        );
+        let source = fixtures.into_source(1);
+        cache.verify(&source).await.unwrap();
+        assert!(cache.entries.lock().unwrap().is_empty());
+    }
+}
@@ -10,7 +10,7 @@ pub(crate) async fn parse_blocks(
     source: Arc<Source>,
 ) -> Result<(Option<Vec<Option<String>>>, Option<Vec<BlockChunk>>), ParseError> {
     let (files, explicit_numbers): (Vec<&String>, Vec<&String>) = match &args.blocks {
-        Some(blocks) => blocks.iter().partition(|tx| std::path::Path::new(tx).exists()),
+        Some(blocks) => blocks.iter().partition(|input| is_block_file(input)),
         None => return Ok((None, None)),
     };
 
@@ -25,6 +25,16 @@ pub(crate) async fn parse_blocks(
             } else {
                 "block_number"
             };
+            if !path.ends_with(".parquet") {
+                let chunk = read_sparse_block_list(path, column)?;
+                let chunks = match args.n_chunks {
+                    Some(n_chunks) => chunk.subchunk_by_count(&n_chunks),
+                    None => chunk.subchunk_by_size(&args.chunk_size),
+                };
+                file_labels.extend(vec![None; chunks.len()]);
+                file_chunks.extend(chunks);
+                continue
+            }
             let integers = read_integer_column(path, column)
                 .map_err(|_e| ParseError::ParseError("could not read input".to_string()))?;
             let chunk = BlockChunk::Numbers(integers);
@@ -67,6 +77,63 @@ pub(crate) async fn parse_blocks(
     Ok((labels, Some(block_chunks)))
 }
 
+/// extensions of files that --blocks reads block numbers from
+const BLOCK_FILE_EXTENSIONS: [&str; 3] = ["parquet", "csv", "txt"];
+
+/// whether a --blocks input is a file of block numbers rather than a block range
+fn is_block_file(input: &str) -> bool {
+    let path = std::path::Path::new(input);
+    path.is_file() &&
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| BLOCK_FILE_EXTENSIONS.contains(&ext))
+}
+
+/// read an explicit list of block numbers from a csv file or a text file with one block per line
+///
+/// blocks are sorted and deduplicated, so that sparse lists of interesting blocks can be
+/// partitioned into chunks by count and re-collected
+fn read_sparse_block_list(path: &str, column: &str) -> Result<BlockChunk, ParseError> {
+    let mut blocks = if path.ends_with(".csv") {
+        read_csv_integer_column(path, column)?
+    } else {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_e| ParseError::ParseError("could not open file path".to_string()))?;
+        contents
+            .lines()
+            .map(|line| line.trim().replace('_', ""))
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.parse::<u64>().map_err(|_e| {
+                    ParseError::ParseError(format!("could not parse block number: {}", line))
+                })
+            })
+            .collect::<Result<Vec<u64>, ParseError>>()?
+    };
+    blocks.sort_unstable();
+    blocks.dedup();
+    if blocks.is_empty() {
+        return Err(ParseError::ParseError(format!("no block numbers found in {}", path)))
+    }
+    Ok(BlockChunk::Numbers(blocks))
+}
+
+fn read_csv_integer_column(path: &str, column: &str) -> Result<Vec<u64>, ParseError> {
+    let df = CsvReader::from_path(path)
+        .and_then(|reader| reader.has_header(true).finish())
+        .map_err(|_e| ParseError::ParseError("could not read csv file".to_string()))?;
+    let series = df
+        .column(column)
+        .and_then(|series| series.cast(&DataType::UInt64))
+        .map_err(|_e| ParseError::ParseError(format!("could not get column {}", column)))?;
+    series
+        .u64()
+        .map_err(|_e| ParseError::ParseError("could not convert to integer column".to_string()))?
+        .into_iter()
+        .map(|v| v.ok_or_else(|| ParseError::ParseError("block number missing".to_string())))
+        .collect()
+}
+
 fn read_integer_column(path: &str, column: &str) -> Result<Vec<u64>, ParseError> {
     let file = std::fs::File::open(path)
         .map_err(|_e| ParseError::ParseError("could not open file path".to_string()))?;
@@ -417,6 +484,10 @@ mod tests {
             provider,
             semaphore: Arc::new(None),
             rate_limiter: Arc::new(None),
+            shared_rate_limiter: None,
+            request_jitter: None,
+            throttled_until: Arc::new(std::sync::Mutex::new(None)),
+            block_receipts_support: Arc::new(std::sync::Mutex::new(None)),
             chain_id: 1,
             inner_request_size: 1,
             max_concurrent_chunks: None,
@@ -487,6 +558,10 @@ mod tests {
             semaphore: Arc::new(None),
             max_concurrent_chunks: Some(1),
             rate_limiter: Arc::new(None),
+            shared_rate_limiter: None,
+            request_jitter: None,
+            throttled_until: Arc::new(std::sync::Mutex::new(None)),
+            block_receipts_support: Arc::new(std::sync::Mutex::new(None)),
             labels: cryo_freeze::SourceLabels::default(),
         });
         for (test, res) in tests {
@@ -560,6 +635,10 @@ mod tests {
             provider,
             semaphore: Arc::new(None),
             rate_limiter: Arc::new(None),
+            shared_rate_limiter: None,
+            request_jitter: None,
+            throttled_until: Arc::new(std::sync::Mutex::new(None)),
+            block_receipts_support: Arc::new(std::sync::Mutex::new(None)),
             chain_id: 1,
             inner_request_size: 1,
             max_concurrent_chunks: Some(1),
@@ -714,4 +793,45 @@ mod tests {
         mock_server.spawn().await;
         block_number_test_helper(tests, mock_ipc_path).await;
     }
+
+    #[test]
+    fn test_read_sparse_block_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocks.txt");
+        std::fs::write(&path, "# interesting blocks\n30\n1_000\n\n10\n30\n").unwrap();
+        let path = path.to_str().unwrap();
+        assert!(is_block_file(path));
+        let chunk = read_sparse_block_list(path, "block_number").unwrap();
+        let BlockChunk::Numbers(blocks) = chunk else { panic!("Unexpected shape") };
+        assert_eq!(blocks, vec![10, 30, 1000]);
+
+        std::fs::write(dir.path().join("empty.txt"), "# nothing\n").unwrap();
+        let empty = dir.path().join("empty.txt");
+        assert!(read_sparse_block_list(empty.to_str().unwrap(), "block_number").is_err());
+    }
+
+    #[test]
+    fn test_read_parquet_block_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ethereum__blocks__00000010_to_00000012.parquet");
+        let mut df = df!("block_number" => [10u32, 11, 12]).unwrap();
+        ParquetWriter::new(std::fs::File::create(&path).unwrap()).finish(&mut df).unwrap();
+        let path = path.to_str().unwrap();
+        assert!(is_block_file(path));
+        assert_eq!(read_integer_column(path, "block_number").unwrap(), vec![10, 11, 12]);
+    }
+
+    #[test]
+    fn test_only_block_files_are_read() {
+        // directories and files of other types are parsed as block ranges instead
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_block_file(dir.path().to_str().unwrap()));
+        let other = dir.path().join("notes.md");
+        std::fs::write(&other, "10\n").unwrap();
+        assert!(!is_block_file(other.to_str().unwrap()));
+        let renamed = dir.path().join("blocks.csv");
+        std::fs::create_dir(&renamed).unwrap();
+        assert!(!is_block_file(renamed.to_str().unwrap()));
+        assert!(!is_block_file("10:20"));
+    }
 }
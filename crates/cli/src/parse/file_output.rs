@@ -5,6 +5,11 @@ use std::fs;
 
 pub(crate) fn parse_file_output(args: &Args, source: &Source) -> Result<FileOutput, ParseError> {
     // process output directory
+    if args.output_dir.contains("://") {
+        return Err(ParseError::ParseError(
+            "output dir must be a local path, object store uris are not supported".to_string(),
+        ))
+    }
     std::fs::create_dir_all(args.output_dir.clone())
         .map_err(|_| ParseError::ParseError("could not create dir".to_string()))?;
     let output_dir = std::fs::canonicalize(args.output_dir.clone()).map_err(|_e| {
@@ -30,16 +35,36 @@ pub(crate) fn parse_file_output(args: &Args, source: &Source) -> Result<FileOutp
 
     let subdirs = parse_subdirs(args);
 
+    let (hashed_columns, hash_key) = parse_hashed_columns(args)?;
+
+    let tmp_dir = match &args.tmp_dir {
+        Some(tmp_dir) => {
+            fs::create_dir_all(tmp_dir).map_err(|e| {
+                ParseError::ParseError(format!("could not create tmp dir {}: {}", tmp_dir, e))
+            })?;
+            Some(std::path::PathBuf::from(tmp_dir))
+        }
+        None => None,
+    };
+
     let output = FileOutput {
         output_dir,
         subdirs,
         parquet_statistics: !args.no_stats,
         overwrite: args.overwrite,
+        version_outputs: args.version_outputs,
+        tmp_dir,
+        max_tmp_bytes: args.max_tmp_bytes,
+        max_block_bytes: args.max_block_bytes,
         prefix: file_prefix,
         format,
         suffix: label.clone(),
         parquet_compression,
         row_group_size,
+        hashed_columns,
+        hash_key,
+        dropped_columns: args.drop_columns.clone().unwrap_or_default(),
+        masked_columns: args.mask_columns.clone().unwrap_or_default(),
     };
 
     Ok(output)
@@ -59,6 +84,21 @@ pub(crate) fn parse_subdirs(args: &Args) -> Vec<SubDir> {
     subdirs
 }
 
+/// the hash key is read from the environment so that it never appears in reports or remembered
+/// commands
+fn parse_hashed_columns(args: &Args) -> Result<(Vec<String>, Option<Vec<u8>>), ParseError> {
+    match &args.hash_columns {
+        Some(columns) if !columns.is_empty() => match std::env::var("CRYO_HASH_KEY") {
+            Ok(key) if !key.is_empty() => Ok((columns.clone(), Some(key.into_bytes()))),
+            _ => Err(ParseError::ParseError(
+                "--hash-columns requires a key in the CRYO_HASH_KEY environment variable"
+                    .to_string(),
+            )),
+        },
+        _ => Ok((vec![], None)),
+    }
+}
+
 pub(crate) fn parse_network_name(args: &Args, chain_id: u64) -> String {
     match &args.network_name {
         Some(name) => name.clone(),
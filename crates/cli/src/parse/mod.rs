@@ -1,4 +1,5 @@
 mod args;
+mod block_cache;
 mod blocks;
 mod execution;
 mod file_output;
@@ -6,7 +7,7 @@ mod parse_utils;
 mod partitions;
 mod query;
 pub(crate) mod schemas;
-mod source;
+pub(crate) mod source;
 mod timestamps;
 
 pub use args::*;
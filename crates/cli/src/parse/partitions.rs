@@ -1,6 +1,6 @@
 use super::{
     blocks,
-    parse_utils::{hex_string_to_binary, hex_strings_to_binary, parse_binary_arg},
+    parse_utils::{hex_string_to_binary, hex_strings_to_binary, parse_binary_arg, BinaryInputList},
     timestamps,
 };
 use crate::args::Args;
@@ -30,7 +30,14 @@ pub(crate) async fn parse_partitions(
     };
     let (transaction_hash_labels, transactions) =
         parse_transaction_chunks(&args.txs, "transaction_hash")?;
-    let call_datas = parse_call_datas(&args.call_data, &args.function, &args.inputs)?;
+    let call_datas = match &args.call_signature {
+        Some(_) => parse_signature_call_datas(args)?,
+        None if args.call_args.is_some() => {
+            let message = "must specify call_signature if specifying call_args";
+            return Err(ParseError::ParseError(message.to_string()))
+        }
+        None => parse_call_datas(&args.call_data, &args.function, &args.inputs)?,
+    };
     let call_data_labels = None;
     let (address_labels, addresses) = parse_address_chunks(&args.address, "address")?;
     let (contract_labels, contracts) = parse_address_chunks(&args.contract, "contract_address")?;
@@ -173,6 +180,27 @@ fn parse_call_datas(
     Ok(Some(vec![CallDataChunk::Values(call_datas)]))
 }
 
+/// encode the call data of --call-signature for each of --call-args
+fn parse_signature_call_datas(args: &Args) -> Result<Option<Vec<CallDataChunk>>, ParseError> {
+    if args.call_data.is_some() || args.function.is_some() || args.inputs.is_some() {
+        let message = "cannot specify call_signature with call_data, function, or inputs";
+        return Err(ParseError::ParseError(message.to_string()))
+    }
+    let decoder = match super::schemas::parse_call_decoder(args)? {
+        Some(decoder) => decoder,
+        None => return Ok(None),
+    };
+    let call_args = match &args.call_args {
+        Some(call_args) => call_args.clone(),
+        None => vec![String::new()],
+    };
+    let call_datas = call_args
+        .iter()
+        .map(|call_args| decoder.encode_call(call_args).map_err(ParseError::ParseError))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(vec![CallDataChunk::Values(call_datas)]))
+}
+
 pub(crate) fn parse_transaction_chunks(
     input: &Option<Vec<String>>,
     default_column: &str,
@@ -206,7 +234,19 @@ pub(crate) fn parse_slot_chunks(
     default_column: &str,
 ) -> Result<(Option<ChunkLabels>, Option<Vec<SlotChunk>>), ParseError> {
     if let Some(input) = input {
-        let parsed = parse_binary_arg(input, default_column)?;
+        // named slots and mapping expressions are resolved before parsing hex strings and files
+        let mut resolved = Vec::new();
+        let mut remaining = Vec::new();
+        for slot in input.iter() {
+            match cryo_freeze::resolve_slot_expression(slot)? {
+                Some(value) => resolved.push(value),
+                None => remaining.push(slot.clone()),
+            }
+        }
+        let mut parsed = parse_binary_arg(&remaining, default_column)?;
+        if !resolved.is_empty() {
+            parsed.entry(BinaryInputList::Explicit).or_default().extend(resolved);
+        }
         let labels: Vec<Option<String>> = parsed.keys().map(|x| x.clone().to_label()).collect();
         let chunks = parsed.values().map(|a| SlotChunk::Values(a.clone())).collect();
         Ok((Some(labels), Some(chunks)))
@@ -1,11 +1,11 @@
 use super::{parse_schemas, partitions};
 use crate::args::Args;
-use cryo_freeze::{Dim, ParseError, Query, QueryLabels, Schemas, Source};
-use std::sync::Arc;
+use cryo_freeze::{Dim, ParseError, Query, QueryLabels, RowCountCheck, Schemas, Source};
+use std::{str::FromStr, sync::Arc};
 
 /// parse Query struct from cli Args
 pub async fn parse_query(args: &Args, source: Arc<Source>) -> Result<Query, ParseError> {
-    let (datatypes, schemas) = parse_schemas(args)?;
+    let (datatypes, schemas) = parse_schemas(args, Some(source.chain_id))?;
 
     let arg_aliases = find_arg_aliases(args, &schemas);
     let new_args =
@@ -16,6 +16,20 @@ pub async fn parse_query(args: &Args, source: Arc<Source>) -> Result<Query, Pars
         partitions::parse_partitions(args, source, &schemas).await?;
     let datatypes = cryo_freeze::cluster_datatypes(datatypes);
     let labels = QueryLabels { align: args.align, reorg_buffer: args.reorg_buffer };
+    let row_count_checks = match &args.check {
+        Some(checks) => {
+            checks.iter().map(|check| RowCountCheck::from_str(check)).collect::<Result<_, _>>()?
+        }
+        None => Vec::new(),
+    };
+    let gas_percentiles =
+        args.gas_percentiles.clone().unwrap_or_else(|| vec![10.0, 25.0, 50.0, 75.0, 90.0]);
+    if gas_percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) {
+        return Err(ParseError::ParseError("gas percentiles must be between 0 and 100".to_string()))
+    }
+    if args.multicall == Some(0) {
+        return Err(ParseError::ParseError("--multicall must be at least 1 call".to_string()))
+    }
     Ok(Query {
         datatypes,
         schemas,
@@ -24,6 +38,18 @@ pub async fn parse_query(args: &Args, source: Arc<Source>) -> Result<Query, Pars
         partitioned_by,
         exclude_failed: args.exclude_failed,
         js_tracer: args.js_tracer.clone(),
+        genesis_file: args.genesis_file.clone(),
+        beacon_url: args.beacon_url.clone(),
+        debug_trace_calls: args.debug_trace_calls,
+        state_diff_appearances: args.state_diff_appearances,
+        validators: args.validators.clone(),
+        gas_percentiles,
+        row_count_checks,
+        strict_checks: args.strict_checks,
+        multicall: args.multicall,
+        mempool_window: args.mempool_window,
+        mempool_duration: args.mempool_duration,
+        block_flush: None,
         labels,
     })
 }
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 use cryo_freeze::{
-    ColumnEncoding, Datatype, FileFormat, LogDecoder, MultiDatatype, ParseError, Table,
+    ChainProfile, ColumnEncoding, Datatype, FileFormat, FunctionDecoder, GasNullPolicy, LogDecoder,
+    MultiDatatype, ParseError, Table, TraceDecoder, CATEGORICAL_COLUMNS, MAX_TRACE_ADDRESS_COLUMNS,
 };
 
 use super::file_output;
@@ -28,11 +29,15 @@ fn parse_datatypes(raw_inputs: &Vec<String>) -> Result<Vec<Datatype>, ParseError
 
 pub(crate) fn parse_schemas(
     args: &Args,
+    chain_id: Option<u64>,
 ) -> Result<(Vec<Datatype>, HashMap<Datatype, Table>), ParseError> {
     // parse inputs
     let datatypes = parse_datatypes(&args.datatype)?;
     let sort = parse_sort_columns(&args.sort, &datatypes)?;
     let u256_types = parse_u256_types(args)?;
+    let trace_address_columns = parse_trace_address_columns(args)?;
+    let include_columns = parse_include_columns(args, &datatypes, chain_id, &trace_address_columns);
+    let gas_null_policy: GasNullPolicy = args.gas_nulls.parse()?;
     let output_format = file_output::parse_output_format(args)?;
     let binary_column_format = match args.hex | (output_format != FileFormat::Parquet) {
         true => ColumnEncoding::Hex,
@@ -47,6 +52,18 @@ pub(crate) fn parse_schemas(
         None => None,
     };
 
+    let call_decoder = parse_call_decoder(args)?;
+    if datatypes.contains(&Datatype::CallResults) && call_decoder.is_none() {
+        return Err(ParseError::ParseError("call_results requires --call-signature".to_string()))
+    }
+
+    let trace_decoder = parse_trace_decoder(args)?;
+    if datatypes.contains(&Datatype::CustomTraces) && trace_decoder.is_none() {
+        return Err(ParseError::ParseError(
+            "custom_traces requires --tracer and --trace-schema".to_string(),
+        ))
+    }
+
     // create schemas
     let schemas: Result<HashMap<Datatype, Table>, ParseError> = datatypes
         .iter()
@@ -55,13 +72,25 @@ pub(crate) fn parse_schemas(
                 .table_schema(
                     &u256_types,
                     &binary_column_format,
-                    &args.include_columns,
+                    &include_columns,
                     &args.exclude_columns,
-                    &args.columns,
+                    &parse_columns(args, datatype, &trace_address_columns),
                     sort[datatype].clone(),
                     log_decoder.clone(),
                 )
-                .map(|schema| (*datatype, schema))
+                .map(|mut schema| {
+                    schema.gas_null_policy = gas_null_policy;
+                    if *datatype == Datatype::CallResults {
+                        schema.function_decoder = call_decoder.clone();
+                    }
+                    if *datatype == Datatype::CustomTraces {
+                        schema.trace_decoder = trace_decoder.clone();
+                    }
+                    if args.categorical {
+                        schema.set_categorical_columns(&CATEGORICAL_COLUMNS);
+                    }
+                    (*datatype, schema)
+                })
                 .map_err(|e| {
                     ParseError::ParseError(format!(
                         "Failed to get schema for datatype: {:?}, {:?}",
@@ -84,6 +113,134 @@ pub(crate) fn parse_schemas(
     Ok((datatypes, schemas?))
 }
 
+/// parse a decoder for each function of the --abi files
+pub(crate) fn parse_function_decoders(args: &Args) -> Result<Vec<FunctionDecoder>, ParseError> {
+    let mut decoders = Vec::new();
+    for path in args.abi.iter().flatten() {
+        decoders.extend(FunctionDecoder::from_abi_file(path).map_err(ParseError::ParseError)?);
+    }
+    Ok(decoders)
+}
+
+/// parse the decoder of --call-signature
+pub(crate) fn parse_call_decoder(args: &Args) -> Result<Option<FunctionDecoder>, ParseError> {
+    match &args.call_signature {
+        Some(signature) => {
+            FunctionDecoder::new(signature.clone()).map(Some).map_err(ParseError::ParseError)
+        }
+        None => Ok(None),
+    }
+}
+
+/// parse the tracer of --tracer and the column mapping of --trace-schema
+pub(crate) fn parse_trace_decoder(args: &Args) -> Result<Option<TraceDecoder>, ParseError> {
+    let (tracer, mapping) = match (&args.tracer, &args.trace_schema) {
+        (Some(tracer), Some(mapping)) => (read_file_or_value(tracer)?, mapping),
+        (None, None) => return Ok(None),
+        _ => {
+            return Err(ParseError::ParseError(
+                "--tracer and --trace-schema must be used together".to_string(),
+            ))
+        }
+    };
+    let mapping = parse_json(&read_file_or_value(mapping)?, "--trace-schema")?;
+    let tracer_config = match &args.tracer_config {
+        Some(config) => Some(parse_json(&read_file_or_value(config)?, "--tracer-config")?),
+        None => None,
+    };
+    TraceDecoder::new(tracer, tracer_config, &mapping).map(Some).map_err(ParseError::ParseError)
+}
+
+/// contents of a file if value is an existing path, otherwise value itself
+fn read_file_or_value(value: &str) -> Result<String, ParseError> {
+    match std::path::Path::new(value).is_file() {
+        true => std::fs::read_to_string(value)
+            .map_err(|e| ParseError::ParseError(format!("could not read {}: {}", value, e))),
+        false => Ok(value.to_string()),
+    }
+}
+
+fn parse_json(value: &str, arg: &str) -> Result<serde_json::Value, ParseError> {
+    serde_json::from_str(value)
+        .map_err(|e| ParseError::ParseError(format!("invalid json for {}: {}", arg, e)))
+}
+
+/// parse a decoder for each event of the --abi files and for --event-signature
+pub(crate) fn parse_event_decoders(args: &Args) -> Result<Vec<LogDecoder>, ParseError> {
+    let mut decoders = Vec::new();
+    for path in args.abi.iter().flatten() {
+        decoders.extend(LogDecoder::from_abi_file(path).map_err(ParseError::ParseError)?);
+    }
+    if let Some(signature) = &args.event_signature {
+        let decoder = LogDecoder::new(signature.clone()).map_err(ParseError::ParseError)?;
+        if !decoders.iter().any(|other| other.event.selector() == decoder.event.selector()) {
+            decoders.push(decoder);
+        }
+    }
+    Ok(decoders)
+}
+
+/// trace_address_N columns requested by --trace-address-columns
+fn parse_trace_address_columns(args: &Args) -> Result<Vec<String>, ParseError> {
+    match args.trace_address_columns {
+        Some(n_columns) if n_columns > MAX_TRACE_ADDRESS_COLUMNS => Err(ParseError::ParseError(
+            format!("at most {} trace address columns are supported", MAX_TRACE_ADDRESS_COLUMNS),
+        )),
+        Some(n_columns) => Ok((0..n_columns).map(|i| format!("trace_address_{}", i)).collect()),
+        None => Ok(vec![]),
+    }
+}
+
+/// add the trace address columns and the columns of the chain profile of chain_id to
+/// --include-columns, unless it already includes all columns
+fn parse_include_columns(
+    args: &Args,
+    datatypes: &[Datatype],
+    chain_id: Option<u64>,
+    trace_address_columns: &[String],
+) -> Option<Vec<String>> {
+    let profile = chain_id.map(ChainProfile::from_chain_id).unwrap_or_default();
+    let chain_columns: Vec<&str> =
+        datatypes.iter().flat_map(|datatype| profile.columns(datatype)).collect();
+    match &args.include_columns {
+        Some(include_columns) if *include_columns == ["all"] => Some(include_columns.clone()),
+        _ if trace_address_columns.is_empty() & chain_columns.is_empty() => {
+            args.include_columns.clone()
+        }
+        include_columns => {
+            let mut include_columns = include_columns.clone().unwrap_or_default();
+            include_columns.extend(trace_address_columns.iter().cloned());
+            include_columns.extend(chain_columns.into_iter().map(|column| column.to_string()));
+            Some(include_columns)
+        }
+    }
+}
+
+/// add the trace address columns to the --columns of datatypes that have them, unless --columns
+/// already selects all columns
+fn parse_columns(
+    args: &Args,
+    datatype: &Datatype,
+    trace_address_columns: &[String],
+) -> Option<Vec<String>> {
+    match &args.columns {
+        Some(columns)
+            if (*columns != ["all"]) &
+                !trace_address_columns.is_empty() &
+                datatype.column_types().contains_key("trace_address_0") =>
+        {
+            let mut columns = columns.clone();
+            for column in trace_address_columns.iter() {
+                if !columns.contains(column) {
+                    columns.push(column.clone());
+                }
+            }
+            Some(columns)
+        }
+        columns => columns.clone(),
+    }
+}
+
 fn parse_u256_types(args: &Args) -> Result<Vec<U256Type>, ParseError> {
     args.u256_types.as_ref().map_or(
         Ok(vec![U256Type::Binary, U256Type::String, U256Type::F64]),
@@ -192,3 +349,27 @@ fn parse_sort_columns(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_cryo::Parser;
+
+    #[test]
+    fn test_trace_address_columns() {
+        let parse = |command: &str| Args::parse_from(command.split_whitespace());
+        let args = parse("cryo traces --trace-address-columns 2 --include-columns all");
+        let trace_address_columns = parse_trace_address_columns(&args).unwrap();
+        let include_columns =
+            parse_include_columns(&args, &[Datatype::Traces], None, &trace_address_columns);
+        assert_eq!(include_columns, Some(vec!["all".to_string()]));
+
+        let args = parse("cryo traces blocks --trace-address-columns 2 --columns block_number");
+        let trace_address_columns = parse_trace_address_columns(&args).unwrap();
+        let columns = parse_columns(&args, &Datatype::Traces, &trace_address_columns);
+        let expected = ["block_number", "trace_address_0", "trace_address_1"];
+        assert_eq!(columns, Some(expected.iter().map(|x| x.to_string()).collect()));
+        let columns = parse_columns(&args, &Datatype::Blocks, &trace_address_columns);
+        assert_eq!(columns, Some(vec!["block_number".to_string()]));
+    }
+}
@@ -4,27 +4,63 @@ use crate::args::Args;
 use alloy::{
     providers::{Provider, ProviderBuilder, RootProvider},
     rpc::client::{BuiltInConnectionString, ClientBuilder, RpcClient},
-    transports::{layers::RetryBackoffLayer, BoxTransport},
+    transports::{
+        http::{reqwest, Http},
+        layers::RetryBackoffLayer,
+        BoxTransport, BoxTransportConnect, Transport,
+    },
+};
+use cryo_freeze::{
+    ParseError, RecordingTransport, RpcFixtures, SharedRateLimiter, Source, SourceLabels,
+    ThrottleLayer,
 };
-use cryo_freeze::{ParseError, Source, SourceLabels};
 use governor::{Quota, RateLimiter};
 use polars::prelude::*;
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, time::Duration};
+
+/// maximum concurrent requests of the polite profile
+const POLITE_MAX_CONCURRENT_REQUESTS: u64 = 4;
+
+/// maximum random delay before each request of the polite profile
+const POLITE_REQUEST_JITTER: Duration = Duration::from_millis(250);
 
 pub(crate) async fn parse_source(args: &Args) -> Result<Source, ParseError> {
+    if let Some(path) = &args.replay_fixtures {
+        return parse_replay_source(path).await
+    }
+
     // parse network info
     let rpc_url = parse_rpc_url(args)?;
+    let polite = parse_polite_profile(args)?;
     let retry_layer = RetryBackoffLayer::new(
         args.max_retries,
         args.initial_backoff,
         args.compute_units_per_second,
     );
     let connect: BuiltInConnectionString = rpc_url.parse().map_err(ParseError::ProviderError)?;
+    let transport = match (&connect, polite) {
+        (BuiltInConnectionString::Http(url), true) => {
+            let user_agent = format!("cryo/{} (polite profile)", env!("CARGO_PKG_VERSION"));
+            let client =
+                reqwest::Client::builder().user_agent(user_agent).build().map_err(|e| {
+                    ParseError::ParseError(format!("could not build http client: {}", e))
+                })?;
+            Http::with_client(client, url.clone()).boxed()
+        }
+        _ => connect.connect_boxed().await.map_err(ParseError::ProviderError)?,
+    };
+    let transport = match &args.record_fixtures {
+        Some(path) => RecordingTransport::new(transport, path.into()).boxed(),
+        None => transport,
+    };
+    // the throttle goes below the retry layer so that it sees every rate limited attempt
+    let throttled_until = Arc::new(std::sync::Mutex::new(None));
+    let throttle_layer =
+        ThrottleLayer::new(throttled_until.clone(), Duration::from_millis(args.initial_backoff));
     let client: RpcClient<BoxTransport> = ClientBuilder::default()
         .layer(retry_layer)
-        .connect_boxed(connect)
-        .await
-        .map_err(ParseError::ProviderError)?
+        .layer(throttle_layer)
+        .transport(transport, connect.is_local())
         .boxed();
     let provider: RootProvider<BoxTransport> = ProviderBuilder::default().on_client(client);
     let chain_id = provider.get_chain_id().await.map_err(ParseError::ProviderError)?;
@@ -38,14 +74,39 @@ pub(crate) async fn parse_source(args: &Args) -> Result<Source, ParseError> {
         },
         None => None,
     };
+    let (rate_limiter, shared_rate_limiter) =
+        match (args.shared_rate_limit, args.requests_per_second) {
+            (true, Some(rate_limit)) => {
+                let dir = match &args.rate_limit_dir {
+                    Some(dir) => std::path::PathBuf::from(dir),
+                    None => env::temp_dir().join("cryo_rate_limits"),
+                };
+                let limiter = SharedRateLimiter::new(&dir, &rpc_url, rate_limit).map_err(|e| {
+                    ParseError::ParseError(format!("invalid shared rate limit: {}", e))
+                })?;
+                (None, Some(limiter))
+            }
+            (true, None) => {
+                let message = "--shared-rate-limit requires --requests-per-second";
+                return Err(ParseError::ParseError(message.to_string()))
+            }
+            _ => (rate_limiter, None),
+        };
 
     // process concurrency info
-    let max_concurrent_requests = args.max_concurrent_requests.unwrap_or(100);
-    let max_concurrent_chunks = match args.max_concurrent_chunks {
+    let mut max_concurrent_requests = args.max_concurrent_requests.unwrap_or(100);
+    let mut max_concurrent_chunks = match args.max_concurrent_chunks {
         Some(0) => None,
         Some(max) => Some(max),
         None => Some(4),
     };
+    let request_jitter = if polite {
+        max_concurrent_requests = max_concurrent_requests.min(POLITE_MAX_CONCURRENT_REQUESTS);
+        max_concurrent_chunks = Some(1);
+        Some(POLITE_REQUEST_JITTER)
+    } else {
+        None
+    };
 
     let semaphore = tokio::sync::Semaphore::new(max_concurrent_requests as usize);
     let semaphore = Arc::new(Some(semaphore));
@@ -56,10 +117,17 @@ pub(crate) async fn parse_source(args: &Args) -> Result<Source, ParseError> {
         max_concurrent_chunks,
         semaphore,
         rate_limiter: rate_limiter.into(),
+        shared_rate_limiter,
+        request_jitter,
+        throttled_until,
+        block_receipts_support: Arc::new(std::sync::Mutex::new(None)),
         rpc_url,
         provider,
         labels: SourceLabels {
-            max_concurrent_requests: args.max_concurrent_requests,
+            max_concurrent_requests: match polite {
+                true => Some(max_concurrent_requests),
+                false => args.max_concurrent_requests,
+            },
             max_requests_per_second: args.requests_per_second.map(|x| x as u64),
             max_retries: Some(args.max_retries),
             initial_backoff: Some(args.initial_backoff),
@@ -69,6 +137,27 @@ pub(crate) async fn parse_source(args: &Args) -> Result<Source, ParseError> {
     Ok(output)
 }
 
+/// whether requests should use the polite profile for free public rpc endpoints
+fn parse_polite_profile(args: &Args) -> Result<bool, ParseError> {
+    match args.profile.as_deref() {
+        None | Some("default") => Ok(false),
+        Some("polite") => Ok(true),
+        Some(profile) => Err(ParseError::ParseError(format!(
+            "invalid profile: {}, options are default and polite",
+            profile
+        ))),
+    }
+}
+
+/// create a source answering every request from a fixture file recorded with --record-fixtures
+async fn parse_replay_source(path: &str) -> Result<Source, ParseError> {
+    let fixtures = RpcFixtures::load(std::path::Path::new(path))
+        .map_err(|e| ParseError::ParseError(format!("could not load fixtures: {}", e)))?;
+    let mut source = fixtures.into_source(0);
+    source.chain_id = source.provider.get_chain_id().await.map_err(ParseError::ProviderError)?;
+    Ok(source)
+}
+
 pub(crate) fn parse_rpc_url(args: &Args) -> Result<String, ParseError> {
     // get MESC url
     let mesc_url = if mesc::is_mesc_enabled() {
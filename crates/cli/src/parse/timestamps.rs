@@ -3,7 +3,10 @@ use cryo_freeze::{BlockChunk, ParseError, Source};
 use polars::prelude::*;
 
 use crate::{
-    parse::blocks::{block_range_to_block_chunk, postprocess_block_chunks},
+    parse::{
+        block_cache::BlockCache,
+        blocks::{block_range_to_block_chunk, postprocess_block_chunks},
+    },
     Args,
 };
 
@@ -46,11 +49,18 @@ pub(crate) async fn parse_timestamps(
 
     let explicit_chunks = if !explicit_numbers.is_empty() {
         // parse inputs into BlockChunks
+        let cryo_dir = std::path::Path::new(&args.output_dir).join(".cryo");
+        let cache = BlockCache::load(&cryo_dir, source.chain_id);
+        cache.verify(&source).await?;
         let mut block_chunks = Vec::new();
         for explicit_number in explicit_numbers {
-            let outputs = parse_timestamp_inputs(explicit_number, source.clone()).await?;
+            let outputs = parse_timestamp_inputs(explicit_number, source.clone(), &cache).await?;
             block_chunks.extend(outputs);
         }
+        // the cache only saves refetching, so failing to write it should not fail the run
+        if let Err(e) = cache.save() {
+            eprintln!("could not save block cache: {}", e);
+        }
         postprocess_block_chunks(block_chunks, args, source).await?
     } else {
         Vec::new()
@@ -110,6 +120,7 @@ fn read_integer_column(path: &str, column: &str) -> Result<Vec<u64>, ParseError>
 async fn parse_timestamp_inputs(
     inputs: &str,
     source: Arc<Source>,
+    cache: &BlockCache,
 ) -> Result<Vec<BlockChunk>, ParseError> {
     let parts: Vec<&str> = inputs.split(' ').collect();
     match parts.len() {
@@ -117,12 +128,12 @@ async fn parse_timestamp_inputs(
             let first_input = parts.first().ok_or_else(|| {
                 ParseError::ParseError("Failed to get the first input".to_string())
             })?;
-            parse_timestamp_token(first_input, true, source).await.map(|x| vec![x])
+            parse_timestamp_token(first_input, true, source, cache).await.map(|x| vec![x])
         }
         _ => {
             let mut chunks = Vec::new();
             for part in parts {
-                chunks.push(parse_timestamp_token(part, false, source.clone()).await?);
+                chunks.push(parse_timestamp_token(part, false, source.clone(), cache).await?);
             }
             Ok(chunks)
         }
@@ -138,6 +149,7 @@ async fn parse_timestamp_token(
     s: &str,
     as_range: bool,
     source: Arc<Source>,
+    cache: &BlockCache,
 ) -> Result<BlockChunk, ParseError> {
     let s = s.replace('_', "");
 
@@ -146,7 +158,7 @@ async fn parse_timestamp_token(
         [timestamp_ref] => {
             let timestamp =
                 parse_timestamp_number(timestamp_ref, RangePosition::None, source.clone()).await?;
-            let block = timestamp_to_block_number(timestamp, source).await?;
+            let block = timestamp_to_block_number(timestamp, source, cache).await?;
 
             Ok(BlockChunk::Numbers(vec![block]))
         }
@@ -164,8 +176,8 @@ async fn parse_timestamp_token(
             let (start_timestamp, end_timestamp) =
                 parse_timestamp_range(first_ref, second_ref, source.clone()).await?;
             let (start_block, end_block) = (
-                timestamp_to_block_number(start_timestamp, source.clone()).await?,
-                timestamp_to_block_number(end_timestamp, source).await?,
+                timestamp_to_block_number(start_timestamp, source.clone(), cache).await?,
+                timestamp_to_block_number(end_timestamp, source, cache).await?,
             );
             block_range_to_block_chunk(start_block, end_block, as_range, None, n_keep)
         }
@@ -271,30 +283,26 @@ fn scale_timestamp_str_by_metric_unit(
 
 // perform binary search to determine the closest block number smaller than or equal to a given
 // timestamp
-async fn timestamp_to_block_number(timestamp: u64, source: Arc<Source>) -> Result<u64, ParseError> {
+async fn timestamp_to_block_number(
+    timestamp: u64,
+    source: Arc<Source>,
+    cache: &BlockCache,
+) -> Result<u64, ParseError> {
     let latest_block_number = get_latest_block_number(source.clone()).await?;
 
     let mut l = 0;
     let mut r = latest_block_number;
     let mut mid = (l + r) / 2;
-    let mut block = source
-        .get_block(mid, BlockTransactionsKind::Hashes)
-        .await
-        .map_err(|_e| ParseError::ParseError("Error fetching block for timestamp".to_string()))?
-        .unwrap();
+    let mut block_timestamp = cache.get_timestamp(mid, latest_block_number, &source).await?;
 
     while l <= r {
         mid = (l + r) / 2;
-        block = source
-            .get_block(mid, BlockTransactionsKind::Hashes)
-            .await
-            .map_err(|_e| ParseError::ParseError("Error fetching block for timestamp".to_string()))?
-            .unwrap();
+        block_timestamp = cache.get_timestamp(mid, latest_block_number, &source).await?;
 
         #[allow(clippy::comparison_chain)]
-        if block.header.timestamp == timestamp {
+        if block_timestamp == timestamp {
             return Ok(mid);
-        } else if block.header.timestamp < timestamp {
+        } else if block_timestamp < timestamp {
             l = mid + 1;
         } else {
             r = mid - 1;
@@ -302,7 +310,7 @@ async fn timestamp_to_block_number(timestamp: u64, source: Arc<Source>) -> Resul
     }
 
     // If timestamp is between two different blocks, return the lower block.
-    if mid > 0 && block.header.timestamp > timestamp {
+    if mid > 0 && block_timestamp > timestamp {
         Ok(mid - 1)
     } else {
         Ok(mid)
@@ -364,6 +372,10 @@ mod tests {
             provider,
             semaphore: Arc::new(Some(semaphore)),
             rate_limiter: Arc::new(rate_limiter),
+            shared_rate_limiter: None,
+            request_jitter: None,
+            throttled_until: Arc::new(std::sync::Mutex::new(None)),
+            block_receipts_support: Arc::new(std::sync::Mutex::new(None)),
             chain_id: 1,
             inner_request_size: 1,
             max_concurrent_chunks: None,
@@ -376,15 +388,17 @@ mod tests {
     async fn test_extrema_timestamp_to_block_number() {
         let source = setup_source().await;
         let source = Arc::new(source);
+        let cache = BlockCache::default();
 
         // Before genesis block
-        assert!(timestamp_to_block_number(1438260000, source).await.unwrap() == 0);
+        assert!(timestamp_to_block_number(1438260000, source, &cache).await.unwrap() == 0);
     }
 
     #[tokio::test]
     async fn test_latest_timestamp_to_block_number() {
         let source = setup_source().await;
         let source = Arc::new(source);
+        let cache = BlockCache::default();
         let latest_block_number = get_latest_block_number(source.clone()).await.unwrap();
         let latest_block = source
             .get_block(latest_block_number, BlockTransactionsKind::Hashes)
@@ -394,7 +408,7 @@ mod tests {
         let latest_timestamp = latest_block.header.timestamp;
 
         assert_eq!(
-            timestamp_to_block_number(latest_timestamp, source).await.unwrap(),
+            timestamp_to_block_number(latest_timestamp, source, &cache).await.unwrap(),
             latest_block_number
         );
     }
@@ -403,22 +417,33 @@ mod tests {
     async fn test_timestamp_between_blocks() {
         let source = setup_source().await;
         let source = Arc::new(source);
+        let cache = BlockCache::default();
 
         // Block 1000, and the timestamp surrounding block 1020
-        assert!(timestamp_to_block_number(1438272177, source.clone()).await.unwrap() == 1020);
-        assert!(timestamp_to_block_number(1438272178, source.clone()).await.unwrap() == 1020);
+        assert!(
+            timestamp_to_block_number(1438272177, source.clone(), &cache).await.unwrap() == 1020
+        );
+        assert!(
+            timestamp_to_block_number(1438272178, source.clone(), &cache).await.unwrap() == 1020
+        );
 
         // Timestamp 1438272176 is 1 seconds after block 1019 and 1 second before block 1020. Lower
         // block is returned
-        assert!(timestamp_to_block_number(1438272176, source.clone()).await.unwrap() == 1019);
+        assert!(
+            timestamp_to_block_number(1438272176, source.clone(), &cache).await.unwrap() == 1019
+        );
 
         // Timestamp 1438272187 is 1 seconds after block 1024 and 1 second before block 1025. Lower
         // block is returned
-        assert!(timestamp_to_block_number(1438272187, source.clone()).await.unwrap() == 1024);
+        assert!(
+            timestamp_to_block_number(1438272187, source.clone(), &cache).await.unwrap() == 1024
+        );
 
         // Timestamp 1438272169 is 4 seconds after block 1016 and 4 seconds before block 1017. Lower
         // block is returned
-        assert!(timestamp_to_block_number(1438272169, source.clone()).await.unwrap() == 1016);
+        assert!(
+            timestamp_to_block_number(1438272169, source.clone(), &cache).await.unwrap() == 1016
+        );
     }
 
     #[tokio::test]
@@ -481,13 +506,14 @@ mod tests {
     async fn test_parse_timestamp_range_to_block_number_range() {
         let source = setup_source().await;
         let source = Arc::new(source);
+        let cache = BlockCache::default();
 
         let (start_timestamp, end_timestamp) =
             parse_timestamp_range("1700000000", "1700000015", source.clone()).await.unwrap();
         assert_eq!(
             (
-                timestamp_to_block_number(start_timestamp, source.clone()).await.unwrap(),
-                timestamp_to_block_number(end_timestamp, source.clone()).await.unwrap()
+                timestamp_to_block_number(start_timestamp, source.clone(), &cache).await.unwrap(),
+                timestamp_to_block_number(end_timestamp, source.clone(), &cache).await.unwrap()
             ),
             (18573050, 18573051)
         );
@@ -496,8 +522,8 @@ mod tests {
             parse_timestamp_range("-15", "1700000015", source.clone()).await.unwrap();
         assert_eq!(
             (
-                timestamp_to_block_number(start_timestamp, source.clone()).await.unwrap(),
-                timestamp_to_block_number(end_timestamp, source.clone()).await.unwrap()
+                timestamp_to_block_number(start_timestamp, source.clone(), &cache).await.unwrap(),
+                timestamp_to_block_number(end_timestamp, source.clone(), &cache).await.unwrap()
             ),
             (18573050, 18573052)
         );
@@ -506,8 +532,8 @@ mod tests {
             parse_timestamp_range("1700000000", "+15", source.clone()).await.unwrap();
         assert_eq!(
             (
-                timestamp_to_block_number(start_timestamp, source.clone()).await.unwrap(),
-                timestamp_to_block_number(end_timestamp, source.clone()).await.unwrap()
+                timestamp_to_block_number(start_timestamp, source.clone(), &cache).await.unwrap(),
+                timestamp_to_block_number(end_timestamp, source.clone(), &cache).await.unwrap()
             ),
             (18573050, 18573051)
         );
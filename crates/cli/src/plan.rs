@@ -0,0 +1,99 @@
+//! chunk planning for external orchestrators
+//!
+//! A plan lists the chunks that a cryo command would collect and the files each chunk writes,
+//! using the same range parsing, partitioning, alignment, and file naming as a real run. An
+//! orchestrator can compute the plan once, distribute the chunks across machines, and run cryo
+//! on each chunk by passing its `blocks_args` as `--blocks`. Files are named deterministically,
+//! so every machine writes exactly the paths listed in the plan.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), cryo_freeze::CollectError> {
+//! let args = cryo_cli::parse_str("cryo blocks -b 18M:19M --chunk-size 100000").await?;
+//! for chunk in cryo_cli::plan::plan_chunks(&args).await? {
+//!     println!("{} {:?}", chunk.label, chunk.blocks_args);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{parse, Args};
+use cryo_freeze::{BlockChunk, CollectError, FileOutput, Partition, Query};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// a chunk of a query and the files it writes
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ChunkPlan {
+    /// label of the chunk, as used in its file names
+    pub label: String,
+    /// first and last block of the chunk, if it is partitioned by block
+    pub block_range: Option<(u64, u64)>,
+    /// `--blocks` arguments that collect exactly the blocks of the chunk
+    pub blocks_args: Option<Vec<String>>,
+    /// output path of each datatype
+    pub paths: BTreeMap<String, PathBuf>,
+}
+
+/// plan the chunks of a cryo command without collecting data or creating files
+///
+/// Resolving block references such as `latest` requires the rpc endpoint of the command.
+pub async fn plan_chunks(args: &Args) -> Result<Vec<ChunkPlan>, CollectError> {
+    let (query, _source, sink, _env) = parse::parse_args(args).await?;
+    plan_query_chunks(&query, &sink)
+}
+
+/// plan the chunks of a parsed query written to sink
+pub fn plan_query_chunks(query: &Query, sink: &FileOutput) -> Result<Vec<ChunkPlan>, CollectError> {
+    query.partitions.iter().map(|partition| plan_partition(query, sink, partition)).collect()
+}
+
+fn plan_partition(
+    query: &Query,
+    sink: &FileOutput,
+    partition: &Partition,
+) -> Result<ChunkPlan, CollectError> {
+    let mut paths = BTreeMap::new();
+    for meta_datatype in query.datatypes.iter() {
+        for datatype in meta_datatype.datatypes().into_iter() {
+            if query.schemas.contains_key(&datatype) {
+                paths.insert(datatype.name(), sink.output_path(query, partition, datatype)?);
+            }
+        }
+    }
+    let block_chunks = partition.block_numbers.as_deref().unwrap_or_default();
+    let block_range = block_chunks
+        .iter()
+        .filter_map(|chunk| chunk.bounds())
+        .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)));
+    let blocks_args = match block_chunks.is_empty() {
+        true => None,
+        false => Some(block_chunks.iter().flat_map(chunk_blocks_args).collect()),
+    };
+    Ok(ChunkPlan {
+        label: partition.label(&query.partitioned_by)?,
+        block_range,
+        blocks_args,
+        paths,
+    })
+}
+
+/// `--blocks` arguments selecting the blocks of a chunk, block ranges exclude their end
+fn chunk_blocks_args(chunk: &BlockChunk) -> Vec<String> {
+    match chunk {
+        BlockChunk::Range(start, end) => vec![format!("{}:{}", start, end + 1)],
+        BlockChunk::Numbers(numbers) => numbers.iter().map(|n| n.to_string()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_blocks_args() {
+        assert_eq!(chunk_blocks_args(&BlockChunk::Range(100, 199)), vec!["100:200".to_string()]);
+        assert_eq!(
+            chunk_blocks_args(&BlockChunk::Numbers(vec![5, 7])),
+            vec!["5".to_string(), "7".to_string()]
+        );
+    }
+}
@@ -0,0 +1,421 @@
+// a file-based work queue distributes the chunks of a command across worker processes
+// - `cryo <DATASETS> ... --enqueue <QUEUE_DIR>` plans the chunks of a command and enqueues them
+// - `cryo worker <QUEUE_DIR>` claims chunks one at a time and freezes them until none remain
+// - the queue directory must be shared by all workers, e.g. on a network filesystem
+// - jobs move between pending/, claimed/, done/, and failed/ using atomic renames, and job files
+//   are rewritten by renaming a temporary file over them so that no reader sees a partial job
+// - a worker renews the lease on its claimed job with a heartbeat, jobs whose lease expires are
+//   returned to pending/ so that chunks of crashed workers are retried by other workers
+// - each claim writes a token to the job, a worker only renews or finishes a job that still holds
+//   its token, so a worker whose lease expired cannot touch the claim of another worker
+// - jobs that fail MAX_ATTEMPTS times are moved to failed/
+
+use crate::{args::Args, plan, run};
+use cryo_freeze::{err, CollectError, FreezeSummary};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const COMMAND_FILENAME: &str = "command.json";
+const PENDING: &str = "pending";
+const CLAIMED: &str = "claimed";
+const DONE: &str = "done";
+const FAILED: &str = "failed";
+
+/// seconds after its last heartbeat that a claimed job is considered abandoned
+const LEASE_SECONDS: u64 = 300;
+
+/// seconds between heartbeats of a worker, and between polls of a worker waiting for jobs
+const HEARTBEAT_SECONDS: u64 = 30;
+
+/// number of failed attempts after which a job is moved to failed/
+const MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct QueuedCommand {
+    cryo_version: String,
+    args: Args,
+}
+
+/// a chunk of the queued command
+#[derive(Serialize, Deserialize)]
+struct Job {
+    label: String,
+    blocks: Vec<String>,
+    /// first and last block of the chunk
+    #[serde(default)]
+    block_range: Option<(u64, u64)>,
+    attempts: u32,
+    worker: Option<String>,
+    heartbeat: Option<u64>,
+    /// number of times the job has been claimed
+    #[serde(default)]
+    claims: u32,
+    /// token of the current claim, the claiming worker and its claim number
+    #[serde(default)]
+    claim: Option<String>,
+}
+
+/// Plan the chunks of a command and write them to a queue directory for workers to claim.
+pub(crate) async fn enqueue(args: Args) -> Result<Option<FreezeSummary>, CollectError> {
+    let queue_dir = PathBuf::from(args.enqueue.clone().ok_or(err("missing --enqueue"))?);
+    let args = Args { enqueue: None, remember: false, ..args };
+    let chunks = plan::plan_chunks(&args).await?;
+
+    for subdir in [PENDING, CLAIMED, DONE, FAILED] {
+        std::fs::create_dir_all(queue_dir.join(subdir))
+            .map_err(|_| err("could not create queue directory"))?;
+    }
+    let command = QueuedCommand { cryo_version: cryo_freeze::CRYO_VERSION.to_string(), args };
+    write_json(&queue_dir.join(COMMAND_FILENAME), &command)?;
+
+    for chunk in chunks.iter() {
+        let blocks = chunk
+            .blocks_args
+            .clone()
+            .ok_or(err("work queues only support commands partitioned by block"))?;
+        let job = Job {
+            label: chunk.label.clone(),
+            blocks,
+            block_range: chunk.block_range,
+            attempts: 0,
+            worker: None,
+            heartbeat: None,
+            claims: 0,
+            claim: None,
+        };
+        write_json(&job_path(&queue_dir, PENDING, &chunk.label), &job)?;
+    }
+    println!("enqueued {} chunks in {}", chunks.len(), queue_dir.display());
+    Ok(None)
+}
+
+/// Claim and freeze jobs of a queue directory until no pending or claimed jobs remain.
+pub(crate) async fn run_worker(args: Args) -> Result<Option<FreezeSummary>, CollectError> {
+    let queue_dir = match &args.datatype[1..] {
+        [queue_dir] => PathBuf::from(queue_dir),
+        _ => return Err(err("usage: cryo worker <QUEUE_DIR>")),
+    };
+    let command: QueuedCommand = read_json(&queue_dir.join(COMMAND_FILENAME))?;
+    if command.cryo_version != cryo_freeze::CRYO_VERSION {
+        eprintln!("queued command comes from a different Cryo version, proceed with caution\n");
+    }
+    let worker = format!(
+        "{}-{}",
+        std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string()),
+        std::process::id()
+    );
+
+    loop {
+        requeue_expired_jobs(&queue_dir)?;
+        let (label, job) = match claim_next_job(&queue_dir, &worker)? {
+            Some(claimed) => claimed,
+            None if list_labels(&queue_dir, CLAIMED)?.is_empty() => break,
+            None => {
+                // other workers hold leases, wait in case their jobs are returned to the queue
+                tokio::time::sleep(Duration::from_secs(HEARTBEAT_SECONDS)).await;
+                continue
+            }
+        };
+
+        println!("{} claimed chunk {}", worker, label);
+        let heartbeat =
+            tokio::spawn(send_heartbeats(job_path(&queue_dir, CLAIMED, &label), job.claim.clone()));
+        let result = run::run_freeze_process(job_args(&command.args, &job)).await;
+        heartbeat.abort();
+
+        match result {
+            Ok(Some(summary)) if !summary.errored.is_empty() => {
+                release_job(&queue_dir, &label, job, "chunk errored")?
+            }
+            Ok(_) => {
+                let finished = holds_claim(&queue_dir, &label, &job.claim) &&
+                    move_job(&queue_dir, &label, CLAIMED, DONE).is_ok();
+                if !finished {
+                    // the lease expired while freezing and the job was requeued or reclaimed
+                    eprintln!("chunk {} completed after its lease expired", label);
+                }
+            }
+            Err(e) => release_job(&queue_dir, &label, job, &e.to_string())?,
+        }
+    }
+    println!("{} found no remaining chunks in {}", worker, queue_dir.display());
+    Ok(None)
+}
+
+/// args that freeze exactly the chunk of a job as a single chunk
+fn job_args(args: &Args, job: &Job) -> Args {
+    let chunk_size = match job.block_range {
+        Some((start, end)) => end - start + 1,
+        None => args.chunk_size,
+    };
+    Args {
+        blocks: Some(job.blocks.clone()),
+        chunk_size,
+        n_chunks: None,
+        align: false,
+        ..args.clone()
+    }
+}
+
+/// claim the first pending job
+///
+/// the job is renamed to a claiming file named after the claim time, renames are atomic so each
+/// job is claimed by a single worker, and it is listed in claimed/ only once it is written there
+/// with its heartbeat
+fn claim_next_job(queue_dir: &Path, worker: &str) -> Result<Option<(String, Job)>, CollectError> {
+    for label in list_labels(queue_dir, PENDING)? {
+        let path = claiming_path(queue_dir, &label, now(), worker);
+        if std::fs::rename(job_path(queue_dir, PENDING, &label), &path).is_err() {
+            // claimed by another worker
+            continue
+        }
+        let mut job: Job = read_json(&path)?;
+        job.worker = Some(worker.to_string());
+        job.heartbeat = Some(now());
+        job.claims += 1;
+        job.claim = Some(format!("{}:{}", worker, job.claims));
+        write_json(&job_path(queue_dir, CLAIMED, &label), &job)?;
+        let _ = std::fs::remove_file(&path);
+        return Ok(Some((label, job)))
+    }
+    Ok(None)
+}
+
+/// path of a job being claimed, which is not listed as a claimed job
+fn claiming_path(queue_dir: &Path, label: &str, timestamp: u64, worker: &str) -> PathBuf {
+    queue_dir.join(CLAIMED).join(format!("{}.claiming.{}.{}", label, timestamp, worker))
+}
+
+/// renew the lease of a claimed job until the task is aborted or the claim is lost
+async fn send_heartbeats(path: PathBuf, claim: Option<String>) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(HEARTBEAT_SECONDS)).await;
+        match read_json::<Job>(&path) {
+            Ok(mut job) if job.claim == claim => {
+                job.heartbeat = Some(now());
+                let _ = write_json(&path, &job);
+            }
+            // the lease expired and the job was requeued or claimed by another worker
+            _ => return,
+        }
+    }
+}
+
+/// whether the claimed job with label still holds the claim token of a worker
+fn holds_claim(queue_dir: &Path, label: &str, claim: &Option<String>) -> bool {
+    read_json::<Job>(&job_path(queue_dir, CLAIMED, label)).is_ok_and(|job| job.claim == *claim)
+}
+
+/// return claimed jobs whose lease expired to pending/
+fn requeue_expired_jobs(queue_dir: &Path) -> Result<(), CollectError> {
+    // jobs whose worker stopped while claiming them
+    for (label, timestamp, path) in list_claiming(queue_dir)? {
+        if now().saturating_sub(timestamp) <= LEASE_SECONDS {
+            continue
+        }
+        if job_path(queue_dir, CLAIMED, &label).exists() {
+            // the claim was written but its claiming file was not removed
+            let _ = std::fs::remove_file(&path);
+        } else {
+            println!("claim of chunk {} was not completed, returning it to the queue", label);
+            let _ = std::fs::rename(&path, job_path(queue_dir, PENDING, &label));
+        }
+    }
+
+    for label in list_labels(queue_dir, CLAIMED)? {
+        let path = job_path(queue_dir, CLAIMED, &label);
+        let heartbeat = match read_json::<Job>(&path) {
+            Ok(Job { heartbeat: Some(heartbeat), .. }) => heartbeat,
+            // jobs claimed by older versions are written before their heartbeat
+            Ok(Job { heartbeat: None, .. }) => modified_time(&path).unwrap_or_else(now),
+            // the job was moved by another worker
+            Err(_) => continue,
+        };
+        if now().saturating_sub(heartbeat) > LEASE_SECONDS {
+            println!("lease of chunk {} expired, returning it to the queue", label);
+            // another worker may requeue the same job first
+            let _ = move_job(queue_dir, &label, CLAIMED, PENDING);
+        }
+    }
+    Ok(())
+}
+
+/// return a failed job to pending/, or move it to failed/ after MAX_ATTEMPTS
+fn release_job(queue_dir: &Path, label: &str, job: Job, error: &str) -> Result<(), CollectError> {
+    if !holds_claim(queue_dir, label, &job.claim) {
+        eprintln!("chunk {} failed after its lease expired: {}", label, error);
+        return Ok(())
+    }
+    // the heartbeat is kept so that the job still expires if the worker stops before moving it
+    let job = Job { attempts: job.attempts + 1, worker: None, claim: None, ..job };
+    eprintln!("chunk {} failed (attempt {}): {}", label, job.attempts, error);
+    write_json(&job_path(queue_dir, CLAIMED, label), &job)?;
+    let destination = if job.attempts >= MAX_ATTEMPTS { FAILED } else { PENDING };
+    move_job(queue_dir, label, CLAIMED, destination)
+}
+
+fn move_job(queue_dir: &Path, label: &str, from: &str, to: &str) -> Result<(), CollectError> {
+    std::fs::rename(job_path(queue_dir, from, label), job_path(queue_dir, to, label)).map_err(
+        |_| err(format!("could not move chunk {} from {} to {}", label, from, to).as_str()),
+    )
+}
+
+/// labels of the jobs in a subdirectory of the queue, in order
+fn list_labels(queue_dir: &Path, subdir: &str) -> Result<Vec<String>, CollectError> {
+    let entries = std::fs::read_dir(queue_dir.join(subdir))
+        .map_err(|_| err(format!("could not read queue directory {}", subdir).as_str()))?;
+    let mut labels: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            entry.file_name().to_str().and_then(|name| name.strip_suffix(".json")).map(String::from)
+        })
+        .collect();
+    labels.sort();
+    Ok(labels)
+}
+
+/// label, claim time, and path of the jobs being claimed
+fn list_claiming(queue_dir: &Path) -> Result<Vec<(String, u64, PathBuf)>, CollectError> {
+    let entries = std::fs::read_dir(queue_dir.join(CLAIMED))
+        .map_err(|_| err("could not read queue directory claimed"))?;
+    let mut claiming = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some((label, rest)) = name.split_once(".claiming.") {
+            if let Some(timestamp) = rest.split('.').next().and_then(|t| t.parse().ok()) {
+                claiming.push((label.to_string(), timestamp, entry.path()));
+            }
+        }
+    }
+    Ok(claiming)
+}
+
+fn job_path(queue_dir: &Path, subdir: &str, label: &str) -> PathBuf {
+    queue_dir.join(subdir).join(format!("{}.json", label))
+}
+
+/// modification time of a file in seconds since the epoch
+fn modified_time(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).ok()
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, CollectError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|_| err(format!("could not read {}", path.display()).as_str()))?;
+    serde_json::from_str(&contents)
+        .map_err(|_| err(format!("could not parse {}", path.display()).as_str()))
+}
+
+/// write a queue file by renaming a temporary file over it, so that readers of the queue file
+/// never see a partially written file
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), CollectError> {
+    let json = serde_json::to_string(value).map_err(|_| err("could not serialize queue file"))?;
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp.{}", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+    std::fs::write(&tmp_path, json)
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|_| err(format!("could not write {}", path.display()).as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_and_release_job() {
+        let queue_dir = std::env::temp_dir().join(format!("cryo_queue_test_{}", now()));
+        for subdir in [PENDING, CLAIMED, DONE, FAILED] {
+            std::fs::create_dir_all(queue_dir.join(subdir)).unwrap();
+        }
+        let job = Job {
+            label: "00000000_to_00000999".to_string(),
+            blocks: vec!["0:1000".to_string()],
+            block_range: Some((0, 999)),
+            attempts: MAX_ATTEMPTS - 1,
+            worker: None,
+            heartbeat: None,
+            claims: 0,
+            claim: None,
+        };
+        write_json(&job_path(&queue_dir, PENDING, &job.label), &job).unwrap();
+
+        let (label, job) = claim_next_job(&queue_dir, "a").unwrap().unwrap();
+        assert_eq!(job.worker, Some("a".to_string()));
+        assert!(claim_next_job(&queue_dir, "b").unwrap().is_none());
+
+        // the lease of a expires and b claims the job, a can no longer release it
+        move_job(&queue_dir, &label, CLAIMED, PENDING).unwrap();
+        let (_, other_job) = claim_next_job(&queue_dir, "b").unwrap().unwrap();
+        assert!(!holds_claim(&queue_dir, &label, &job.claim));
+        release_job(&queue_dir, &label, job, "error").unwrap();
+        assert_eq!(list_labels(&queue_dir, CLAIMED).unwrap(), vec![label.clone()]);
+
+        release_job(&queue_dir, &label, other_job, "error").unwrap();
+        assert_eq!(list_labels(&queue_dir, FAILED).unwrap(), vec![label]);
+        std::fs::remove_dir_all(queue_dir).unwrap();
+    }
+
+    #[test]
+    fn test_claimed_jobs_hold_their_lease() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let queue_dir = tmp_dir.path();
+        for subdir in [PENDING, CLAIMED, DONE, FAILED] {
+            std::fs::create_dir_all(queue_dir.join(subdir)).unwrap();
+        }
+        for label in ["a", "b"] {
+            let job = Job {
+                label: label.to_string(),
+                blocks: vec!["0:1000".to_string()],
+                block_range: Some((0, 999)),
+                attempts: 0,
+                worker: None,
+                heartbeat: None,
+                claims: 0,
+                claim: None,
+            };
+            write_json(&job_path(queue_dir, PENDING, label), &job).unwrap();
+        }
+
+        // a freshly claimed job is written with its heartbeat and is not expired
+        let (label, _) = claim_next_job(queue_dir, "w").unwrap().unwrap();
+        requeue_expired_jobs(queue_dir).unwrap();
+        assert_eq!(list_labels(queue_dir, CLAIMED).unwrap(), vec![label]);
+
+        // a claim that was never completed is returned to the queue once its lease expires
+        let stale = claiming_path(queue_dir, "b", now() - LEASE_SECONDS - 1, "w");
+        std::fs::rename(job_path(queue_dir, PENDING, "b"), stale).unwrap();
+        assert!(list_labels(queue_dir, PENDING).unwrap().is_empty());
+        requeue_expired_jobs(queue_dir).unwrap();
+        assert_eq!(list_labels(queue_dir, PENDING).unwrap(), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_job_args_freeze_a_single_chunk() {
+        use clap_cryo::Parser;
+        let args = Args::parse_from(["cryo", "blocks", "--n-chunks", "7", "--align"]);
+        let job = Job {
+            label: "00001000_to_00001499".to_string(),
+            blocks: vec!["1000:1500".to_string()],
+            block_range: Some((1000, 1499)),
+            attempts: 0,
+            worker: None,
+            heartbeat: None,
+            claims: 0,
+            claim: None,
+        };
+        let args = job_args(&args, &job);
+        assert_eq!(args.blocks, Some(vec!["1000:1500".to_string()]));
+        assert_eq!(args.chunk_size, 500);
+        assert_eq!(args.n_chunks, None);
+        assert!(!args.align);
+    }
+}
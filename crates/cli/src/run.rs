@@ -1,9 +1,12 @@
-use crate::{args, parse, remember};
+use crate::{args, compare, diff, parse, queue, remember, sql, wizard};
 use clap_cryo::Parser;
 use color_print::cstr;
 use colored::Colorize;
-use cryo_freeze::{err, CollectError, ExecutionEnv, FreezeSummary};
-use std::{sync::Arc, time::SystemTime};
+use cryo_freeze::{
+    err, CollectError, Datatype, ExecutionEnv, FileOutput, FreezeSummary, MetaDatatype, Query,
+    SchemaFormat, Source,
+};
+use std::{str::FromStr, sync::Arc, time::SystemTime};
 
 /// Entry point to run the CLI application.
 pub async fn run(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
@@ -11,6 +14,30 @@ pub async fn run(args: args::Args) -> Result<Option<FreezeSummary>, CollectError
         return handle_help_subcommands(args);
     }
 
+    if is_sql_command(&args) {
+        return sql::run_sql_query(args);
+    }
+
+    if is_schema_command(&args) {
+        return handle_schema_subcommand(args);
+    }
+
+    if is_wizard_command(&args) {
+        return wizard::run_wizard(args).await;
+    }
+
+    if is_compare_providers_command(&args) {
+        return compare::run_compare_providers(args).await;
+    }
+
+    if is_diff_command(&args) {
+        return diff::run_diff(args);
+    }
+
+    if is_worker_command(&args) {
+        return queue::run_worker(args).await;
+    }
+
     let cryo_dir = build_cryo_directory(std::path::Path::new(&args.output_dir));
 
     let args =
@@ -21,6 +48,10 @@ pub async fn run(args: args::Args) -> Result<Option<FreezeSummary>, CollectError
         remember::save_remembered_command(cryo_dir, &args)?;
     }
 
+    if args.enqueue.is_some() {
+        return queue::enqueue(args).await;
+    }
+
     // handle regular flow
     run_freeze_process(args).await
 }
@@ -30,6 +61,36 @@ fn is_help_command(args: &args::Args) -> bool {
     args.datatype.first() == Some(&"help".to_string())
 }
 
+/// Check if the command is a sql query command.
+fn is_sql_command(args: &args::Args) -> bool {
+    args.datatype.first() == Some(&"query".to_string())
+}
+
+/// Check if the command is a schema export command.
+fn is_schema_command(args: &args::Args) -> bool {
+    args.datatype.first() == Some(&"schema".to_string())
+}
+
+/// Check if the command is an interactive wizard command.
+fn is_wizard_command(args: &args::Args) -> bool {
+    args.datatype.first() == Some(&"wizard".to_string())
+}
+
+/// Check if the command is a provider comparison command.
+fn is_compare_providers_command(args: &args::Args) -> bool {
+    args.datatype.first() == Some(&"compare-providers".to_string())
+}
+
+/// Check if the command is an output directory diff command.
+fn is_diff_command(args: &args::Args) -> bool {
+    args.datatype.first() == Some(&"diff".to_string())
+}
+
+/// Check if the command is a work queue worker command.
+fn is_worker_command(args: &args::Args) -> bool {
+    args.datatype.first() == Some(&"worker".to_string())
+}
+
 /// Build the cryo directory path.
 fn build_cryo_directory(output_dir: &std::path::Path) -> std::path::PathBuf {
     output_dir.join(".cryo")
@@ -62,16 +123,133 @@ fn print_remembered_command(command: &[String]) {
 }
 
 /// Run the main freezing process with the provided arguments.
-async fn run_freeze_process(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
+pub(crate) async fn run_freeze_process(
+    args: args::Args,
+) -> Result<Option<FreezeSummary>, CollectError> {
     let t_start_parse = Some(SystemTime::now());
     let (query, source, sink, env) = parse::parse_args(&args).await?;
 
     let source = Arc::new(source);
     let env = ExecutionEnv { t_start_parse, ..env }.set_start_time();
 
+    if query.schemas.contains_key(&Datatype::Mempool) {
+        if query.schemas.len() > 1 {
+            return Err(err("mempool is streamed and cannot be collected with other datasets"))
+        }
+        return cryo_freeze::stream_mempool(&query, source, &sink, &env).await.map(Some)
+    }
+
+    if DECODED_DATATYPES.iter().any(|datatype| query.schemas.contains_key(datatype)) {
+        return freeze_decoded_datatypes(&args, query, &source, sink, &env).await
+    }
+
     cryo_freeze::freeze(&query, &source, &sink, &env).await
 }
 
+/// Datatypes whose columns depend on a decoder, frozen once per function or event
+const DECODED_DATATYPES: [Datatype; 2] = [Datatype::DecodedCalls, Datatype::DecodedLogs];
+
+/// Freeze decoded datatypes with each function or event of the --abi files, each into its own
+/// files labeled by the function or event, and freeze the other datatypes of the query once.
+/// The calls of all functions and the logs of all events are fetched once per chunk.
+async fn freeze_decoded_datatypes(
+    args: &args::Args,
+    query: Query,
+    source: &Arc<Source>,
+    sink: FileOutput,
+    env: &ExecutionEnv,
+) -> Result<Option<FreezeSummary>, CollectError> {
+    let mut decoded_schemas = Vec::new();
+    if let Some(schema) = query.schemas.get(&Datatype::DecodedCalls) {
+        let decoders = parse::schemas::parse_function_decoders(args)?;
+        if decoders.is_empty() {
+            return Err(err("decoded_calls requires --abi files with at least one function"))
+        }
+        for decoder in decoders.into_iter() {
+            let mut schema = schema.clone();
+            let label = decoder.label();
+            schema.function_decoder = Some(decoder);
+            decoded_schemas.push((label, schema));
+        }
+    }
+    if let Some(schema) = query.schemas.get(&Datatype::DecodedLogs) {
+        let decoders = parse::schemas::parse_event_decoders(args)?;
+        if decoders.is_empty() {
+            return Err(err("decoded_logs requires --abi files with events or --event-signature"))
+        }
+        let events: Vec<_> = decoders.iter().map(|decoder| decoder.event.selector()).collect();
+        for decoder in decoders.into_iter() {
+            let mut schema = schema.clone();
+            let label = decoder.label();
+            schema.log_decoder = Some(decoder);
+            schema.decoded_events = events.clone();
+            decoded_schemas.push((label, schema));
+        }
+    }
+
+    let mut summary = None;
+    let datatypes: Vec<MetaDatatype> = query
+        .datatypes
+        .iter()
+        .filter(|datatype| {
+            !matches!(datatype, MetaDatatype::Scalar(scalar) if DECODED_DATATYPES.contains(scalar))
+        })
+        .cloned()
+        .collect();
+    if !datatypes.is_empty() {
+        let mut schemas = query.schemas.clone();
+        schemas.retain(|datatype, _| !DECODED_DATATYPES.contains(datatype));
+        let other_query = Query { datatypes, schemas, ..query.clone() };
+        summary = cryo_freeze::freeze(&other_query, source, &sink, env).await?;
+    }
+
+    // decoded_calls of every function share the traces fetched for each chunk, and decoded_logs
+    // of every event share the logs fetched for each chunk
+    let mut call_variants = Vec::new();
+    let mut log_variants = Vec::new();
+    for (label, schema) in decoded_schemas.into_iter() {
+        let suffix = match &sink.suffix {
+            Some(suffix) => format!("{}__{}", suffix, label),
+            None => label,
+        };
+        let datatype = schema.datatype;
+        let decoded_query = Query {
+            datatypes: vec![MetaDatatype::Scalar(datatype)],
+            schemas: [(datatype, schema)].into_iter().collect(),
+            ..query.clone()
+        };
+        let decoded_sink = FileOutput { suffix: Some(suffix), ..sink.clone() };
+        match datatype {
+            Datatype::DecodedCalls => call_variants.push((decoded_query, decoded_sink)),
+            _ => log_variants.push((decoded_query, decoded_sink)),
+        }
+    }
+    for variants in [call_variants, log_variants].into_iter().filter(|v| !v.is_empty()) {
+        let decoded_summary = cryo_freeze::freeze_variants(&variants, source, env).await?;
+        summary = merge_summaries(summary, decoded_summary);
+    }
+    Ok(summary)
+}
+
+/// Combine the summaries of several freezes.
+fn merge_summaries(a: Option<FreezeSummary>, b: Option<FreezeSummary>) -> Option<FreezeSummary> {
+    match (a, b) {
+        (Some(mut a), Some(b)) => {
+            a.completed.extend(b.completed);
+            a.skipped.extend(b.skipped);
+            a.errored.extend(b.errored);
+            a.n_rows += b.n_rows;
+            a.check_violations.extend(b.check_violations);
+            for (name, quality) in b.quality.into_iter() {
+                a.quality.entry(name).or_default().merge(quality);
+            }
+            a.split_files.extend(b.split_files);
+            Some(a)
+        }
+        (a, b) => a.or(b),
+    }
+}
+
 /// Handle help-related subcommands.
 fn handle_help_subcommands(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
     match args.datatype.len() {
@@ -100,6 +278,8 @@ fn print_syntax_help() {
 - can use ranges                     <white><bold>--blocks 12M:13M 15M:16M</bold></white>
 - can use a parquet file             <white><bold>--blocks ./path/to/file.parquet[:COLUMN_NAME]</bold></white>
 - can use multiple parquet files     <white><bold>--blocks ./path/to/files/*.parquet[:COLUMN_NAME]</bold></white>
+- can use a sparse list of blocks    <white><bold>--blocks ./path/to/blocks.csv</bold></white> or <white><bold>./path/to/blocks.txt</bold></white>
+                                     (chunked by count using <white><bold>--chunk-size</bold></white> or <white><bold>--n-chunks</bold></white>)
 - numbers can contain { _ . K M B }  <white><bold>5_000 5K 15M 15.5M</bold></white>
 - omitting range end means latest    <white><bold>15.5M:</bold></white> == <white><bold>15.5M:latest</bold></white>
 - omitting range start means 0       <white><bold>:700</bold></white> == <white><bold>0:700</bold></white>
@@ -120,7 +300,7 @@ fn print_syntax_help() {
 /// Handle detailed help by parsing schemas and printing dataset information.
 fn handle_detailed_help(args: args::Args) -> Result<(), CollectError> {
     let args = args::Args { datatype: args.datatype[1..].to_vec(), ..args };
-    let (datatypes, schemas) = super::parse::schemas::parse_schemas(&args)?;
+    let (datatypes, schemas) = super::parse::schemas::parse_schemas(&args, None)?;
 
     for datatype in datatypes.into_iter() {
         if schemas.len() > 1 {
@@ -136,6 +316,26 @@ fn handle_detailed_help(args: args::Args) -> Result<(), CollectError> {
     Ok(())
 }
 
+/// Print schemas of datasets as sql ddl, arrow schema, or json schema.
+fn handle_schema_subcommand(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
+    if args.datatype.len() < 3 {
+        return Err(err("usage: cryo schema <FORMAT> <DATASET(S)>"))
+    }
+    let format = SchemaFormat::from_str(&args.datatype[1])?;
+    let args = args::Args { datatype: args.datatype[2..].to_vec(), ..args };
+    let (datatypes, schemas) = super::parse::schemas::parse_schemas(&args, None)?;
+
+    for datatype in datatypes.into_iter() {
+        if let Some(schema) = schemas.get(&datatype) {
+            println!("{}\n", schema.export_schema(format));
+        } else {
+            return Err(err(format!("missing schema for datatype: {:?}", datatype).as_str()));
+        }
+    }
+
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
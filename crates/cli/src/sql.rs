@@ -0,0 +1,28 @@
+use crate::args;
+use cryo_freeze::{err, CollectError, FreezeSummary};
+use polars::{prelude::*, sql::SQLContext};
+
+/// Run a sql query over the datasets saved in the output directory.
+///
+/// Each datatype found in the output directory is registered as a table of the same name, e.g.
+/// `cryo query "SELECT COUNT(*) FROM transactions"`
+pub(crate) fn run_sql_query(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
+    let sql = args.datatype[1..].join(" ");
+    if sql.trim().is_empty() {
+        return Err(err("missing sql query, usage: cryo query \"<SQL>\" [-o OUTPUT_DIR]"))
+    }
+
+    let tables = cryo_freeze::find_dataset_files(std::path::Path::new(&args.output_dir))?;
+    if tables.is_empty() {
+        return Err(err(format!("no parquet datasets found in {}", args.output_dir).as_str()))
+    }
+
+    let mut context = SQLContext::new();
+    for (datatype, paths) in tables.into_iter() {
+        let lf = LazyFrame::scan_parquet_files(paths.into(), ScanArgsParquet::default())?;
+        context.register(datatype.as_str(), lf);
+    }
+    let df = context.execute(&sql)?.collect()?;
+    println!("{}", df);
+    Ok(None)
+}
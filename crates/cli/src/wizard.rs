@@ -0,0 +1,184 @@
+use crate::{args, run};
+use chrono::NaiveDate;
+use colored::Colorize;
+use cryo_freeze::{err, redact_rpc_url, CollectError, FreezeSummary};
+use std::io::Write;
+
+/// Interactively build a cryo command, show its dry run estimate, and then execute it.
+///
+/// Options given on the command line, e.g. `cryo wizard --rpc <URL> --requests-per-second 10`,
+/// are kept, and are used as the defaults of the questions they answer.
+pub(crate) async fn run_wizard(args: args::Args) -> Result<Option<FreezeSummary>, CollectError> {
+    println!("{}", "cryo wizard".green().bold());
+    println!("press enter to accept the [default] of each question\n");
+
+    let default_rpc = args.rpc.clone().or_else(|| std::env::var("ETH_RPC_URL").ok());
+    let rpc = prompt("rpc url or MESC network name", default_rpc.as_deref())?;
+    let default_datasets = match args.datatype[1..].join(" ") {
+        datasets if datasets.is_empty() => "blocks".to_string(),
+        datasets => datasets,
+    };
+    let datasets = prompt("dataset(s), see `cryo help datasets`", Some(&default_datasets))?;
+    let dates = prompt("date range as YYYY-MM-DD:YYYY-MM-DD, or blank to enter blocks", Some(""))?;
+    let (blocks, timestamps) = if dates.is_empty() {
+        let default_blocks = args.blocks.as_ref().map(|blocks| blocks.join(" "));
+        (Some(prompt("block range, see `cryo help syntax`", default_blocks.as_deref())?), None)
+    } else {
+        (None, Some(parse_date_range(&dates)?))
+    };
+    let output_dir = prompt("output directory", Some(&args.output_dir))?;
+    let default_format = match (args.csv, args.json) {
+        (true, _) => "csv",
+        (_, true) => "json",
+        _ => "parquet",
+    };
+    let format = prompt("output format (parquet, csv, json)", Some(default_format))?;
+
+    let answers = Answers { rpc, datasets, blocks, timestamps, output_dir, format };
+    println!("\n{} {}\n", "command:".truecolor(170, 170, 170), answers.command().bold());
+    let args = apply_answers(args, answers)?;
+    run::run_freeze_process(args::Args { dry: true, ..args.clone() }).await?;
+
+    let confirm = prompt("\nrun this command?", Some("y"))?;
+    if confirm.eq_ignore_ascii_case("y") || confirm.eq_ignore_ascii_case("yes") {
+        run::run_freeze_process(args).await
+    } else {
+        Ok(None)
+    }
+}
+
+/// answers to the questions of the wizard
+struct Answers {
+    rpc: String,
+    datasets: String,
+    blocks: Option<String>,
+    timestamps: Option<String>,
+    output_dir: String,
+    format: String,
+}
+
+impl Answers {
+    /// command line of the answered options
+    fn command(&self) -> String {
+        let mut command = vec![self.datasets.clone()];
+        if let Some(blocks) = &self.blocks {
+            command.extend(["--blocks".to_string(), blocks.clone()]);
+        }
+        if let Some(timestamps) = &self.timestamps {
+            command.extend(["--timestamps".to_string(), timestamps.clone()]);
+        }
+        command.extend(["--output-dir".to_string(), self.output_dir.clone()]);
+        if self.format != "parquet" {
+            command.push(format!("--{}", self.format));
+        }
+        if !self.rpc.is_empty() {
+            command.extend(["--rpc".to_string(), redact_rpc_url(&self.rpc)]);
+        }
+        command.join(" ")
+    }
+}
+
+/// override the options of args answered in the wizard, keeping every other option
+fn apply_answers(args: args::Args, answers: Answers) -> Result<args::Args, CollectError> {
+    let (csv, json) = match answers.format.as_str() {
+        "parquet" => (false, false),
+        "csv" => (true, false),
+        "json" => (false, true),
+        format => return Err(err(format!("invalid output format: {}", format).as_str())),
+    };
+    let datatype: Vec<String> =
+        answers.datasets.split_whitespace().map(|s| s.to_string()).collect();
+    if datatype.is_empty() {
+        return Err(err("no dataset given"))
+    }
+    Ok(args::Args {
+        datatype,
+        blocks: answers.blocks.map(|blocks| vec![blocks]),
+        timestamps: answers.timestamps.map(|timestamps| vec![timestamps]),
+        output_dir: answers.output_dir,
+        csv,
+        json,
+        rpc: if answers.rpc.is_empty() { None } else { Some(answers.rpc) },
+        ..args
+    })
+}
+
+/// Ask a question on stdout and read the answer from stdin.
+fn prompt(question: &str, default: Option<&str>) -> Result<String, CollectError> {
+    match default {
+        Some(default) if !default.is_empty() => print!("{} [{}]: ", question.bold(), default),
+        _ => print!("{}: ", question.bold()),
+    }
+    std::io::stdout().flush().map_err(|_| err("could not write to stdout"))?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).map_err(|_| err("could not read from stdin"))?;
+    let answer = answer.trim();
+    match (answer.is_empty(), default) {
+        (true, Some(default)) => Ok(default.to_string()),
+        (true, None) => Err(err(format!("no answer given for {}", question).as_str())),
+        (false, _) => Ok(answer.to_string()),
+    }
+}
+
+/// Convert an inclusive range of UTC dates into a range of unix timestamps.
+fn parse_date_range(dates: &str) -> Result<String, CollectError> {
+    let (start, end) =
+        dates.split_once(':').ok_or_else(|| err("date range must be START_DATE:END_DATE"))?;
+    let start = parse_date(start)?;
+    let end = parse_date(end)?.succ_opt().ok_or_else(|| err("end date out of range"))?;
+    if end <= start {
+        return Err(err("end date should not be before start date"))
+    }
+    let to_timestamp =
+        |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|datetime| datetime.and_utc().timestamp());
+    match (to_timestamp(start), to_timestamp(end)) {
+        (Some(start), Some(end)) => Ok(format!("{}:{}", start, end - 1)),
+        _ => Err(err("could not convert dates to timestamps")),
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, CollectError> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| err(format!("invalid date, expected YYYY-MM-DD: {}", date).as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_cryo::Parser;
+
+    #[test]
+    fn test_parse_date_range() {
+        assert_eq!(parse_date_range("2024-01-01:2024-01-01").unwrap(), "1704067200:1704153599");
+        assert!(parse_date_range("2024-01-02:2024-01-01").is_err());
+        assert!(parse_date_range("2024-01-01").is_err());
+    }
+
+    #[test]
+    fn test_apply_answers_keeps_other_options() {
+        let args = args::Args::parse_from([
+            "cryo",
+            "wizard",
+            "--requests-per-second",
+            "10",
+            "--include-columns",
+            "gas_used",
+        ]);
+        let answers = Answers {
+            rpc: "http://localhost:8545".to_string(),
+            datasets: "blocks transactions".to_string(),
+            blocks: Some("1:10".to_string()),
+            timestamps: None,
+            output_dir: "data".to_string(),
+            format: "csv".to_string(),
+        };
+        let args = apply_answers(args, answers).unwrap();
+        assert_eq!(args.datatype, vec!["blocks", "transactions"]);
+        assert_eq!(args.blocks, Some(vec!["1:10".to_string()]));
+        assert_eq!(args.output_dir, "data");
+        assert!(args.csv && !args.json);
+        assert_eq!(args.rpc.as_deref(), Some("http://localhost:8545"));
+        assert_eq!(args.requests_per_second, Some(10));
+        assert_eq!(args.include_columns, Some(vec!["gas_used".to_string()]));
+    }
+}
@@ -0,0 +1,111 @@
+//! output paths of a freeze whose file name pieces come from hostile user input
+
+use clap_cryo::Parser;
+use cryo_cli::Args;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+/// recorded responses for collecting the blocks dataset of block 1
+fn write_block_fixtures(path: &Path) {
+    let block = json!({
+        "hash": "0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6",
+        "parentHash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "miner": "0x05a56e2d52c817161883f50c441c3228cfe54d9f",
+        "stateRoot": "0xd67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3",
+        "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "logsBloom": format!("0x{}", "0".repeat(512)),
+        "difficulty": "0x3ff800000",
+        "totalDifficulty": "0x7ff800000",
+        "number": "0x1",
+        "gasLimit": "0x1388",
+        "gasUsed": "0x0",
+        "timestamp": "0x55ba4224",
+        "extraData": "0x476574682f76312e302e302f6c696e75782f676f312e342e32",
+        "mixHash": "0x969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59",
+        "nonce": "0x539bd4979fef1ec4",
+        "size": "0x219",
+        "uncles": [],
+        "transactions": [],
+    });
+    let fixtures = json!([
+        {"method": "eth_chainId", "params": null, "result": "0x1"},
+        {"method": "eth_blockNumber", "params": null, "result": "0x10"},
+        {"method": "eth_getBlockByNumber", "params": ["0x1", false], "result": block},
+    ]);
+    std::fs::write(path, serde_json::to_string(&fixtures).unwrap()).unwrap();
+}
+
+fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(files_under(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+#[tokio::test]
+async fn test_freeze_sanitizes_hostile_output_paths() {
+    let root = tempfile::tempdir().unwrap();
+    let root = root.path();
+    let output_dir = root.join("output");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let fixtures = root.join("fixtures.json");
+    write_block_fixtures(&fixtures);
+
+    let args = Args::parse_from([
+        "cryo",
+        "blocks",
+        "--blocks",
+        "1",
+        "--replay-fixtures",
+        fixtures.to_str().unwrap(),
+        "--output-dir",
+        output_dir.to_str().unwrap(),
+        "--network-name",
+        "../../evil:net",
+        "--label",
+        "x/../y?",
+        "--subdirs",
+        "network",
+        "../up",
+        "--no-verbose",
+        "--no-report",
+    ]);
+    let summary = cryo_cli::run(args).await.unwrap().unwrap();
+    assert!(summary.errored.is_empty());
+    assert_eq!(summary.completed.len(), 1);
+
+    // nothing may be written next to the output directory
+    let mut siblings: Vec<String> = std::fs::read_dir(root)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    siblings.sort();
+    assert_eq!(siblings, vec!["fixtures.json", "output"]);
+
+    let output_dir = output_dir.canonicalize().unwrap();
+    let parquet_files: Vec<PathBuf> = files_under(&output_dir)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "parquet"))
+        .collect();
+    assert_eq!(parquet_files.len(), 1);
+    let relative = parquet_files[0].strip_prefix(&output_dir).unwrap();
+    let components: Vec<String> =
+        relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    assert_eq!(components.len(), 3);
+    assert_eq!(components[0], "_._.._evil_net");
+    assert_eq!(components[1], "_._up");
+    assert!(components[2].starts_with("_._.._evil_net__blocks__x_.._y___"));
+    assert!(components[2].ends_with(".parquet"));
+
+    // the written file must be found again when reading the output directory
+    let df = cryo_freeze::scan_dataset(&output_dir, "blocks", None).unwrap().collect().unwrap();
+    assert_eq!(df.height(), 1);
+}
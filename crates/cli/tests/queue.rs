@@ -0,0 +1,100 @@
+//! freezing the chunks of a command through a work queue
+
+use clap_cryo::Parser;
+use cryo_cli::Args;
+use serde_json::json;
+use std::path::Path;
+
+/// recorded responses for collecting the blocks dataset of blocks 1 to 4
+fn write_block_fixtures(path: &Path) {
+    let mut fixtures = vec![
+        json!({"method": "eth_chainId", "params": null, "result": "0x1"}),
+        json!({"method": "eth_blockNumber", "params": null, "result": "0x10"}),
+    ];
+    for number in 1..=4u64 {
+        let block = json!({
+            "hash": format!("0x{:064x}", number),
+            "parentHash": format!("0x{:064x}", number - 1),
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0x05a56e2d52c817161883f50c441c3228cfe54d9f",
+            "stateRoot": "0xd67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3",
+            "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "difficulty": "0x3ff800000",
+            "totalDifficulty": "0x7ff800000",
+            "number": format!("0x{:x}", number),
+            "gasLimit": "0x1388",
+            "gasUsed": "0x0",
+            "timestamp": format!("0x{:x}", 1438269988 + number),
+            "extraData": "0x",
+            "mixHash": "0x969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59",
+            "nonce": "0x539bd4979fef1ec4",
+            "size": "0x219",
+            "uncles": [],
+            "transactions": [],
+        });
+        fixtures.push(json!({
+            "method": "eth_getBlockByNumber",
+            "params": [format!("0x{:x}", number), false],
+            "result": block,
+        }));
+    }
+    std::fs::write(path, serde_json::to_string(&fixtures).unwrap()).unwrap();
+}
+
+fn sorted_file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_worker_freezes_each_queued_chunk() {
+    let root = tempfile::tempdir().unwrap();
+    let fixtures = root.path().join("fixtures.json");
+    write_block_fixtures(&fixtures);
+    let output_dir = root.path().join("output");
+    let queue_dir = root.path().join("queue");
+
+    // two chunks of two blocks, which a worker must not split again with --n-chunks
+    let args = Args::parse_from([
+        "cryo",
+        "blocks",
+        "--blocks",
+        "1:5",
+        "--n-chunks",
+        "2",
+        "--replay-fixtures",
+        fixtures.to_str().unwrap(),
+        "--output-dir",
+        output_dir.to_str().unwrap(),
+        "--enqueue",
+        queue_dir.to_str().unwrap(),
+        "--no-verbose",
+        "--no-report",
+    ]);
+    cryo_cli::run(args).await.unwrap();
+    assert_eq!(sorted_file_names(&queue_dir.join("pending")).len(), 2);
+
+    let args = Args::parse_from(["cryo", "worker", queue_dir.to_str().unwrap()]);
+    cryo_cli::run(args).await.unwrap();
+
+    assert!(sorted_file_names(&queue_dir.join("pending")).is_empty());
+    assert!(sorted_file_names(&queue_dir.join("claimed")).is_empty());
+    assert_eq!(sorted_file_names(&queue_dir.join("done")).len(), 2);
+    let parquet_files: Vec<String> = sorted_file_names(&output_dir)
+        .into_iter()
+        .filter(|name| name.ends_with(".parquet"))
+        .collect();
+    assert_eq!(
+        parquet_files,
+        vec![
+            "ethereum__blocks__00000001_to_00000002.parquet",
+            "ethereum__blocks__00000003_to_00000004.parquet",
+        ]
+    );
+}
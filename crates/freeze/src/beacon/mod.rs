@@ -0,0 +1,176 @@
+use crate::{err, CollectError, Query, Source};
+use alloy::{
+    eips::eip4844::kzg_to_versioned_hash, primitives::B256, rpc::types::BlockTransactionsKind,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+type R<T> = ::core::result::Result<T, CollectError>;
+
+/// slots per beacon chain epoch
+pub const SLOTS_PER_EPOCH: u64 = 32;
+
+/// genesis time of each beacon node, fetched once per process
+static GENESIS_TIMES: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// seconds per slot of each beacon node, fetched once per process
+static SLOT_TIMES: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// beacon node api url of query, without trailing slash
+pub fn beacon_url(query: &Query) -> R<String> {
+    match &query.beacon_url {
+        Some(beacon_url) => Ok(beacon_url.trim_end_matches('/').to_string()),
+        None => Err(err("beacon datasets require --beacon-url")),
+    }
+}
+
+/// slot of the beacon block produced at timestamp
+pub async fn slot_at_timestamp(source: &Source, beacon_url: &str, timestamp: u64) -> R<u64> {
+    let genesis_time = get_genesis_time(source, beacon_url).await?;
+    let seconds_per_slot = get_seconds_per_slot(source, beacon_url).await?;
+    Ok(timestamp.saturating_sub(genesis_time) / seconds_per_slot)
+}
+
+/// slot of the beacon block that contains an execution block
+pub async fn slot_of_block(source: &Source, beacon_url: &str, block_number: u64) -> R<u64> {
+    let block = source
+        .get_block(block_number, BlockTransactionsKind::Hashes)
+        .await?
+        .ok_or(CollectError::CollectError("block not found".to_string()))?;
+    slot_at_timestamp(source, beacon_url, block.header.timestamp).await
+}
+
+/// genesis time of the beacon chain served by beacon_url
+pub async fn get_genesis_time(source: &Source, beacon_url: &str) -> R<u64> {
+    if let Some(genesis_time) = GENESIS_TIMES.lock().ok().and_then(|t| t.get(beacon_url).copied()) {
+        return Ok(genesis_time)
+    }
+    let genesis = get_beacon_json(source, &format!("{}/eth/v1/beacon/genesis", beacon_url)).await?;
+    let genesis_time = json_u64(&genesis["data"]["genesis_time"])
+        .map_err(|_| err("invalid genesis_time in beacon api response"))?;
+    if let Ok(mut genesis_times) = GENESIS_TIMES.lock() {
+        genesis_times.insert(beacon_url.to_string(), genesis_time);
+    }
+    Ok(genesis_time)
+}
+
+/// seconds per slot of the beacon chain served by beacon_url, read from its config spec
+pub async fn get_seconds_per_slot(source: &Source, beacon_url: &str) -> R<u64> {
+    if let Some(seconds) = SLOT_TIMES.lock().ok().and_then(|t| t.get(beacon_url).copied()) {
+        return Ok(seconds)
+    }
+    let spec = get_beacon_json(source, &format!("{}/eth/v1/config/spec", beacon_url)).await?;
+    let seconds = match json_u64(&spec["data"]["SECONDS_PER_SLOT"]) {
+        Ok(seconds) if seconds > 0 => seconds,
+        _ => return Err(err("invalid SECONDS_PER_SLOT in beacon api response")),
+    };
+    if let Ok(mut slot_times) = SLOT_TIMES.lock() {
+        slot_times.insert(beacon_url.to_string(), seconds);
+    }
+    Ok(seconds)
+}
+
+/// signed beacon block at slot, None if the slot was missed
+pub async fn get_block(
+    source: &Source,
+    beacon_url: &str,
+    slot: u64,
+) -> R<Option<serde_json::Value>> {
+    let url = format!("{}/eth/v2/beacon/blocks/{}", beacon_url, slot);
+    Ok(source.get_http_json(&url).await?.map(|block| block["data"]["message"].clone()))
+}
+
+/// blobs of the beacon block at slot, keyed by the versioned hash of their kzg commitment
+pub async fn get_blob_sidecars(
+    source: &Source,
+    beacon_url: &str,
+    slot: u64,
+) -> R<HashMap<B256, Vec<u8>>> {
+    let sidecars =
+        get_beacon_json(source, &format!("{}/eth/v1/beacon/blob_sidecars/{}", beacon_url, slot))
+            .await?;
+    let mut blobs = HashMap::new();
+    for sidecar in sidecars["data"].as_array().cloned().unwrap_or_default().iter() {
+        let commitment = json_bytes(&sidecar["kzg_commitment"])?;
+        if commitment.len() != 48 {
+            return Err(err("invalid kzg_commitment in beacon api response"))
+        }
+        blobs.insert(kzg_to_versioned_hash(&commitment), json_bytes(&sidecar["blob"])?);
+    }
+    Ok(blobs)
+}
+
+/// (validator index, balance in gwei) of the validators with the given indices or pubkeys at slot
+pub async fn get_validator_balances(
+    source: &Source,
+    beacon_url: &str,
+    slot: u64,
+    ids: &[String],
+) -> R<Vec<(u64, u64)>> {
+    let url = format!(
+        "{}/eth/v1/beacon/states/{}/validator_balances?id={}",
+        beacon_url,
+        slot,
+        ids.join(",")
+    );
+    let balances = get_beacon_json(source, &url).await?;
+    balances["data"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|balance| Ok((json_u64(&balance["index"])?, json_u64(&balance["balance"])?)))
+        .collect()
+}
+
+/// fetch json from the beacon api, failing on any unsuccessful response
+pub async fn get_beacon_json(source: &Source, url: &str) -> R<serde_json::Value> {
+    source
+        .get_http_json(url)
+        .await?
+        .ok_or(err(format!("beacon api resource not found: {}", url).as_str()))
+}
+
+/// parse a beacon api integer, which are encoded as decimal strings
+pub fn json_u64(value: &serde_json::Value) -> R<u64> {
+    value.as_str().and_then(|x| x.parse::<u64>().ok()).ok_or(err(format!(
+        "invalid integer in beacon api response: {}",
+        value
+    )
+    .as_str()))
+}
+
+/// parse beacon api bytes, which are encoded as 0x-prefixed hex strings
+pub fn json_bytes(value: &serde_json::Value) -> R<Vec<u8>> {
+    value
+        .as_str()
+        .and_then(|x| prefix_hex::decode::<Vec<u8>>(x).ok())
+        .ok_or(err("invalid bytes in beacon api response"))
+}
+
+/// number of set bits of a hex encoded ssz bitlist, excluding its length delimiter bit
+pub fn count_bitlist_bits(value: &serde_json::Value) -> R<u32> {
+    let n_bits: u32 = json_bytes(value)?.iter().map(|byte| byte.count_ones()).sum();
+    Ok(n_bits.saturating_sub(1))
+}
+
+/// number of set bits of a hex encoded ssz bitvector
+pub fn count_bitvector_bits(value: &serde_json::Value) -> R<u32> {
+    Ok(json_bytes(value)?.iter().map(|byte| byte.count_ones()).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_bits() {
+        let bitlist = serde_json::json!("0x0d01");
+        assert_eq!(count_bitlist_bits(&bitlist).unwrap(), 3);
+        assert_eq!(count_bitvector_bits(&bitlist).unwrap(), 4);
+        assert_eq!(json_u64(&serde_json::json!("1606824023")).unwrap(), 1606824023);
+        assert!(json_u64(&serde_json::json!(12)).is_err());
+    }
+}
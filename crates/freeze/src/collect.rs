@@ -1,5 +1,6 @@
 use crate::{collect_partition, CollectError, Query, Source};
 use polars::prelude::*;
+use tokio_util::sync::CancellationToken;
 
 /// collect single dataframe
 pub async fn collect(query: Arc<Query>, source: Arc<Source>) -> Result<DataFrame, CollectError> {
@@ -28,3 +29,17 @@ pub async fn collect(query: Arc<Query>, source: Arc<Source>) -> Result<DataFrame
         }
     }
 }
+
+/// collect single dataframe, aborting the tasks of in-flight requests once `cancel` is cancelled
+///
+/// returns `CollectError::Cancelled` if collection was cancelled before completing
+pub async fn collect_with_cancel(
+    query: Arc<Query>,
+    source: Arc<Source>,
+    cancel: CancellationToken,
+) -> Result<DataFrame, CollectError> {
+    tokio::select! {
+        _ = cancel.cancelled() => Err(CollectError::Cancelled),
+        result = collect(query, source) => result,
+    }
+}
@@ -1,9 +1,10 @@
 use crate::*;
 use alloy::{
+    eips::BlockNumberOrTag,
     primitives::{Address, TxHash},
     rpc::types::{
         eth::{Block, Log},
-        trace::parity::{Action, LocalizedTransactionTrace, TraceOutput},
+        trace::parity::{Action, LocalizedTransactionTrace, StateDiff, TraceOutput, TraceType},
         BlockTransactionsKind, Filter, FilterBlockOption,
     },
     sol_types::SolEvent,
@@ -40,15 +41,35 @@ impl Dataset for AddressAppearances {
     fn default_sort() -> Option<Vec<&'static str>> {
         Some(vec!["block_number", "transaction_hash", "address", "relationship"])
     }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "addresses appearing in each transaction, from transactions, logs, and traces, one \
+             row per (address, transaction, relationship), addresses of state diffs are included \
+             with --state-diff-appearances",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [(
+            "relationship",
+            "how the address appears, e.g. tx_from, call_to, erc20_transfer_from, state_diff",
+        )]
+        .into_iter()
+        .collect()
+    }
 }
 
-type BlockLogsTraces = (Block, Vec<Log>, Vec<LocalizedTransactionTrace>);
+/// addresses whose state changed in each transaction
+type StateDiffAddresses = Vec<(TxHash, Vec<Address>)>;
+
+type BlockLogsTraces = (Block, Vec<Log>, Vec<LocalizedTransactionTrace>, StateDiffAddresses);
 
 #[async_trait::async_trait]
 impl CollectByBlock for AddressAppearances {
     type Response = BlockLogsTraces;
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let block_number = request.ethers_block_number()?;
         let block =
             source.get_block(request.block_number()?, BlockTransactionsKind::Hashes).await?;
@@ -62,7 +83,21 @@ impl CollectByBlock for AddressAppearances {
         };
         let logs = source.get_logs(&filter).await?;
         let traces = source.trace_block(request.block_number()?).await?;
-        Ok((block, logs, traces))
+        let state_diffs = match query.state_diff_appearances {
+            true => source
+                .trace_replay_block_transactions(
+                    BlockNumberOrTag::Number(request.block_number()?),
+                    vec![TraceType::StateDiff],
+                )
+                .await?
+                .into_iter()
+                .map(|result| {
+                    (result.transaction_hash, state_diff_addresses(result.full_trace.state_diff))
+                })
+                .collect(),
+            false => vec![],
+        };
+        Ok((block, logs, traces, state_diffs))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
@@ -75,7 +110,7 @@ impl CollectByBlock for AddressAppearances {
 impl CollectByTransaction for AddressAppearances {
     type Response = BlockLogsTraces;
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let tx_hash = request.ethers_transaction_hash()?;
 
         let tx_data = source.get_transaction_by_hash(tx_hash).await?.ok_or_else(|| {
@@ -102,7 +137,19 @@ impl CollectByTransaction for AddressAppearances {
         // traces
         let traces = source.trace_transaction(request.ethers_transaction_hash()?).await?;
 
-        Ok((block, logs, traces))
+        // state diffs
+        let state_diffs = match query.state_diff_appearances {
+            true => {
+                let state_diff = source
+                    .trace_replay_transaction(tx_hash, vec![TraceType::StateDiff])
+                    .await?
+                    .state_diff;
+                vec![(tx_hash, state_diff_addresses(state_diff))]
+            }
+            false => vec![],
+        };
+
+        Ok((block, logs, traces, state_diffs))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
@@ -111,6 +158,10 @@ impl CollectByTransaction for AddressAppearances {
     }
 }
 
+fn state_diff_addresses(state_diff: Option<StateDiff>) -> Vec<Address> {
+    state_diff.map(|state_diff| state_diff.0.into_keys().collect()).unwrap_or_default()
+}
+
 fn name(log: &Log) -> Option<&'static str> {
     let event = log.topic0().unwrap();
     if event == *ERC20::Transfer::SIGNATURE_HASH {
@@ -146,10 +197,9 @@ impl AddressAppearances {
                         let mut from: [u8; 20] = [0; 20];
                         from.copy_from_slice(&log.topics()[1][12..32]);
 
-                        let name = &(name.to_string() + "_from");
                         self.process_address(
                             Address::from_slice(&from),
-                            name,
+                            &(name.to_string() + "_from"),
                             block_number,
                             &block_hash,
                             tx_hash,
@@ -157,11 +207,10 @@ impl AddressAppearances {
                         );
 
                         let mut to: [u8; 20] = [0; 20];
-                        to.copy_from_slice(&log.topics()[1][12..32]);
-                        let name = &(name.to_string() + "_to");
+                        to.copy_from_slice(&log.topics()[2][12..32]);
                         self.process_address(
                             Address::from_slice(&to),
-                            name,
+                            &(name.to_string() + "_to"),
                             block_number,
                             &block_hash,
                             tx_hash,
@@ -318,7 +367,7 @@ fn process_appearances(
     columns: &mut AddressAppearances,
     schema: &Table,
 ) -> R<()> {
-    let (block, logs, traces) = traces;
+    let (block, logs, traces, state_diffs) = traces;
     let mut logs_by_tx: HashMap<TxHash, Vec<Log>> = HashMap::new();
     for log in logs.into_iter() {
         if let Some(tx_hash) = log.transaction_hash {
@@ -340,5 +389,20 @@ fn process_appearances(
         }
     }
 
+    let block_number = block.header.number as u32;
+    let block_hash = block.header.hash.to_vec();
+    for (tx_hash, addresses) in state_diffs.into_iter() {
+        for address in addresses.into_iter() {
+            columns.process_address(
+                address,
+                "state_diff",
+                block_number,
+                &block_hash,
+                tx_hash,
+                schema,
+            );
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,138 @@
+use crate::*;
+use alloy::primitives::{Address, U256};
+use polars::prelude::*;
+use std::str::FromStr;
+
+/// transactions requested per page of ots_searchTransactionsAfter
+const PAGE_SIZE: u64 = 25;
+
+/// columns for address transactions
+#[cryo_to_df::to_df(Datatype::AddressTransactions)]
+#[derive(Default)]
+pub struct AddressTransactions {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    address: Vec<Vec<u8>>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    value: Vec<U256>,
+    success: Vec<Option<bool>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for AddressTransactions {
+    fn aliases() -> Vec<&'static str> {
+        vec!["ots_transactions"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["address", "block_number", "transaction_index"])
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "transactions sent from or to an address, paged from otterscan's \
+             ots_searchTransactionsAfter (erigon), use a large --inner-request-size",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [("address", "address whose transaction history was searched")].into_iter().collect()
+    }
+}
+
+/// (transaction, receipt) pairs of address within block range
+type AddressHistory = (Vec<u8>, Vec<(serde_json::Value, serde_json::Value)>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for AddressTransactions {
+    type Response = AddressHistory;
+
+    /// page forward through history of address until the end of the block range is passed
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let address = request.address()?;
+        let (start_block, end_block) = request.block_range()?;
+        let mut cursor = start_block.saturating_sub(1);
+        let mut history = Vec::new();
+        loop {
+            let page = source
+                .ots_search_transactions_after(Address::from_slice(&address), cursor, PAGE_SIZE)
+                .await?;
+            let txs = page["txs"].as_array().cloned().unwrap_or_default();
+            let receipts = page["receipts"].as_array().cloned().unwrap_or_default();
+            let mut max_block = None;
+            for (tx, receipt) in txs.into_iter().zip(receipts) {
+                let block = hex_u64(&tx["blockNumber"])?;
+                max_block = max_block.max(Some(block));
+                if block >= start_block && block <= end_block {
+                    history.push((tx, receipt));
+                }
+            }
+            // pages never split a block, so the next page starts after the last block seen
+            match max_block {
+                Some(max_block)
+                    if max_block < end_block && !page["firstPage"].as_bool().unwrap_or(true) =>
+                {
+                    cursor = max_block
+                }
+                _ => break,
+            }
+        }
+        Ok((address, history))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::AddressTransactions)?;
+        let (address, history) = response;
+        for (tx, receipt) in history.iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, hex_u64(&tx["blockNumber"])? as u32);
+            store!(schema, columns, transaction_index, hex_u64(&tx["transactionIndex"])? as u32);
+            store!(schema, columns, transaction_hash, hex_bytes(&tx["hash"])?);
+            store!(schema, columns, address, address.clone());
+            store!(schema, columns, from_address, hex_bytes(&tx["from"])?);
+            store!(schema, columns, to_address, hex_bytes(&tx["to"]).ok());
+            store!(schema, columns, value, hex_u256(&tx["value"])?);
+            store!(schema, columns, success, hex_u64(&receipt["status"]).ok().map(|x| x == 1));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for AddressTransactions {
+    type Response = ();
+}
+
+fn hex_u64(value: &serde_json::Value) -> R<u64> {
+    value
+        .as_str()
+        .and_then(|x| x.strip_prefix("0x"))
+        .and_then(|x| u64::from_str_radix(x, 16).ok())
+        .ok_or(err("invalid hex number in otterscan response"))
+}
+
+fn hex_u256(value: &serde_json::Value) -> R<U256> {
+    value
+        .as_str()
+        .and_then(|x| U256::from_str(x).ok())
+        .ok_or(err("invalid hex number in otterscan response"))
+}
+
+fn hex_bytes(value: &serde_json::Value) -> R<Vec<u8>> {
+    value
+        .as_str()
+        .and_then(|x| prefix_hex::decode::<Vec<u8>>(x).ok())
+        .ok_or(err("invalid hex data in otterscan response"))
+}
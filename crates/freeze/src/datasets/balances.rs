@@ -1,5 +1,8 @@
 use crate::*;
-use alloy::primitives::{Address, U256};
+use alloy::{
+    primitives::{Address, U256},
+    sol_types::SolCall,
+};
 use polars::prelude::*;
 
 /// columns for balances
@@ -26,6 +29,14 @@ impl Dataset for Balances {
     fn default_blocks() -> Option<String> {
         Some("latest".to_string())
     }
+
+    fn description() -> Option<&'static str> {
+        Some("native balances of addresses at given blocks")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [("balance", "native balance of the address at the block, in wei")].into_iter().collect()
+    }
 }
 
 type BlockTxAddressOutput = (u32, Option<Vec<u8>>, Vec<u8>, U256);
@@ -42,6 +53,24 @@ impl CollectByBlock for Balances {
         Ok((block_number, None, address, balance))
     }
 
+    fn multicall_request(request: &Params) -> R<(Address, Vec<u8>)> {
+        let call_data =
+            Multicall3::getEthBalanceCall { addr: request.ethers_address()? }.abi_encode();
+        Ok((rpc::MULTICALL3_ADDRESS, call_data))
+    }
+
+    fn multicall_response(request: Params, output: Option<Vec<u8>>) -> R<Self::Response> {
+        let output = output.ok_or(err("could not get balance"))?;
+        let balance = Multicall3::getEthBalanceCall::abi_decode_returns(&output, true)
+            .map_err(|_| err("could not decode balance"))?
+            .balance;
+        Ok((request.block_number()? as u32, None, request.address()?, balance))
+    }
+
+    fn can_multicall() -> bool {
+        true
+    }
+
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get(&Datatype::Balances).ok_or(err("schema not provided"))?;
         process_balance(columns, response, schema)
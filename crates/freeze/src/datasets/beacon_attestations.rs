@@ -0,0 +1,114 @@
+use crate::*;
+use polars::prelude::*;
+
+/// columns for attestations included in beacon chain blocks
+#[cryo_to_df::to_df(Datatype::BeaconAttestations)]
+#[derive(Default)]
+pub struct BeaconAttestations {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    slot: Vec<u64>,
+    attestation_index: Vec<u32>,
+    committee_index: Vec<u64>,
+    attested_slot: Vec<u64>,
+    beacon_block_root: Vec<Vec<u8>>,
+    source_epoch: Vec<u64>,
+    source_root: Vec<Vec<u8>>,
+    target_epoch: Vec<u64>,
+    target_root: Vec<Vec<u8>>,
+    n_attesting: Vec<u32>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for BeaconAttestations {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "slot",
+            "attestation_index",
+            "committee_index",
+            "attested_slot",
+            "beacon_block_root",
+            "source_epoch",
+            "target_epoch",
+            "target_root",
+            "n_attesting",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "attestation_index"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "attestations included in the beacon chain block containing each execution block, \
+             one row per aggregate attestation, fetched from the beacon api of --beacon-url",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("slot", "beacon chain slot of the block including the attestation"),
+            ("attestation_index", "index of the attestation within the block"),
+            ("committee_index", "index of the attesting committee within its slot"),
+            ("attested_slot", "slot being attested to"),
+            ("beacon_block_root", "root of the beacon block voted as head"),
+            ("n_attesting", "number of validators aggregated into the attestation"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// execution block number and the beacon block containing it
+type BeaconBlockResponse = (u32, serde_json::Value);
+
+#[async_trait::async_trait]
+impl CollectByBlock for BeaconAttestations {
+    type Response = BeaconBlockResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        BeaconBlocks::extract(request, source, query).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BeaconAttestations)?;
+        let (block_number, block) = response;
+        let slot = beacon::json_u64(&block["slot"])?;
+        let attestations = block["body"]["attestations"].as_array().cloned().unwrap_or_default();
+        for (attestation_index, attestation) in attestations.iter().enumerate() {
+            let data = &attestation["data"];
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, slot, slot);
+            store!(schema, columns, attestation_index, attestation_index as u32);
+            store!(schema, columns, committee_index, beacon::json_u64(&data["index"])?);
+            store!(schema, columns, attested_slot, beacon::json_u64(&data["slot"])?);
+            store!(
+                schema,
+                columns,
+                beacon_block_root,
+                beacon::json_bytes(&data["beacon_block_root"])?
+            );
+            store!(schema, columns, source_epoch, beacon::json_u64(&data["source"]["epoch"])?);
+            store!(schema, columns, source_root, beacon::json_bytes(&data["source"]["root"])?);
+            store!(schema, columns, target_epoch, beacon::json_u64(&data["target"]["epoch"])?);
+            store!(schema, columns, target_root, beacon::json_bytes(&data["target"]["root"])?);
+            store!(
+                schema,
+                columns,
+                n_attesting,
+                beacon::count_bitlist_bits(&attestation["aggregation_bits"])?
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for BeaconAttestations {
+    type Response = ();
+}
@@ -0,0 +1,133 @@
+use crate::*;
+use polars::prelude::*;
+
+/// columns for beacon chain blocks
+#[cryo_to_df::to_df(Datatype::BeaconBlocks)]
+#[derive(Default)]
+pub struct BeaconBlocks {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    slot: Vec<u64>,
+    epoch: Vec<u64>,
+    proposer_index: Vec<u64>,
+    parent_root: Vec<Vec<u8>>,
+    state_root: Vec<Vec<u8>>,
+    graffiti: Vec<Vec<u8>>,
+    execution_block_hash: Vec<Option<Vec<u8>>>,
+    n_attestations: Vec<u32>,
+    n_deposits: Vec<u32>,
+    n_voluntary_exits: Vec<u32>,
+    n_withdrawals: Vec<Option<u32>>,
+    sync_committee_participation: Vec<Option<u32>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for BeaconBlocks {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "slot",
+            "epoch",
+            "proposer_index",
+            "parent_root",
+            "state_root",
+            "execution_block_hash",
+            "n_attestations",
+            "n_deposits",
+            "n_voluntary_exits",
+            "n_withdrawals",
+            "sync_committee_participation",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "beacon chain blocks containing each execution block, fetched from the beacon api of \
+             --beacon-url",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("slot", "beacon chain slot of the block"),
+            ("epoch", "beacon chain epoch of the block"),
+            ("proposer_index", "validator index of the block proposer"),
+            ("execution_block_hash", "hash of the execution payload of the block"),
+            ("n_attestations", "number of attestations included in the block"),
+            ("n_withdrawals", "number of withdrawals in the execution payload"),
+            ("sync_committee_participation", "number of sync committee members that signed"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// execution block number and the beacon block containing it
+type BeaconBlockResponse = (u32, serde_json::Value);
+
+#[async_trait::async_trait]
+impl CollectByBlock for BeaconBlocks {
+    type Response = BeaconBlockResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let beacon_url = beacon::beacon_url(&query)?;
+        let block_number = request.block_number()?;
+        let slot = beacon::slot_of_block(&source, &beacon_url, block_number).await?;
+        let message = format!("beacon block not found at slot {}", slot);
+        let block =
+            beacon::get_block(&source, &beacon_url, slot).await?.ok_or(err(message.as_str()))?;
+        Ok((block_number as u32, block))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BeaconBlocks)?;
+        let (block_number, block) = response;
+        let body = &block["body"];
+        let slot = beacon::json_u64(&block["slot"])?;
+        let count = |key: &str| body[key].as_array().map(|x| x.len() as u32).unwrap_or(0);
+        let payload = &body["execution_payload"];
+        let sync_bits = &body["sync_aggregate"]["sync_committee_bits"];
+
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block_number);
+        store!(schema, columns, slot, slot);
+        store!(schema, columns, epoch, slot / beacon::SLOTS_PER_EPOCH);
+        store!(schema, columns, proposer_index, beacon::json_u64(&block["proposer_index"])?);
+        store!(schema, columns, parent_root, beacon::json_bytes(&block["parent_root"])?);
+        store!(schema, columns, state_root, beacon::json_bytes(&block["state_root"])?);
+        store!(schema, columns, graffiti, beacon::json_bytes(&body["graffiti"])?);
+        store!(
+            schema,
+            columns,
+            execution_block_hash,
+            beacon::json_bytes(&payload["block_hash"]).ok()
+        );
+        store!(schema, columns, n_attestations, count("attestations"));
+        store!(schema, columns, n_deposits, count("deposits"));
+        store!(schema, columns, n_voluntary_exits, count("voluntary_exits"));
+        store!(
+            schema,
+            columns,
+            n_withdrawals,
+            payload["withdrawals"].as_array().map(|x| x.len() as u32)
+        );
+        store!(
+            schema,
+            columns,
+            sync_committee_participation,
+            beacon::count_bitvector_bits(sync_bits).ok()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for BeaconBlocks {
+    type Response = ();
+}
@@ -0,0 +1,182 @@
+use crate::*;
+use alloy::{
+    primitives::{address, Address},
+    rpc::types::{Filter, Log, Topic},
+    sol_types::SolEvent,
+};
+use polars::prelude::*;
+
+/// columns for beacon chain deposits, decoded from deposit contract DepositEvent logs
+#[cryo_to_df::to_df(Datatype::BeaconDeposits)]
+#[derive(Default)]
+pub struct BeaconDeposits {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    deposit_contract: Vec<Vec<u8>>,
+    pubkey: Vec<Vec<u8>>,
+    withdrawal_credentials: Vec<Vec<u8>>,
+    amount_gwei: Vec<u64>,
+    signature: Vec<Vec<u8>>,
+    deposit_index: Vec<u64>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for BeaconDeposits {
+    fn aliases() -> Vec<&'static str> {
+        vec!["deposits"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "log_index",
+            "transaction_hash",
+            "pubkey",
+            "withdrawal_credentials",
+            "amount_gwei",
+            "deposit_index",
+            "chain_id",
+        ])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "deposits to the beacon chain deposit contract, on mainnet, sepolia, holesky, and \
+             hoodi the contract defaults to the official deposit contract, use --contract to set \
+             it on other chains",
+        )
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for BeaconDeposits {
+    type Response = Vec<Log>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let mut topics: [Topic; 4] = Default::default();
+        topics[0] = DepositContract::DepositEvent::SIGNATURE_HASH.into();
+        let mut filter = Filter { topics, ..request.ethers_log_filter()? };
+        if let (None, Some(contract)) =
+            (&request.address, default_deposit_contract(source.chain_id))
+        {
+            filter = filter.address(contract);
+        }
+        let logs = source.get_logs(&filter).await?;
+        Ok(logs.into_iter().filter(is_deposit_event).collect())
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BeaconDeposits)?;
+        process_beacon_deposits(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for BeaconDeposits {
+    type Response = Vec<Log>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let logs = source.get_transaction_logs(request.transaction_hash()?).await?;
+        let contract = match &request.address {
+            Some(address) => Some(Address::from_slice(address)),
+            None => default_deposit_contract(source.chain_id),
+        };
+        Ok(logs
+            .into_iter()
+            .filter(is_deposit_event)
+            .filter(|log| contract.map_or(true, |contract| log.address() == contract))
+            .collect())
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BeaconDeposits)?;
+        process_beacon_deposits(response, columns, schema)
+    }
+}
+
+/// deposit contract of chain_id, for chains where it is known
+fn default_deposit_contract(chain_id: u64) -> Option<Address> {
+    match chain_id {
+        // mainnet and hoodi
+        1 | 560048 => Some(address!("00000000219ab540356cBB839Cbe05303d7705Fa")),
+        // sepolia
+        11155111 => Some(address!("7f02C3E3c98b133055B8B348B2Ac625669Ed295D")),
+        // holesky
+        17000 => Some(address!("4242424242424242424242424242424242424242")),
+        _ => None,
+    }
+}
+
+fn is_deposit_event(log: &Log) -> bool {
+    log.topics().len() == 1 && log.topics()[0] == DepositContract::DepositEvent::SIGNATURE_HASH
+}
+
+/// amount and index are emitted as 8 byte little-endian integers
+fn decode_little_endian_u64(bytes: &[u8]) -> R<u64> {
+    let bytes: [u8; 8] =
+        bytes.try_into().map_err(|_| err("deposit event integer is not 8 bytes"))?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// fields of a deposit event, None if the log does not follow the deposit contract layout
+fn decode_deposit(log: &Log) -> Option<(DepositContract::DepositEvent, u64, u64)> {
+    let event = DepositContract::DepositEvent::decode_log_data(log.data(), true).ok()?;
+    let amount = decode_little_endian_u64(&event.amount).ok()?;
+    let index = decode_little_endian_u64(&event.index).ok()?;
+    Some((event, amount, index))
+}
+
+/// process block into columns
+///
+/// logs that cannot be decoded, e.g. events of other contracts with the same signature, are
+/// skipped and counted in a warning
+fn process_beacon_deposits(logs: Vec<Log>, columns: &mut BeaconDeposits, schema: &Table) -> R<()> {
+    let mut n_undecodable = 0;
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let (event, amount_gwei, deposit_index) = match decode_deposit(log) {
+                Some(deposit) => deposit,
+                None => {
+                    n_undecodable += 1;
+                    continue
+                }
+            };
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn as u32);
+            store!(schema, columns, block_hash, log.block_hash.map(|bh| bh.to_vec()));
+            store!(schema, columns, transaction_index, ti as u32);
+            store!(schema, columns, log_index, li as u32);
+            store!(schema, columns, transaction_hash, tx.to_vec());
+            store!(schema, columns, deposit_contract, log.address().to_vec());
+            store!(schema, columns, pubkey, event.pubkey.to_vec());
+            store!(schema, columns, withdrawal_credentials, event.withdrawal_credentials.to_vec());
+            store!(schema, columns, amount_gwei, amount_gwei);
+            store!(schema, columns, signature, event.signature.to_vec());
+            store!(schema, columns, deposit_index, deposit_index);
+        }
+    }
+    if n_undecodable > 0 {
+        eprintln!("skipped {} deposit events that could not be decoded", n_undecodable);
+    }
+    Ok(())
+}
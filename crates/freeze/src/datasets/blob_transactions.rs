@@ -0,0 +1,167 @@
+use crate::*;
+use alloy::{
+    consensus::Transaction as ConsensusTransaction,
+    eips::eip4844::DATA_GAS_PER_BLOB,
+    primitives::{TxKind, B256},
+    rpc::types::{Block, BlockTransactionsKind},
+};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// columns for blobs of eip-4844 transactions
+#[cryo_to_df::to_df(Datatype::BlobTransactions)]
+#[derive(Default)]
+pub struct BlobTransactions {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Vec<u8>>,
+    transaction_index: Vec<Option<u64>>,
+    transaction_hash: Vec<Vec<u8>>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    blob_index: Vec<u32>,
+    blob_versioned_hash: Vec<Vec<u8>>,
+    n_blobs: Vec<u32>,
+    blob_gas_used: Vec<u64>,
+    max_fee_per_blob_gas: Vec<Option<u64>>,
+    blob_gas_price: Vec<Option<u64>>,
+    blob_data: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for BlobTransactions {
+    fn aliases() -> Vec<&'static str> {
+        vec!["blobs"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "from_address",
+            "to_address",
+            "blob_index",
+            "blob_versioned_hash",
+            "n_blobs",
+            "blob_gas_used",
+            "max_fee_per_blob_gas",
+            "blob_gas_price",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "blob_index"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "blobs of eip-4844 transactions, one row per blob, blob_data is fetched from \
+             --beacon-url when included",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("blob_index", "index of the blob within the transaction"),
+            ("n_blobs", "number of blobs of the transaction"),
+            (
+                "blob_gas_used",
+                "blob gas used by the blob, summing over the rows of a transaction gives the blob \
+                 gas used by the transaction",
+            ),
+            ("blob_gas_price", "blob base fee of the block"),
+            (
+                "blob_data",
+                "blob contents from the beacon api blob sidecar whose kzg commitment matches \
+                 blob_versioned_hash",
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// block and the data of its blobs keyed by versioned hash, if requested
+type BlockBlobs = (Block, Option<HashMap<B256, Vec<u8>>>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for BlobTransactions {
+    type Response = BlockBlobs;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let block = source
+            .get_block(request.block_number()?, BlockTransactionsKind::Full)
+            .await?
+            .ok_or(CollectError::CollectError("block not found".to_string()))?;
+        let schema = query.schemas.get_schema(&Datatype::BlobTransactions)?;
+        let has_blobs =
+            block.transactions.txns().any(|tx| tx.inner.blob_versioned_hashes().is_some());
+        let blobs = match (&query.beacon_url, schema.has_column("blob_data") && has_blobs) {
+            (Some(beacon_url), true) => {
+                let beacon_url = beacon_url.trim_end_matches('/');
+                let slot =
+                    beacon::slot_at_timestamp(&source, beacon_url, block.header.timestamp).await?;
+                Some(beacon::get_blob_sidecars(&source, beacon_url, slot).await?)
+            }
+            (None, true) => return Err(err("blob_data column requires --beacon-url")),
+            _ => None,
+        };
+        Ok((block, blobs))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::BlobTransactions)?;
+        let (block, blobs) = response;
+        let blob_gas_price = block.header.blob_fee().map(|x| x as u64);
+        for tx in block.transactions.txns() {
+            let versioned_hashes = match tx.inner.blob_versioned_hashes() {
+                Some(versioned_hashes) => versioned_hashes,
+                None => continue,
+            };
+            let n_blobs = versioned_hashes.len() as u64;
+            for (blob_index, versioned_hash) in versioned_hashes.iter().enumerate() {
+                columns.n_rows += 1;
+                store!(schema, columns, block_number, block.header.number as u32);
+                store!(schema, columns, block_hash, block.header.hash.to_vec());
+                store!(schema, columns, transaction_index, tx.transaction_index);
+                store!(schema, columns, transaction_hash, tx.inner.tx_hash().to_vec());
+                store!(schema, columns, from_address, tx.from.to_vec());
+                store!(
+                    schema,
+                    columns,
+                    to_address,
+                    match tx.inner.kind() {
+                        TxKind::Create => None,
+                        TxKind::Call(address) => Some(address.to_vec()),
+                    }
+                );
+                store!(schema, columns, blob_index, blob_index as u32);
+                store!(schema, columns, blob_versioned_hash, versioned_hash.to_vec());
+                store!(schema, columns, n_blobs, n_blobs as u32);
+                store!(schema, columns, blob_gas_used, DATA_GAS_PER_BLOB);
+                store!(
+                    schema,
+                    columns,
+                    max_fee_per_blob_gas,
+                    tx.inner.max_fee_per_blob_gas().map(|x| x as u64)
+                );
+                store!(schema, columns, blob_gas_price, blob_gas_price);
+                store!(
+                    schema,
+                    columns,
+                    blob_data,
+                    blobs.as_ref().and_then(|blobs| blobs.get(versioned_hash).cloned())
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for BlobTransactions {
+    type Response = ();
+}
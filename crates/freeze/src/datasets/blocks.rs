@@ -21,6 +21,8 @@ pub struct Blocks {
     gas_used: Vec<u64>,
     gas_limit: Vec<u64>,
     extra_data: Vec<Vec<u8>>,
+    extra_data_text: Vec<Option<String>>,
+    miner_tag: Vec<Option<String>>,
     logs_bloom: Vec<Option<Vec<u8>>>,
     timestamp: Vec<u32>,
     difficulty: Vec<u64>,
@@ -30,6 +32,7 @@ pub struct Blocks {
     nonce: Vec<Option<Vec<u8>>>,
     base_fee_per_gas: Vec<Option<u64>>,
     withdrawals_root: Vec<Option<Vec<u8>>>,
+    transaction_count: Vec<u64>,
     chain_id: Vec<u64>,
 }
 
@@ -47,6 +50,35 @@ impl Dataset for Blocks {
             "chain_id",
         ])
     }
+
+    fn description() -> Option<&'static str> {
+        Some("block headers, one row per block")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("parent_hash", "hash of the parent block"),
+            ("uncles_hash", "hash of the list of uncle headers"),
+            ("author", "address that received the block reward"),
+            ("state_root", "root of the state trie after the block"),
+            ("transactions_root", "root of the transactions trie of the block"),
+            ("receipts_root", "root of the receipts trie of the block"),
+            ("base_fee_per_gas", "base fee per gas of the block, null before london"),
+            ("extra_data", "arbitrary data included by the block producer"),
+            ("extra_data_text", "extra data decoded as utf-8, null if not printable"),
+            ("miner_tag", "mining pool identified from the extra data"),
+            ("logs_bloom", "bloom filter of the addresses and topics of the logs of the block"),
+            ("difficulty", "proof of work difficulty of the block, 0 after the merge"),
+            ("total_difficulty", "sum of the difficulties of the chain up to the block"),
+            ("size", "size of the block in bytes"),
+            ("mix_hash", "proof of work mix hash, the beacon randao value after the merge"),
+            ("nonce", "proof of work nonce of the block, 0 after the merge"),
+            ("withdrawals_root", "root of the withdrawals trie, null before shanghai"),
+            ("transaction_count", "number of transactions in the block"),
+        ]
+        .into_iter()
+        .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -112,6 +144,18 @@ pub(crate) fn process_block<TX>(block: Block<TX>, columns: &mut Blocks, schema:
     store!(schema, columns, gas_used, block.header.gas_used);
     store!(schema, columns, gas_limit, block.header.gas_limit);
     store!(schema, columns, extra_data, block.header.extra_data.to_vec());
+    if schema.has_column("extra_data_text") | schema.has_column("miner_tag") {
+        let text = String::from_utf8_lossy(&block.header.extra_data);
+        store!(schema, columns, miner_tag, miner_tag(&text));
+        let printable =
+            !text.contains(char::REPLACEMENT_CHARACTER) && !text.chars().any(|c| c.is_control());
+        store!(
+            schema,
+            columns,
+            extra_data_text,
+            if printable { Some(text.into_owned()) } else { None }
+        );
+    }
     store!(schema, columns, logs_bloom, Some(block.header.logs_bloom.to_vec()));
     store!(schema, columns, timestamp, block.header.timestamp as u32);
     store!(schema, columns, difficulty, block.header.difficulty.wrapping_to::<u64>());
@@ -121,5 +165,32 @@ pub(crate) fn process_block<TX>(block: Block<TX>, columns: &mut Blocks, schema:
     store!(schema, columns, mix_hash, Some(block.header.mix_hash.to_vec()));
     store!(schema, columns, nonce, Some(block.header.nonce.0.to_vec()));
     store!(schema, columns, withdrawals_root, block.header.withdrawals_root.map(|x| x.0.to_vec()));
+    store!(schema, columns, transaction_count, block.transactions.len() as u64);
     Ok(())
 }
+
+/// extra data fragments used by mining pools to sign their blocks
+const MINER_TAGS: [(&str, &str); 13] = [
+    ("ethermine", "ethermine"),
+    ("nanopool", "nanopool"),
+    ("sparkpool", "sparkpool"),
+    ("spark pool", "sparkpool"),
+    ("f2pool", "f2pool"),
+    ("七彩神仙鱼", "f2pool"),
+    ("hiveon", "hiveon"),
+    ("2miners", "2miners"),
+    ("dwarfpool", "dwarfpool"),
+    ("miningpoolhub", "miningpoolhub"),
+    ("flexpool", "flexpool"),
+    ("poolin", "poolin"),
+    ("ezil", "ezil"),
+];
+
+/// identify the mining pool of a block from its extra data
+fn miner_tag(extra_data: &str) -> Option<String> {
+    let extra_data = extra_data.to_lowercase();
+    MINER_TAGS
+        .iter()
+        .find(|(fragment, _)| extra_data.contains(fragment))
+        .map(|(_, tag)| tag.to_string())
+}
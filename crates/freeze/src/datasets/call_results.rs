@@ -0,0 +1,129 @@
+use crate::*;
+use alloy::{
+    dyn_abi::DynSolValue,
+    primitives::{Address, TxKind},
+    rpc::types::{TransactionInput, TransactionRequest},
+};
+use polars::prelude::*;
+
+/// columns for decoded results of a view function called at each block
+#[cryo_to_df::to_df(Datatype::CallResults)]
+#[derive(Default)]
+pub struct CallResults {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    contract_address: Vec<Vec<u8>>,
+    call_data: Vec<Vec<u8>>,
+    output_data: Vec<Option<Vec<u8>>>,
+    output_cols: indexmap::IndexMap<String, Vec<Option<DynSolValue>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for CallResults {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "contract_address", "call_data", "chain_id"])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "contract_address", "call_data"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "return values of a function called at every block, decoded into output__{name} \
+             columns, the function is set with --call-signature and its arguments with \
+             --call-args, output columns are null where the call reverts or its output cannot \
+             be decoded",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("call_data", "selector and abi encoded arguments of the call"),
+            ("output_data", "raw return data of the call, null if the call reverted"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Address, Dim::Contract), (Dim::ToAddress, Dim::Contract)].into_iter().collect())
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Contract, Dim::CallData]
+    }
+}
+
+type CallResultsResponse = (u32, Vec<u8>, Vec<u8>, Option<Vec<u8>>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for CallResults {
+    type Response = CallResultsResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let transaction = TransactionRequest {
+            to: Some(TxKind::Call(request.ethers_contract()?)),
+            input: TransactionInput::new(request.call_data()?.into()),
+            ..Default::default()
+        };
+        let number = request.block_number()?;
+        let output = match source.call(transaction, number).await {
+            Ok(output) => Some(output.to_vec()),
+            Err(e) if is_execution_error(&e) => None,
+            Err(e) => return Err(e),
+        };
+        Self::multicall_response(request, output)
+    }
+
+    fn multicall_request(request: &Params) -> R<(Address, Vec<u8>)> {
+        Ok((request.ethers_contract()?, request.call_data()?))
+    }
+
+    fn multicall_response(request: Params, output: Option<Vec<u8>>) -> R<Self::Response> {
+        Ok((request.block_number()? as u32, request.contract()?, request.call_data()?, output))
+    }
+
+    fn can_multicall() -> bool {
+        true
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::CallResults)?;
+        let decoder = schema
+            .function_decoder
+            .as_ref()
+            .ok_or(err("call_results requires --call-signature"))?;
+        let (block_number, contract_address, call_data, output_data) = response;
+        let values =
+            output_data.as_ref().and_then(|output_data| decoder.decode_output(output_data));
+        match values {
+            Some(values) => {
+                for (name, value) in decoder.output_names().into_iter().zip(values) {
+                    columns.output_cols.entry(name).or_default().push(Some(value));
+                }
+            }
+            None => {
+                for name in decoder.output_names().into_iter() {
+                    columns.output_cols.entry(name).or_default().push(None);
+                }
+            }
+        }
+
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block_number);
+        store!(schema, columns, contract_address, contract_address);
+        store!(schema, columns, call_data, call_data);
+        store!(schema, columns, output_data, output_data);
+        Ok(())
+    }
+}
+
+impl CollectByTransaction for CallResults {
+    type Response = ();
+}
@@ -20,7 +20,11 @@ pub struct CodeDiffs {
 }
 
 #[async_trait::async_trait]
-impl Dataset for CodeDiffs {}
+impl Dataset for CodeDiffs {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address"])
+    }
+}
 
 type BlockTxTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<TraceResults>);
 
@@ -17,7 +17,11 @@ pub struct CodeReads {
 }
 
 #[async_trait::async_trait]
-impl Dataset for CodeReads {}
+impl Dataset for CodeReads {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "contract_address"])
+    }
+}
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<Address, AccountState>>);
 
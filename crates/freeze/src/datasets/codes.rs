@@ -1,5 +1,5 @@
 use crate::*;
-use alloy::primitives::Address;
+use alloy::primitives::{keccak256, Address};
 use polars::prelude::*;
 
 /// columns for balances
@@ -10,6 +10,7 @@ pub struct Codes {
     block_number: Vec<u32>,
     address: Vec<Vec<u8>>,
     code: Vec<Vec<u8>>,
+    code_hash: Vec<Vec<u8>>,
     chain_id: Vec<u64>,
 }
 
@@ -30,6 +31,14 @@ impl Dataset for Codes {
     fn default_blocks() -> Option<String> {
         Some("latest".to_string())
     }
+
+    fn description() -> Option<&'static str> {
+        Some("contract bytecode of addresses at given blocks")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [("code_hash", "keccak256 hash of the code, computed locally")].into_iter().collect()
+    }
 }
 
 type BlockTxAddressOutput = (u32, Option<Vec<u8>>, Vec<u8>, Vec<u8>);
@@ -61,6 +70,9 @@ fn process_code(columns: &mut Codes, data: BlockTxAddressOutput, schema: &Table)
     columns.n_rows += 1;
     store!(schema, columns, block_number, block);
     store!(schema, columns, address, address);
+    if schema.has_column("code_hash") {
+        store!(schema, columns, code_hash, keccak256(&output).to_vec());
+    }
     store!(schema, columns, code, output);
     Ok(())
 }
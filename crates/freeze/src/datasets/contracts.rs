@@ -18,6 +18,7 @@ pub struct Contracts {
     contract_address: Vec<Vec<u8>>,
     deployer: Vec<Vec<u8>>,
     factory: Vec<Vec<u8>>,
+    created_by_factory: Vec<bool>,
     init_code: Vec<Vec<u8>>,
     code: Vec<Vec<u8>>,
     init_code_hash: Vec<Vec<u8>>,
@@ -32,6 +33,31 @@ impl Dataset for Contracts {
     fn default_sort() -> Option<Vec<&'static str>> {
         Some(vec!["block_number", "create_index"])
     }
+
+    fn description() -> Option<&'static str> {
+        Some("contracts created by create traces, one row per contract")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("create_index", "position of the contract among the contracts created in its block"),
+            ("contract_address", "address of the created contract"),
+            ("deployer", "address that sent the transaction creating the contract"),
+            (
+                "factory",
+                "address that executed the create, the deployer unless created by a contract",
+            ),
+            ("created_by_factory", "whether the contract was created by another contract"),
+            ("init_code", "code executed to create the contract"),
+            ("code", "code deployed at the contract address"),
+            ("init_code_hash", "keccak256 hash of the init code"),
+            ("code_hash", "keccak256 hash of the deployed code"),
+            ("n_init_code_bytes", "size of the init code in bytes"),
+            ("n_code_bytes", "size of the deployed code in bytes"),
+        ]
+        .into_iter()
+        .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -96,10 +122,11 @@ pub(crate) fn process_contracts(
             store!(schema, columns, contract_address, result.address.to_vec());
             store!(schema, columns, deployer, deployer.to_vec());
             store!(schema, columns, factory, create.from.to_vec());
+            store!(schema, columns, created_by_factory, !trace.trace.trace_address.is_empty());
             store!(schema, columns, init_code, create.init.to_vec());
             store!(schema, columns, code, result.code.to_vec());
-            store!(schema, columns, init_code_hash, keccak256(result.code.clone()).to_vec());
-            store!(schema, columns, code_hash, keccak256(create.init.clone()).to_vec());
+            store!(schema, columns, init_code_hash, keccak256(create.init.clone()).to_vec());
+            store!(schema, columns, code_hash, keccak256(result.code.clone()).to_vec());
             store!(schema, columns, n_init_code_bytes, create.init.len() as u32);
             store!(schema, columns, n_code_bytes, result.code.len() as u32);
         }
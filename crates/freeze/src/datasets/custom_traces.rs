@@ -0,0 +1,103 @@
+use crate::*;
+use polars::prelude::*;
+
+/// columns for traces of a user supplied tracer
+#[cryo_to_df::to_df(Datatype::CustomTraces)]
+#[derive(Default)]
+pub struct CustomTraces {
+    n_rows: u64,
+    block_number: Vec<Option<u32>>,
+    transaction_index: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    row_index: Vec<u32>,
+    trace_cols: indexmap::IndexMap<String, Vec<serde_json::Value>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for CustomTraces {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "row_index", "chain_id"])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "row_index"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "traces of a built-in or javascript geth tracer set with --tracer, flattened into \
+             columns by the json mapping of --trace-schema, each trace is one row unless the \
+             mapping selects an array of rows",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [("row_index", "index of the row within the trace of its transaction")]
+            .into_iter()
+            .collect()
+    }
+}
+
+type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<serde_json::Value>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for CustomTraces {
+    type Response = BlockTxsTraces;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::CustomTraces)?;
+        let options = trace_decoder(schema)?.tracing_options();
+        let include_txs = schema.has_column("transaction_hash");
+        let block = request.block_number()? as u32;
+        source.geth_debug_trace_block_json(block, include_txs, options).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_custom_traces(response, columns, query)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for CustomTraces {
+    type Response = BlockTxsTraces;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::CustomTraces)?;
+        let options = trace_decoder(schema)?.tracing_options();
+        let include_block_number = schema.has_column("block_number");
+        let tx = request.transaction_hash()?;
+        source.geth_debug_trace_transaction_json(tx, include_block_number, options).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_custom_traces(response, columns, query)
+    }
+}
+
+fn trace_decoder(schema: &Table) -> R<&TraceDecoder> {
+    schema.trace_decoder.as_ref().ok_or(err("custom_traces requires --tracer and --trace-schema"))
+}
+
+fn process_custom_traces(
+    response: BlockTxsTraces,
+    columns: &mut CustomTraces,
+    query: &Arc<Query>,
+) -> R<()> {
+    let schema = query.schemas.get_schema(&Datatype::CustomTraces)?;
+    let decoder = trace_decoder(schema)?;
+    let (block_number, txs, traces) = response;
+    for (transaction_index, (trace, tx)) in traces.iter().zip(txs).enumerate() {
+        for (row_index, row) in decoder.rows(trace).into_iter().enumerate() {
+            for (column, value) in decoder.columns.iter().zip(decoder.extract(row)) {
+                columns.trace_cols.entry(column.name.clone()).or_default().push(value);
+            }
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, transaction_index, Some(transaction_index as u32));
+            store!(schema, columns, transaction_hash, tx.clone());
+            store!(schema, columns, row_index, row_index as u32);
+        }
+    }
+    Ok(())
+}
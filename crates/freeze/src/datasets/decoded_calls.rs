@@ -0,0 +1,156 @@
+use crate::*;
+use alloy::{
+    dyn_abi::DynSolValue,
+    primitives::U256,
+    rpc::types::trace::parity::{Action, LocalizedTransactionTrace},
+};
+use polars::prelude::*;
+
+/// columns for calls decoded with a function abi
+#[cryo_to_df::to_df(Datatype::DecodedCalls)]
+#[derive(Default)]
+pub struct DecodedCalls {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Vec<u8>>,
+    transaction_index: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    trace_address: Vec<String>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Vec<u8>>,
+    value: Vec<U256>,
+    selector: Vec<Vec<u8>>,
+    error: Vec<Option<String>>,
+    input_cols: indexmap::IndexMap<String, Vec<DynSolValue>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for DecodedCalls {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "trace_address",
+            "from_address",
+            "to_address",
+            "value",
+            "error",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "trace_address"])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::FromAddress, Dim::ToAddress]
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "transaction and internal calls decoded with the functions of --abi files, one \
+             output file per function, one row per call",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("trace_address", "position of the call in the call tree, empty for transactions"),
+            ("selector", "4-byte selector of the decoded function"),
+            ("error", "error of the call if it failed"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for DecodedCalls {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let traces = source.trace_block(request.block_number()?).await?;
+        Ok(traces::filter_traces_by_from_to_addresses(
+            traces,
+            &request.from_address,
+            &request.to_address,
+        ))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let traces =
+            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
+        process_decoded_calls(&traces, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for DecodedCalls {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let traces = source.trace_transaction(request.ethers_transaction_hash()?).await?;
+        Ok(traces::filter_traces_by_from_to_addresses(
+            traces,
+            &request.from_address,
+            &request.to_address,
+        ))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let traces =
+            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
+        process_decoded_calls(&traces, columns, &query.schemas)
+    }
+}
+
+/// decode the calls matching the function of the schema into columns
+fn process_decoded_calls(
+    traces: &[LocalizedTransactionTrace],
+    columns: &mut DecodedCalls,
+    schemas: &Schemas,
+) -> R<()> {
+    let schema = schemas.get_schema(&Datatype::DecodedCalls)?;
+    let decoder = schema.function_decoder.as_ref().ok_or(err("decoded_calls requires --abi"))?;
+    let field_names = decoder.field_names();
+    for trace in traces.iter() {
+        let action = match &trace.trace.action {
+            Action::Call(action) => action,
+            _ => continue,
+        };
+        let values = match decoder.decode_input(&action.input) {
+            Some(values) => values,
+            None => continue,
+        };
+        for (name, value) in field_names.iter().zip(values) {
+            columns.input_cols.entry(name.clone()).or_default().push(value);
+        }
+
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, trace.block_number.unwrap_or_default() as u32);
+        store!(schema, columns, block_hash, trace.block_hash.unwrap_or_default().to_vec());
+        store!(schema, columns, transaction_index, trace.transaction_position.map(|x| x as u32));
+        store!(schema, columns, transaction_hash, trace.transaction_hash.map(|x| x.to_vec()));
+        store!(
+            schema,
+            columns,
+            trace_address,
+            trace
+                .trace
+                .trace_address
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<String>>()
+                .join("_")
+        );
+        store!(schema, columns, from_address, action.from.to_vec());
+        store!(schema, columns, to_address, action.to.to_vec());
+        store!(schema, columns, value, action.value);
+        store!(schema, columns, selector, decoder.selector().to_vec());
+        store!(schema, columns, error, trace.trace.error.clone());
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use crate::*;
+use alloy::{dyn_abi::DynSolValue, rpc::types::Log};
+use polars::prelude::*;
+
+/// columns for logs decoded with an event abi
+#[cryo_to_df::to_df(Datatype::DecodedLogs)]
+#[derive(Default)]
+pub struct DecodedLogs {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    address: Vec<Vec<u8>>,
+    topic0: Vec<Vec<u8>>,
+    arg_cols: indexmap::IndexMap<String, Vec<DynSolValue>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for DecodedLogs {
+    fn aliases() -> Vec<&'static str> {
+        vec!["decoded_events"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "log_index",
+            "transaction_hash",
+            "address",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "log_index"])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Address, Dim::Topic1, Dim::Topic2, Dim::Topic3]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "logs decoded with the events of --abi files or --event-signature, one output file \
+             per event, one row per log, one arg__{name} column per event argument",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [("topic0", "event signature hash of the decoded event")].into_iter().collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for DecodedLogs {
+    type Response = Vec<Log>;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::DecodedLogs)?;
+        let decoder = schema.log_decoder.as_ref().ok_or(err("decoded_logs requires --abi"))?;
+        let events = match schema.decoded_events.is_empty() {
+            true => vec![decoder.event.selector()],
+            false => schema.decoded_events.clone(),
+        };
+        let filter = request.ethers_log_filter()?.event_signature(events);
+        source.get_logs(&filter).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_decoded_logs(response, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for DecodedLogs {
+    type Response = Vec<Log>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.get_transaction_logs(request.transaction_hash()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_decoded_logs(response, columns, &query.schemas)
+    }
+}
+
+/// decode the logs matching the event of the schema into columns
+fn process_decoded_logs(logs: Vec<Log>, columns: &mut DecodedLogs, schemas: &Schemas) -> R<()> {
+    let schema = schemas.get_schema(&Datatype::DecodedLogs)?;
+    let decoder = schema.log_decoder.as_ref().ok_or(err("decoded_logs requires --abi"))?;
+    let arg_names = decoder.arg_names();
+    let topic0 = decoder.event.selector();
+    for log in logs.iter() {
+        if log.topics().first() != Some(&topic0) {
+            continue
+        }
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let values = match decoder.decode_args(log) {
+                Some(values) => values,
+                None => continue,
+            };
+            for (name, value) in arg_names.iter().zip(values) {
+                columns.arg_cols.entry(name.clone()).or_default().push(value);
+            }
+
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn as u32);
+            store!(schema, columns, block_hash, log.block_hash.map(|bh| bh.to_vec()));
+            store!(schema, columns, transaction_index, ti as u32);
+            store!(schema, columns, log_index, li as u32);
+            store!(schema, columns, transaction_hash, tx.to_vec());
+            store!(schema, columns, address, log.address().to_vec());
+            store!(schema, columns, topic0, topic0.to_vec());
+        }
+    }
+    Ok(())
+}
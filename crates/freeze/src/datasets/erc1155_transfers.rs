@@ -0,0 +1,182 @@
+use crate::*;
+use alloy::{
+    primitives::{B256, U256},
+    rpc::types::{Filter, Log, Topic},
+    sol_types::SolEvent,
+};
+use polars::prelude::*;
+
+/// columns for erc1155 transfers, one row per transferred (token_id, amount)
+#[cryo_to_df::to_df(Datatype::Erc1155Transfers)]
+#[derive(Default)]
+pub struct Erc1155Transfers {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    erc1155: Vec<Vec<u8>>,
+    operator: Vec<Vec<u8>>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Vec<u8>>,
+    token_id: Vec<U256>,
+    amount: Vec<U256>,
+    batch_index: Vec<u32>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Erc1155Transfers {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "log_index",
+            "transaction_hash",
+            "erc1155",
+            "operator",
+            "from_address",
+            "to_address",
+            "token_id",
+            "amount",
+            "batch_index",
+            "chain_id",
+        ])
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Address, Dim::FromAddress, Dim::ToAddress]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("erc1155 transfers, one row per token id of each TransferSingle or TransferBatch")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("erc1155", "address of the token contract"),
+            ("operator", "address approved to make the transfer"),
+            (
+                "batch_index",
+                "position of the token id within a TransferBatch, 0 for TransferSingle",
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "log_index", "batch_index"])
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for Erc1155Transfers {
+    type Response = Vec<Log>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let logs = source.get_logs(&erc1155_transfer_filter(&request)?).await?;
+
+        Ok(logs.into_iter().filter(is_erc1155_transfer).collect())
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Erc1155Transfers)?;
+        process_erc1155_transfers(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Erc1155Transfers {
+    type Response = Vec<Log>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let logs = source.get_transaction_logs(request.transaction_hash()?).await?;
+        Ok(logs.into_iter().filter(is_erc1155_transfer).collect())
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Erc1155Transfers)?;
+        process_erc1155_transfers(response, columns, schema)
+    }
+}
+
+/// eth_getLogs filter of the transfers of request, from and to addresses are topics 2 and 3
+pub(crate) fn erc1155_transfer_filter(request: &Params) -> R<Filter> {
+    let mut topics: [Topic; 4] = Default::default();
+    topics[0] =
+        vec![ERC1155::TransferSingle::SIGNATURE_HASH, ERC1155::TransferBatch::SIGNATURE_HASH]
+            .into();
+    if let Some(from_address) = &request.from_address {
+        let v = B256::from_slice(from_address);
+        topics[2] = v.into();
+    };
+    if let Some(to_address) = &request.to_address {
+        let v = B256::from_slice(to_address);
+        topics[3] = v.into();
+    };
+    Ok(Filter { topics, ..request.ethers_log_filter()? })
+}
+
+pub(crate) fn is_erc1155_transfer(log: &Log) -> bool {
+    log.topics().len() == 4 &&
+        (log.topics()[0] == ERC1155::TransferSingle::SIGNATURE_HASH ||
+            log.topics()[0] == ERC1155::TransferBatch::SIGNATURE_HASH)
+}
+
+/// decode transfer log into (token_id, amount) pairs, batches are exploded in order
+fn decode_transfers(log: &Log) -> Option<Vec<(U256, U256)>> {
+    if log.topics()[0] == ERC1155::TransferSingle::SIGNATURE_HASH {
+        let event = ERC1155::TransferSingle::decode_log_data(log.data(), true).ok()?;
+        Some(vec![(event.id, event.value)])
+    } else {
+        let event = ERC1155::TransferBatch::decode_log_data(log.data(), true).ok()?;
+        if event.ids.len() != event.values.len() {
+            return None
+        }
+        Some(event.ids.into_iter().zip(event.values).collect())
+    }
+}
+
+/// process block into columns
+pub(crate) fn process_erc1155_transfers(
+    logs: Vec<Log>,
+    columns: &mut Erc1155Transfers,
+    schema: &Table,
+) -> R<()> {
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let transfers = match decode_transfers(log) {
+                Some(transfers) => transfers,
+                None => continue,
+            };
+            for (batch_index, (token_id, amount)) in transfers.into_iter().enumerate() {
+                columns.n_rows += 1;
+                store!(schema, columns, block_number, bn as u32);
+                store!(schema, columns, block_hash, log.block_hash.map(|bh| bh.to_vec()));
+                store!(schema, columns, transaction_index, ti as u32);
+                store!(schema, columns, log_index, li as u32);
+                store!(schema, columns, transaction_hash, tx.to_vec());
+                store!(schema, columns, erc1155, log.address().to_vec());
+                store!(schema, columns, operator, log.topics()[1][12..].to_vec());
+                store!(schema, columns, from_address, log.topics()[2][12..].to_vec());
+                store!(schema, columns, to_address, log.topics()[3][12..].to_vec());
+                store!(schema, columns, token_id, token_id);
+                store!(schema, columns, amount, amount);
+                store!(schema, columns, batch_index, batch_index as u32);
+            }
+        }
+    }
+    Ok(())
+}
@@ -58,19 +58,7 @@ impl CollectByBlock for Erc20Approvals {
     type Response = Vec<Log>;
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let mut topics: [Topic; 4] = Default::default();
-        topics[0] = ERC20::Approval::SIGNATURE_HASH.into();
-        if let Some(from_address) = &request.from_address {
-            let v = B256::from_slice(from_address);
-            topics[1] = v.into();
-        }
-        if let Some(to_address) = &request.to_address {
-            let v = B256::from_slice(to_address);
-            topics[2] = v.into();
-        }
-        let filter = Filter { topics, ..request.ethers_log_filter()? };
-
-        let logs = source.get_logs(&filter).await?;
+        let logs = source.get_logs(&erc20_approval_filter(&request)?).await?;
 
         Ok(logs
             .into_iter()
@@ -94,13 +82,32 @@ impl CollectByTransaction for Erc20Approvals {
     }
 }
 
-fn is_erc20_approval(log: &Log) -> bool {
+/// eth_getLogs filter of the approvals of request, owner and spender addresses are topics 1 and 2
+pub(crate) fn erc20_approval_filter(request: &Params) -> R<Filter> {
+    let mut topics: [Topic; 4] = Default::default();
+    topics[0] = ERC20::Approval::SIGNATURE_HASH.into();
+    if let Some(from_address) = &request.from_address {
+        let v = B256::from_slice(from_address);
+        topics[1] = v.into();
+    }
+    if let Some(to_address) = &request.to_address {
+        let v = B256::from_slice(to_address);
+        topics[2] = v.into();
+    }
+    Ok(Filter { topics, ..request.ethers_log_filter()? })
+}
+
+pub(crate) fn is_erc20_approval(log: &Log) -> bool {
     log.topics().len() == 3 &&
         log.data().data.len() == 32 &&
         log.topics()[0] == ERC20::Approval::SIGNATURE_HASH
 }
 
-fn process_erc20_approval(logs: Vec<Log>, columns: &mut Erc20Approvals, schema: &Table) -> R<()> {
+pub(crate) fn process_erc20_approval(
+    logs: Vec<Log>,
+    columns: &mut Erc20Approvals,
+    schema: &Table,
+) -> R<()> {
     for log in logs.iter() {
         if let (Some(bn), Some(tx), Some(ti), Some(li)) =
             (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
@@ -1,5 +1,8 @@
 use crate::*;
-use alloy::{primitives::U256, sol_types::SolCall};
+use alloy::{
+    primitives::{Address, U256},
+    sol_types::SolCall,
+};
 use polars::prelude::*;
 
 /// columns for transactions
@@ -23,6 +26,10 @@ impl Dataset for Erc20Balances {
     fn required_parameters() -> Vec<Dim> {
         vec![Dim::Contract, Dim::Address]
     }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "erc20", "address"])
+    }
 }
 
 #[async_trait::async_trait]
@@ -30,17 +37,31 @@ impl CollectByBlock for Erc20Balances {
     type Response = (u32, Vec<u8>, Vec<u8>, Option<U256>);
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let (contract, call_data) = Self::multicall_request(&request)?;
+        let block_number = request.ethers_block_number()?;
+        let output = source.call2(contract, call_data, block_number).await.ok();
+        Self::multicall_response(request, output.map(|x| x.to_vec()))
+    }
+
+    fn multicall_request(request: &Params) -> R<(Address, Vec<u8>)> {
         let signature = ERC20::balanceOfCall::SELECTOR;
         let mut call_data = signature.clone().to_vec();
         call_data.extend(vec![0; 12]);
         call_data.extend(request.address()?);
-        let block_number = request.ethers_block_number()?;
-        let contract = request.ethers_contract()?;
-        let balance = source.call2(contract, call_data, block_number).await.ok();
-        let balance = balance.map(|x| U256::from_be_slice(x.as_ref()));
+        Ok((request.ethers_contract()?, call_data))
+    }
+
+    fn multicall_response(request: Params, output: Option<Vec<u8>>) -> R<Self::Response> {
+        let balance = output
+            .and_then(|x| ERC20::balanceOfCall::abi_decode_returns(&x, false).ok())
+            .map(|x| x._0);
         Ok((request.block_number()? as u32, request.contract()?, request.address()?, balance))
     }
 
+    fn can_multicall() -> bool {
+        true
+    }
+
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Erc20Balances)?;
         let (block, erc20, address, balance) = response;
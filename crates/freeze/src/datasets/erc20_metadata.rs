@@ -1,5 +1,8 @@
 use crate::*;
-use alloy::sol_types::SolCall;
+use alloy::{
+    primitives::U256,
+    sol_types::{sol_data, SolCall, SolType},
+};
 use polars::prelude::*;
 
 /// columns for transactions
@@ -12,6 +15,7 @@ pub struct Erc20Metadata {
     name: Vec<Option<String>>,
     symbol: Vec<Option<String>>,
     decimals: Vec<Option<u32>>,
+    total_supply: Vec<Option<U256>>,
     chain_id: Vec<u64>,
 }
 
@@ -32,36 +36,50 @@ impl Dataset for Erc20Metadata {
     fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
         Some([(Dim::Contract, Dim::Address)].into_iter().collect())
     }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "erc20"])
+    }
 }
 
-pub(crate) fn remove_control_characters(s: &str) -> String {
-    let re = regex::Regex::new(r"[ \x00-\x1F\x7F]").unwrap();
+fn remove_control_characters(s: &str) -> String {
+    let re = regex::Regex::new(r"[\x00-\x1F\x7F]").unwrap();
     re.replace_all(s, "").to_string()
 }
 
+/// decode output of a string getter, including legacy tokens that return bytes32 (e.g. MKR)
+pub(crate) fn decode_string_output(output: &[u8]) -> Option<String> {
+    let decoded = match sol_data::String::abi_decode(output, true) {
+        Ok(s) => s,
+        Err(_) if output.len() == 32 => {
+            let end = output.iter().rposition(|b| *b != 0).map(|i| i + 1).unwrap_or(0);
+            String::from_utf8(output[..end].to_vec()).ok()?
+        }
+        Err(_) => return None,
+    };
+    Some(remove_control_characters(&decoded).trim().to_string())
+}
+
 #[async_trait::async_trait]
 impl CollectByBlock for Erc20Metadata {
-    type Response = (u32, Vec<u8>, Option<String>, Option<String>, Option<u32>);
+    type Response = (u32, Vec<u8>, Option<String>, Option<String>, Option<u32>, Option<U256>);
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::Erc20Metadata)?;
         let block_number = request.ethers_block_number()?;
         let address = request.ethers_address()?;
 
         // name
         let call_data = ERC20::nameCall::SELECTOR.to_vec();
         let name = match source.call2(address, call_data, block_number).await {
-            Ok(output) => {
-                String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s))
-            }
+            Ok(output) => decode_string_output(&output),
             Err(_) => None,
         };
 
         // symbol
         let call_data = ERC20::symbolCall::SELECTOR.to_vec();
         let symbol = match source.call2(address, call_data, block_number).await {
-            Ok(output) => {
-                String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s))
-            }
+            Ok(output) => decode_string_output(&output),
             Err(_) => None,
         };
 
@@ -72,18 +90,33 @@ impl CollectByBlock for Erc20Metadata {
             Err(_) => None,
         };
 
-        Ok((request.block_number()? as u32, request.address()?, name, symbol, decimals))
+        // total supply
+        let total_supply = if schema.has_column("total_supply") {
+            let call_data = ERC20::totalSupplyCall::SELECTOR.to_vec();
+            match source.call2(address, call_data, block_number).await {
+                Ok(output) => {
+                    ERC20::totalSupplyCall::abi_decode_returns(&output, true).ok().map(|x| x._0)
+                }
+                Err(_) => None,
+            }
+        } else {
+            None
+        };
+
+        let block_number = request.block_number()? as u32;
+        Ok((block_number, request.address()?, name, symbol, decimals, total_supply))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Erc20Metadata)?;
-        let (block, address, name, symbol, decimals) = response;
+        let (block, address, name, symbol, decimals, total_supply) = response;
         columns.n_rows += 1;
         store!(schema, columns, block_number, block);
         store!(schema, columns, erc20, address);
         store!(schema, columns, name, name);
         store!(schema, columns, symbol, symbol);
         store!(schema, columns, decimals, decimals);
+        store!(schema, columns, total_supply, total_supply);
         Ok(())
     }
 }
@@ -91,3 +124,20 @@ impl CollectByBlock for Erc20Metadata {
 impl CollectByTransaction for Erc20Metadata {
     type Response = ();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_string_output() {
+        let output = sol_data::String::abi_encode(&"Wrapped Ether".to_string());
+        assert_eq!(decode_string_output(&output), Some("Wrapped Ether".to_string()));
+
+        let mut output = b"MKR".to_vec();
+        output.resize(32, 0);
+        assert_eq!(decode_string_output(&output), Some("MKR".to_string()));
+
+        assert_eq!(decode_string_output(&[1, 2, 3]), None);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::*;
-use alloy::{primitives::U256, sol_types::SolCall};
+use alloy::{
+    primitives::{Address, U256},
+    sol_types::SolCall,
+};
 use polars::prelude::*;
 
-/// columns for transactions
+/// columns for erc20 total supplies
 #[cryo_to_df::to_df(Datatype::Erc20Supplies)]
 #[derive(Default)]
 pub struct Erc20Supplies {
@@ -30,6 +33,13 @@ impl Dataset for Erc20Supplies {
     fn default_blocks() -> Option<String> {
         Some("latest".to_string())
     }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "totalSupply() of each erc20 at each block, null where the call reverts or returns \
+             malformed data, calls can be batched with --multicall",
+        )
+    }
 }
 
 #[async_trait::async_trait]
@@ -37,14 +47,25 @@ impl CollectByBlock for Erc20Supplies {
     type Response = (u32, Vec<u8>, Option<U256>);
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let signature: Vec<u8> = ERC20::totalSupplyCall::SELECTOR.to_vec();
-        let mut call_data = signature.clone();
-        call_data.extend(request.address()?);
+        let (contract, call_data) = Self::multicall_request(&request)?;
         let block_number = request.ethers_block_number()?;
-        let contract = request.ethers_address()?;
         let output = source.call2(contract, call_data, block_number).await.ok();
-        let output = output.map(|x| U256::from_be_slice(x.as_ref()));
-        Ok((request.block_number()? as u32, request.address()?, output))
+        Self::multicall_response(request, output.map(|x| x.to_vec()))
+    }
+
+    fn multicall_request(request: &Params) -> R<(Address, Vec<u8>)> {
+        Ok((request.ethers_address()?, ERC20::totalSupplyCall {}.abi_encode()))
+    }
+
+    fn multicall_response(request: Params, output: Option<Vec<u8>>) -> R<Self::Response> {
+        let total_supply = output
+            .and_then(|x| ERC20::totalSupplyCall::abi_decode_returns(&x, false).ok())
+            .map(|x| x._0);
+        Ok((request.block_number()? as u32, request.address()?, total_supply))
+    }
+
+    fn can_multicall() -> bool {
+        true
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
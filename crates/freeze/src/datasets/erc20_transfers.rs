@@ -51,6 +51,21 @@ impl Dataset for Erc20Transfers {
     fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
         Some([(Dim::Contract, Dim::Address)].into_iter().collect())
     }
+
+    fn description() -> Option<&'static str> {
+        Some("erc20 Transfer events, one row per transfer")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("erc20", "address of the erc20 token contract"),
+            ("from_address", "address the tokens were transferred from"),
+            ("to_address", "address the tokens were transferred to"),
+            ("value", "amount of tokens transferred, in base units of the token"),
+        ]
+        .into_iter()
+        .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -58,18 +73,7 @@ impl CollectByBlock for Erc20Transfers {
     type Response = Vec<Log>;
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let mut topics: [Topic; 4] = Default::default();
-        topics[0] = ERC20::Transfer::SIGNATURE_HASH.into();
-        if let Some(from_address) = &request.from_address {
-            let v = B256::from_slice(from_address);
-            topics[1] = v.into();
-        }
-        if let Some(to_address) = &request.to_address {
-            let v = B256::from_slice(to_address);
-            topics[2] = v.into();
-        }
-        let filter = Filter { topics, ..request.ethers_log_filter()? };
-        let logs = source.get_logs(&filter).await?;
+        let logs = source.get_logs(&erc20_transfer_filter(&request)?).await?;
 
         Ok(logs
             .into_iter()
@@ -98,14 +102,33 @@ impl CollectByTransaction for Erc20Transfers {
     }
 }
 
-fn is_erc20_transfer(log: &Log) -> bool {
+/// eth_getLogs filter of the transfers of request, from and to addresses are topics 1 and 2
+pub(crate) fn erc20_transfer_filter(request: &Params) -> R<Filter> {
+    let mut topics: [Topic; 4] = Default::default();
+    topics[0] = ERC20::Transfer::SIGNATURE_HASH.into();
+    if let Some(from_address) = &request.from_address {
+        let v = B256::from_slice(from_address);
+        topics[1] = v.into();
+    }
+    if let Some(to_address) = &request.to_address {
+        let v = B256::from_slice(to_address);
+        topics[2] = v.into();
+    }
+    Ok(Filter { topics, ..request.ethers_log_filter()? })
+}
+
+pub(crate) fn is_erc20_transfer(log: &Log) -> bool {
     log.topics().len() == 3 &&
         log.data().data.len() == 32 &&
-        log.topics()[0] == ERC20::Approval::SIGNATURE_HASH
+        log.topics()[0] == ERC20::Transfer::SIGNATURE_HASH
 }
 
 /// process block into columns
-fn process_erc20_transfers(logs: Vec<Log>, columns: &mut Erc20Transfers, schema: &Table) -> R<()> {
+pub(crate) fn process_erc20_transfers(
+    logs: Vec<Log>,
+    columns: &mut Erc20Transfers,
+    schema: &Table,
+) -> R<()> {
     for log in logs.iter() {
         if let (Some(bn), Some(tx), Some(ti), Some(li)) =
             (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
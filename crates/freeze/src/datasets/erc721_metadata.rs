@@ -1,4 +1,4 @@
-use super::erc20_metadata::remove_control_characters;
+use super::erc20_metadata::decode_string_output;
 use crate::*;
 use alloy::sol_types::SolCall;
 use polars::prelude::*;
@@ -27,6 +27,10 @@ impl Dataset for Erc721Metadata {
     fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
         Some([(Dim::Contract, Dim::Address)].into_iter().collect())
     }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "erc721"])
+    }
 }
 
 #[async_trait::async_trait]
@@ -40,18 +44,14 @@ impl CollectByBlock for Erc721Metadata {
         // name
         let call_data = ERC721::nameCall::SELECTOR.to_vec();
         let name = match source.call2(address, call_data, block_number).await {
-            Ok(output) => {
-                String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s))
-            }
+            Ok(output) => decode_string_output(&output),
             Err(_) => None,
         };
 
         // symbol
         let call_data = ERC721::symbolCall::SELECTOR.to_vec();
         let symbol = match source.call2(address, call_data, block_number).await {
-            Ok(output) => {
-                String::from_utf8(output.to_vec()).ok().map(|s| remove_control_characters(&s))
-            }
+            Ok(output) => decode_string_output(&output),
             Err(_) => None,
         };
 
@@ -58,18 +58,7 @@ impl CollectByBlock for Erc721Transfers {
     type Response = Vec<Log>;
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let mut topics: [Topic; 4] = Default::default();
-        topics[0] = ERC721::Transfer::SIGNATURE_HASH.into();
-        if let Some(from_address) = &request.from_address {
-            let v = B256::from_slice(from_address);
-            topics[1] = v.into();
-        };
-        if let Some(to_address) = &request.to_address {
-            let v = B256::from_slice(to_address);
-            topics[2] = v.into();
-        };
-        let filter = Filter { topics, ..request.ethers_log_filter()? };
-        let logs = source.get_logs(&filter).await?;
+        let logs = source.get_logs(&erc721_transfer_filter(&request)?).await?;
 
         Ok(logs.into_iter().filter(|x| x.topics().len() == 4 && x.data().data.len() == 0).collect())
     }
@@ -95,14 +84,29 @@ impl CollectByTransaction for Erc721Transfers {
     }
 }
 
-fn is_erc721_transfer(log: &Log) -> bool {
+/// eth_getLogs filter of the transfers of request, from and to addresses are topics 1 and 2
+pub(crate) fn erc721_transfer_filter(request: &Params) -> R<Filter> {
+    let mut topics: [Topic; 4] = Default::default();
+    topics[0] = ERC721::Transfer::SIGNATURE_HASH.into();
+    if let Some(from_address) = &request.from_address {
+        let v = B256::from_slice(from_address);
+        topics[1] = v.into();
+    };
+    if let Some(to_address) = &request.to_address {
+        let v = B256::from_slice(to_address);
+        topics[2] = v.into();
+    };
+    Ok(Filter { topics, ..request.ethers_log_filter()? })
+}
+
+pub(crate) fn is_erc721_transfer(log: &Log) -> bool {
     log.topics().len() == 4 &&
         log.data().data.len() == 0 &&
         log.topics()[0] == ERC721::Transfer::SIGNATURE_HASH
 }
 
 /// process block into columns
-fn process_erc721_transfers(
+pub(crate) fn process_erc721_transfers(
     logs: Vec<Log>,
     columns: &mut Erc721Transfers,
     schema: &Table,
@@ -21,6 +21,10 @@ impl Dataset for FourByteCounts {
     fn aliases() -> Vec<&'static str> {
         vec!["4byte_counts"]
     }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "signature", "size"])
+    }
 }
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<String, u64>>);
@@ -0,0 +1,80 @@
+use crate::*;
+use alloy::rpc::types::{Block, BlockTransactionsKind, TransactionReceipt};
+use polars::prelude::*;
+
+/// columns for per-block gas price percentiles
+#[cryo_to_df::to_df(Datatype::GasPercentiles)]
+#[derive(Default)]
+pub struct GasPercentiles {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    timestamp: Vec<u32>,
+    percentile: Vec<f64>,
+    effective_gas_price: Vec<Option<u64>>,
+    priority_fee: Vec<Option<u64>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GasPercentiles {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "percentile"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "percentiles of gas prices paid in each block, one row per (block, percentile), \
+             percentiles are set with --gas-percentiles",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("percentile", "percentile in [0, 100] over the transactions of the block"),
+            ("effective_gas_price", "effective gas price at the percentile, null if no txs"),
+            ("priority_fee", "priority fee per gas above the base fee at the percentile"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for GasPercentiles {
+    type Response = (Block, Vec<TransactionReceipt>);
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let block = source
+            .get_block(block_number, BlockTransactionsKind::Hashes)
+            .await?
+            .ok_or(CollectError::CollectError("block not found".to_string()))?;
+        let receipts = source.get_receipts_by_block_number(block_number).await?;
+        Ok((block, receipts))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::GasPercentiles)?;
+        let (block, receipts) = response;
+        let base_fee = block.header.base_fee_per_gas;
+        let priority_fees = gas_stats::sorted_priority_fees(&receipts, base_fee);
+        let mut gas_prices: Vec<u64> =
+            receipts.iter().map(|receipt| receipt.effective_gas_price as u64).collect();
+        gas_prices.sort_unstable();
+
+        for p in query.gas_percentiles.iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block.header.number as u32);
+            store!(schema, columns, timestamp, block.header.timestamp as u32);
+            store!(schema, columns, percentile, *p);
+            store!(schema, columns, effective_gas_price, percentile(&gas_prices, p / 100.0));
+            store!(schema, columns, priority_fee, percentile(&priority_fees, p / 100.0));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GasPercentiles {
+    type Response = ();
+}
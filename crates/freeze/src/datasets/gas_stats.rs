@@ -0,0 +1,120 @@
+use crate::*;
+use alloy::{
+    primitives::U256,
+    rpc::types::{Block, BlockTransactionsKind, TransactionReceipt},
+};
+use polars::prelude::*;
+
+/// columns for per-block gas statistics
+#[cryo_to_df::to_df(Datatype::GasStats)]
+#[derive(Default)]
+pub struct GasStats {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    timestamp: Vec<u32>,
+    n_transactions: Vec<u32>,
+    gas_used: Vec<u64>,
+    gas_limit: Vec<u64>,
+    base_fee_per_gas: Vec<Option<u64>>,
+    min_priority_fee: Vec<Option<u64>>,
+    median_priority_fee: Vec<Option<u64>>,
+    max_priority_fee: Vec<Option<u64>>,
+    total_burned: Vec<U256>,
+    total_tips: Vec<U256>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GasStats {
+    fn description() -> Option<&'static str> {
+        Some("gas and fee aggregates of each block computed from receipts, one row per block")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("min_priority_fee", "lowest priority fee per gas paid above the base fee"),
+            ("median_priority_fee", "median priority fee per gas paid above the base fee"),
+            ("max_priority_fee", "highest priority fee per gas paid above the base fee"),
+            (
+                "total_burned",
+                "wei burned by the base fee and the blob base fee, base_fee_per_gas * gas_used + \
+                 blob_gas_price * blob_gas_used",
+            ),
+            ("total_tips", "wei paid to the block producer above the base fee"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for GasStats {
+    type Response = (Block, Vec<TransactionReceipt>);
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let block = source
+            .get_block(block_number, BlockTransactionsKind::Hashes)
+            .await?
+            .ok_or(CollectError::CollectError("block not found".to_string()))?;
+        let receipts = source.get_receipts_by_block_number(block_number).await?;
+        Ok((block, receipts))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::GasStats)?;
+        let (block, receipts) = response;
+        let base_fee = block.header.base_fee_per_gas;
+        let priority_fees = sorted_priority_fees(&receipts, base_fee);
+        let total_tips = receipts.iter().fold(U256::ZERO, |total, receipt| {
+            let tip = priority_fee(receipt, base_fee) as u128 * receipt.gas_used;
+            total + U256::from(tip)
+        });
+        let blob_burned = receipts.iter().fold(U256::ZERO, |total, receipt| {
+            match (receipt.blob_gas_used, receipt.blob_gas_price) {
+                (Some(used), Some(price)) => total + U256::from(used) * U256::from(price),
+                _ => total,
+            }
+        });
+
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block.header.number as u32);
+        store!(schema, columns, timestamp, block.header.timestamp as u32);
+        store!(schema, columns, n_transactions, receipts.len() as u32);
+        store!(schema, columns, gas_used, block.header.gas_used);
+        store!(schema, columns, gas_limit, block.header.gas_limit);
+        store!(schema, columns, base_fee_per_gas, base_fee);
+        store!(schema, columns, min_priority_fee, priority_fees.first().copied());
+        store!(schema, columns, median_priority_fee, percentile(&priority_fees, 0.5));
+        store!(schema, columns, max_priority_fee, priority_fees.last().copied());
+        store!(
+            schema,
+            columns,
+            total_burned,
+            U256::from(base_fee.unwrap_or(0)) * U256::from(block.header.gas_used) + blob_burned
+        );
+        store!(schema, columns, total_tips, total_tips);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GasStats {
+    type Response = ();
+}
+
+/// priority fee per gas paid by a transaction, its effective gas price above the base fee
+pub(crate) fn priority_fee(receipt: &TransactionReceipt, base_fee: Option<u64>) -> u64 {
+    (receipt.effective_gas_price as u64).saturating_sub(base_fee.unwrap_or(0))
+}
+
+/// priority fees per gas of all transactions of a block, in ascending order
+pub(crate) fn sorted_priority_fees(
+    receipts: &[TransactionReceipt],
+    base_fee: Option<u64>,
+) -> Vec<u64> {
+    let mut fees: Vec<u64> =
+        receipts.iter().map(|receipt| priority_fee(receipt, base_fee)).collect();
+    fees.sort_unstable();
+    fees
+}
@@ -0,0 +1,117 @@
+use crate::*;
+use alloy::primitives::{Address, U256};
+use polars::prelude::*;
+use std::str::FromStr;
+
+/// columns for balances allocated at genesis
+#[cryo_to_df::to_df(Datatype::GenesisBalances)]
+#[derive(Default)]
+pub struct GenesisBalances {
+    n_rows: u64,
+    address: Vec<Vec<u8>>,
+    balance: Vec<U256>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for GenesisBalances {
+    fn aliases() -> Vec<&'static str> {
+        vec!["genesis"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["address"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("0".to_string())
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("balances allocated at genesis, one row per account")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [("balance", "native balance allocated to the address at genesis, in wei")]
+            .into_iter()
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for GenesisBalances {
+    type Response = Vec<(Address, U256)>;
+
+    /// read allocation from --genesis-file if given, otherwise dump genesis state of the node
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        if request.block_number()? != 0 {
+            return Ok(vec![])
+        }
+        match &query.genesis_file {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)
+                    .map_err(|_| err(format!("could not read genesis file {}", path).as_str()))?;
+                let spec: serde_json::Value = serde_json::from_str(&contents)
+                    .map_err(|_| err(format!("could not parse genesis file {}", path).as_str()))?;
+                parse_allocation(&spec["alloc"])
+            }
+            None => parse_allocation(&source.geth_debug_dump_block(0).await?["accounts"]),
+        }
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::GenesisBalances)?;
+        for (address, balance) in response.into_iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, address, address.to_vec());
+            store!(schema, columns, balance, balance);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for GenesisBalances {
+    type Response = ();
+}
+
+/// parse map of address to account, balances can be decimal or 0x-prefixed hex strings
+fn parse_allocation(accounts: &serde_json::Value) -> R<Vec<(Address, U256)>> {
+    let accounts = accounts.as_object().ok_or(err("genesis allocation not found"))?;
+    let mut allocation = Vec::with_capacity(accounts.len());
+    for (address, account) in accounts.iter() {
+        let address = Address::from_str(address)
+            .map_err(|_| err(format!("invalid genesis address {}", address).as_str()))?;
+        let balance = match &account["balance"] {
+            serde_json::Value::String(balance) => U256::from_str(balance)
+                .map_err(|_| err(format!("invalid genesis balance {}", balance).as_str()))?,
+            serde_json::Value::Number(balance) => U256::from_str(&balance.to_string())
+                .map_err(|_| err(format!("invalid genesis balance {}", balance).as_str()))?,
+            _ => U256::ZERO,
+        };
+        allocation.push((address, balance));
+    }
+    Ok(allocation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_allocation() {
+        let spec = serde_json::json!({
+            "alloc": {
+                "0x000d836201318ec6899a67540690382780743280": {"balance": "200000000000000000000"},
+                "001762430ea9c3a26e5749afdb70da5f78ddbb8c": {"balance": "0x3635c9adc5dea00000"},
+            }
+        });
+        let allocation: std::collections::HashMap<Address, U256> =
+            parse_allocation(&spec["alloc"]).unwrap().into_iter().collect();
+        let ether = U256::from(10).pow(U256::from(18));
+        let first = Address::from_str("0x000d836201318ec6899a67540690382780743280").unwrap();
+        let second = Address::from_str("0x001762430ea9c3a26e5749afdb70da5f78ddbb8c").unwrap();
+        assert_eq!(allocation[&first], U256::from(200) * ether);
+        assert_eq!(allocation[&second], U256::from(1000) * ether);
+    }
+}
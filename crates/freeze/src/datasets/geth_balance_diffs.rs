@@ -17,7 +17,11 @@ pub struct GethBalanceDiffs {
 }
 
 #[async_trait::async_trait]
-impl Dataset for GethBalanceDiffs {}
+impl Dataset for GethBalanceDiffs {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address"])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethBalanceDiffs {
@@ -1,5 +1,7 @@
 use crate::*;
-use alloy::{primitives::U256, rpc::types::trace::geth::CallFrame};
+use alloy::{
+    primitives::U256, rpc::types::trace::geth::CallFrame, sol_types::decode_revert_reason,
+};
 use polars::prelude::*;
 
 /// columns for geth traces
@@ -16,6 +18,7 @@ pub struct GethCalls {
     input: Vec<Vec<u8>>,
     output: Vec<Option<Vec<u8>>>,
     error: Vec<Option<String>>,
+    revert_reason: Vec<Option<String>>,
     block_number: Vec<Option<u32>>,
     transaction_hash: Vec<Option<Vec<u8>>>,
     transaction_index: Vec<u32>,
@@ -24,7 +27,15 @@ pub struct GethCalls {
 }
 
 #[async_trait::async_trait]
-impl Dataset for GethCalls {}
+impl Dataset for GethCalls {
+    fn aliases() -> Vec<&'static str> {
+        vec!["geth_traces"]
+    }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "trace_address"])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethCalls {
@@ -89,8 +100,13 @@ fn process_trace(
     store!(schema, columns, gas, trace.gas);
     store!(schema, columns, gas_used, trace.gas_used);
     store!(schema, columns, input, trace.input.0.to_vec());
+    let revert_reason = match (&trace.error, &trace.output) {
+        (Some(_), Some(output)) => trace.revert_reason.or_else(|| decode_revert_reason(output)),
+        _ => None,
+    };
     store!(schema, columns, output, trace.output.map(|x| x.0.to_vec()));
     store!(schema, columns, error, trace.error);
+    store!(schema, columns, revert_reason, revert_reason);
     store!(schema, columns, block_number, *block_number);
     store!(schema, columns, transaction_hash, tx.clone());
     store!(schema, columns, transaction_index, tx_index);
@@ -16,7 +16,11 @@ pub struct GethCodeDiffs {
 }
 
 #[async_trait::async_trait]
-impl Dataset for GethCodeDiffs {}
+impl Dataset for GethCodeDiffs {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address"])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethCodeDiffs {
@@ -16,7 +16,11 @@ pub struct GethNonceDiffs {
 }
 
 #[async_trait::async_trait]
-impl Dataset for GethNonceDiffs {}
+impl Dataset for GethNonceDiffs {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address"])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethNonceDiffs {
@@ -16,7 +16,11 @@ pub struct GethStorageDiffs {
     pub(crate) chain_id: Vec<u64>,
 }
 
-impl Dataset for GethStorageDiffs {}
+impl Dataset for GethStorageDiffs {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address", "slot"])
+    }
+}
 
 #[async_trait::async_trait]
 impl CollectByBlock for GethStorageDiffs {
@@ -23,6 +23,7 @@ pub struct Logs {
     data: Vec<Vec<u8>>,
     n_data_bytes: Vec<u32>,
     event_cols: indexmap::IndexMap<String, Vec<DynSolValue>>,
+    global_index: Vec<u64>,
     chain_id: Vec<u64>,
 }
 
@@ -47,6 +48,7 @@ impl Dataset for Logs {
             "data",
             "n_data_bytes",
             // "event_cols",
+            // "global_index",
             "chain_id",
         ])
     }
@@ -62,6 +64,25 @@ impl Dataset for Logs {
     fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
         Some([(Dim::Contract, Dim::Address)].into_iter().collect())
     }
+
+    fn description() -> Option<&'static str> {
+        Some("event logs emitted by contracts, one row per log")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("topic0", "first topic of the log, usually the event signature hash"),
+            ("address", "address of the contract that emitted the log"),
+            ("topic1", "second topic of the log, the first indexed event parameter"),
+            ("topic2", "third topic of the log, the second indexed event parameter"),
+            ("topic3", "fourth topic of the log, the third indexed event parameter"),
+            ("data", "non-indexed data of the log"),
+            ("n_data_bytes", "size of the data of the log in bytes"),
+            ("global_index", "block_number * 1e9 + log_index, unique across the chain"),
+        ]
+        .into_iter()
+        .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -93,7 +114,7 @@ impl CollectByTransaction for Logs {
 }
 
 /// process block into columns
-fn process_logs(logs: Vec<Log>, columns: &mut Logs, schema: &Table) -> R<()> {
+pub(crate) fn process_logs(logs: Vec<Log>, columns: &mut Logs, schema: &Table) -> R<()> {
     // let decode_keys = match &schema.log_decoder {
     //     None => None,
     //     Some(decoder) => {
@@ -171,6 +192,7 @@ fn process_logs(logs: Vec<Log>, columns: &mut Logs, schema: &Table) -> R<()> {
             store!(schema, columns, address, log.address().to_vec());
             store!(schema, columns, data, log.data().data.to_vec());
             store!(schema, columns, n_data_bytes, log.data().data.len() as u32);
+            store!(schema, columns, global_index, global_index(bn, li));
 
             // topics
             for i in 0..4 {
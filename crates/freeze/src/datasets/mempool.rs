@@ -0,0 +1,120 @@
+use crate::*;
+use alloy::{
+    consensus::Transaction as ConsensusTransaction,
+    primitives::{TxKind, U256},
+    rpc::types::Transaction,
+};
+use polars::prelude::*;
+
+/// columns for pending transactions
+#[cryo_to_df::to_df(Datatype::Mempool)]
+#[derive(Default)]
+pub struct Mempool {
+    n_rows: u64,
+    first_seen_ms: Vec<u64>,
+    transaction_hash: Vec<Vec<u8>>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    nonce: Vec<u64>,
+    value: Vec<U256>,
+    input: Vec<Vec<u8>>,
+    gas_limit: Vec<u64>,
+    gas_price: Vec<Option<u64>>,
+    max_fee_per_gas: Vec<Option<u64>>,
+    max_priority_fee_per_gas: Vec<Option<u64>>,
+    transaction_type: Vec<u32>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Mempool {
+    fn aliases() -> Vec<&'static str> {
+        vec!["pending_transactions"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "first_seen_ms",
+            "transaction_hash",
+            "from_address",
+            "to_address",
+            "nonce",
+            "value",
+            "gas_limit",
+            "gas_price",
+            "max_fee_per_gas",
+            "max_priority_fee_per_gas",
+            "transaction_type",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["first_seen_ms", "transaction_hash"])
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "pending transactions streamed from the mempool, one row per transaction when it is \
+             first seen, subscribes to newPendingTransactions with ws and ipc rpc urls and polls \
+             txpool_content otherwise, files are written every --mempool-window seconds",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("first_seen_ms", "unix time in milliseconds when cryo first saw the transaction"),
+            ("gas_price", "gas price of legacy and eip2930 transactions"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for Mempool {
+    type Response = ();
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Mempool {
+    type Response = ();
+}
+
+pub(crate) fn process_pending_transaction(
+    tx: &Transaction,
+    first_seen_ms: u64,
+    columns: &mut Mempool,
+    schema: &Table,
+) {
+    columns.n_rows += 1;
+    store!(schema, columns, first_seen_ms, first_seen_ms);
+    store!(schema, columns, transaction_hash, tx.inner.tx_hash().to_vec());
+    store!(schema, columns, from_address, tx.from.to_vec());
+    store!(
+        schema,
+        columns,
+        to_address,
+        match tx.inner.kind() {
+            TxKind::Create => None,
+            TxKind::Call(address) => Some(address.to_vec()),
+        }
+    );
+    store!(schema, columns, nonce, tx.inner.nonce());
+    store!(schema, columns, value, tx.inner.value());
+    store!(schema, columns, input, tx.inner.input().to_vec());
+    store!(schema, columns, gas_limit, tx.inner.gas_limit());
+    store!(schema, columns, gas_price, tx.inner.gas_price().map(|value| value as u64));
+    store!(schema, columns, max_fee_per_gas, transactions::get_max_fee_per_gas(tx));
+    store!(
+        schema,
+        columns,
+        max_priority_fee_per_gas,
+        tx.inner.max_priority_fee_per_gas().map(|value| value as u64)
+    );
+    store!(schema, columns, transaction_type, tx.inner.tx_type() as u32);
+}
@@ -1,13 +1,25 @@
 /// address appearances
 pub mod address_appearances;
+/// address transactions
+pub mod address_transactions;
 /// balance diffs
 pub mod balance_diffs;
 /// balance reads
 pub mod balance_reads;
 /// balances
 pub mod balances;
+/// beacon attestations
+pub mod beacon_attestations;
+/// beacon blocks
+pub mod beacon_blocks;
+/// beacon deposits
+pub mod beacon_deposits;
+/// blob transactions
+pub mod blob_transactions;
 /// blocks
 pub mod blocks;
+/// call results
+pub mod call_results;
 /// code diffs
 pub mod code_diffs;
 /// code reads
@@ -16,6 +28,14 @@ pub mod code_reads;
 pub mod codes;
 /// contracts
 pub mod contracts;
+/// custom traces
+pub mod custom_traces;
+/// decoded calls
+pub mod decoded_calls;
+/// decoded logs
+pub mod decoded_logs;
+/// erc1155 transfers
+pub mod erc1155_transfers;
 /// erc20 approval
 pub mod erc20_approvals;
 /// erc20 balances
@@ -34,6 +54,12 @@ pub mod erc721_transfers;
 pub mod eth_calls;
 /// four byte counts
 pub mod four_byte_counts;
+/// gas percentiles
+pub mod gas_percentiles;
+/// gas stats
+pub mod gas_stats;
+/// genesis balances
+pub mod genesis_balances;
 /// geth balance diffs
 pub mod geth_balance_diffs;
 /// geth calls
@@ -50,6 +76,8 @@ pub mod geth_storage_diffs;
 pub mod javascript_traces;
 /// logs
 pub mod logs;
+/// mempool
+pub mod mempool;
 /// native transfers
 pub mod native_transfers;
 /// nonce diffs
@@ -58,6 +86,20 @@ pub mod nonce_diffs;
 pub mod nonce_reads;
 /// nonces
 pub mod nonces;
+/// proof stats
+pub mod proof_stats;
+/// proofs
+pub mod proofs;
+/// proxy upgrades
+pub mod proxy_upgrades;
+/// receipts
+pub mod receipts;
+/// safe executions
+pub mod safe_executions;
+/// selector counts
+pub mod selector_counts;
+/// selfdestructs
+pub mod selfdestructs;
 /// slots
 pub mod slots;
 /// storage diffs
@@ -66,22 +108,42 @@ pub mod storage_diffs;
 pub mod storage_reads;
 /// trace calls
 pub mod trace_calls;
+/// trace gas
+pub mod trace_gas;
+/// trace trees
+pub mod trace_trees;
 /// traces
 pub mod traces;
 /// transactions
 pub mod transactions;
+/// uncles
+pub mod uncles;
+/// user operations
+pub mod user_operations;
+/// validator balances
+pub mod validator_balances;
 /// vm traces
 pub mod vm_traces;
 
 pub use address_appearances::*;
+pub use address_transactions::*;
 pub use balance_diffs::*;
 pub use balance_reads::*;
 pub use balances::*;
+pub use beacon_attestations::*;
+pub use beacon_blocks::*;
+pub use beacon_deposits::*;
+pub use blob_transactions::*;
 pub use blocks::*;
+pub use call_results::*;
 pub use code_diffs::*;
 pub use code_reads::*;
 pub use codes::*;
 pub use contracts::*;
+pub use custom_traces::*;
+pub use decoded_calls::*;
+pub use decoded_logs::*;
+pub use erc1155_transfers::*;
 pub use erc20_approvals::*;
 pub use erc20_balances::*;
 pub use erc20_metadata::*;
@@ -91,6 +153,9 @@ pub use erc721_metadata::*;
 pub use erc721_transfers::*;
 pub use eth_calls::*;
 pub use four_byte_counts::*;
+pub use gas_percentiles::*;
+pub use gas_stats::*;
+pub use genesis_balances::*;
 pub use geth_balance_diffs::*;
 pub use geth_calls::*;
 pub use geth_code_diffs::*;
@@ -99,14 +164,27 @@ pub use geth_opcodes::*;
 pub use geth_storage_diffs::*;
 pub use javascript_traces::*;
 pub use logs::*;
+pub use mempool::*;
 pub use native_transfers::*;
 pub use nonce_diffs::*;
 pub use nonce_reads::*;
 pub use nonces::*;
+pub use proof_stats::*;
+pub use proofs::*;
+pub use proxy_upgrades::*;
+pub use receipts::*;
+pub use safe_executions::*;
+pub use selector_counts::*;
+pub use selfdestructs::*;
 pub use slots::*;
 pub use storage_diffs::*;
 pub use storage_reads::*;
 pub use trace_calls::*;
+pub use trace_gas::*;
+pub use trace_trees::*;
 pub use traces::*;
 pub use transactions::*;
+pub use uncles::*;
+pub use user_operations::*;
+pub use validator_balances::*;
 pub use vm_traces::*;
@@ -1,7 +1,7 @@
 use crate::*;
 use alloy::{
     primitives::U256,
-    rpc::types::trace::parity::{Action, LocalizedTransactionTrace, TraceOutput},
+    rpc::types::trace::parity::{Action, CallType, LocalizedTransactionTrace, TraceOutput},
 };
 use polars::prelude::*;
 
@@ -26,6 +26,27 @@ impl Dataset for NativeTransfers {
     fn optional_parameters() -> Vec<Dim> {
         vec![Dim::FromAddress, Dim::ToAddress]
     }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "transfers of native ether found in call traces, value moved by failed traces and by \
+             delegatecall, staticcall, and callcode traces is not transferred and is excluded",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("transfer_index", "position of the transfer among the transfers of its block"),
+            ("from_address", "address the ether was transferred from"),
+            ("to_address", "address the ether was transferred to"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transfer_index"])
+    }
 }
 
 #[async_trait::async_trait]
@@ -34,13 +55,13 @@ impl CollectByBlock for NativeTransfers {
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
         let traces = source.trace_block(request.block_number()?).await?;
+        // failed traces are dropped before address filtering removes the parents of their subtraces
+        let traces = traces::filter_failed_traces(traces);
         Ok(filter_traces_by_from_to_addresses(traces, &request.from_address, &request.to_address))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
-        let traces =
-            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
-        process_native_transfers(&traces, columns, &query.schemas)
+        process_native_transfers(&response, columns, &query.schemas)
     }
 }
 
@@ -50,58 +71,65 @@ impl CollectByTransaction for NativeTransfers {
 
     async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
         let traces = source.trace_transaction(request.ethers_transaction_hash()?).await?;
+        // failed traces are dropped before address filtering removes the parents of their subtraces
+        let traces = traces::filter_failed_traces(traces);
         Ok(filter_traces_by_from_to_addresses(traces, &request.from_address, &request.to_address))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
-        let traces =
-            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
-        process_native_transfers(&traces, columns, &query.schemas)
+        process_native_transfers(&response, columns, &query.schemas)
     }
 }
 
 /// process block into columns
+///
+/// traces that failed or are inside a failed trace never moved their value, so they are skipped
+/// regardless of --exclude-failed
 pub(crate) fn process_native_transfers(
     traces: &[LocalizedTransactionTrace],
     columns: &mut NativeTransfers,
     schemas: &Schemas,
 ) -> R<()> {
     let schema = schemas.get(&Datatype::NativeTransfers).ok_or(err("schema not provided"))?;
-    for (transfer_index, trace) in traces.iter().enumerate() {
-        columns.n_rows += 1;
-        store!(schema, columns, block_number, trace.block_number.unwrap_or(0) as u32);
-        store!(schema, columns, transaction_index, trace.transaction_position.map(|x| x as u32));
-        store!(schema, columns, block_hash, trace.block_hash.unwrap().to_vec());
-        store!(schema, columns, transfer_index, transfer_index as u32);
-        store!(schema, columns, transaction_hash, trace.transaction_hash.map(|x| x.to_vec()));
-
-        match &trace.trace.action {
-            Action::Call(action) => {
-                store!(schema, columns, from_address, action.from.to_vec());
-                store!(schema, columns, to_address, action.to.to_vec());
-                store!(schema, columns, value, action.value);
+    let mut transfer_index: u32 = 0;
+    for trace in traces::filter_failed_traces(traces.to_vec()).iter() {
+        let (from_address, to_address, value) = match &trace.trace.action {
+            // these calls carry the value of their parent call without transferring it
+            Action::Call(action)
+                if matches!(
+                    action.call_type,
+                    CallType::DelegateCall | CallType::StaticCall | CallType::CallCode
+                ) =>
+            {
+                continue
             }
+            Action::Call(action) => (action.from.to_vec(), action.to.to_vec(), action.value),
             Action::Create(action) => {
-                store!(schema, columns, from_address, action.from.to_vec());
-                match &trace.trace.result.as_ref() {
-                    Some(TraceOutput::Create(res)) => {
-                        store!(schema, columns, to_address, res.address.0.to_vec())
-                    }
-                    _ => store!(schema, columns, to_address, vec![0; 32]),
-                }
-                store!(schema, columns, value, action.value);
+                let to_address = match &trace.trace.result.as_ref() {
+                    Some(TraceOutput::Create(res)) => res.address.0.to_vec(),
+                    _ => vec![0; 20],
+                };
+                (action.from.to_vec(), to_address, action.value)
             }
             Action::Selfdestruct(action) => {
-                store!(schema, columns, from_address, action.address.to_vec());
-                store!(schema, columns, to_address, action.refund_address.to_vec());
-                store!(schema, columns, value, action.balance);
-            }
-            Action::Reward(action) => {
-                store!(schema, columns, from_address, vec![0; 20]);
-                store!(schema, columns, to_address, action.author.to_vec());
-                store!(schema, columns, value, action.value);
+                (action.address.to_vec(), action.refund_address.to_vec(), action.balance)
             }
+            Action::Reward(action) => (vec![0; 20], action.author.to_vec(), action.value),
+        };
+        if value.is_zero() {
+            continue
         }
+
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, trace.block_number.unwrap_or(0) as u32);
+        store!(schema, columns, transaction_index, trace.transaction_position.map(|x| x as u32));
+        store!(schema, columns, block_hash, trace.block_hash.unwrap().to_vec());
+        store!(schema, columns, transfer_index, transfer_index);
+        store!(schema, columns, transaction_hash, trace.transaction_hash.map(|x| x.to_vec()));
+        store!(schema, columns, from_address, from_address);
+        store!(schema, columns, to_address, to_address);
+        store!(schema, columns, value, value);
+        transfer_index += 1;
     }
     Ok(())
 }
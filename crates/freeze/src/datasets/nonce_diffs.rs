@@ -20,7 +20,11 @@ pub struct NonceDiffs {
 }
 
 #[async_trait::async_trait]
-impl Dataset for NonceDiffs {}
+impl Dataset for NonceDiffs {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address"])
+    }
+}
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<TraceResults>);
 
@@ -17,7 +17,11 @@ pub struct NonceReads {
 }
 
 #[async_trait::async_trait]
-impl Dataset for NonceReads {}
+impl Dataset for NonceReads {
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address"])
+    }
+}
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<Address, AccountState>>);
 
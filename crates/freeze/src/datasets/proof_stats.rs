@@ -0,0 +1,95 @@
+use crate::{
+    datasets::proofs::{fetch_proof, requested_storage_proof, BlockProofOutput},
+    *,
+};
+use polars::prelude::*;
+
+/// columns for proof stats, one row per address (and slot) per block
+///
+/// proof depth is the number of trie nodes from the root to the leaf, so it tracks the depth of
+/// the account in the state trie and of the slot in the storage trie
+#[cryo_to_df::to_df(Datatype::ProofStats)]
+#[derive(Default)]
+pub struct ProofStats {
+    n_rows: usize,
+    block_number: Vec<u32>,
+    address: Vec<Vec<u8>>,
+    slot: Vec<Option<Vec<u8>>>,
+    account_proof_depth: Vec<u32>,
+    account_proof_bytes: Vec<u64>,
+    storage_proof_depth: Vec<Option<u32>>,
+    storage_proof_bytes: Vec<Option<u64>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for ProofStats {
+    fn aliases() -> Vec<&'static str> {
+        vec!["trie_stats"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address", "slot"])
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Slot]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for ProofStats {
+    type Response = BlockProofOutput;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        fetch_proof(&request, &source).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::ProofStats)?;
+        process_proof_stats(columns, response, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for ProofStats {
+    type Response = ();
+}
+
+fn process_proof_stats(columns: &mut ProofStats, data: BlockProofOutput, schema: &Table) -> R<()> {
+    let (block, address, slot, proof) = data;
+    let storage_proof = match &slot {
+        Some(_) => Some(requested_storage_proof(&proof)?.proof.clone()),
+        None => None,
+    };
+    columns.n_rows += 1;
+    store!(schema, columns, block_number, block);
+    store!(schema, columns, address, address);
+    store!(schema, columns, slot, slot);
+    store!(schema, columns, account_proof_depth, proof.account_proof.len() as u32);
+    store!(schema, columns, account_proof_bytes, proof_bytes(&proof.account_proof));
+    store!(schema, columns, storage_proof_depth, storage_proof.as_ref().map(|p| p.len() as u32));
+    store!(
+        schema,
+        columns,
+        storage_proof_bytes,
+        storage_proof.as_ref().map(|p| proof_bytes(p.as_slice()))
+    );
+    Ok(())
+}
+
+fn proof_bytes(nodes: &[alloy::primitives::Bytes]) -> u64 {
+    nodes.iter().map(|node| node.len() as u64).sum()
+}
@@ -0,0 +1,116 @@
+use crate::*;
+use alloy::{
+    primitives::{Address, B256},
+    rpc::types::{EIP1186AccountProofResponse, EIP1186StorageProof},
+};
+use polars::prelude::*;
+
+/// columns for proofs, one row per proof node
+///
+/// when no slots are given, rows contain the account proof of each address; when slots are
+/// given, rows contain the storage proof of each slot
+#[cryo_to_df::to_df(Datatype::Proofs)]
+#[derive(Default)]
+pub struct Proofs {
+    n_rows: usize,
+    block_number: Vec<u32>,
+    address: Vec<Vec<u8>>,
+    slot: Vec<Option<Vec<u8>>>,
+    node_index: Vec<u32>,
+    node: Vec<Vec<u8>>,
+    value: Vec<Option<Vec<u8>>>,
+    storage_hash: Vec<Vec<u8>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Proofs {
+    fn aliases() -> Vec<&'static str> {
+        vec!["storage_proofs"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "address", "slot", "node_index"])
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Slot]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+
+    fn default_blocks() -> Option<String> {
+        Some("latest".to_string())
+    }
+}
+
+/// block number, address, slot, and eth_getProof response of a proof request
+pub(crate) type BlockProofOutput = (u32, Vec<u8>, Option<Vec<u8>>, EIP1186AccountProofResponse);
+
+/// fetch the account proof of the request address, with the storage proof of its slot if given
+pub(crate) async fn fetch_proof(request: &Params, source: &Source) -> R<BlockProofOutput> {
+    let address = request.address()?;
+    let block_number = request.block_number()? as u32;
+    let slot = request.slot().ok();
+    let slots = match &slot {
+        Some(slot) => vec![B256::left_padding_from(slot)],
+        None => vec![],
+    };
+    let proof = source.get_proof(Address::from_slice(&address), slots, block_number.into()).await?;
+    Ok((block_number, address, slot, proof))
+}
+
+/// storage proof of the single slot requested by fetch_proof
+pub(crate) fn requested_storage_proof(
+    proof: &EIP1186AccountProofResponse,
+) -> R<&EIP1186StorageProof> {
+    proof.storage_proof.first().ok_or(err("storage proof missing from response"))
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for Proofs {
+    type Response = BlockProofOutput;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        fetch_proof(&request, &source).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Proofs)?;
+        process_proof(columns, response, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Proofs {
+    type Response = ();
+}
+
+fn process_proof(columns: &mut Proofs, data: BlockProofOutput, schema: &Table) -> R<()> {
+    let (block, address, slot, proof) = data;
+    let storage_hash = proof.storage_hash.to_vec();
+    let (nodes, value) = match &slot {
+        Some(_) => {
+            let storage_proof = requested_storage_proof(&proof)?;
+            (storage_proof.proof.clone(), Some(storage_proof.value.to_vec_u8()))
+        }
+        None => (proof.account_proof, None),
+    };
+    for (index, node) in nodes.into_iter().enumerate() {
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block);
+        store!(schema, columns, address, address.clone());
+        store!(schema, columns, slot, slot.clone());
+        store!(schema, columns, node_index, index as u32);
+        store!(schema, columns, node, node.to_vec());
+        store!(schema, columns, value, value.clone());
+        store!(schema, columns, storage_hash, storage_hash.clone());
+    }
+    Ok(())
+}
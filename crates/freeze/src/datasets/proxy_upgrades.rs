@@ -0,0 +1,145 @@
+use crate::*;
+use alloy::primitives::{Address, U256};
+use polars::prelude::*;
+use std::str::FromStr;
+
+/// columns for proxy upgrades
+#[cryo_to_df::to_df(Datatype::ProxyUpgrades)]
+#[derive(Default)]
+pub struct ProxyUpgrades {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    proxy_address: Vec<Vec<u8>>,
+    implementation: Vec<Option<Vec<u8>>>,
+    admin: Vec<Option<Vec<u8>>>,
+    beacon: Vec<Option<Vec<u8>>>,
+    previous_implementation: Vec<Option<Vec<u8>>>,
+    previous_admin: Vec<Option<Vec<u8>>>,
+    previous_beacon: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for ProxyUpgrades {
+    fn aliases() -> Vec<&'static str> {
+        vec!["proxy_implementations"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["proxy_address", "block_number"])
+    }
+
+    fn required_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "upgrade history of eip-1967 proxies, one row per block where the implementation, \
+             admin, or beacon slot of a proxy changes, found by bisecting each \
+             --inner-request-size block range so use a large --inner-request-size, use slots \
+             with --slot eip1967.implementation to read the slots at every block",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("block_number", "block where the slots changed"),
+            ("implementation", "implementation address after the change, null if unset"),
+            ("admin", "admin address after the change, null if unset"),
+            ("beacon", "beacon address after the change, null if unset"),
+            ("previous_implementation", "implementation address before the change"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// values of the implementation, admin, and beacon slots
+type ProxySlots = [U256; 3];
+
+/// proxy address and (block, previous slots, slots) of each change
+type ProxyChanges = (Vec<u8>, Vec<(u64, ProxySlots, ProxySlots)>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for ProxyUpgrades {
+    type Response = ProxyChanges;
+
+    /// bisect the block range, only descending into halves whose endpoint slots differ
+    ///
+    /// a change that is reverted within the same half of a range is not detected
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let proxy = request.address()?;
+        let address = Address::from_slice(&proxy);
+        let (start_block, end_block) = request.block_range()?;
+        let before_start = start_block.saturating_sub(1);
+        let start_slots = read_proxy_slots(&source, address, before_start).await?;
+        let end_slots = read_proxy_slots(&source, address, end_block).await?;
+
+        let mut changes = Vec::new();
+        let mut ranges = vec![(before_start, start_slots, end_block, end_slots)];
+        while let Some((low, low_slots, high, high_slots)) = ranges.pop() {
+            if low_slots == high_slots {
+                continue
+            }
+            if high <= low + 1 {
+                changes.push((high, low_slots, high_slots));
+                continue
+            }
+            let middle = low + (high - low) / 2;
+            let middle_slots = read_proxy_slots(&source, address, middle).await?;
+            ranges.push((low, low_slots, middle, middle_slots));
+            ranges.push((middle, middle_slots, high, high_slots));
+        }
+        changes.sort_by_key(|(block, _, _)| *block);
+        Ok((proxy, changes))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::ProxyUpgrades)?;
+        let (proxy, changes) = response;
+        for (block, previous, current) in changes.into_iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block as u32);
+            store!(schema, columns, proxy_address, proxy.clone());
+            store!(schema, columns, implementation, slot_address(current[0]));
+            store!(schema, columns, admin, slot_address(current[1]));
+            store!(schema, columns, beacon, slot_address(current[2]));
+            store!(schema, columns, previous_implementation, slot_address(previous[0]));
+            store!(schema, columns, previous_admin, slot_address(previous[1]));
+            store!(schema, columns, previous_beacon, slot_address(previous[2]));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for ProxyUpgrades {
+    type Response = ();
+}
+
+async fn read_proxy_slots(source: &Source, address: Address, block: u64) -> R<ProxySlots> {
+    let slot = |slot: &str| U256::from_str(slot).map_err(|_| err("invalid eip-1967 slot"));
+    let (implementation, admin, beacon) = tokio::try_join!(
+        source.get_storage_at(address, slot(slots::EIP1967_IMPLEMENTATION_SLOT)?, block),
+        source.get_storage_at(address, slot(slots::EIP1967_ADMIN_SLOT)?, block),
+        source.get_storage_at(address, slot(slots::EIP1967_BEACON_SLOT)?, block),
+    )?;
+    Ok([implementation, admin, beacon])
+}
+
+/// address stored in the low 20 bytes of a slot, None if the slot is empty
+fn slot_address(value: U256) -> Option<Vec<u8>> {
+    match value.is_zero() {
+        true => None,
+        false => Some(value.to_be_bytes_vec()[12..].to_vec()),
+    }
+}
@@ -0,0 +1,170 @@
+use super::transactions;
+use crate::*;
+use alloy::{
+    consensus::{Eip658Value, Transaction as ConsensusTransaction, TxReceipt},
+    primitives::TxHash,
+    rpc::types::{BlockTransactionsKind, TransactionReceipt},
+};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// columns for receipts
+#[cryo_to_df::to_df(Datatype::Receipts)]
+#[derive(Default)]
+pub struct Receipts {
+    n_rows: u64,
+    block_number: Vec<Option<u32>>,
+    block_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<Option<u64>>,
+    transaction_hash: Vec<Vec<u8>>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    contract_address: Vec<Option<Vec<u8>>>,
+    success: Vec<Option<bool>>,
+    success_derived: Vec<bool>,
+    cumulative_gas_used: Vec<u64>,
+    gas_used: Vec<u64>,
+    effective_gas_price: Vec<u64>,
+    blob_gas_used: Vec<Option<u64>>,
+    blob_gas_price: Vec<Option<u64>>,
+    n_logs: Vec<u32>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Receipts {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "from_address",
+            "to_address",
+            "contract_address",
+            "success",
+            "cumulative_gas_used",
+            "gas_used",
+            "effective_gas_price",
+            "n_logs",
+            "chain_id",
+        ])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("transaction receipts, one row per transaction")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("cumulative_gas_used", "gas used by this and all previous transactions of the block"),
+            ("effective_gas_price", "price per unit of gas paid by the transaction"),
+            ("contract_address", "address of the contract created by the transaction"),
+            ("n_logs", "number of logs emitted by the transaction"),
+            ("blob_gas_used", "blob gas used by the transaction, null before cancun"),
+            ("blob_gas_price", "price per unit of blob gas paid by the transaction"),
+            (
+                "success_derived",
+                "whether success was derived from gas usage for pre-byzantium receipts, which is \
+                 only possible when the transaction left part of its gas limit unused",
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// receipts, and gas limits of their transactions if any receipt predates eip-658 (byzantium)
+type ReceiptsAndGasLimits = (Vec<TransactionReceipt>, HashMap<TxHash, u64>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for Receipts {
+    type Response = ReceiptsAndGasLimits;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let receipts = source.get_receipts_by_block_number(block_number).await?;
+        let gas_limits = if receipts.iter().any(is_pre_byzantium) {
+            let block = source
+                .get_block(block_number, BlockTransactionsKind::Full)
+                .await?
+                .ok_or(CollectError::CollectError("block not found".to_string()))?;
+            block
+                .transactions
+                .as_transactions()
+                .unwrap_or_default()
+                .iter()
+                .map(|tx| (*tx.inner.tx_hash(), tx.inner.gas_limit()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Ok((receipts, gas_limits))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Receipts)?;
+        let (receipts, gas_limits) = response;
+        for receipt in receipts.into_iter() {
+            let gas_limit = gas_limits.get(&receipt.transaction_hash).copied();
+            process_receipt(receipt, gas_limit, columns, schema);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Receipts {
+    type Response = ReceiptsAndGasLimits;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let tx_hash = request.ethers_transaction_hash()?;
+        let receipt = source
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .ok_or(CollectError::CollectError("could not find tx receipt".to_string()))?;
+        let mut gas_limits = HashMap::new();
+        if is_pre_byzantium(&receipt) {
+            if let Some(tx) = source.get_transaction_by_hash(tx_hash).await? {
+                gas_limits.insert(tx_hash, tx.inner.gas_limit());
+            }
+        }
+        Ok((vec![receipt], gas_limits))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        <Receipts as CollectByBlock>::transform(response, columns, query)
+    }
+}
+
+fn is_pre_byzantium(receipt: &TransactionReceipt) -> bool {
+    matches!(receipt.inner.status_or_post_state(), Eip658Value::PostState(_))
+}
+
+/// process receipt into columns
+///
+/// success of receipts before eip-658 (byzantium) is derived from the gas limit of the
+/// transaction in the same way as in the transactions dataset
+fn process_receipt(
+    receipt: TransactionReceipt,
+    gas_limit: Option<u64>,
+    columns: &mut Receipts,
+    schema: &Table,
+) {
+    let (success, success_derived) = transactions::receipt_success(&receipt, gas_limit);
+    columns.n_rows += 1;
+    store!(schema, columns, block_number, receipt.block_number.map(|x| x as u32));
+    store!(schema, columns, block_hash, receipt.block_hash.map(|x| x.to_vec()));
+    store!(schema, columns, transaction_index, receipt.transaction_index);
+    store!(schema, columns, transaction_hash, receipt.transaction_hash.to_vec());
+    store!(schema, columns, from_address, receipt.from.to_vec());
+    store!(schema, columns, to_address, receipt.to.map(|x| x.to_vec()));
+    store!(schema, columns, contract_address, receipt.contract_address.map(|x| x.to_vec()));
+    store!(schema, columns, success, success);
+    store!(schema, columns, success_derived, success_derived);
+    store!(schema, columns, cumulative_gas_used, receipt.inner.cumulative_gas_used() as u64);
+    store!(schema, columns, gas_used, receipt.gas_used as u64);
+    store!(schema, columns, effective_gas_price, receipt.effective_gas_price as u64);
+    store!(schema, columns, blob_gas_used, receipt.blob_gas_used.map(|x| x as u64));
+    store!(schema, columns, blob_gas_price, receipt.blob_gas_price.map(|x| x as u64));
+    store!(schema, columns, n_logs, receipt.inner.logs().len() as u32);
+}
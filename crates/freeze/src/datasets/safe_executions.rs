@@ -0,0 +1,264 @@
+use crate::*;
+use alloy::{
+    consensus::Transaction as ConsensusTransaction,
+    primitives::{Address, TxKind, B256, U256},
+    rpc::types::{Filter, Log, Topic},
+    sol_types::{SolCall, SolEvent},
+};
+use polars::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// columns for safe multisig executions, decoded from ExecutionSuccess and ExecutionFailure logs
+///
+/// the inner call of each execution is decoded from the execTransaction calldata of its
+/// transaction, which is only fetched when one of those columns is requested
+#[cryo_to_df::to_df(Datatype::SafeExecutions)]
+#[derive(Default)]
+pub struct SafeExecutions {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    safe: Vec<Vec<u8>>,
+    safe_tx_hash: Vec<Vec<u8>>,
+    payment: Vec<U256>,
+    success: Vec<bool>,
+    to_address: Vec<Option<Vec<u8>>>,
+    value: Vec<Option<U256>>,
+    data: Vec<Option<Vec<u8>>>,
+    operation: Vec<Option<u32>>,
+    safe_tx_gas: Vec<Option<U256>>,
+    base_gas: Vec<Option<U256>>,
+    gas_price: Vec<Option<U256>>,
+    gas_token: Vec<Option<Vec<u8>>>,
+    refund_receiver: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for SafeExecutions {
+    fn aliases() -> Vec<&'static str> {
+        vec!["gnosis_safe_executions"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "log_index",
+            "transaction_hash",
+            "safe",
+            "safe_tx_hash",
+            "payment",
+            "success",
+            "chain_id",
+        ])
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            (
+                "to_address",
+                "target of the inner call, decoded from the execTransaction call of the \
+                 transaction. inner call columns are null when the transaction does not call \
+                 execTransaction of the safe directly, e.g. when a module or relayer calls it",
+            ),
+            ("operation", "0 for a call, 1 for a delegatecall"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for SafeExecutions {
+    type Response = (Vec<Log>, ExecTransactions);
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let mut topics: [Topic; 4] = Default::default();
+        topics[0] =
+            vec![Safe::ExecutionSuccess::SIGNATURE_HASH, Safe::ExecutionFailure::SIGNATURE_HASH]
+                .into();
+        let filter = Filter { topics, ..request.ethers_log_filter()? };
+        let logs = source.get_logs(&filter).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_safe_execution).collect();
+        let schema = query.schemas.get_schema(&Datatype::SafeExecutions)?;
+        let calls = fetch_exec_transactions(&logs, &source, schema).await?;
+        Ok((logs, calls))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::SafeExecutions)?;
+        process_safe_executions(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for SafeExecutions {
+    type Response = (Vec<Log>, ExecTransactions);
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let logs = source.get_transaction_logs(request.transaction_hash()?).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_safe_execution).collect();
+        let schema = query.schemas.get_schema(&Datatype::SafeExecutions)?;
+        let calls = fetch_exec_transactions(&logs, &source, schema).await?;
+        Ok((logs, calls))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::SafeExecutions)?;
+        process_safe_executions(response, columns, schema)
+    }
+}
+
+/// safe v1.3+ emits both fields as data, older versions with different layouts are skipped
+fn is_safe_execution(log: &Log) -> bool {
+    log.topics().len() == 1 &&
+        log.data().data.len() == 64 &&
+        (log.topics()[0] == Safe::ExecutionSuccess::SIGNATURE_HASH ||
+            log.topics()[0] == Safe::ExecutionFailure::SIGNATURE_HASH)
+}
+
+/// execTransaction call of each transaction, keyed by transaction hash
+type ExecTransactions = HashMap<B256, ExecTransaction>;
+
+/// safe that a transaction calls and the decoded execTransaction call
+struct ExecTransaction {
+    safe: Address,
+    call: Safe::execTransactionCall,
+}
+
+/// columns decoded from execTransaction calldata rather than from execution logs
+const EXEC_TRANSACTION_COLUMNS: [&str; 9] = [
+    "to_address",
+    "value",
+    "data",
+    "operation",
+    "safe_tx_gas",
+    "base_gas",
+    "gas_price",
+    "gas_token",
+    "refund_receiver",
+];
+
+/// fetch the transactions of logs and decode their execTransaction calldata, if any of the
+/// inner call columns are requested
+async fn fetch_exec_transactions(
+    logs: &[Log],
+    source: &Source,
+    schema: &Table,
+) -> R<ExecTransactions> {
+    let mut calls = HashMap::new();
+    if !EXEC_TRANSACTION_COLUMNS.iter().any(|column| schema.has_column(column)) {
+        return Ok(calls)
+    }
+    let tx_hashes: BTreeSet<B256> = logs.iter().filter_map(|log| log.transaction_hash).collect();
+    for tx_hash in tx_hashes.into_iter() {
+        let tx = match source.get_transaction_by_hash(tx_hash).await? {
+            Some(tx) => tx,
+            None => continue,
+        };
+        if let (TxKind::Call(safe), Some(call)) =
+            (tx.inner.kind(), decode_exec_transaction(tx.inner.input()))
+        {
+            calls.insert(tx_hash, ExecTransaction { safe, call });
+        }
+    }
+    Ok(calls)
+}
+
+/// decode execTransaction calldata, None for calldata of other functions
+fn decode_exec_transaction(input: &[u8]) -> Option<Safe::execTransactionCall> {
+    Safe::execTransactionCall::abi_decode(input, true).ok()
+}
+
+/// process block into columns
+fn process_safe_executions(
+    response: (Vec<Log>, ExecTransactions),
+    columns: &mut SafeExecutions,
+    schema: &Table,
+) -> R<()> {
+    let (logs, calls) = response;
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let data = log.data().data.to_vec();
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn as u32);
+            store!(schema, columns, block_hash, log.block_hash.map(|bh| bh.to_vec()));
+            store!(schema, columns, transaction_index, ti as u32);
+            store!(schema, columns, log_index, li as u32);
+            store!(schema, columns, transaction_hash, tx.to_vec());
+            store!(schema, columns, safe, log.address().to_vec());
+            store!(schema, columns, safe_tx_hash, data[..32].to_vec());
+            store!(schema, columns, payment, U256::from_be_slice(&data[32..64]));
+            store!(
+                schema,
+                columns,
+                success,
+                log.topics()[0] == Safe::ExecutionSuccess::SIGNATURE_HASH
+            );
+
+            // only a call of the emitting safe itself describes the execution
+            let call =
+                calls.get(&tx).filter(|exec| exec.safe == log.address()).map(|exec| &exec.call);
+            store!(schema, columns, to_address, call.map(|call| call.to.to_vec()));
+            store!(schema, columns, value, call.map(|call| call.value));
+            store!(schema, columns, data, call.map(|call| call.data.to_vec()));
+            store!(schema, columns, operation, call.map(|call| call.operation as u32));
+            store!(schema, columns, safe_tx_gas, call.map(|call| call.safeTxGas));
+            store!(schema, columns, base_gas, call.map(|call| call.baseGas));
+            store!(schema, columns, gas_price, call.map(|call| call.gasPrice));
+            store!(schema, columns, gas_token, call.map(|call| call.gasToken.to_vec()));
+            store!(schema, columns, refund_receiver, call.map(|call| call.refundReceiver.to_vec()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Bytes;
+
+    #[test]
+    fn test_decode_exec_transaction() {
+        let call = Safe::execTransactionCall {
+            to: Address::with_last_byte(1),
+            value: U256::from(10),
+            data: Bytes::from(vec![0xab, 0xcd]),
+            operation: 1,
+            safeTxGas: U256::from(2),
+            baseGas: U256::from(3),
+            gasPrice: U256::from(4),
+            gasToken: Address::ZERO,
+            refundReceiver: Address::with_last_byte(5),
+            signatures: Bytes::from(vec![0u8; 65]),
+        };
+        let decoded = decode_exec_transaction(&call.abi_encode()).unwrap();
+        assert_eq!(decoded.to, Address::with_last_byte(1));
+        assert_eq!(decoded.value, U256::from(10));
+        assert_eq!(decoded.data.to_vec(), vec![0xab, 0xcd]);
+        assert_eq!(decoded.operation, 1);
+        assert_eq!(decoded.safeTxGas, U256::from(2));
+        assert_eq!(decoded.refundReceiver, Address::with_last_byte(5));
+
+        // calldata of other functions is not an execution
+        assert!(decode_exec_transaction(&[0x12, 0x34, 0x56, 0x78]).is_none());
+    }
+}
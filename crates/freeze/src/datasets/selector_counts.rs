@@ -0,0 +1,111 @@
+use crate::*;
+use alloy::rpc::types::trace::parity::{Action, LocalizedTransactionTrace};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+/// columns for per-block function selector counts
+#[cryo_to_df::to_df(Datatype::SelectorCounts)]
+#[derive(Default)]
+pub struct SelectorCounts {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    selector: Vec<Vec<u8>>,
+    n_transactions: Vec<u64>,
+    n_calls: Vec<u64>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for SelectorCounts {
+    fn aliases() -> Vec<&'static str> {
+        vec!["selectors"]
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "selector"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "counts of the 4-byte selectors of transaction and call inputs from trace_block, \
+             one row per (block, selector)",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("selector", "first 4 bytes of the call input"),
+            ("n_transactions", "number of transactions whose input starts with the selector"),
+            ("n_calls", "number of calls, including internal calls, with the selector"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for SelectorCounts {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_block(request.block_number()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let traces =
+            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
+        process_selector_counts(&traces, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for SelectorCounts {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_transaction(request.ethers_transaction_hash()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let traces =
+            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
+        process_selector_counts(&traces, columns, &query.schemas)
+    }
+}
+
+/// count calls by (block, selector), top-level calls are also counted as transactions
+fn process_selector_counts(
+    traces: &[LocalizedTransactionTrace],
+    columns: &mut SelectorCounts,
+    schemas: &Schemas,
+) -> R<()> {
+    let schema = schemas.get(&Datatype::SelectorCounts).ok_or(err("schema not provided"))?;
+    let mut counts: BTreeMap<(u64, [u8; 4]), (u64, u64)> = BTreeMap::new();
+    for trace in traces.iter() {
+        let selector = match (&trace.trace.action, trace.block_number) {
+            (Action::Call(action), Some(block_number)) => match selector(&action.input) {
+                Some(selector) => (block_number, selector),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let (n_transactions, n_calls) = counts.entry(selector).or_default();
+        if trace.trace.trace_address.is_empty() && trace.transaction_hash.is_some() {
+            *n_transactions += 1;
+        }
+        *n_calls += 1;
+    }
+    for ((block_number, selector), (n_transactions, n_calls)) in counts.into_iter() {
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, block_number as u32);
+        store!(schema, columns, selector, selector.to_vec());
+        store!(schema, columns, n_transactions, n_transactions);
+        store!(schema, columns, n_calls, n_calls);
+    }
+    Ok(())
+}
+
+/// first 4 bytes of call input, if the input is long enough to hold a selector
+fn selector(input: &[u8]) -> Option<[u8; 4]> {
+    input.get(..4).and_then(|bytes| bytes.try_into().ok())
+}
@@ -0,0 +1,124 @@
+use super::traces;
+use crate::*;
+use alloy::{
+    primitives::U256,
+    rpc::types::trace::parity::{Action, LocalizedTransactionTrace},
+};
+use polars::prelude::*;
+
+/// columns for selfdestructs
+#[cryo_to_df::to_df(Datatype::Selfdestructs)]
+#[derive(Default)]
+pub struct Selfdestructs {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Vec<u8>>,
+    transaction_index: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    trace_address: Vec<String>,
+    address: Vec<Vec<u8>>,
+    refund_address: Vec<Vec<u8>>,
+    balance: Vec<U256>,
+    error: Vec<Option<String>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Selfdestructs {
+    fn aliases() -> Vec<&'static str> {
+        vec!["suicides"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "transaction_hash",
+            "trace_address",
+            "address",
+            "refund_address",
+            "balance",
+            "error",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "trace_address"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("selfdestruct (suicide) actions of traces, one row per selfdestruct")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("address", "address of the contract that selfdestructed"),
+            ("refund_address", "address receiving the balance of the contract"),
+            ("balance", "balance transferred to the refund address"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for Selfdestructs {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_block(request.ethers_block_number()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let traces =
+            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
+        process_selfdestructs(&traces, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Selfdestructs {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_transaction(request.ethers_transaction_hash()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let traces =
+            if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
+        process_selfdestructs(&traces, columns, &query.schemas)
+    }
+}
+
+/// process traces into columns
+fn process_selfdestructs(
+    traces: &[LocalizedTransactionTrace],
+    columns: &mut Selfdestructs,
+    schemas: &Schemas,
+) -> R<()> {
+    let schema = schemas.get_schema(&Datatype::Selfdestructs)?;
+    for trace in traces.iter() {
+        if let Action::Selfdestruct(action) = &trace.trace.action {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, trace.block_number.unwrap() as u32);
+            store!(schema, columns, block_hash, trace.block_hash.unwrap().to_vec());
+            store!(
+                schema,
+                columns,
+                transaction_index,
+                trace.transaction_position.map(|x| x as u32)
+            );
+            store!(schema, columns, transaction_hash, trace.transaction_hash.map(|x| x.to_vec()));
+            let trace_address: Vec<String> =
+                trace.trace.trace_address.iter().map(|n| n.to_string()).collect();
+            store!(schema, columns, trace_address, trace_address.join("_"));
+            store!(schema, columns, address, action.address.to_vec());
+            store!(schema, columns, refund_address, action.refund_address.to_vec());
+            store!(schema, columns, balance, action.balance);
+            store!(schema, columns, error, trace.trace.error.clone());
+        }
+    }
+    Ok(())
+}
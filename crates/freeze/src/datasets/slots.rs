@@ -1,6 +1,19 @@
 use crate::*;
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{keccak256, Address, U256};
 use polars::prelude::*;
+use std::str::FromStr;
+
+/// eip-1967 slot of proxy implementation address, keccak256("eip1967.proxy.implementation") - 1
+pub const EIP1967_IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// eip-1967 slot of proxy admin address, keccak256("eip1967.proxy.admin") - 1
+pub const EIP1967_ADMIN_SLOT: &str =
+    "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103";
+
+/// eip-1967 slot of proxy beacon address, keccak256("eip1967.proxy.beacon") - 1
+pub const EIP1967_BEACON_SLOT: &str =
+    "0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50";
 
 /// columns for balances
 #[cryo_to_df::to_df(Datatype::Slots)]
@@ -35,6 +48,13 @@ impl Dataset for Slots {
     fn default_blocks() -> Option<String> {
         Some("latest".to_string())
     }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "storage slot values of contracts, --slot accepts eip1967.implementation or \
+             mapping:KEY:INDEX to compute slots",
+        )
+    }
 }
 
 type BlockTxAddressOutput = (u32, Option<Vec<u8>>, Vec<u8>, Vec<u8>, Vec<u8>);
@@ -77,3 +97,100 @@ fn process_slot(columns: &mut Slots, data: BlockTxAddressOutput, schema: &Table)
     store!(schema, columns, value, output);
     Ok(())
 }
+
+/// compute slot of `mapping[key]` for a mapping declared at storage slot `index`
+///
+/// this is keccak256(key . index) with both key and index left-padded to 32 bytes
+pub fn mapping_slot(key: &[u8], index: &[u8]) -> Result<Vec<u8>, ParseError> {
+    if key.len() > 32 || index.len() > 32 {
+        return Err(ParseError::ParseError("mapping key and index must be at most 32 bytes".into()))
+    }
+    let mut preimage = [0u8; 64];
+    preimage[32 - key.len()..32].copy_from_slice(key);
+    preimage[64 - index.len()..].copy_from_slice(index);
+    Ok(keccak256(preimage).to_vec())
+}
+
+/// resolve named or computed slot expression, returning None for plain slots
+///
+/// supported expressions are `eip1967.implementation`, `eip1967.admin`, `eip1967.beacon`, and
+/// `mapping:KEY[:KEY...]:INDEX` for (nested) mappings, e.g. `mapping:OWNER:SPENDER:1` is the
+/// slot of `allowance[OWNER][SPENDER]` when `allowance` is declared at slot 1. Keys and index
+/// are 0x-prefixed hex or decimal integers
+pub fn resolve_slot_expression(expression: &str) -> Result<Option<Vec<u8>>, ParseError> {
+    let named = match expression {
+        "eip1967.implementation" => Some(EIP1967_IMPLEMENTATION_SLOT),
+        "eip1967.admin" => Some(EIP1967_ADMIN_SLOT),
+        "eip1967.beacon" => Some(EIP1967_BEACON_SLOT),
+        _ => None,
+    };
+    if let Some(named) = named {
+        return prefix_hex::decode(named)
+            .map(Some)
+            .map_err(|_| ParseError::ParseError("invalid named slot".into()))
+    }
+
+    let pieces: Vec<&str> = match expression.strip_prefix("mapping:") {
+        Some(rest) => rest.split(':').collect(),
+        None => return Ok(None),
+    };
+    if pieces.len() < 2 {
+        return Err(ParseError::ParseError(format!(
+            "invalid slot {}, expected mapping:KEY[:KEY...]:INDEX",
+            expression
+        )))
+    }
+    let (index, keys) = pieces.split_last().expect("pieces is non-empty");
+    let mut slot = parse_slot_word(index)?;
+    for key in keys.iter() {
+        slot = mapping_slot(&parse_slot_word(key)?, &slot)?;
+    }
+    Ok(Some(slot))
+}
+
+/// parse 0x-prefixed hex bytes or decimal integer as a big-endian word
+fn parse_slot_word(word: &str) -> Result<Vec<u8>, ParseError> {
+    let parsed = if word.starts_with("0x") {
+        prefix_hex::decode::<Vec<u8>>(word).ok()
+    } else {
+        U256::from_str(word).ok().map(|x| x.to_be_bytes_vec())
+    };
+    parsed.ok_or_else(|| ParseError::ParseError(format!("invalid slot key or index {}", word)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eip1967_slots() {
+        for (label, slot) in [
+            ("eip1967.proxy.implementation", "eip1967.implementation"),
+            ("eip1967.proxy.admin", "eip1967.admin"),
+            ("eip1967.proxy.beacon", "eip1967.beacon"),
+        ] {
+            let expected = U256::from_be_bytes(keccak256(label).0) - U256::from(1);
+            let resolved = resolve_slot_expression(slot).unwrap().unwrap();
+            assert_eq!(resolved, expected.to_be_bytes_vec());
+        }
+    }
+
+    #[test]
+    fn test_resolve_mapping_slot() {
+        let owner = "0x000000000000000000000000000000000000dead";
+        let spender = "0x000000000000000000000000000000000000beef";
+        let balance = resolve_slot_expression(&format!("mapping:{}:0", owner)).unwrap().unwrap();
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(&prefix_hex::decode::<Vec<u8>>(owner).unwrap());
+        assert_eq!(balance, keccak256(preimage).to_vec());
+
+        let allowance =
+            resolve_slot_expression(&format!("mapping:{}:{}:0x01", owner, spender)).unwrap();
+        let inner = mapping_slot(&prefix_hex::decode::<Vec<u8>>(owner).unwrap(), &[1]).unwrap();
+        let outer = mapping_slot(&prefix_hex::decode::<Vec<u8>>(spender).unwrap(), &inner);
+        assert_eq!(allowance, Some(outer.unwrap()));
+
+        assert_eq!(resolve_slot_expression("0x01").unwrap(), None);
+        assert!(resolve_slot_expression("mapping:1").is_err());
+    }
+}
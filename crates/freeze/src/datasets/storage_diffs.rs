@@ -25,6 +25,10 @@ impl Dataset for StorageDiffs {
     fn aliases() -> Vec<&'static str> {
         vec!["slot_diffs"]
     }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "address", "slot"])
+    }
 }
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<TraceResults>);
@@ -22,6 +22,10 @@ impl Dataset for StorageReads {
     fn aliases() -> Vec<&'static str> {
         vec!["slot_reads"]
     }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "contract_address", "slot"])
+    }
 }
 
 type BlockTxsTraces = (Option<u32>, Vec<Option<Vec<u8>>>, Vec<BTreeMap<Address, AccountState>>);
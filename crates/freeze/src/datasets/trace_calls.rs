@@ -1,6 +1,12 @@
 use super::traces;
 use crate::*;
-use alloy::rpc::types::trace::parity::{Action, TraceOutput, TraceType, TransactionTrace};
+use alloy::rpc::types::trace::{
+    geth::CallFrame,
+    parity::{
+        Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput, CreationMethod,
+        SelfdestructAction, TraceOutput, TraceType, TransactionTrace,
+    },
+};
 use polars::prelude::*;
 
 /// columns for transactions
@@ -36,6 +42,25 @@ impl Dataset for TraceCalls {
         Some("latest".to_string())
     }
 
+    fn description() -> Option<&'static str> {
+        Some(
+            "traces of a call simulated at each block, one row per call frame, uses trace_call \
+             or debug_traceCall with --debug-trace-calls",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("transaction_index", "index of the call frame within the simulated call"),
+            ("result_gas_used", "gas used by the call frame"),
+            ("result_output", "return data of the call frame"),
+            ("tx_to_address", "address called by the simulated call"),
+            ("tx_call_data", "call data of the simulated call"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
     fn required_parameters() -> Vec<Dim> {
         vec![Dim::Contract, Dim::CallData]
     }
@@ -49,16 +74,29 @@ impl Dataset for TraceCalls {
 impl CollectByBlock for TraceCalls {
     type Response = (u32, Vec<u8>, Vec<u8>, Vec<TransactionTrace>);
 
-    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
-        let traces: Vec<TransactionTrace> = source
-            .trace_call2(
-                request.ethers_contract()?,
-                request.call_data()?,
-                vec![TraceType::Trace],
-                Some(request.ethers_block_number()?),
-            )
-            .await?
-            .trace;
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let traces: Vec<TransactionTrace> = if query.debug_trace_calls {
+            let frame = source
+                .geth_debug_trace_call(
+                    request.ethers_contract()?,
+                    request.call_data()?,
+                    request.block_number()?,
+                )
+                .await?;
+            let mut traces = Vec::new();
+            flatten_call_frame(frame, vec![], &mut traces);
+            traces
+        } else {
+            source
+                .trace_call2(
+                    request.ethers_contract()?,
+                    request.call_data()?,
+                    vec![TraceType::Trace],
+                    Some(request.ethers_block_number()?),
+                )
+                .await?
+                .trace
+        };
         Ok((request.block_number()? as u32, request.contract()?, request.call_data()?, traces))
     }
 
@@ -177,3 +215,90 @@ fn process_result(result: &Option<TraceOutput>, columns: &mut TraceCalls, schema
         }
     }
 }
+
+/// convert a callTracer frame and its subcalls into traces in trace_call order
+fn flatten_call_frame(
+    frame: CallFrame,
+    trace_address: Vec<usize>,
+    traces: &mut Vec<TransactionTrace>,
+) {
+    let gas = frame.gas.saturating_to::<u64>();
+    let gas_used = frame.gas_used.saturating_to::<u64>();
+    let value = frame.value.unwrap_or_default();
+    let to = frame.to.unwrap_or_default();
+    let output = frame.output.unwrap_or_default();
+    let (action, result) = match frame.typ.to_uppercase().as_str() {
+        typ @ ("CREATE" | "CREATE2") => {
+            let creation_method = match typ {
+                "CREATE2" => CreationMethod::Create2,
+                _ => CreationMethod::Create,
+            };
+            let action =
+                CreateAction { from: frame.from, gas, init: frame.input, value, creation_method };
+            let result = CreateOutput { address: to, code: output, gas_used };
+            (Action::Create(action), TraceOutput::Create(result))
+        }
+        "SELFDESTRUCT" => {
+            let action =
+                SelfdestructAction { address: frame.from, balance: value, refund_address: to };
+            let result = CallOutput { gas_used, output };
+            (Action::Selfdestruct(action), TraceOutput::Call(result))
+        }
+        typ => {
+            let call_type = match typ {
+                "CALL" => CallType::Call,
+                "CALLCODE" => CallType::CallCode,
+                "DELEGATECALL" => CallType::DelegateCall,
+                "STATICCALL" => CallType::StaticCall,
+                _ => CallType::None,
+            };
+            let action =
+                CallAction { from: frame.from, call_type, gas, input: frame.input, to, value };
+            let result = CallOutput { gas_used, output };
+            (Action::Call(action), TraceOutput::Call(result))
+        }
+    };
+    let result = match (&frame.error, &action) {
+        (Some(_), _) | (None, Action::Selfdestruct(_)) => None,
+        (None, _) => Some(result),
+    };
+    traces.push(TransactionTrace {
+        action,
+        error: frame.error,
+        result,
+        subtraces: frame.calls.len(),
+        trace_address: trace_address.clone(),
+    });
+    for (index, subcall) in frame.calls.into_iter().enumerate() {
+        let mut sub_trace_address = trace_address.clone();
+        sub_trace_address.push(index);
+        flatten_call_frame(subcall, sub_trace_address, traces);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(typ: &str, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame { typ: typ.to_string(), calls, ..Default::default() }
+    }
+
+    #[test]
+    fn test_flatten_call_frame() {
+        let mut reverted = frame("CALL", vec![]);
+        reverted.error = Some("execution reverted".to_string());
+        let root =
+            frame("CALL", vec![frame("STATICCALL", vec![frame("CREATE2", vec![])]), reverted]);
+        let mut traces = Vec::new();
+        flatten_call_frame(root, vec![], &mut traces);
+        let addresses: Vec<_> = traces.iter().map(|t| t.trace_address.clone()).collect();
+        assert_eq!(addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+        assert_eq!(traces[0].subtraces, 2);
+        assert!(
+            matches!(&traces[1].action, Action::Call(a) if a.call_type == CallType::StaticCall)
+        );
+        assert!(matches!(traces[2].result, Some(TraceOutput::Create(_))));
+        assert!(traces[3].result.is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use super::traces;
+use crate::*;
+use alloy::rpc::types::trace::parity::{Action, LocalizedTransactionTrace, TraceOutput};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// columns for gas attributed to call frames
+#[cryo_to_df::to_df(Datatype::TraceGas)]
+#[derive(Default)]
+pub struct TraceGas {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    trace_address: Vec<String>,
+    parent_trace_address: Vec<Option<String>>,
+    depth: Vec<u32>,
+    action_type: Vec<String>,
+    from_address: Vec<Vec<u8>>,
+    to_address: Vec<Option<Vec<u8>>>,
+    subtraces: Vec<u32>,
+    gas_used: Vec<Option<u64>>,
+    exclusive_gas_used: Vec<Option<u64>>,
+    error: Vec<Option<String>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for TraceGas {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "transaction_index", "trace_address"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "gas used by each call frame of traces, exclusive_gas_used subtracts the gas used by \
+             direct subcalls from the gas used by the frame",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("parent_trace_address", "trace address of the calling frame, null for top calls"),
+            ("depth", "number of frames above the frame, 0 for top calls"),
+            ("gas_used", "gas used by the frame including subcalls, null if the frame failed"),
+            (
+                "exclusive_gas_used",
+                "gas used by the frame excluding subcalls, null if the frame or a direct subcall \
+                 failed",
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for TraceGas {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_block(request.ethers_block_number()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_trace_gas(&response, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for TraceGas {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_transaction(request.ethers_transaction_hash()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_trace_gas(&response, columns, &query.schemas)
+    }
+}
+
+fn process_trace_gas(
+    traces: &[LocalizedTransactionTrace],
+    columns: &mut TraceGas,
+    schemas: &Schemas,
+) -> R<()> {
+    let schema = schemas.get_schema(&Datatype::TraceGas)?;
+    let exclusive_gas = exclusive_gas_used(traces);
+    for (trace, exclusive_gas_used) in traces.iter().zip(exclusive_gas) {
+        let (from_address, to_address) = match &trace.trace.action {
+            Action::Call(action) => (action.from, Some(action.to)),
+            Action::Create(action) => match &trace.trace.result {
+                Some(TraceOutput::Create(result)) => (action.from, Some(result.address)),
+                _ => (action.from, None),
+            },
+            Action::Selfdestruct(action) => (action.address, Some(action.refund_address)),
+            Action::Reward(_) => continue,
+        };
+        let trace_address: Vec<String> =
+            trace.trace.trace_address.iter().map(|n| n.to_string()).collect();
+        let parent_trace_address = trace_address.split_last().map(|(_, parent)| parent.join("_"));
+
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, trace.block_number.unwrap_or(0) as u32);
+        store!(schema, columns, transaction_index, trace.transaction_position.map(|x| x as u32));
+        store!(schema, columns, transaction_hash, trace.transaction_hash.map(|x| x.to_vec()));
+        store!(schema, columns, trace_address, trace_address.join("_"));
+        store!(schema, columns, parent_trace_address, parent_trace_address);
+        store!(schema, columns, depth, trace_address.len() as u32);
+        store!(
+            schema,
+            columns,
+            action_type,
+            traces::action_type_to_string(&trace.trace.action.kind())
+        );
+        store!(schema, columns, from_address, from_address.to_vec());
+        store!(schema, columns, to_address, to_address.map(|x| x.to_vec()));
+        store!(schema, columns, subtraces, trace.trace.subtraces as u32);
+        store!(schema, columns, gas_used, trace_gas_used(trace));
+        store!(schema, columns, exclusive_gas_used, exclusive_gas_used);
+        store!(schema, columns, error, trace.trace.error.clone());
+    }
+    Ok(())
+}
+
+fn trace_gas_used(trace: &LocalizedTransactionTrace) -> Option<u64> {
+    match &trace.trace.result {
+        Some(TraceOutput::Call(result)) => Some(result.gas_used),
+        Some(TraceOutput::Create(result)) => Some(result.gas_used),
+        None => None,
+    }
+}
+
+/// gas used by each trace minus the gas used by its direct subcalls
+///
+/// traces are grouped by transaction, a trace with a direct subcall whose gas used is unknown,
+/// such as a failed subcall, has unknown exclusive gas
+fn exclusive_gas_used(traces: &[LocalizedTransactionTrace]) -> Vec<Option<u64>> {
+    let mut subcall_gas: HashMap<(Option<usize>, &[usize]), Option<u64>> = HashMap::new();
+    for trace in traces.iter() {
+        if let Some((_, parent)) = trace.trace.trace_address.split_last() {
+            let gas = subcall_gas.entry((trace.transaction_position, parent)).or_insert(Some(0));
+            *gas = gas.zip(trace_gas_used(trace)).map(|(total, gas_used)| total + gas_used);
+        }
+    }
+    traces
+        .iter()
+        .map(|trace| {
+            let key = (trace.transaction_position, trace.trace.trace_address.as_slice());
+            let subcalls = subcall_gas.get(&key).copied().unwrap_or(Some(0))?;
+            trace_gas_used(trace).map(|gas_used| gas_used.saturating_sub(subcalls))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::types::trace::parity::{CallOutput, TransactionTrace};
+
+    fn trace(
+        position: usize,
+        trace_address: Vec<usize>,
+        gas_used: Option<u64>,
+    ) -> LocalizedTransactionTrace {
+        let result = gas_used.map(|gas_used| CallOutput { gas_used, output: Default::default() });
+        LocalizedTransactionTrace {
+            trace: TransactionTrace {
+                trace_address,
+                result: result.map(TraceOutput::Call),
+                ..Default::default()
+            },
+            block_hash: None,
+            block_number: Some(1),
+            transaction_hash: None,
+            transaction_position: Some(position),
+        }
+    }
+
+    #[test]
+    fn test_exclusive_gas_used() {
+        let traces = vec![
+            trace(0, vec![], Some(100)),
+            trace(0, vec![0], Some(30)),
+            trace(0, vec![0, 0], Some(10)),
+            trace(0, vec![1], Some(20)),
+            trace(1, vec![], Some(50)),
+            trace(1, vec![0], None),
+            trace(1, vec![0, 0], Some(5)),
+        ];
+        let expected = vec![Some(50), Some(20), Some(10), Some(20), None, None, Some(5)];
+        assert_eq!(exclusive_gas_used(&traces), expected);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::*;
+use alloy::rpc::types::trace::parity::LocalizedTransactionTrace;
+use polars::prelude::*;
+
+/// columns for trace trees
+#[cryo_to_df::to_df(Datatype::TraceTrees)]
+#[derive(Default)]
+pub struct TraceTrees {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    transaction_index: Vec<Option<u32>>,
+    transaction_hash: Vec<Option<Vec<u8>>>,
+    n_traces: Vec<u32>,
+    trace_tree: Vec<String>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for TraceTrees {
+    fn aliases() -> Vec<&'static str> {
+        vec!["traces_json"]
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("call traces from trace_block as unflattened json, one row per transaction")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("n_traces", "number of traces in the tree"),
+            ("trace_tree", "root trace as json, with the subtraces of each trace nested in calls"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for TraceTrees {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_block(request.block_number()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::TraceTrees)?;
+        process_trace_trees(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for TraceTrees {
+    type Response = Vec<LocalizedTransactionTrace>;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        source.trace_transaction(request.ethers_transaction_hash()?).await
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::TraceTrees)?;
+        process_trace_trees(response, columns, schema)
+    }
+}
+
+/// process traces into one row per transaction, block rewards each form their own row
+fn process_trace_trees(
+    traces: Vec<LocalizedTransactionTrace>,
+    columns: &mut TraceTrees,
+    schema: &Table,
+) -> R<()> {
+    let mut groups: Vec<Vec<LocalizedTransactionTrace>> = Vec::new();
+    for trace in traces.into_iter() {
+        match groups.last_mut() {
+            Some(group)
+                if !trace.trace.trace_address.is_empty() &&
+                    group[0].transaction_hash == trace.transaction_hash =>
+            {
+                group.push(trace)
+            }
+            _ => groups.push(vec![trace]),
+        }
+    }
+    for group in groups.into_iter() {
+        let root = &group[0];
+        columns.n_rows += 1;
+        store!(schema, columns, block_number, root.block_number.unwrap_or_default() as u32);
+        store!(schema, columns, transaction_index, root.transaction_position.map(|x| x as u32));
+        store!(schema, columns, transaction_hash, root.transaction_hash.map(|x| x.to_vec()));
+        store!(schema, columns, n_traces, group.len() as u32);
+        if schema.has_column("trace_tree") {
+            store!(schema, columns, trace_tree, build_trace_tree(&group)?.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// nest traces of a transaction under their parents according to their trace addresses
+fn build_trace_tree(traces: &[LocalizedTransactionTrace]) -> R<serde_json::Value> {
+    let mut roots: Vec<serde_json::Value> = Vec::new();
+    for trace in traces.iter() {
+        let mut node = serde_json::to_value(&trace.trace)
+            .map_err(|_| err("could not serialize trace to json"))?;
+        node["calls"] = serde_json::Value::Array(Vec::new());
+        let path = &trace.trace.trace_address;
+        let parent = match path.split_last() {
+            Some((_, ancestors)) => ancestors.iter().fold(roots.last_mut(), |parent, index| {
+                parent.and_then(|parent| parent.get_mut("calls")?.get_mut(*index))
+            }),
+            None => None,
+        };
+        match parent.and_then(|parent| parent.get_mut("calls")?.as_array_mut()) {
+            Some(calls) => calls.push(node),
+            None => roots.push(node),
+        }
+    }
+    match roots.len() {
+        1 => Ok(roots.swap_remove(0)),
+        _ => Ok(serde_json::Value::Array(roots)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::types::trace::parity::TransactionTrace;
+
+    fn trace(trace_address: Vec<usize>) -> LocalizedTransactionTrace {
+        LocalizedTransactionTrace {
+            trace: TransactionTrace { trace_address, ..Default::default() },
+            block_hash: None,
+            block_number: Some(1),
+            transaction_hash: None,
+            transaction_position: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_build_trace_tree() {
+        let traces = vec![trace(vec![]), trace(vec![0]), trace(vec![0, 0]), trace(vec![1])];
+        let tree = build_trace_tree(&traces).unwrap();
+        assert_eq!(tree["calls"].as_array().unwrap().len(), 2);
+        assert_eq!(tree["calls"][0]["calls"].as_array().unwrap().len(), 1);
+        assert_eq!(tree["calls"][0]["calls"][0]["traceAddress"], serde_json::json!([0, 0]));
+        assert_eq!(tree["calls"][1]["calls"].as_array().unwrap().len(), 0);
+    }
+}
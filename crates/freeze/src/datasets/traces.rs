@@ -4,9 +4,13 @@ use alloy::{
     rpc::types::trace::parity::{
         Action, ActionType, CallType, LocalizedTransactionTrace, RewardType, TraceOutput,
     },
+    sol_types::decode_revert_reason,
 };
 use polars::prelude::*;
 
+/// maximum number of trace_address_N columns, see --trace-address-columns
+pub const MAX_TRACE_ADDRESS_COLUMNS: usize = 8;
+
 /// columns for transactions
 #[cryo_to_df::to_df(Datatype::Traces)]
 #[derive(Default)]
@@ -26,20 +30,111 @@ pub struct Traces {
     result_code: Vec<Option<Vec<u8>>>,
     result_address: Vec<Option<Vec<u8>>>,
     trace_address: Vec<String>,
+    trace_depth: Vec<u32>,
+    trace_address_0: Vec<Option<u32>>,
+    trace_address_1: Vec<Option<u32>>,
+    trace_address_2: Vec<Option<u32>>,
+    trace_address_3: Vec<Option<u32>>,
+    trace_address_4: Vec<Option<u32>>,
+    trace_address_5: Vec<Option<u32>>,
+    trace_address_6: Vec<Option<u32>>,
+    trace_address_7: Vec<Option<u32>>,
     subtraces: Vec<u32>,
     transaction_index: Vec<Option<u32>>,
     transaction_hash: Vec<Option<Vec<u8>>>,
     block_number: Vec<u32>,
     block_hash: Vec<Vec<u8>>,
     error: Vec<Option<String>>,
+    revert_reason: Vec<Option<String>>,
+    global_index: Vec<Option<u64>>,
     chain_id: Vec<u64>,
 }
 
 #[async_trait::async_trait]
 impl Dataset for Traces {
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "action_from",
+            "action_to",
+            "action_value",
+            "action_gas",
+            "action_input",
+            "action_call_type",
+            "action_init",
+            "action_reward_type",
+            "action_type",
+            "result_gas_used",
+            "result_output",
+            "result_code",
+            "result_address",
+            "trace_address",
+            "trace_depth",
+            "subtraces",
+            "transaction_index",
+            "transaction_hash",
+            "block_number",
+            "block_hash",
+            "error",
+            "revert_reason",
+            // "global_index",
+            "chain_id",
+        ])
+    }
+
     fn optional_parameters() -> Vec<Dim> {
         vec![Dim::FromAddress, Dim::ToAddress]
     }
+
+    fn description() -> Option<&'static str> {
+        Some("call traces from trace_block, one row per trace")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("action_from", "address that made the call or create"),
+            ("action_to", "address that was called"),
+            ("action_value", "amount of wei sent with the call or create"),
+            ("action_gas", "gas provided to the call or create"),
+            ("action_input", "call data of the call"),
+            ("action_call_type", "kind of call: call, delegatecall, staticcall, or callcode"),
+            ("action_init", "init code of the create"),
+            ("action_reward_type", "kind of reward: block or uncle"),
+            ("action_type", "kind of trace: call, create, suicide, or reward"),
+            ("result_gas_used", "gas used by the call or create"),
+            ("result_output", "data returned by the call"),
+            ("result_code", "code deployed by the create"),
+            ("result_address", "address of the contract deployed by the create"),
+            ("trace_address", "position of the trace in the call tree, indices joined by _"),
+            ("trace_depth", "number of indices in trace_address, 0 for top-level calls"),
+            ("trace_address_0", "first index of trace_address, null beyond trace_depth"),
+            ("trace_address_1", "second index of trace_address, null beyond trace_depth"),
+            ("trace_address_2", "third index of trace_address, null beyond trace_depth"),
+            ("trace_address_3", "fourth index of trace_address, null beyond trace_depth"),
+            ("trace_address_4", "fifth index of trace_address, null beyond trace_depth"),
+            ("trace_address_5", "sixth index of trace_address, null beyond trace_depth"),
+            ("trace_address_6", "seventh index of trace_address, null beyond trace_depth"),
+            ("trace_address_7", "eighth index of trace_address, null beyond trace_depth"),
+            ("subtraces", "number of traces called directly by the trace"),
+            ("revert_reason", "reason decoded from the output of a reverted call"),
+            (
+                "global_index",
+                "block_number * 1e9 + position of the trace in its block, null when collected by \
+                 transaction",
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn unique_key() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "trace_address",
+            "action_reward_type",
+            "action_to",
+        ])
+    }
 }
 
 #[async_trait::async_trait]
@@ -54,7 +149,7 @@ impl CollectByBlock for Traces {
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let traces =
             if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
-        process_traces(&traces, columns, &query.schemas)
+        process_traces(&traces, columns, &query.schemas, true)
     }
 }
 
@@ -70,7 +165,7 @@ impl CollectByTransaction for Traces {
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let traces =
             if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
-        process_traces(&traces, columns, &query.schemas)
+        process_traces(&traces, columns, &query.schemas, false)
     }
 }
 
@@ -113,13 +208,17 @@ pub(crate) fn filter_traces_by_from_to_addresses(
 }
 
 /// process block into columns
+///
+/// whole_block is whether traces come from the traces of a block rather than of a transaction,
+/// in which case global_index counts the position of each trace in the block
 pub(crate) fn process_traces(
     traces: &[LocalizedTransactionTrace],
     columns: &mut Traces,
     schemas: &Schemas,
+    whole_block: bool,
 ) -> R<()> {
     let schema = schemas.get(&Datatype::Traces).ok_or(err("schema not provided"))?;
-    for trace in traces.iter() {
+    for (trace_position, trace) in traces.iter().enumerate() {
         columns.n_rows += 1;
         process_action(&trace.trace.action, columns, schema);
         process_result(&trace.trace.result, columns, schema);
@@ -136,16 +235,49 @@ pub(crate) fn process_traces(
                 .collect::<Vec<String>>()
                 .join("_")
         );
+        process_trace_address(&trace.trace.trace_address, columns, schema);
         store!(schema, columns, subtraces, trace.trace.subtraces as u32);
         store!(schema, columns, transaction_index, trace.transaction_position.map(|x| x as u32));
         store!(schema, columns, transaction_hash, trace.transaction_hash.map(|x| x.to_vec()));
         store!(schema, columns, block_number, trace.block_number.unwrap() as u32);
         store!(schema, columns, block_hash, trace.block_hash.unwrap().to_vec());
         store!(schema, columns, error, trace.trace.error.clone());
+        store!(schema, columns, revert_reason, revert_reason(trace));
+        store!(
+            schema,
+            columns,
+            global_index,
+            trace
+                .block_number
+                .filter(|_| whole_block)
+                .map(|block_number| global_index(block_number, trace_position as u64))
+        );
     }
     Ok(())
 }
 
+/// store the depth of a trace and the leading indices of its trace address as integer columns
+fn process_trace_address(trace_address: &[usize], columns: &mut Traces, schema: &Table) {
+    let index = |i: usize| trace_address.get(i).map(|x| *x as u32);
+    store!(schema, columns, trace_depth, trace_address.len() as u32);
+    store!(schema, columns, trace_address_0, index(0));
+    store!(schema, columns, trace_address_1, index(1));
+    store!(schema, columns, trace_address_2, index(2));
+    store!(schema, columns, trace_address_3, index(3));
+    store!(schema, columns, trace_address_4, index(4));
+    store!(schema, columns, trace_address_5, index(5));
+    store!(schema, columns, trace_address_6, index(6));
+    store!(schema, columns, trace_address_7, index(7));
+}
+
+/// decode Error(string) and Panic(uint256) payloads from the output of reverted calls
+fn revert_reason(trace: &LocalizedTransactionTrace) -> Option<String> {
+    match (&trace.trace.error, &trace.trace.result) {
+        (Some(_), Some(TraceOutput::Call(result))) => decode_revert_reason(&result.output),
+        _ => None,
+    }
+}
+
 fn process_action(action: &Action, columns: &mut Traces, schema: &Table) {
     match action {
         Action::Call(action) => {
@@ -1,12 +1,13 @@
 use crate::*;
 use alloy::{
-    consensus::Transaction as ConsensusTransaction,
-    primitives::{Address, TxKind, U256},
+    consensus::{Eip658Value, Transaction as ConsensusTransaction, TxReceipt},
+    primitives::{Address, TxKind, B256, U256},
     rpc::types::{
         Block, BlockTransactions, BlockTransactionsKind, Transaction, TransactionReceipt,
     },
 };
 use polars::prelude::*;
+use std::{collections::HashMap, str::FromStr};
 
 /// columns for transactions
 #[cryo_to_df::to_df(Datatype::Transactions)]
@@ -27,17 +28,29 @@ pub struct Transactions {
     transaction_type: Vec<u32>,
     max_priority_fee_per_gas: Vec<Option<u64>>,
     max_fee_per_gas: Vec<Option<u64>>,
-    success: Vec<bool>,
+    success: Vec<Option<bool>>,
+    success_derived: Vec<bool>,
     n_input_bytes: Vec<u32>,
     n_input_zero_bytes: Vec<u32>,
     n_input_nonzero_bytes: Vec<u32>,
     n_rlp_bytes: Vec<u32>,
+    n_access_list_items: Vec<u32>,
     block_hash: Vec<Vec<u8>>,
     chain_id: Vec<u64>,
     timestamp: Vec<u32>,
     r: Vec<Vec<u8>>,
     s: Vec<Vec<u8>>,
     v: Vec<bool>,
+    global_index: Vec<Option<u64>>,
+    source_hash: Vec<Option<Vec<u8>>>,
+    mint: Vec<Option<U256>>,
+    is_system_tx: Vec<Option<bool>>,
+    l1_fee: Vec<Option<U256>>,
+    l1_gas_used: Vec<Option<u64>>,
+    l1_gas_price: Vec<Option<u64>>,
+    l1_blob_base_fee: Vec<Option<u64>>,
+    gas_used_for_l1: Vec<Option<u64>>,
+    l1_block_number: Vec<Option<u64>>,
 }
 
 #[async_trait::async_trait]
@@ -73,79 +86,162 @@ impl Dataset for Transactions {
     fn optional_parameters() -> Vec<Dim> {
         vec![Dim::FromAddress, Dim::ToAddress]
     }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "transactions, one row per transaction, on l2 chains system transactions such as \
+             optimism deposits are included and l2 columns are included by default",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("nonce", "number of transactions previously sent by the sender"),
+            ("gas_price", "price per unit of gas paid by the transaction"),
+            ("transaction_type", "eip-2718 type of the transaction"),
+            ("max_priority_fee_per_gas", "maximum tip per unit of gas, null before london"),
+            ("max_fee_per_gas", "maximum total fee per unit of gas, null before london"),
+            (
+                "success_derived",
+                "whether success was derived from gas usage for pre-byzantium receipts, which is \
+                 only possible when the transaction left part of its gas limit unused",
+            ),
+            ("n_input_bytes", "size of the input in bytes"),
+            ("n_input_zero_bytes", "number of zero bytes in the input"),
+            ("n_input_nonzero_bytes", "number of nonzero bytes in the input"),
+            ("n_rlp_bytes", "size of the rlp encoded transaction in bytes"),
+            ("n_access_list_items", "number of entries in the access list"),
+            ("r", "r value of the transaction signature"),
+            ("s", "s value of the transaction signature"),
+            ("v", "v value of the transaction signature"),
+            ("global_index", "block_number * 1e9 + transaction_index, unique across the chain"),
+            ("source_hash", "source hash of optimism deposit transactions"),
+            ("mint", "value minted on l2 by optimism deposit transactions"),
+            ("is_system_tx", "whether an optimism deposit transaction is a system transaction"),
+            ("l1_fee", "fee paid for posting the transaction data to l1, on optimism"),
+            ("l1_gas_used", "l1 gas attributed to the transaction data, on optimism"),
+            ("l1_gas_price", "l1 base fee used to compute the l1 fee, on optimism"),
+            ("l1_blob_base_fee", "l1 blob base fee used to compute the l1 fee, on optimism"),
+            ("gas_used_for_l1", "l2 gas spent on posting the transaction data to l1, on arbitrum"),
+            ("l1_block_number", "l1 block number reported by the transaction, on arbitrum"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// tuple representing transaction, optional receipt, and l2 fields
+pub type TransactionAndReceipt = (Transaction, Option<TransactionReceipt>, L2Fields);
+
+/// fields of transactions and receipts that only exist on l2 chains, see [ChainProfile]
+#[derive(Clone, Debug, Default)]
+pub struct L2Fields {
+    /// type of a system transaction that was parsed as a legacy transaction
+    transaction_type: Option<u32>,
+    source_hash: Option<Vec<u8>>,
+    mint: Option<U256>,
+    is_system_tx: Option<bool>,
+    l1_fee: Option<U256>,
+    l1_gas_used: Option<u64>,
+    l1_gas_price: Option<u64>,
+    l1_blob_base_fee: Option<u64>,
+    gas_used_for_l1: Option<u64>,
+    l1_block_number: Option<u64>,
+}
+
+impl L2Fields {
+    fn new(
+        tx: &serde_json::Value,
+        receipt: Option<&serde_json::Value>,
+        transaction_type: Option<u32>,
+    ) -> L2Fields {
+        let receipt_u64 = |key: &str| receipt.and_then(|receipt| json_u64(receipt, key));
+        L2Fields {
+            transaction_type,
+            source_hash: json_bytes(tx, "sourceHash"),
+            mint: json_u256(tx, "mint"),
+            is_system_tx: tx.get("isSystemTx").and_then(|x| x.as_bool()),
+            l1_fee: receipt.and_then(|receipt| json_u256(receipt, "l1Fee")),
+            l1_gas_used: receipt_u64("l1GasUsed"),
+            l1_gas_price: receipt_u64("l1GasPrice"),
+            l1_blob_base_fee: receipt_u64("l1BlobBaseFee"),
+            gas_used_for_l1: receipt_u64("gasUsedForL1"),
+            l1_block_number: receipt_u64("l1BlockNumber"),
+        }
+    }
 }
 
-/// tuple representing transaction and optional receipt
-pub type TransactionAndReceipt = (Transaction, Option<TransactionReceipt>);
+/// columns read from the receipts of l2 chains
+const L2_RECEIPT_COLUMNS: [&str; 6] = [
+    "l1_fee",
+    "l1_gas_used",
+    "l1_gas_price",
+    "l1_blob_base_fee",
+    "gas_used_for_l1",
+    "l1_block_number",
+];
+
+/// highest transaction type known to ethereum, higher types are l2 system transactions
+const MAX_ETHEREUM_TRANSACTION_TYPE: u64 = 4;
 
 #[async_trait::async_trait]
 impl CollectByBlock for Transactions {
     type Response = (Block, Vec<TransactionAndReceipt>, bool);
 
     async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let schema = query.schemas.get_schema(&Datatype::Transactions)?;
+        if ChainProfile::from_chain_id(source.chain_id).is_l2() {
+            let (block, transactions) = extract_l2_block(&request, &source, schema).await?;
+            return Ok((block, transactions, query.exclude_failed))
+        }
         let block = source
             .get_block(request.block_number()?, BlockTransactionsKind::Full)
             .await?
             .ok_or(CollectError::CollectError("block not found".to_string()))?;
-        let schema = query.schemas.get_schema(&Datatype::Transactions)?;
 
         // 1. collect transactions and filter them if optional parameters are supplied
-        // filter by from_address
-        let from_filter: Box<dyn Fn(&Transaction) -> bool + Send> =
-            if let Some(from_address) = &request.from_address {
-                Box::new(move |tx| tx.from == Address::from_slice(from_address))
-            } else {
-                Box::new(|_| true)
-            };
-        // filter by to_address
-        let to_filter: Box<dyn Fn(&Transaction) -> bool + Send> =
-            if let Some(to_address) = &request.to_address {
-                Box::new(move |tx| match tx.inner.kind() {
-                    TxKind::Create => false,
-                    TxKind::Call(address) => address == Address::from_slice(to_address),
-                })
-            } else {
-                Box::new(|_| true)
-            };
         let transactions: Vec<Transaction> = block
             .transactions
             .clone()
             .as_transactions()
             .unwrap()
             .iter()
-            .filter(|&x| from_filter(x))
-            .filter(|&x| to_filter(x))
+            .filter(|&x| matches_request(&request, x))
             .cloned()
             .collect();
 
         // 2. collect receipts if necessary
         // if transactions are filtered fetch by set of transaction hashes, else fetch all receipts
         // in block
-        let receipts: Vec<Option<_>> =
-            if schema.has_column("gas_used") | schema.has_column("success") {
-                // receipts required
-                let receipts = if request.from_address.is_some() || request.to_address.is_some() {
-                    source.get_tx_receipts(BlockTransactions::Full(transactions.clone())).await?
-                } else {
-                    source.get_tx_receipts_in_block(&block).await?
-                };
-                receipts.into_iter().map(Some).collect()
+        let receipts: Vec<Option<_>> = if needs_receipts(schema) {
+            // receipts required
+            let receipts = if request.from_address.is_some() || request.to_address.is_some() {
+                source.get_tx_receipts(BlockTransactions::Full(transactions.clone())).await?
             } else {
-                vec![None; block.transactions.len()]
+                source.get_tx_receipts_in_block(&block).await?
             };
+            receipts.into_iter().map(Some).collect()
+        } else {
+            vec![None; block.transactions.len()]
+        };
 
-        let transactions_with_receips = transactions.into_iter().zip(receipts).collect();
+        let transactions_with_receips = transactions
+            .into_iter()
+            .zip(receipts)
+            .map(|(tx, receipt)| (tx, receipt, L2Fields::default()))
+            .collect();
         Ok((block, transactions_with_receips, query.exclude_failed))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Transactions)?;
         let (block, transactions_with_receipts, exclude_failed) = response;
-        for (tx, receipt) in transactions_with_receipts.into_iter() {
+        for (tx, receipt, l2_fields) in transactions_with_receipts.into_iter() {
             let gas_price = get_gas_price(&block, &tx);
             process_transaction(
                 tx,
                 receipt,
+                l2_fields,
                 columns,
                 schema,
                 exclude_failed,
@@ -164,14 +260,31 @@ impl CollectByTransaction for Transactions {
     async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
         let tx_hash = request.ethers_transaction_hash()?;
         let schema = query.schemas.get_schema(&Datatype::Transactions)?;
-        let transaction = source
-            .get_transaction_by_hash(tx_hash)
-            .await?
-            .ok_or(CollectError::CollectError("transaction not found".to_string()))?;
-        let receipt = if schema.has_column("gas_used") {
-            source.get_transaction_receipt(tx_hash).await?
+        let needs_receipt = schema.has_column("gas_used") |
+            L2_RECEIPT_COLUMNS.iter().any(|column| schema.has_column(column));
+        let is_l2 = ChainProfile::from_chain_id(source.chain_id).is_l2();
+        let (transaction, receipt, l2_fields) = if is_l2 {
+            let tx_json = source
+                .get_transaction_by_hash_json(tx_hash)
+                .await?
+                .ok_or(CollectError::CollectError("transaction not found".to_string()))?;
+            let receipt_json = if needs_receipt {
+                source.get_transaction_receipt_json(tx_hash).await?
+            } else {
+                None
+            };
+            let (transaction, transaction_type) = parse_l2_transaction(tx_json.clone())?;
+            let receipt = receipt_json.clone().map(parse_l2_receipt).transpose()?;
+            let l2_fields = L2Fields::new(&tx_json, receipt_json.as_ref(), transaction_type);
+            (transaction, receipt, l2_fields)
         } else {
-            None
+            let transaction = source
+                .get_transaction_by_hash(tx_hash)
+                .await?
+                .ok_or(CollectError::CollectError("transaction not found".to_string()))?;
+            let receipt =
+                if needs_receipt { source.get_transaction_receipt(tx_hash).await? } else { None };
+            (transaction, receipt, L2Fields::default())
         };
 
         let block_number = transaction
@@ -185,16 +298,17 @@ impl CollectByTransaction for Transactions {
 
         let timestamp = block.header.timestamp as u32;
 
-        Ok(((transaction, receipt), block, query.exclude_failed, timestamp))
+        Ok(((transaction, receipt, l2_fields), block, query.exclude_failed, timestamp))
     }
 
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let schema = query.schemas.get_schema(&Datatype::Transactions)?;
-        let ((transaction, receipt), block, exclude_failed, timestamp) = response;
+        let ((transaction, receipt, l2_fields), block, exclude_failed, timestamp) = response;
         let gas_price = get_gas_price(&block, &transaction);
         process_transaction(
             transaction,
             receipt,
+            l2_fields,
             columns,
             schema,
             exclude_failed,
@@ -205,28 +319,175 @@ impl CollectByTransaction for Transactions {
     }
 }
 
+fn needs_receipts(schema: &Table) -> bool {
+    schema.has_column("gas_used") |
+        schema.has_column("success") |
+        schema.has_column("success_derived")
+}
+
+/// whether transaction matches the from_address and to_address parameters of request
+fn matches_request(request: &Params, tx: &Transaction) -> bool {
+    let from_matches = match &request.from_address {
+        Some(from_address) => tx.from == Address::from_slice(from_address),
+        None => true,
+    };
+    let to_matches = match &request.to_address {
+        Some(to_address) => match tx.inner.kind() {
+            TxKind::Create => false,
+            TxKind::Call(address) => address == Address::from_slice(to_address),
+        },
+        None => true,
+    };
+    from_matches & to_matches
+}
+
+/// collect transactions of a block of an l2 chain from raw json
+///
+/// typed blocks cannot hold l2 system transactions, so the block is fetched as json and its
+/// transactions and receipts are parsed one by one, keeping the l2 fields of each, receipts are
+/// read with `eth_getBlockReceipts` or per transaction when the provider does not support it
+async fn extract_l2_block(
+    request: &Params,
+    source: &Source,
+    schema: &Table,
+) -> R<(Block, Vec<TransactionAndReceipt>)> {
+    let block_number = request.block_number()?;
+    let mut block_json = source
+        .get_block_json(block_number, true)
+        .await?
+        .ok_or(CollectError::CollectError("block not found".to_string()))?;
+    let txs_json = match block_json["transactions"].take() {
+        serde_json::Value::Array(txs_json) => txs_json,
+        _ => return Err(err("block json has no transactions")),
+    };
+    block_json["transactions"] = serde_json::Value::Array(vec![]);
+    let mut block: Block = serde_json::from_value(block_json)
+        .map_err(|e| err(format!("could not parse block: {}", e).as_str()))?;
+
+    let mut block_transactions = Vec::new();
+    let mut requested = Vec::new();
+    for tx_json in txs_json.into_iter() {
+        let (tx, transaction_type) = parse_l2_transaction(tx_json.clone())?;
+        block_transactions.push(tx.clone());
+        if matches_request(request, &tx) {
+            requested.push((tx, tx_json, transaction_type));
+        }
+    }
+
+    let needs_receipts = needs_receipts(schema);
+    let mut receipts_json: HashMap<B256, serde_json::Value> = if needs_receipts |
+        L2_RECEIPT_COLUMNS.iter().any(|column| schema.has_column(column))
+    {
+        let tx_hashes: Vec<B256> = requested.iter().map(|(tx, _, _)| *tx.inner.tx_hash()).collect();
+        source
+            .get_receipts_json_by_block_number(block_number, &tx_hashes)
+            .await?
+            .into_iter()
+            .filter_map(|receipt| {
+                let hash = receipt.get("transactionHash")?.as_str()?;
+                Some((B256::from_str(hash).ok()?, receipt))
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut transactions = Vec::new();
+    for (tx, tx_json, transaction_type) in requested.into_iter() {
+        let receipt_json = receipts_json.remove(tx.inner.tx_hash());
+        let receipt = match (needs_receipts, &receipt_json) {
+            (true, Some(receipt_json)) => Some(parse_l2_receipt(receipt_json.clone())?),
+            (true, None) => return Err(err("receipt missing from block receipts")),
+            (false, _) => None,
+        };
+        let l2_fields = L2Fields::new(&tx_json, receipt_json.as_ref(), transaction_type);
+        transactions.push((tx, receipt, l2_fields));
+    }
+    block.transactions = BlockTransactions::Full(block_transactions);
+    Ok((block, transactions))
+}
+
+/// parse transaction json, parsing l2 system transactions as unsigned legacy transactions
+///
+/// the original type of a system transaction is returned alongside it, the rlp size and signature
+/// of the legacy transaction do not describe the system transaction
+fn parse_l2_transaction(mut tx: serde_json::Value) -> R<(Transaction, Option<u32>)> {
+    let transaction_type = match json_u64(&tx, "type") {
+        Some(tx_type) if tx_type > MAX_ETHEREUM_TRANSACTION_TYPE => Some(tx_type as u32),
+        _ => None,
+    };
+    if let (Some(_), Some(fields)) = (transaction_type, tx.as_object_mut()) {
+        fields.remove("type");
+        fields.remove("chainId");
+        fields.entry("gasPrice").or_insert("0x0".into());
+        fields.insert("v".to_string(), "0x1b".into());
+        fields.insert("r".to_string(), "0x0".into());
+        fields.insert("s".to_string(), "0x0".into());
+    }
+    let tx = serde_json::from_value(tx)
+        .map_err(|e| err(format!("could not parse transaction: {}", e).as_str()))?;
+    Ok((tx, transaction_type))
+}
+
+/// parse receipt json, parsing receipts of l2 system transactions as legacy receipts
+fn parse_l2_receipt(mut receipt: serde_json::Value) -> R<TransactionReceipt> {
+    let is_system_receipt =
+        json_u64(&receipt, "type").is_some_and(|x| x > MAX_ETHEREUM_TRANSACTION_TYPE);
+    if let (true, Some(fields)) = (is_system_receipt, receipt.as_object_mut()) {
+        fields.insert("type".to_string(), "0x0".into());
+        fields.entry("effectiveGasPrice").or_insert("0x0".into());
+    }
+    serde_json::from_value(receipt)
+        .map_err(|e| err(format!("could not parse receipt: {}", e).as_str()))
+}
+
+fn json_u64(value: &serde_json::Value, key: &str) -> Option<u64> {
+    value
+        .get(key)?
+        .as_str()
+        .and_then(|x| x.strip_prefix("0x"))
+        .and_then(|x| u64::from_str_radix(x, 16).ok())
+}
+
+fn json_u256(value: &serde_json::Value, key: &str) -> Option<U256> {
+    value.get(key)?.as_str().and_then(|x| U256::from_str(x).ok())
+}
+
+fn json_bytes(value: &serde_json::Value, key: &str) -> Option<Vec<u8>> {
+    value.get(key)?.as_str().and_then(|x| prefix_hex::decode::<Vec<u8>>(x).ok())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn process_transaction(
     tx: Transaction,
     receipt: Option<TransactionReceipt>,
+    l2_fields: L2Fields,
     columns: &mut Transactions,
     schema: &Table,
     exclude_failed: bool,
     timestamp: u32,
     gas_price: Option<u64>,
 ) -> R<()> {
-    let success = if exclude_failed | schema.has_column("success") {
-        let success = tx_success(&tx, &receipt)?;
-        if exclude_failed & !success {
-            return Ok(())
-        }
-        success
-    } else {
-        false
-    };
+    let (success, success_derived) =
+        if exclude_failed | schema.has_column("success") | schema.has_column("success_derived") {
+            let (success, success_derived) = tx_success(&tx, &receipt)?;
+            if exclude_failed & (success == Some(false)) {
+                return Ok(())
+            }
+            (success, success_derived)
+        } else {
+            (None, false)
+        };
 
     columns.n_rows += 1;
     store!(schema, columns, block_number, tx.block_number.map(|x| x as u32));
     store!(schema, columns, transaction_index, tx.transaction_index);
+    store!(
+        schema,
+        columns,
+        global_index,
+        tx.block_number.zip(tx.transaction_index).map(|(bn, ti)| global_index(bn, ti))
+    );
     store!(schema, columns, transaction_hash, tx.inner.tx_hash().to_vec());
     store!(schema, columns, from_address, tx.from.to_vec());
     store!(
@@ -243,6 +504,7 @@ pub(crate) fn process_transaction(
     store!(schema, columns, input, tx.inner.input().to_vec());
     store!(schema, columns, gas_limit, tx.inner.gas_limit());
     store!(schema, columns, success, success);
+    store!(schema, columns, success_derived, success_derived);
     if schema.has_column("n_input_bytes") |
         schema.has_column("n_input_zero_bytes") |
         schema.has_column("n_input_nonzero_bytes")
@@ -255,10 +517,21 @@ pub(crate) fn process_transaction(
     }
     // in alloy eip2718_encoded_length is rlp_encoded_length
     store!(schema, columns, n_rlp_bytes, tx.inner.eip2718_encoded_length() as u32);
+    store!(
+        schema,
+        columns,
+        n_access_list_items,
+        tx.inner.access_list().map(|access_list| access_list.len() as u32).unwrap_or(0)
+    );
     store!(schema, columns, gas_used, receipt.as_ref().map(|r| r.gas_used as u64));
     // store!(schema, columns, gas_price, Some(receipt.unwrap().effective_gas_price as u64));
     store!(schema, columns, gas_price, gas_price);
-    store!(schema, columns, transaction_type, tx.inner.tx_type() as u32);
+    store!(
+        schema,
+        columns,
+        transaction_type,
+        l2_fields.transaction_type.unwrap_or(tx.inner.tx_type() as u32)
+    );
     store!(schema, columns, max_fee_per_gas, get_max_fee_per_gas(&tx));
     store!(
         schema,
@@ -273,10 +546,20 @@ pub(crate) fn process_transaction(
     store!(schema, columns, r, tx.inner.signature().r().to_vec_u8());
     store!(schema, columns, s, tx.inner.signature().s().to_vec_u8());
 
+    store!(schema, columns, source_hash, l2_fields.source_hash);
+    store!(schema, columns, mint, l2_fields.mint);
+    store!(schema, columns, is_system_tx, l2_fields.is_system_tx);
+    store!(schema, columns, l1_fee, l2_fields.l1_fee);
+    store!(schema, columns, l1_gas_used, l2_fields.l1_gas_used);
+    store!(schema, columns, l1_gas_price, l2_fields.l1_gas_price);
+    store!(schema, columns, l1_blob_base_fee, l2_fields.l1_blob_base_fee);
+    store!(schema, columns, gas_used_for_l1, l2_fields.gas_used_for_l1);
+    store!(schema, columns, l1_block_number, l2_fields.l1_block_number);
+
     Ok(())
 }
 
-fn get_max_fee_per_gas(tx: &Transaction) -> Option<u64> {
+pub(crate) fn get_max_fee_per_gas(tx: &Transaction) -> Option<u64> {
     match &tx.inner {
         alloy::consensus::TxEnvelope::Legacy(_) => None,
         alloy::consensus::TxEnvelope::Eip2930(_) => None,
@@ -301,18 +584,67 @@ pub(crate) fn get_gas_price(block: &Block, tx: &Transaction) -> Option<u64> {
     }
 }
 
-fn tx_success(tx: &Transaction, receipt: &Option<TransactionReceipt>) -> R<bool> {
-    if let Some(r) = receipt {
-        Ok(r.status())
-    } else if let (Some(1), Some(true)) =
-        (tx.inner.chain_id(), tx.block_number.map(|x| x < 4370000))
-    {
-        if let Some(r) = receipt {
-            Ok(r.gas_used == 0)
-        } else {
-            return Err(err("could not determine status of transaction"))
-        }
-    } else {
-        return Err(err("could not determine status of transaction"))
+/// return transaction success, and whether success was derived rather than read from the receipt
+fn tx_success(tx: &Transaction, receipt: &Option<TransactionReceipt>) -> R<(Option<bool>, bool)> {
+    match receipt {
+        Some(r) => Ok(receipt_success(r, Some(tx.inner.gas_limit()))),
+        None => Err(err("could not determine status of transaction")),
+    }
+}
+
+/// return receipt success, and whether success was derived rather than read from the receipt
+///
+/// receipts before eip-658 (byzantium) contain a post-state root instead of a status code. before
+/// byzantium every failure consumed the entire gas limit, so a transaction that left gas unused
+/// succeeded, while one that used all of its gas may have either succeeded or failed and its
+/// success is null
+pub(crate) fn receipt_success(
+    receipt: &TransactionReceipt,
+    gas_limit: Option<u64>,
+) -> (Option<bool>, bool) {
+    match receipt.inner.status_or_post_state() {
+        Eip658Value::Eip658(status) => (Some(status), false),
+        Eip658Value::PostState(_) => match gas_limit {
+            Some(gas_limit) if (receipt.gas_used as u64) < gas_limit => (Some(true), true),
+            _ => (None, true),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_l2_transaction() {
+        let hash = "0xa4f5a2f7e5c2d1c8cce4f8f0b6e2a4ffa7ee8b7e8c4b2b77d4bd2bc7a81d1d8e";
+        let tx_json = serde_json::json!({
+            "type": "0x7e",
+            "sourceHash": "0x6e2e3a1f8e5bfbd0e03c1bb8e9b1d8a5a6cbf0b81b0b3df4d15a0c3bb6e1d5f7",
+            "from": "0xdeaddeaddeaddeaddeaddeaddeaddeaddead0001",
+            "to": "0x4200000000000000000000000000000000000015",
+            "mint": "0x0",
+            "value": "0x0",
+            "gas": "0xf4240",
+            "isSystemTx": false,
+            "input": "0x440a5e20",
+            "nonce": "0x8b2f1b",
+            "hash": hash,
+            "blockHash": "0x3b5fc2f5d2d8d4e5ab52c59f9c9ea8d1f4c1e8b1d2a0b3f68f2ad4bd4cb8a4e1",
+            "blockNumber": "0x1",
+            "transactionIndex": "0x0",
+        });
+        let receipt_json = serde_json::json!({"l1Fee": "0x2a", "l1GasUsed": "0x640"});
+        let (tx, transaction_type) = parse_l2_transaction(tx_json.clone()).unwrap();
+        assert_eq!(transaction_type, Some(0x7e));
+        assert_eq!(*tx.inner.tx_hash(), B256::from_str(hash).unwrap());
+        assert_eq!(tx.inner.gas_limit(), 1_000_000);
+
+        let l2_fields = L2Fields::new(&tx_json, Some(&receipt_json), transaction_type);
+        assert_eq!(l2_fields.source_hash.map(|x| x.len()), Some(32));
+        assert_eq!(l2_fields.is_system_tx, Some(false));
+        assert_eq!(l2_fields.l1_fee, Some(U256::from(42)));
+        assert_eq!(l2_fields.l1_gas_used, Some(1600));
+        assert_eq!(l2_fields.gas_used_for_l1, None);
     }
 }
@@ -0,0 +1,163 @@
+use crate::*;
+use alloy::{
+    primitives::U256,
+    rpc::types::{Block, BlockTransactionsKind},
+};
+use polars::prelude::*;
+
+/// columns for uncles
+#[cryo_to_df::to_df(Datatype::Uncles)]
+#[derive(Default)]
+pub struct Uncles {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Vec<u8>>,
+    uncle_index: Vec<u32>,
+    uncle_hash: Vec<Vec<u8>>,
+    uncle_number: Vec<u32>,
+    uncle_depth: Vec<u32>,
+    parent_hash: Vec<Vec<u8>>,
+    miner: Vec<Vec<u8>>,
+    timestamp: Vec<u32>,
+    difficulty: Vec<u64>,
+    gas_used: Vec<u64>,
+    gas_limit: Vec<u64>,
+    extra_data: Vec<Vec<u8>>,
+    uncle_reward: Vec<Option<U256>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for Uncles {
+    fn aliases() -> Vec<&'static str> {
+        vec!["ommers"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "uncle_index",
+            "uncle_hash",
+            "uncle_number",
+            "uncle_depth",
+            "miner",
+            "timestamp",
+            "gas_used",
+            "uncle_reward",
+            "chain_id",
+        ])
+    }
+
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "uncle_index"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some("uncle (ommer) headers included by canonical blocks, one row per uncle")
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("block_number", "canonical block that included the uncle"),
+            ("uncle_index", "index of the uncle within the including block"),
+            ("uncle_depth", "number of blocks between the uncle and the including block"),
+            ("miner", "address that received the uncle reward"),
+            ("uncle_reward", "wei paid to the uncle miner, mainnet reward schedule only"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// block with its uncles, and the chain id used to select the reward schedule
+type BlockUncles = (Block, Vec<Block>, u64);
+
+#[async_trait::async_trait]
+impl CollectByBlock for Uncles {
+    type Response = BlockUncles;
+
+    async fn extract(request: Params, source: Arc<Source>, _: Arc<Query>) -> R<Self::Response> {
+        let block_number = request.block_number()?;
+        let block = source
+            .get_block(block_number, BlockTransactionsKind::Hashes)
+            .await?
+            .ok_or(CollectError::CollectError("block not found".to_string()))?;
+        let mut uncles = Vec::with_capacity(block.uncles.len());
+        for index in 0..block.uncles.len() {
+            let uncle = source
+                .get_uncle(block_number, index as u64)
+                .await?
+                .ok_or(CollectError::CollectError("uncle not found".to_string()))?;
+            uncles.push(uncle);
+        }
+        Ok((block, uncles, source.chain_id))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::Uncles)?;
+        let (block, uncles, chain_id) = response;
+        for (index, uncle) in uncles.into_iter().enumerate() {
+            let depth = block.header.number.saturating_sub(uncle.header.number);
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block.header.number as u32);
+            store!(schema, columns, block_hash, block.header.hash.to_vec());
+            store!(schema, columns, uncle_index, index as u32);
+            store!(schema, columns, uncle_hash, uncle.header.hash.to_vec());
+            store!(schema, columns, uncle_number, uncle.header.number as u32);
+            store!(schema, columns, uncle_depth, depth as u32);
+            store!(schema, columns, parent_hash, uncle.header.parent_hash.to_vec());
+            store!(schema, columns, miner, uncle.header.beneficiary.to_vec());
+            store!(schema, columns, timestamp, uncle.header.timestamp as u32);
+            store!(schema, columns, difficulty, uncle.header.difficulty.wrapping_to::<u64>());
+            store!(schema, columns, gas_used, uncle.header.gas_used);
+            store!(schema, columns, gas_limit, uncle.header.gas_limit);
+            store!(schema, columns, extra_data, uncle.header.extra_data.to_vec());
+            store!(
+                schema,
+                columns,
+                uncle_reward,
+                mainnet_uncle_reward(chain_id, block.header.number, depth)
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for Uncles {
+    type Response = ();
+}
+
+/// reward of an uncle included `depth` blocks after its own block, (8 - depth) / 8 of the block
+/// reward, which was 5 eth before byzantium, 3 eth before constantinople, and 2 eth until the merge
+fn mainnet_uncle_reward(chain_id: u64, block_number: u64, depth: u64) -> Option<U256> {
+    if chain_id != 1 || depth == 0 || depth > 7 {
+        return None
+    }
+    let block_reward_eth = match block_number {
+        0..=4_369_999 => 5,
+        4_370_000..=7_279_999 => 3,
+        _ => 2,
+    };
+    let block_reward = U256::from(block_reward_eth) * U256::from(10).pow(U256::from(18));
+    Some(block_reward * U256::from(8 - depth) / U256::from(8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainnet_uncle_reward() {
+        let ether = U256::from(10).pow(U256::from(18));
+        assert_eq!(
+            mainnet_uncle_reward(1, 1_000_000, 1),
+            Some(U256::from(35) * ether / U256::from(8))
+        );
+        assert_eq!(
+            mainnet_uncle_reward(1, 10_000_000, 2),
+            Some(U256::from(3) * ether / U256::from(2))
+        );
+        assert_eq!(mainnet_uncle_reward(5, 1_000_000, 1), None);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::*;
+use alloy::{
+    consensus::Transaction as ConsensusTransaction,
+    primitives::{Address, B256, U256},
+    rpc::types::{Filter, Log, Topic},
+    sol_types::{SolCall, SolEvent},
+};
+use polars::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// columns for erc-4337 user operations, decoded from EntryPoint UserOperationEvent logs
+///
+/// the fields of each operation are decoded from the handleOps calldata of its transaction,
+/// which is only fetched when one of those columns is requested
+#[cryo_to_df::to_df(Datatype::UserOperations)]
+#[derive(Default)]
+pub struct UserOperations {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    block_hash: Vec<Option<Vec<u8>>>,
+    transaction_index: Vec<u32>,
+    log_index: Vec<u32>,
+    transaction_hash: Vec<Vec<u8>>,
+    entry_point: Vec<Vec<u8>>,
+    user_op_hash: Vec<Vec<u8>>,
+    sender: Vec<Vec<u8>>,
+    paymaster: Vec<Vec<u8>>,
+    nonce: Vec<U256>,
+    success: Vec<bool>,
+    actual_gas_cost: Vec<U256>,
+    actual_gas_used: Vec<U256>,
+    init_code: Vec<Option<Vec<u8>>>,
+    call_data: Vec<Option<Vec<u8>>>,
+    call_gas_limit: Vec<Option<U256>>,
+    verification_gas_limit: Vec<Option<U256>>,
+    pre_verification_gas: Vec<Option<U256>>,
+    max_fee_per_gas: Vec<Option<U256>>,
+    max_priority_fee_per_gas: Vec<Option<U256>>,
+    paymaster_and_data: Vec<Option<Vec<u8>>>,
+    beneficiary: Vec<Option<Vec<u8>>>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for UserOperations {
+    fn aliases() -> Vec<&'static str> {
+        vec!["user_ops"]
+    }
+
+    fn default_columns() -> Option<Vec<&'static str>> {
+        Some(vec![
+            "block_number",
+            "transaction_index",
+            "log_index",
+            "transaction_hash",
+            "entry_point",
+            "user_op_hash",
+            "sender",
+            "paymaster",
+            "nonce",
+            "success",
+            "actual_gas_cost",
+            "actual_gas_used",
+            "chain_id",
+        ])
+    }
+
+    fn column_descriptions() -> HashMap<&'static str, &'static str> {
+        [
+            (
+                "call_data",
+                "calldata of the operation, decoded from the handleOps call of its transaction. \
+                 calldata columns are null when the transaction does not call handleOps of the \
+                 entry point directly, e.g. when a bundler contract relays it",
+            ),
+            ("beneficiary", "address receiving the gas refund of the bundle"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn optional_parameters() -> Vec<Dim> {
+        vec![Dim::Address]
+    }
+
+    fn use_block_ranges() -> bool {
+        true
+    }
+
+    fn arg_aliases() -> Option<std::collections::HashMap<Dim, Dim>> {
+        Some([(Dim::Contract, Dim::Address)].into_iter().collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByBlock for UserOperations {
+    type Response = (Vec<Log>, HandleOps);
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let mut topics: [Topic; 4] = Default::default();
+        topics[0] = EntryPoint::UserOperationEvent::SIGNATURE_HASH.into();
+        let filter = Filter { topics, ..request.ethers_log_filter()? };
+        let logs = source.get_logs(&filter).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_user_operation_event).collect();
+        let schema = query.schemas.get_schema(&Datatype::UserOperations)?;
+        let ops = fetch_handle_ops(&logs, &source, schema).await?;
+        Ok((logs, ops))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::UserOperations)?;
+        process_user_operations(response, columns, schema)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for UserOperations {
+    type Response = (Vec<Log>, HandleOps);
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let logs = source.get_transaction_logs(request.transaction_hash()?).await?;
+        let logs: Vec<Log> = logs.into_iter().filter(is_user_operation_event).collect();
+        let schema = query.schemas.get_schema(&Datatype::UserOperations)?;
+        let ops = fetch_handle_ops(&logs, &source, schema).await?;
+        Ok((logs, ops))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::UserOperations)?;
+        process_user_operations(response, columns, schema)
+    }
+}
+
+fn is_user_operation_event(log: &Log) -> bool {
+    log.topics().len() == 4 && log.topics()[0] == EntryPoint::UserOperationEvent::SIGNATURE_HASH
+}
+
+/// operations decoded from the handleOps calldata of each transaction
+type HandleOps = HashMap<B256, Vec<HandleOp>>;
+
+/// fields of a user operation and its bundle, decoded from handleOps calldata
+#[derive(Clone, Debug, PartialEq)]
+struct HandleOp {
+    sender: Address,
+    nonce: U256,
+    init_code: Vec<u8>,
+    call_data: Vec<u8>,
+    call_gas_limit: U256,
+    verification_gas_limit: U256,
+    pre_verification_gas: U256,
+    max_fee_per_gas: U256,
+    max_priority_fee_per_gas: U256,
+    paymaster_and_data: Vec<u8>,
+    beneficiary: Address,
+}
+
+/// columns decoded from handleOps calldata rather than from UserOperationEvent logs
+const HANDLE_OPS_COLUMNS: [&str; 9] = [
+    "init_code",
+    "call_data",
+    "call_gas_limit",
+    "verification_gas_limit",
+    "pre_verification_gas",
+    "max_fee_per_gas",
+    "max_priority_fee_per_gas",
+    "paymaster_and_data",
+    "beneficiary",
+];
+
+/// fetch the transactions of logs and decode their handleOps calldata, if any of the calldata
+/// columns are requested
+async fn fetch_handle_ops(logs: &[Log], source: &Source, schema: &Table) -> R<HandleOps> {
+    let mut ops = HashMap::new();
+    if !HANDLE_OPS_COLUMNS.iter().any(|column| schema.has_column(column)) {
+        return Ok(ops)
+    }
+    let tx_hashes: BTreeSet<B256> = logs.iter().filter_map(|log| log.transaction_hash).collect();
+    for tx_hash in tx_hashes.into_iter() {
+        if let Some(tx) = source.get_transaction_by_hash(tx_hash).await? {
+            ops.insert(tx_hash, decode_handle_ops(tx.inner.input()));
+        }
+    }
+    Ok(ops)
+}
+
+/// decode handleOps calldata of the v0.7 or v0.6 entry point, other calldata has no operations
+fn decode_handle_ops(input: &[u8]) -> Vec<HandleOp> {
+    if let Ok(call) = EntryPoint::handleOpsCall::abi_decode(input, true) {
+        call.ops
+            .into_iter()
+            .map(|op| {
+                // gas limits and fees are packed as two uint128 values per bytes32
+                let (verification_gas_limit, call_gas_limit) = unpack_uints(op.accountGasLimits);
+                let (max_priority_fee_per_gas, max_fee_per_gas) = unpack_uints(op.gasFees);
+                HandleOp {
+                    sender: op.sender,
+                    nonce: op.nonce,
+                    init_code: op.initCode.to_vec(),
+                    call_data: op.callData.to_vec(),
+                    call_gas_limit,
+                    verification_gas_limit,
+                    pre_verification_gas: op.preVerificationGas,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    paymaster_and_data: op.paymasterAndData.to_vec(),
+                    beneficiary: call.beneficiary,
+                }
+            })
+            .collect()
+    } else if let Ok(call) = EntryPointV06::handleOpsCall::abi_decode(input, true) {
+        call.ops
+            .into_iter()
+            .map(|op| HandleOp {
+                sender: op.sender,
+                nonce: op.nonce,
+                init_code: op.initCode.to_vec(),
+                call_data: op.callData.to_vec(),
+                call_gas_limit: op.callGasLimit,
+                verification_gas_limit: op.verificationGasLimit,
+                pre_verification_gas: op.preVerificationGas,
+                max_fee_per_gas: op.maxFeePerGas,
+                max_priority_fee_per_gas: op.maxPriorityFeePerGas,
+                paymaster_and_data: op.paymasterAndData.to_vec(),
+                beneficiary: call.beneficiary,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// split a bytes32 into its high and low uint128 halves
+fn unpack_uints(packed: B256) -> (U256, U256) {
+    (U256::from_be_slice(&packed[..16]), U256::from_be_slice(&packed[16..]))
+}
+
+/// process block into columns
+fn process_user_operations(
+    response: (Vec<Log>, HandleOps),
+    columns: &mut UserOperations,
+    schema: &Table,
+) -> R<()> {
+    let (logs, ops) = response;
+    for log in logs.iter() {
+        if let (Some(bn), Some(tx), Some(ti), Some(li)) =
+            (log.block_number, log.transaction_hash, log.transaction_index, log.log_index)
+        {
+            let event = match EntryPoint::UserOperationEvent::decode_log_data(log.data(), true) {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, bn as u32);
+            store!(schema, columns, block_hash, log.block_hash.map(|bh| bh.to_vec()));
+            store!(schema, columns, transaction_index, ti as u32);
+            store!(schema, columns, log_index, li as u32);
+            store!(schema, columns, transaction_hash, tx.to_vec());
+            store!(schema, columns, entry_point, log.address().to_vec());
+            store!(schema, columns, user_op_hash, event.userOpHash.to_vec());
+            store!(schema, columns, sender, event.sender.to_vec());
+            store!(schema, columns, paymaster, event.paymaster.to_vec());
+            store!(schema, columns, nonce, event.nonce);
+            store!(schema, columns, success, event.success);
+            store!(schema, columns, actual_gas_cost, event.actualGasCost);
+            store!(schema, columns, actual_gas_used, event.actualGasUsed);
+
+            // operations are identified within a bundle by their sender and nonce
+            let op = ops.get(&tx).and_then(|ops| {
+                ops.iter().find(|op| op.sender == event.sender && op.nonce == event.nonce)
+            });
+            store!(schema, columns, init_code, op.map(|op| op.init_code.clone()));
+            store!(schema, columns, call_data, op.map(|op| op.call_data.clone()));
+            store!(schema, columns, call_gas_limit, op.map(|op| op.call_gas_limit));
+            store!(schema, columns, verification_gas_limit, op.map(|op| op.verification_gas_limit));
+            store!(schema, columns, pre_verification_gas, op.map(|op| op.pre_verification_gas));
+            store!(schema, columns, max_fee_per_gas, op.map(|op| op.max_fee_per_gas));
+            store!(
+                schema,
+                columns,
+                max_priority_fee_per_gas,
+                op.map(|op| op.max_priority_fee_per_gas)
+            );
+            store!(schema, columns, paymaster_and_data, op.map(|op| op.paymaster_and_data.clone()));
+            store!(schema, columns, beneficiary, op.map(|op| op.beneficiary.to_vec()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, FixedBytes};
+
+    #[test]
+    fn test_decode_handle_ops_v07() {
+        let mut account_gas_limits = [0u8; 32];
+        account_gas_limits[15] = 7;
+        account_gas_limits[31] = 9;
+        let mut gas_fees = [0u8; 32];
+        gas_fees[15] = 1;
+        gas_fees[31] = 2;
+        let op = EntryPoint::PackedUserOperation {
+            sender: Address::with_last_byte(1),
+            nonce: U256::from(5),
+            initCode: Bytes::new(),
+            callData: Bytes::from(vec![0xab, 0xcd]),
+            accountGasLimits: FixedBytes(account_gas_limits),
+            preVerificationGas: U256::from(3),
+            gasFees: FixedBytes(gas_fees),
+            paymasterAndData: Bytes::new(),
+            signature: Bytes::new(),
+        };
+        let call =
+            EntryPoint::handleOpsCall { ops: vec![op], beneficiary: Address::with_last_byte(2) };
+        let ops = decode_handle_ops(&call.abi_encode());
+        assert_eq!(ops.len(), 1);
+        assert_eq!((ops[0].sender, ops[0].nonce), (Address::with_last_byte(1), U256::from(5)));
+        assert_eq!(ops[0].call_data, vec![0xab, 0xcd]);
+        assert_eq!(ops[0].verification_gas_limit, U256::from(7));
+        assert_eq!(ops[0].call_gas_limit, U256::from(9));
+        assert_eq!(ops[0].max_priority_fee_per_gas, U256::from(1));
+        assert_eq!(ops[0].max_fee_per_gas, U256::from(2));
+        assert_eq!(ops[0].beneficiary, Address::with_last_byte(2));
+    }
+
+    #[test]
+    fn test_decode_handle_ops_v06() {
+        let op = EntryPointV06::UserOperation {
+            sender: Address::with_last_byte(1),
+            nonce: U256::from(5),
+            initCode: Bytes::new(),
+            callData: Bytes::from(vec![0xab]),
+            callGasLimit: U256::from(9),
+            verificationGasLimit: U256::from(7),
+            preVerificationGas: U256::from(3),
+            maxFeePerGas: U256::from(2),
+            maxPriorityFeePerGas: U256::from(1),
+            paymasterAndData: Bytes::from(vec![0x01]),
+            signature: Bytes::new(),
+        };
+        let call =
+            EntryPointV06::handleOpsCall { ops: vec![op], beneficiary: Address::with_last_byte(2) };
+        let ops = decode_handle_ops(&call.abi_encode());
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].call_gas_limit, U256::from(9));
+        assert_eq!(ops[0].max_fee_per_gas, U256::from(2));
+        assert_eq!(ops[0].paymaster_and_data, vec![0x01]);
+
+        // calldata of other functions has no operations
+        assert!(decode_handle_ops(&[0x12, 0x34, 0x56, 0x78]).is_empty());
+    }
+}
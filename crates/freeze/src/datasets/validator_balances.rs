@@ -0,0 +1,78 @@
+use crate::*;
+use polars::prelude::*;
+
+/// columns for beacon chain validator balances
+#[cryo_to_df::to_df(Datatype::ValidatorBalances)]
+#[derive(Default)]
+pub struct ValidatorBalances {
+    n_rows: u64,
+    block_number: Vec<u32>,
+    slot: Vec<u64>,
+    validator_index: Vec<u64>,
+    balance: Vec<u64>,
+    chain_id: Vec<u64>,
+}
+
+#[async_trait::async_trait]
+impl Dataset for ValidatorBalances {
+    fn default_sort() -> Option<Vec<&'static str>> {
+        Some(vec!["block_number", "validator_index"])
+    }
+
+    fn description() -> Option<&'static str> {
+        Some(
+            "balances of beacon chain validators at the slot of each execution block, fetched \
+             from the beacon api of --beacon-url, for the validators selected by index or pubkey \
+             with --validators",
+        )
+    }
+
+    fn column_descriptions() -> std::collections::HashMap<&'static str, &'static str> {
+        [
+            ("slot", "beacon chain slot of the balance"),
+            ("validator_index", "index of the validator"),
+            ("balance", "balance of the validator in gwei"),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// execution block number, slot, and (validator index, balance) pairs
+type ValidatorBalancesResponse = (u32, u64, Vec<(u64, u64)>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for ValidatorBalances {
+    type Response = ValidatorBalancesResponse;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let beacon_url = beacon::beacon_url(&query)?;
+        let block_number = request.block_number()?;
+        let slot = beacon::slot_of_block(&source, &beacon_url, block_number).await?;
+        let validators = match &query.validators {
+            Some(validators) if !validators.is_empty() => validators.clone(),
+            _ => return Err(err("validator_balances requires --validators")),
+        };
+        let balances =
+            beacon::get_validator_balances(&source, &beacon_url, slot, &validators).await?;
+        Ok((block_number as u32, slot, balances))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        let schema = query.schemas.get_schema(&Datatype::ValidatorBalances)?;
+        let (block_number, slot, balances) = response;
+        for (validator_index, balance) in balances.into_iter() {
+            columns.n_rows += 1;
+            store!(schema, columns, block_number, block_number);
+            store!(schema, columns, slot, slot);
+            store!(schema, columns, validator_index, validator_index);
+            store!(schema, columns, balance, balance);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for ValidatorBalances {
+    type Response = ();
+}
@@ -18,6 +18,7 @@ pub struct VmTraces {
     storage_key: Vec<Option<Vec<u8>>>,
     storage_val: Vec<Option<Vec<u8>>>,
     op: Vec<Option<String>>,
+    depth: Vec<u32>,
     n_rows: usize,
     chain_id: Vec<u64>,
 }
@@ -75,7 +76,7 @@ fn process_vm_traces(
     let schema = schemas.get(&Datatype::VmTraces).ok_or(err("schema not provided"))?;
     for (tx_pos, block_trace) in block_traces.into_iter().enumerate() {
         if let Some(vm_trace) = block_trace.vm_trace {
-            add_ops(vm_trace, schema, columns, block_number, tx.clone(), tx_pos);
+            add_ops(vm_trace, schema, columns, block_number, tx.clone(), tx_pos, 0);
         }
     }
     Ok(())
@@ -88,6 +89,7 @@ fn add_ops(
     number: Option<u32>,
     tx_hash: Option<Vec<u8>>,
     tx_pos: usize,
+    depth: u32,
 ) {
     for opcode in vm_trace.ops {
         columns.n_rows += 1;
@@ -124,9 +126,10 @@ fn add_ops(
             store!(schema, columns, storage_val, None);
         }
         store!(schema, columns, op, opcode.op);
+        store!(schema, columns, depth, depth);
 
         if let Some(sub) = opcode.sub {
-            add_ops(sub, schema, columns, number, tx_hash.clone(), tx_pos)
+            add_ops(sub, schema, columns, number, tx_hash.clone(), tx_pos, depth + 1)
         }
     }
 }
@@ -1,12 +1,15 @@
 use crate::{
-    collect_partition, dataframes, err, reports, summaries, CollectError, Datatype, ExecutionEnv,
+    collect_partition, collect_partition_variants, dataframes, err, files, reports, summaries,
+    types::collection::block_flush::{block_partition, split_path, take_oversized_blocks},
+    BlockChunk, BlockFlush, ChunkData, CollectError, DatasetQuality, Datatype, ExecutionEnv,
     FileOutput, FreezeSummary, MetaDatatype, Partition, Query, Source,
 };
 use chrono::{DateTime, Local};
 use futures::{stream::FuturesUnordered, StreamExt};
+use polars::prelude::*;
 use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use tokio::sync::Semaphore;
@@ -14,14 +17,19 @@ use tokio::sync::Semaphore;
 type PartitionPayload = (
     Partition,
     MetaDatatype,
-    HashMap<Datatype, PathBuf>,
-    Arc<Query>,
+    Vec<PartitionOutput>,
     Arc<Source>,
-    FileOutput,
     ExecutionEnv,
     Option<std::sync::Arc<Semaphore>>,
 );
 
+/// query, sink, and output paths of one output of a partition
+type PartitionOutput = (Arc<Query>, FileOutput, HashMap<Datatype, PathBuf>);
+
+/// rows, row count check violations, data quality statistics, and split files of a partition
+type PartitionResult =
+    (u64, Vec<String>, BTreeMap<String, DatasetQuality>, BTreeMap<PathBuf, Vec<PathBuf>>);
+
 /// collect data and output as files
 pub async fn freeze(
     query: &Query,
@@ -29,11 +37,27 @@ pub async fn freeze(
     sink: &FileOutput,
     env: &ExecutionEnv,
 ) -> Result<Option<FreezeSummary>, CollectError> {
+    freeze_variants(&[(query.clone(), sink.clone())], source, env).await
+}
+
+/// collect data once and output it as files of several queries that differ only in their
+/// decoders, each written by its own sink
+///
+/// e.g. decoded_calls of every function of an abi fetch the traces of each block once
+pub async fn freeze_variants(
+    variants: &[(Query, FileOutput)],
+    source: &Source,
+    env: &ExecutionEnv,
+) -> Result<Option<FreezeSummary>, CollectError> {
+    let (query, sink) = variants.first().ok_or(err("no queries to freeze"))?;
+
     // check validity of query
-    query.is_valid()?;
+    for (query, _) in variants.iter() {
+        query.is_valid()?;
+    }
 
     // get partitions
-    let (payloads, skipping) = get_payloads(query, source, sink, env)?;
+    let (payloads, skipping) = get_payloads(variants, source, env)?;
 
     // print summary
     if env.verbose >= 1 {
@@ -48,20 +72,53 @@ pub async fn freeze(
     // check if empty
     if payloads.is_empty() {
         let results = FreezeSummary { skipped: skipping, ..Default::default() };
+        for (query, sink) in variants.iter() {
+            reports::write_completion_markers(query, sink, &results)?;
+        }
         if env.verbose >= 1 {
             summaries::print_cryo_conclusion(&results, query, env)
         }
         return Ok(Some(results))
     }
 
-    // create initial report
+    // create initial report and record resolved options of run
     if env.report {
-        reports::write_report(env, query, sink, None)?;
+        reports::write_report(env, query, source, sink, None)?;
+        reports::write_run_metadata(env, query, source, sink)?;
     };
 
     // perform collection
+    let mut written: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for (_, sink, paths) in payloads.iter().flat_map(|payload| payload.2.iter()) {
+        if sink.version_outputs {
+            written.entry(sink.output_dir.clone()).or_default().extend(paths.values().cloned());
+        }
+    }
     let results = freeze_partitions(env, payloads, skipping).await;
 
+    // track latest versions of re-collected files
+    for (output_dir, written) in written.iter() {
+        files::update_version_manifest(output_dir, written)?;
+    }
+
+    // track files of oversized blocks split out of written chunks
+    let output_dirs: HashSet<&PathBuf> =
+        variants.iter().map(|(_, sink)| &sink.output_dir).collect();
+    for output_dir in output_dirs.into_iter() {
+        let splits = results
+            .split_files
+            .iter()
+            .filter(|(path, _)| path.starts_with(output_dir))
+            .map(|(path, splits)| (path.clone(), splits.clone()))
+            .collect();
+        files::update_split_manifest(output_dir, &splits)?;
+    }
+
+    // mark directories whose requested range is fully frozen
+    for (query, sink) in variants.iter() {
+        reports::write_completion_markers(query, sink, &results)?;
+    }
+
     // create summary
     if env.verbose >= 1 {
         summaries::print_cryo_conclusion(&results, query, env)
@@ -69,7 +126,7 @@ pub async fn freeze(
 
     // create final report
     if env.report {
-        reports::write_report(env, query, sink, Some(&results))?;
+        reports::write_report(env, query, source, sink, Some(&results))?;
     };
 
     // return
@@ -77,44 +134,74 @@ pub async fn freeze(
 }
 
 fn get_payloads(
-    query: &Query,
+    variants: &[(Query, FileOutput)],
     source: &Source,
-    sink: &FileOutput,
     env: &ExecutionEnv,
 ) -> Result<(Vec<PartitionPayload>, Vec<Partition>), CollectError> {
+    let (query, _) = variants.first().ok_or(err("no queries to freeze"))?;
     let semaphore = source
         .max_concurrent_chunks
         .map(|x| std::sync::Arc::new(tokio::sync::Semaphore::new(x as usize)));
     let source: Arc<Source> = Arc::new(source.clone());
-    let arc_query = Arc::new(query.clone());
+    let arc_queries: Vec<Arc<Query>> =
+        variants.iter().map(|(query, _)| Arc::new(query.clone())).collect();
+    let mut split_manifests = HashMap::new();
+    for (_, sink) in variants.iter() {
+        if !split_manifests.contains_key(&sink.output_dir) {
+            let manifest = files::load_split_manifest(&sink.output_dir)?;
+            split_manifests.insert(sink.output_dir.clone(), manifest);
+        }
+    }
     let mut payloads = Vec::new();
     let mut skipping = Vec::new();
     let mut all_paths = HashSet::new();
     for datatype in query.datatypes.clone().into_iter() {
         for partition in query.partitions.clone().into_iter() {
-            let paths = sink.get_paths(query, &partition, Some(vec![datatype.clone()]))?;
-            if !sink.overwrite && paths.values().all(|path| path.exists()) {
+            let mut outputs = Vec::new();
+            for ((query, sink), arc_query) in variants.iter().zip(arc_queries.iter()) {
+                let paths = sink.get_paths(query, &partition, Some(vec![datatype.clone()]))?;
+                let split_manifest = &split_manifests[&sink.output_dir];
+                let exists = |path: &PathBuf| {
+                    path.exists() &&
+                        files::split_paths(&sink.output_dir, split_manifest, path)
+                            .iter()
+                            .all(|split| split.exists())
+                };
+                if !sink.overwrite && paths.values().all(exists) {
+                    continue
+                }
+
+                // rewritten files get a new version, files written for the first time are v1
+                let paths: HashMap<Datatype, PathBuf> = if sink.version_outputs {
+                    paths
+                        .into_iter()
+                        .map(|(dt, path)| (dt, files::next_version_path(&path)))
+                        .collect()
+                } else {
+                    paths
+                };
+
+                // check for path collisions
+                let paths_set: HashSet<_> = paths.clone().into_values().collect();
+                if paths_set.intersection(&all_paths).next().is_none() {
+                    all_paths.extend(paths_set);
+                } else {
+                    let message =
+                        format!("output path collision: {:?}", paths_set.intersection(&all_paths));
+                    return Err(err(&message))
+                };
+                outputs.push((arc_query.clone(), sink.clone(), paths));
+            }
+            if outputs.is_empty() {
                 skipping.push(partition);
                 continue
             }
 
-            // check for path collisions
-            let paths_set: HashSet<_> = paths.clone().into_values().collect();
-            if paths_set.intersection(&all_paths).next().is_none() {
-                all_paths.extend(paths_set);
-            } else {
-                let message =
-                    format!("output path collision: {:?}", paths_set.intersection(&all_paths));
-                return Err(err(&message))
-            };
-
             let payload = (
                 partition.clone(),
                 datatype.clone(),
-                paths,
-                arc_query.clone(),
+                outputs,
                 source.clone(),
-                sink.clone(),
                 env.clone(),
                 semaphore.clone(),
             );
@@ -132,7 +219,7 @@ async fn freeze_partitions(
     if let Some(bar) = &env.bar {
         bar.set_length(payloads.len() as u64);
         if let Some(payload) = &payloads.first() {
-            let (_, _, _, _, _, _, env, _) = payload;
+            let (_, _, _, _, env, _) = payload;
             let dt_start: DateTime<Local> = env.t_start.into();
             bar.set_message(format!("started at {}", dt_start.format("%Y-%m-%d %H:%M:%S%.3f")));
         }
@@ -141,19 +228,35 @@ async fn freeze_partitions(
     // spawn task for each partition
     let mut futures = FuturesUnordered::new();
     for payload in payloads.into_iter() {
-        futures.push(tokio::spawn(
-            async move { (payload.0.clone(), freeze_partition(payload).await) },
-        ));
+        let cancel = env.cancel.clone();
+        futures.push(tokio::spawn(async move {
+            let partition = payload.0.clone();
+            match cancel {
+                Some(cancel) => tokio::select! {
+                    _ = cancel.cancelled() => (partition, Err(CollectError::Cancelled)),
+                    result = freeze_partition(payload) => (partition, result),
+                },
+                None => (partition, freeze_partition(payload).await),
+            }
+        }));
     }
 
     // aggregate results
     let mut completed = Vec::new();
     let mut errored = Vec::new();
     let mut n_rows = 0;
+    let mut check_violations = Vec::new();
+    let mut quality: BTreeMap<String, DatasetQuality> = BTreeMap::new();
+    let mut split_files = BTreeMap::new();
     while let Some(result) = futures.next().await {
         match result {
-            Ok((partition, Ok(chunk_n_rows))) => {
+            Ok((partition, Ok((chunk_n_rows, chunk_violations, chunk_quality, chunk_splits)))) => {
                 n_rows += chunk_n_rows;
+                check_violations.extend(chunk_violations);
+                for (name, dataset_quality) in chunk_quality.into_iter() {
+                    quality.entry(name).or_default().merge(dataset_quality);
+                }
+                split_files.extend(chunk_splits);
                 completed.push(partition)
             }
             Ok((partition, Err(e))) => errored.push((Some(partition), e)),
@@ -165,11 +268,14 @@ async fn freeze_partitions(
         bar.finish_and_clear();
     }
 
-    FreezeSummary { completed, errored, skipped, n_rows }
+    FreezeSummary { completed, errored, skipped, n_rows, check_violations, quality, split_files }
 }
 
-async fn freeze_partition(payload: PartitionPayload) -> Result<u64, CollectError> {
-    let (partition, datatype, paths, query, source, sink, env, semaphore) = payload;
+/// collect and write partition, returning its number of rows, row count check violations, data
+/// quality statistics of each dataset, and the files of oversized blocks split out of each chunk
+async fn freeze_partition(payload: PartitionPayload) -> Result<PartitionResult, CollectError> {
+    let (partition, datatype, outputs, source, env, semaphore) = payload;
+    let chain_id = source.chain_id;
 
     // acquire chunk semaphore
     let _permit = match &semaphore {
@@ -177,18 +283,79 @@ async fn freeze_partition(payload: PartitionPayload) -> Result<u64, CollectError
         None => None,
     };
 
-    // collect data
-    let dfs = collect_partition(datatype, partition, query, source).await?;
+    // collect data, once for all outputs, writing oversized blocks while collecting
+    let queries: Vec<Arc<Query>> = outputs.iter().map(|(query, _, _)| query.clone()).collect();
+    let flush = match outputs.as_slice() {
+        [(query, sink, paths)] => {
+            BlockFlush::new(&partition, query.clone(), sink, paths).map(Arc::new)
+        }
+        _ => None,
+    };
+    let all_dfs = match queries.as_slice() {
+        [query] => {
+            let query = match &flush {
+                Some(flush) => {
+                    Arc::new(Query { block_flush: Some(flush.clone()), ..(**query).clone() })
+                }
+                None => query.clone(),
+            };
+            vec![collect_partition(datatype, partition.clone(), query, source).await?]
+        }
+        _ => collect_partition_variants(datatype, partition.clone(), queries, source).await?,
+    };
+    let flushed = match &flush {
+        Some(flush) => flush.take_flushed()?,
+        None => Vec::new(),
+    };
+
+    // evaluate row count checks before anything else is written, blocks that were already
+    // written to their own files were checked when they were written
+    let mut violations = Vec::new();
+    for (dfs, (query, _, _)) in all_dfs.iter().zip(outputs.iter()) {
+        for (datatype, df) in dfs.iter() {
+            let flushed_blocks: Vec<u64> =
+                flushed.iter().filter(|f| f.datatype == *datatype).map(|f| f.block).collect();
+            let check_partition = without_blocks(&partition, &flushed_blocks);
+            for check in query.row_count_checks.iter().filter(|check| check.datatype == *datatype) {
+                violations.extend(check.violations(df, &check_partition)?);
+            }
+        }
+    }
+    violations.extend(flushed.iter().flat_map(|f| f.violations.clone()));
+    let strict_checks = outputs.iter().any(|(query, _, _)| query.strict_checks);
+    if strict_checks && !violations.is_empty() {
+        let message =
+            format!("{} row count check violations, first: {}", violations.len(), violations[0]);
+        return Err(CollectError::CollectError(message))
+    }
 
     // write dataframes to disk
     let mut n_rows = 0;
-    for (datatype, mut df) in dfs {
-        n_rows += df.height() as u64;
-        let path = paths.get(&datatype).ok_or_else(|| {
-            CollectError::CollectError("could not get path for datatype".to_string())
-        })?;
-        let result = dataframes::df_to_file(&mut df, path, &sink);
-        result.map_err(|_| CollectError::CollectError("error writing file".to_string()))?
+    let mut quality: BTreeMap<String, DatasetQuality> = BTreeMap::new();
+    let mut split_files: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for flushed in flushed.into_iter() {
+        n_rows += flushed.n_rows;
+        quality.entry(flushed.datatype.name()).or_default().merge(flushed.quality);
+        split_files.entry(flushed.chunk_path).or_default().push(flushed.path);
+    }
+    for (dfs, (query, sink, paths)) in all_dfs.into_iter().zip(outputs.iter()) {
+        for (datatype, mut df) in dfs {
+            n_rows += df.height() as u64;
+            let chunk_quality = DatasetQuality::from_df(&df, &datatype, chain_id)?;
+            quality.entry(datatype.name()).or_default().merge(chunk_quality);
+            let path = paths.get(&datatype).ok_or_else(|| {
+                CollectError::CollectError("could not get path for datatype".to_string())
+            })?;
+            dataframes::redact_columns(&mut df, sink)?;
+            dataframes::pseudonymize_columns(&mut df, sink)?;
+            let outputs = split_oversized_blocks(df, path, &partition, datatype, query, sink)?;
+            let splits = outputs.iter().map(|(path, _)| path.clone()).filter(|p| p != path);
+            split_files.entry(path.clone()).or_default().extend(splits);
+            for (path, mut df) in outputs.into_iter() {
+                let result = dataframes::df_to_file(&mut df, &path, sink);
+                result.map_err(|_| CollectError::CollectError("error writing file".to_string()))?
+            }
+        }
     }
 
     // update progress bar
@@ -196,5 +363,47 @@ async fn freeze_partition(payload: PartitionPayload) -> Result<u64, CollectError
         bar.inc(1);
     }
 
-    Ok(n_rows)
+    Ok((n_rows, violations, quality, split_files))
+}
+
+/// move blocks whose rows exceed the block byte budget into dedicated single-block files
+///
+/// e.g. a block filled with calldata bombs is written to `..__00001500_to_00001500.parquet`
+/// while the rest of its chunk is written to the chunk's usual path, this catches the blocks
+/// that only exceed the budget once the responses of the whole chunk are combined
+fn split_oversized_blocks(
+    df: DataFrame,
+    path: &Path,
+    partition: &Partition,
+    datatype: Datatype,
+    query: &Query,
+    sink: &FileOutput,
+) -> Result<Vec<(PathBuf, DataFrame)>, CollectError> {
+    let (blocks, rest) = match sink.max_block_bytes {
+        Some(max_block_bytes) => take_oversized_blocks(df, max_block_bytes)?,
+        None => return Ok(vec![(path.to_path_buf(), df)]),
+    };
+    let mut outputs = Vec::new();
+    for (block, block_df) in blocks.into_iter() {
+        let block_partition = block_partition(partition, block);
+        let block_path = split_path(sink, query, path, &block_partition, datatype)?;
+        outputs.push((block_path, block_df));
+    }
+    outputs.push((path.to_path_buf(), rest));
+    Ok(outputs)
+}
+
+/// partition without the given blocks
+fn without_blocks(partition: &Partition, blocks: &[u64]) -> Partition {
+    match &partition.block_numbers {
+        Some(block_numbers) if !blocks.is_empty() => {
+            let remaining =
+                block_numbers.values().into_iter().filter(|b| !blocks.contains(b)).collect();
+            Partition {
+                block_numbers: Some(vec![BlockChunk::Numbers(remaining)]),
+                ..partition.clone()
+            }
+        }
+        _ => partition.clone(),
+    }
 }
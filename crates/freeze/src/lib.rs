@@ -7,14 +7,20 @@
     attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
 ))]
 
+/// beacon chain rest api
+pub mod beacon;
 mod collect;
 mod datasets;
 mod freeze;
 mod multi_datasets;
+/// rpc utilities shared by datasets
+pub mod rpc;
+mod stream;
 mod types;
 
-pub use collect::collect;
+pub use collect::{collect, collect_with_cancel};
 pub use datasets::*;
-pub use freeze::freeze;
+pub use freeze::{freeze, freeze_variants};
 pub use multi_datasets::*;
+pub use stream::stream_mempool;
 pub use types::*;
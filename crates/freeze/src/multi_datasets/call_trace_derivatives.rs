@@ -43,7 +43,7 @@ impl CollectByBlock for CallTraceDerivatives {
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let traces =
             if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
-        process_call_trace_derivatives(traces, columns, &query.schemas)
+        process_call_trace_derivatives(traces, columns, &query.schemas, true)
     }
 }
 
@@ -58,7 +58,7 @@ impl CollectByTransaction for CallTraceDerivatives {
     fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
         let traces =
             if query.exclude_failed { traces::filter_failed_traces(response) } else { response };
-        process_call_trace_derivatives(traces, columns, &query.schemas)
+        process_call_trace_derivatives(traces, columns, &query.schemas, false)
     }
 }
 
@@ -66,6 +66,7 @@ fn process_call_trace_derivatives(
     response: Vec<LocalizedTransactionTrace>,
     columns: &mut CallTraceDerivatives,
     schemas: &HashMap<Datatype, Table>,
+    whole_block: bool,
 ) -> R<()> {
     let CallTraceDerivatives(contracts, native_transfers, traces) = columns;
     if schemas.contains_key(&Datatype::Contracts) {
@@ -75,7 +76,7 @@ fn process_call_trace_derivatives(
         native_transfers::process_native_transfers(&response, native_transfers, schemas)?;
     }
     if schemas.contains_key(&Datatype::Traces) {
-        traces::process_traces(&response, traces, schemas)?;
+        traces::process_traces(&response, traces, schemas, whole_block)?;
     }
     Ok(())
 }
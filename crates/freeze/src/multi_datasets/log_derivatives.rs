@@ -0,0 +1,299 @@
+use crate::*;
+use alloy::rpc::types::{Filter, Log};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// LogDerivatives
+///
+/// datasets decoded from event logs, collected from shared eth_getLogs fetches
+#[derive(Default)]
+pub struct LogDerivatives(
+    erc1155_transfers::Erc1155Transfers,
+    erc20_approvals::Erc20Approvals,
+    erc20_transfers::Erc20Transfers,
+    erc721_transfers::Erc721Transfers,
+    logs::Logs,
+);
+
+impl ToDataFrames for LogDerivatives {
+    fn create_dfs(
+        self,
+        schemas: &HashMap<Datatype, Table>,
+        chain_id: u64,
+    ) -> R<HashMap<Datatype, DataFrame>> {
+        let LogDerivatives(
+            erc1155_transfers,
+            erc20_approvals,
+            erc20_transfers,
+            erc721_transfers,
+            logs,
+        ) = self;
+        let mut output = HashMap::new();
+        if schemas.contains_key(&Datatype::Erc1155Transfers) {
+            output.extend(erc1155_transfers.create_dfs(schemas, chain_id)?);
+        }
+        if schemas.contains_key(&Datatype::Erc20Approvals) {
+            output.extend(erc20_approvals.create_dfs(schemas, chain_id)?);
+        }
+        if schemas.contains_key(&Datatype::Erc20Transfers) {
+            output.extend(erc20_transfers.create_dfs(schemas, chain_id)?);
+        }
+        if schemas.contains_key(&Datatype::Erc721Transfers) {
+            output.extend(erc721_transfers.create_dfs(schemas, chain_id)?);
+        }
+        if schemas.contains_key(&Datatype::Logs) {
+            output.extend(logs.create_dfs(schemas, chain_id)?);
+        }
+        Ok(output)
+    }
+}
+
+/// logs of each derived dataset, in the field order of LogDerivatives
+type SplitLogs = (Vec<Log>, Vec<Log>, Vec<Log>, Vec<Log>, Vec<Log>);
+
+#[async_trait::async_trait]
+impl CollectByBlock for LogDerivatives {
+    type Response = SplitLogs;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        Ok(split_logs(fetch_logs(&request, &source, &query.schemas).await?))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_log_derivatives(response, columns, &query.schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl CollectByTransaction for LogDerivatives {
+    type Response = SplitLogs;
+
+    async fn extract(request: Params, source: Arc<Source>, query: Arc<Query>) -> R<Self::Response> {
+        let logs = source.get_transaction_logs(request.transaction_hash()?).await?;
+        let logs_by_datatype = [
+            Datatype::Erc1155Transfers,
+            Datatype::Erc20Approvals,
+            Datatype::Erc20Transfers,
+            Datatype::Erc721Transfers,
+            Datatype::Logs,
+        ]
+        .into_iter()
+        .filter(|datatype| query.schemas.contains_key(datatype))
+        .map(|datatype| (datatype, logs.clone()))
+        .collect();
+        Ok(split_logs(logs_by_datatype))
+    }
+
+    fn transform(response: Self::Response, columns: &mut Self, query: &Arc<Query>) -> R<()> {
+        process_log_derivatives(response, columns, &query.schemas)
+    }
+}
+
+/// fetch the logs of each requested dataset
+///
+/// each dataset keeps the filter of its own extract. datasets with identical filters (e.g. erc20
+/// and erc721 transfers) share a single eth_getLogs request, and when the logs dataset is
+/// requested without topics, the logs of every other dataset are selected from its fetch
+/// client-side, since they only differ from it in their topics
+async fn fetch_logs(
+    request: &Params,
+    source: &Source,
+    schemas: &HashMap<Datatype, Table>,
+) -> R<HashMap<Datatype, Vec<Log>>> {
+    let filters = log_filters(request, schemas)?;
+    let mut fetched: Vec<(Filter, Vec<Log>)> = Vec::new();
+    if let Some((_, filter)) = filters
+        .iter()
+        .find(|(datatype, filter)| *datatype == Datatype::Logs && has_no_topics(filter))
+    {
+        fetched.push((filter.clone(), source.get_logs(filter).await?));
+    }
+
+    let mut logs_by_datatype = HashMap::new();
+    for (datatype, filter) in filters.into_iter() {
+        let logs = if let Some((_, logs)) = fetched.iter().find(|(f, _)| *f == filter) {
+            logs.clone()
+        } else if let Some((_, logs)) = fetched.iter().find(|(f, _)| contains_logs_of(f, &filter)) {
+            logs.iter().filter(|log| matches_topics(&filter, log)).cloned().collect()
+        } else {
+            let logs = source.get_logs(&filter).await?;
+            fetched.push((filter, logs.clone()));
+            logs
+        };
+        logs_by_datatype.insert(datatype, logs);
+    }
+    Ok(logs_by_datatype)
+}
+
+fn has_no_topics(filter: &Filter) -> bool {
+    filter.topics.iter().all(|topic| topic.is_empty())
+}
+
+/// whether every log matching filter is also returned by superset
+fn contains_logs_of(superset: &Filter, filter: &Filter) -> bool {
+    has_no_topics(superset) &&
+        superset.block_option == filter.block_option &&
+        superset.address == filter.address
+}
+
+/// whether the topics of log match the topics of filter, the way a node applies them
+fn matches_topics(filter: &Filter, log: &Log) -> bool {
+    filter.topics.iter().enumerate().all(|(i, topic)| {
+        topic.is_empty() || log.topics().get(i).is_some_and(|value| topic.matches(value))
+    })
+}
+
+/// eth_getLogs filter of each requested dataset, the same filter as its standalone extract
+fn log_filters(request: &Params, schemas: &HashMap<Datatype, Table>) -> R<Vec<(Datatype, Filter)>> {
+    let mut filters = Vec::new();
+    if schemas.contains_key(&Datatype::Erc1155Transfers) {
+        let filter = erc1155_transfers::erc1155_transfer_filter(request)?;
+        filters.push((Datatype::Erc1155Transfers, filter));
+    }
+    if schemas.contains_key(&Datatype::Erc20Approvals) {
+        filters.push((Datatype::Erc20Approvals, erc20_approvals::erc20_approval_filter(request)?));
+    }
+    if schemas.contains_key(&Datatype::Erc20Transfers) {
+        filters.push((Datatype::Erc20Transfers, erc20_transfers::erc20_transfer_filter(request)?));
+    }
+    if schemas.contains_key(&Datatype::Erc721Transfers) {
+        let filter = erc721_transfers::erc721_transfer_filter(request)?;
+        filters.push((Datatype::Erc721Transfers, filter));
+    }
+    if schemas.contains_key(&Datatype::Logs) {
+        filters.push((Datatype::Logs, request.ethers_log_filter()?));
+    }
+    Ok(filters)
+}
+
+/// keep the logs fetched for each dataset that the dataset decodes
+fn split_logs(mut logs_by_datatype: HashMap<Datatype, Vec<Log>>) -> SplitLogs {
+    let mut select = |datatype: Datatype, is_match: fn(&Log) -> bool| -> Vec<Log> {
+        logs_by_datatype
+            .remove(&datatype)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|log| is_match(log))
+            .collect()
+    };
+    let erc1155_transfers =
+        select(Datatype::Erc1155Transfers, erc1155_transfers::is_erc1155_transfer);
+    let erc20_approvals = select(Datatype::Erc20Approvals, erc20_approvals::is_erc20_approval);
+    let erc20_transfers = select(Datatype::Erc20Transfers, erc20_transfers::is_erc20_transfer);
+    let erc721_transfers = select(Datatype::Erc721Transfers, erc721_transfers::is_erc721_transfer);
+    let logs = select(Datatype::Logs, |_| true);
+    (erc1155_transfers, erc20_approvals, erc20_transfers, erc721_transfers, logs)
+}
+
+fn process_log_derivatives(
+    response: SplitLogs,
+    columns: &mut LogDerivatives,
+    schemas: &HashMap<Datatype, Table>,
+) -> R<()> {
+    let LogDerivatives(erc1155_transfers, erc20_approvals, erc20_transfers, erc721_transfers, logs) =
+        columns;
+    let (erc1155_logs, approval_logs, erc20_logs, erc721_logs, raw_logs) = response;
+    if let Some(schema) = schemas.get(&Datatype::Erc1155Transfers) {
+        erc1155_transfers::process_erc1155_transfers(erc1155_logs, erc1155_transfers, schema)?;
+    }
+    if let Some(schema) = schemas.get(&Datatype::Erc20Approvals) {
+        erc20_approvals::process_erc20_approval(approval_logs, erc20_approvals, schema)?;
+    }
+    if let Some(schema) = schemas.get(&Datatype::Erc20Transfers) {
+        erc20_transfers::process_erc20_transfers(erc20_logs, erc20_transfers, schema)?;
+    }
+    if let Some(schema) = schemas.get(&Datatype::Erc721Transfers) {
+        erc721_transfers::process_erc721_transfers(erc721_logs, erc721_transfers, schema)?;
+    }
+    if let Some(schema) = schemas.get(&Datatype::Logs) {
+        logs::process_logs(raw_logs, logs, schema)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{primitives::B256, sol_types::SolEvent};
+    use serde_json::{json, Value};
+
+    fn log_json(log_index: u64, topics: Vec<B256>) -> Value {
+        json!({
+            "address": "0x0000000000000000000000000000000000000020",
+            "topics": topics,
+            "data": format!("0x{:064x}", 5),
+            "blockHash": B256::with_last_byte(1),
+            "blockNumber": "0x1",
+            "transactionHash": B256::with_last_byte(2),
+            "transactionIndex": "0x0",
+            "logIndex": format!("0x{:x}", log_index),
+            "removed": false,
+        })
+    }
+
+    fn schema(datatype: Datatype) -> Table {
+        let u256_types = vec![U256Type::Binary];
+        datatype
+            .table_schema(&u256_types, &ColumnEncoding::Hex, &None, &None, &None, None, None)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_unfiltered_logs_fetch_is_shared() {
+        let (owner, spender) = (B256::with_last_byte(3), B256::with_last_byte(4));
+        let logs = vec![
+            log_json(0, vec![ERC20::Transfer::SIGNATURE_HASH, owner, spender]),
+            log_json(1, vec![ERC20::Approval::SIGNATURE_HASH, owner, spender]),
+            log_json(2, vec![B256::with_last_byte(5)]),
+        ];
+        let mut fixtures = RpcFixtures::default();
+        fixtures.insert(
+            "eth_getLogs".to_string(),
+            json!([{"fromBlock": "0x1", "toBlock": "0x1", "topics": []}]),
+            Value::Array(logs),
+        );
+        let transport = MockTransport::new(fixtures);
+        let source = transport.clone().into_source(1);
+
+        let schemas: HashMap<Datatype, Table> =
+            [Datatype::Erc20Approvals, Datatype::Erc20Transfers, Datatype::Logs]
+                .into_iter()
+                .map(|datatype| (datatype, schema(datatype)))
+                .collect();
+        let request = Params { block_range: Some((1, 1)), ..Default::default() };
+        let logs_by_datatype = fetch_logs(&request, &source, &schemas).await.unwrap();
+        assert_eq!(transport.requested_methods(), vec!["eth_getLogs"]);
+
+        let (erc1155_logs, approval_logs, erc20_logs, erc721_logs, raw_logs) =
+            split_logs(logs_by_datatype);
+        assert!(erc1155_logs.is_empty());
+        assert!(erc721_logs.is_empty());
+        assert_eq!(approval_logs.iter().map(|log| log.log_index).collect::<Vec<_>>(), [Some(1)]);
+        assert_eq!(erc20_logs.iter().map(|log| log.log_index).collect::<Vec<_>>(), [Some(0)]);
+        assert_eq!(raw_logs.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_filtered_datasets_are_fetched_separately() {
+        let mut fixtures = RpcFixtures::default();
+        for topic0 in [ERC20::Transfer::SIGNATURE_HASH, ERC20::Approval::SIGNATURE_HASH] {
+            fixtures.insert(
+                "eth_getLogs".to_string(),
+                json!([{"fromBlock": "0x1", "toBlock": "0x1", "topics": [topic0]}]),
+                json!([]),
+            );
+        }
+        let transport = MockTransport::new(fixtures);
+        let source = transport.clone().into_source(1);
+
+        // without the logs dataset there is no fetch containing the logs of the others
+        let schemas: HashMap<Datatype, Table> =
+            [Datatype::Erc20Approvals, Datatype::Erc20Transfers]
+                .into_iter()
+                .map(|datatype| (datatype, schema(datatype)))
+                .collect();
+        let request = Params { block_range: Some((1, 1)), ..Default::default() };
+        fetch_logs(&request, &source, &schemas).await.unwrap();
+        assert_eq!(transport.requested_methods(), vec!["eth_getLogs", "eth_getLogs"]);
+    }
+}
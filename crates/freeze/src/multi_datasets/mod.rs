@@ -2,11 +2,13 @@ mod blocks_and_transactions;
 mod call_trace_derivatives;
 /// geth state diffs
 pub mod geth_state_diffs;
+mod log_derivatives;
 mod state_diffs;
 mod state_reads;
 
 pub use blocks_and_transactions::*;
 pub use call_trace_derivatives::*;
 pub use geth_state_diffs::*;
+pub use log_derivatives::*;
 pub use state_diffs::*;
 pub use state_reads::*;
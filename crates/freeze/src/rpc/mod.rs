@@ -0,0 +1,4 @@
+/// batching of contract calls into Multicall3 calls
+pub mod multicall;
+
+pub use multicall::{aggregate, MULTICALL3_ADDRESS};
@@ -0,0 +1,121 @@
+use crate::{
+    err,
+    types::collection::collect_generic::{
+        create_dfs_blocking, join_partition_handles, PartitionHandles,
+    },
+    CollectByBlock, CollectError, Datatype, Multicall3, Params, Partition, Query, Source,
+};
+use alloy::{
+    primitives::{address, Address},
+    sol_types::SolCall,
+};
+use polars::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use tokio::{sync::mpsc, task};
+
+type R<T> = ::core::result::Result<T, CollectError>;
+
+/// address of the Multicall3 contract, deployed at the same address on most evm chains
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// make each (target, call data) call at a block in a single Multicall3 call, returning the
+/// output of each call or None if it reverted
+pub async fn aggregate(
+    source: &Source,
+    calls: Vec<(Address, Vec<u8>)>,
+    block_number: u64,
+) -> R<Vec<Option<Vec<u8>>>> {
+    let n_calls = calls.len();
+    let calls = calls
+        .into_iter()
+        .map(|(target, call_data)| Multicall3::Call3 {
+            target,
+            allowFailure: true,
+            callData: call_data.into(),
+        })
+        .collect();
+    let call_data = Multicall3::aggregate3Call { calls }.abi_encode();
+    let output = source.call2(MULTICALL3_ADDRESS, call_data, block_number).await?;
+    let results = Multicall3::aggregate3Call::abi_decode_returns(&output, true)
+        .map_err(|_| {
+            err(format!(
+                "could not decode multicall output, is Multicall3 deployed at block {}?",
+                block_number
+            )
+            .as_str())
+        })?
+        .returnData;
+    if results.len() != n_calls {
+        return Err(err("multicall returned a different number of results than calls"))
+    }
+    Ok(results
+        .into_iter()
+        .map(|result| result.success.then(|| result.returnData.to_vec()))
+        .collect())
+}
+
+/// collect a partition by batching the contract calls of its requests into Multicall3 calls of
+/// up to batch_size calls at each block
+pub(crate) async fn collect_by_multicall<T: CollectByBlock>(
+    partition: Partition,
+    source: Arc<Source>,
+    query: Arc<Query>,
+    batch_size: usize,
+) -> R<HashMap<Datatype, DataFrame>> {
+    let mut requests_by_block: BTreeMap<u64, Vec<Params>> = BTreeMap::new();
+    for request in partition.param_sets(None)?.into_iter() {
+        requests_by_block.entry(request.block_number()?).or_default().push(request);
+    }
+
+    let (sender, receiver) = mpsc::channel(1);
+    let mut handles = PartitionHandles::default();
+    for (block_number, requests) in requests_by_block.into_iter() {
+        for batch in requests.chunks(batch_size.max(1)) {
+            let batch = batch.to_vec();
+            let sender = sender.clone();
+            let source = source.clone();
+            handles.push(task::spawn(async move {
+                let responses = match collect_batch::<T>(batch, block_number, &source).await {
+                    Ok(responses) => responses.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+                for response in responses.into_iter() {
+                    if sender.send(response).await.is_err() {
+                        return Err(err("tokio mpsc send failure"))
+                    }
+                }
+                Ok(())
+            }));
+        }
+    }
+    drop(sender);
+
+    let columns = T::transform_channel(receiver, &query).await?;
+    join_partition_handles(handles).await?;
+    create_dfs_blocking(columns, query, source.chain_id).await
+}
+
+async fn collect_batch<T: CollectByBlock>(
+    requests: Vec<Params>,
+    block_number: u64,
+    source: &Source,
+) -> R<Vec<T::Response>> {
+    let calls = requests.iter().map(T::multicall_request).collect::<R<Vec<_>>>()?;
+    let outputs = aggregate(source, calls, block_number).await?;
+    requests
+        .into_iter()
+        .zip(outputs)
+        .map(|(request, output)| T::multicall_response(request, output))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multicall3_selectors() {
+        assert_eq!(Multicall3::aggregate3Call::SELECTOR, [0x82, 0xad, 0x56, 0xcb]);
+        assert_eq!(Multicall3::getEthBalanceCall::SELECTOR, [0x4d, 0x23, 0x01, 0xcc]);
+    }
+}
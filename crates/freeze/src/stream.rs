@@ -0,0 +1,139 @@
+use crate::{
+    dataframes, err, mempool::process_pending_transaction, CollectError, Datatype, Dim,
+    ExecutionEnv, FileOutput, FreezeSummary, Mempool, Partition, Query, SchemaFunctions, Source,
+    ToDataFrames,
+};
+use alloy::{primitives::B256, pubsub::Subscription, rpc::types::Transaction};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::{Instant, Interval};
+
+/// seconds between polls of `txpool_content` when subscriptions are not available
+const TXPOOL_POLL_SECONDS: u64 = 1;
+
+/// source of pending transactions
+enum PendingTransactions {
+    /// `newPendingTransactions` subscription of a ws or ipc rpc url
+    Subscription(Subscription<Transaction>),
+    /// polls of `txpool_content`, yielding transactions missing from the previous poll
+    Txpool { interval: Interval, previous: HashSet<B256> },
+}
+
+impl PendingTransactions {
+    async fn new(source: &Source) -> Result<PendingTransactions, CollectError> {
+        let url = source.rpc_url.as_str();
+        if url.starts_with("ws") || url.ends_with(".ipc") {
+            let subscription = source.subscribe_pending_transactions().await?;
+            Ok(PendingTransactions::Subscription(subscription))
+        } else {
+            let interval = tokio::time::interval(Duration::from_secs(TXPOOL_POLL_SECONDS));
+            Ok(PendingTransactions::Txpool { interval, previous: HashSet::new() })
+        }
+    }
+
+    /// wait for the next pending transactions that have not been seen before
+    async fn next(&mut self, source: &Source) -> Result<Vec<Transaction>, CollectError> {
+        match self {
+            PendingTransactions::Subscription(subscription) => match subscription.recv().await {
+                Ok(tx) => Ok(vec![tx]),
+                Err(e) => {
+                    Err(err(format!("pending transaction subscription failed: {}", e).as_str()))
+                }
+            },
+            PendingTransactions::Txpool { interval, previous } => {
+                interval.tick().await;
+                let txs = source.txpool_pending_transactions().await?;
+                let current: HashSet<B256> = txs.iter().map(|tx| *tx.inner.tx_hash()).collect();
+                let new =
+                    txs.into_iter().filter(|tx| !previous.contains(tx.inner.tx_hash())).collect();
+                *previous = current;
+                Ok(new)
+            }
+        }
+    }
+}
+
+/// stream pending transactions into mempool files, one file per `query.mempool_window` seconds
+///
+/// streaming runs until `query.mempool_duration` seconds have passed or env.cancel is
+/// cancelled, the transactions of the current window are written before returning
+pub async fn stream_mempool(
+    query: &Query,
+    source: Arc<Source>,
+    sink: &FileOutput,
+    env: &ExecutionEnv,
+) -> Result<FreezeSummary, CollectError> {
+    // stream files are labeled by their window instead of block ranges
+    let query = Query { partitioned_by: vec![Dim::BlockNumber], ..query.clone() };
+    let mut pending = PendingTransactions::new(&source).await?;
+    let window = Duration::from_secs(query.mempool_window.max(1));
+    let deadline =
+        query.mempool_duration.map(|seconds| Instant::now() + Duration::from_secs(seconds));
+    let mut summary = FreezeSummary::default();
+
+    let mut stopped = false;
+    while !stopped {
+        let window_start = unix_seconds();
+        let mut window_end = Instant::now() + window;
+        if let Some(deadline) = deadline {
+            window_end = window_end.min(deadline);
+        }
+        let mut columns = Mempool::default();
+        let schema = query.schemas.get_schema(&Datatype::Mempool)?;
+        loop {
+            let txs = tokio::select! {
+                _ = tokio::time::sleep_until(window_end) => break,
+                _ = cancelled(env) => {
+                    stopped = true;
+                    break
+                }
+                txs = pending.next(&source) => txs?,
+            };
+            let first_seen_ms = unix_millis();
+            for tx in txs.iter() {
+                process_pending_transaction(tx, first_seen_ms, &mut columns, schema);
+            }
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            stopped = true;
+        }
+
+        if columns.n_rows == 0 {
+            continue
+        }
+        let label = format!("{}_to_{}", window_start, unix_seconds());
+        let partition = Partition { label: Some(vec![Some(label)]), ..Default::default() };
+        let mut dfs = columns.create_dfs(&query.schemas, source.chain_id)?;
+        let mut df = dfs.remove(&Datatype::Mempool).ok_or(err("could not create mempool df"))?;
+        dataframes::redact_columns(&mut df, sink)?;
+        dataframes::pseudonymize_columns(&mut df, sink)?;
+        let path = sink.get_path(&query, &partition, Datatype::Mempool)?;
+        dataframes::df_to_file(&mut df, &path, sink)
+            .map_err(|_| err("error writing mempool file"))?;
+        if env.verbose >= 1 {
+            println!("wrote {} pending transactions to {}", df.height(), path.display());
+        }
+        summary.n_rows += df.height() as u64;
+        summary.completed.push(partition);
+    }
+    Ok(summary)
+}
+
+/// resolves when env.cancel is cancelled, never resolves without a cancellation token
+async fn cancelled(env: &ExecutionEnv) {
+    match &env.cancel {
+        Some(cancel) => cancel.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn unix_seconds() -> u64 {
+    unix_millis() / 1000
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
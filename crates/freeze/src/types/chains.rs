@@ -0,0 +1,80 @@
+/// chain families whose datasets have columns beyond those of ethereum
+///
+/// l2 chains add fields to transactions and receipts, such as the l1 data fee paid by each
+/// transaction, these columns are only populated on chains of the corresponding profile
+use crate::Datatype;
+
+/// family of chains sharing the same extra transaction and receipt fields
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ChainProfile {
+    /// ethereum and chains without extra fields
+    #[default]
+    Ethereum,
+    /// op stack chains, which have deposit transactions and l1 fee fields in receipts
+    Optimism,
+    /// arbitrum nitro chains, which have system transactions and l1 gas fields in receipts
+    Arbitrum,
+}
+
+impl ChainProfile {
+    /// profile of chain, chains that are not known l2s are treated as ethereum
+    pub fn from_chain_id(chain_id: u64) -> ChainProfile {
+        match chain_id {
+            // optimism, base, zora, mode, fraxtal, world chain, unichain, and their testnets
+            10 | 8453 | 7777777 | 34443 | 252 | 480 | 130 | 11155420 | 84532 => {
+                ChainProfile::Optimism
+            }
+            // arbitrum one, arbitrum nova, arbitrum sepolia
+            42161 | 42170 | 421614 => ChainProfile::Arbitrum,
+            _ => ChainProfile::Ethereum,
+        }
+    }
+
+    /// whether chains of profile have transaction types that are unknown to ethereum
+    pub fn is_l2(&self) -> bool {
+        *self != ChainProfile::Ethereum
+    }
+
+    /// columns of datatype that are populated on chains of profile, included by default there
+    pub fn columns(&self, datatype: &Datatype) -> Vec<&'static str> {
+        chain_columns(datatype)
+            .into_iter()
+            .filter(|(_, profile)| profile == self)
+            .map(|(column, _)| column)
+            .collect()
+    }
+}
+
+/// columns of datatype that are null except on chains of the given profile
+pub fn chain_columns(datatype: &Datatype) -> Vec<(&'static str, ChainProfile)> {
+    match datatype {
+        Datatype::Transactions => vec![
+            ("source_hash", ChainProfile::Optimism),
+            ("mint", ChainProfile::Optimism),
+            ("is_system_tx", ChainProfile::Optimism),
+            ("l1_fee", ChainProfile::Optimism),
+            ("l1_gas_used", ChainProfile::Optimism),
+            ("l1_gas_price", ChainProfile::Optimism),
+            ("l1_blob_base_fee", ChainProfile::Optimism),
+            ("gas_used_for_l1", ChainProfile::Arbitrum),
+            ("l1_block_number", ChainProfile::Arbitrum),
+        ],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_profile_columns() {
+        assert_eq!(ChainProfile::from_chain_id(8453), ChainProfile::Optimism);
+        assert_eq!(ChainProfile::from_chain_id(42161), ChainProfile::Arbitrum);
+        assert!(!ChainProfile::from_chain_id(1).is_l2());
+        assert!(ChainProfile::Ethereum.columns(&Datatype::Transactions).is_empty());
+        let arbitrum_columns = ChainProfile::Arbitrum.columns(&Datatype::Transactions);
+        assert_eq!(arbitrum_columns, vec!["gas_used_for_l1", "l1_block_number"]);
+        assert!(ChainProfile::Optimism.columns(&Datatype::Blocks).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+/// expectations on the number of rows collected per block
+///
+/// checks are evaluated on every collected chunk before it is written, e.g.
+/// `transactions:0:2000` expects between 0 and 2000 transactions per block, and
+/// `logs:1::15000000` expects at least one log per block from block 15000000 onwards
+use crate::{ChunkData, CollectError, Datatype, ParseError, Partition};
+use polars::prelude::*;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// expected range of rows per block of a dataset
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct RowCountCheck {
+    /// dataset that is checked
+    pub datatype: Datatype,
+    /// minimum rows per block
+    pub min_rows: Option<u64>,
+    /// maximum rows per block
+    pub max_rows: Option<u64>,
+    /// first block that is checked
+    pub from_block: Option<u64>,
+}
+
+impl FromStr for RowCountCheck {
+    type Err = ParseError;
+
+    /// parse check of the form `DATASET:MIN:MAX[:FROM_BLOCK]`, where empty bounds are unbounded
+    fn from_str(s: &str) -> Result<RowCountCheck, ParseError> {
+        let invalid = || {
+            ParseError::ParseError(format!(
+                "invalid check {}, expected DATASET:MIN:MAX[:FROM_BLOCK]",
+                s
+            ))
+        };
+        let pieces: Vec<&str> = s.split(':').collect();
+        if pieces.len() != 3 && pieces.len() != 4 {
+            return Err(invalid())
+        }
+        let parse_bound = |piece: Option<&&str>| match piece {
+            None | Some(&"") => Ok(None),
+            Some(piece) => piece.parse::<u64>().map(Some).map_err(|_| invalid()),
+        };
+        let check = RowCountCheck {
+            datatype: Datatype::from_str(pieces[0])?,
+            min_rows: parse_bound(pieces.get(1))?,
+            max_rows: parse_bound(pieces.get(2))?,
+            from_block: parse_bound(pieces.get(3))?,
+        };
+        match (check.min_rows, check.max_rows) {
+            (Some(min_rows), Some(max_rows)) if min_rows > max_rows => Err(invalid()),
+            _ => Ok(check),
+        }
+    }
+}
+
+impl RowCountCheck {
+    /// describe each block of partition whose number of rows in df violates check
+    ///
+    /// blocks of the partition without any rows count as zero rows, partitions without block
+    /// numbers only check the blocks that appear in df
+    pub fn violations(
+        &self,
+        df: &DataFrame,
+        partition: &Partition,
+    ) -> Result<Vec<String>, CollectError> {
+        let mut rows_per_block: BTreeMap<u64, u64> = BTreeMap::new();
+        if let Some(block_numbers) = &partition.block_numbers {
+            rows_per_block.extend(block_numbers.values().into_iter().map(|block| (block, 0)));
+        }
+        if let Ok(column) = df.column("block_number") {
+            for block in column.cast(&DataType::UInt64)?.u64()?.into_iter().flatten() {
+                *rows_per_block.entry(block).or_default() += 1;
+            }
+        }
+
+        let mut violations = Vec::new();
+        for (block, n_rows) in rows_per_block.into_iter() {
+            if self.from_block.is_some_and(|from_block| block < from_block) {
+                continue
+            }
+            if let Some(min_rows) = self.min_rows.filter(|min_rows| n_rows < *min_rows) {
+                violations.push(format!(
+                    "{} block {} has {} rows, expected at least {}",
+                    self.datatype.name(),
+                    block,
+                    n_rows,
+                    min_rows
+                ));
+            }
+            if let Some(max_rows) = self.max_rows.filter(|max_rows| n_rows > *max_rows) {
+                violations.push(format!(
+                    "{} block {} has {} rows, expected at most {}",
+                    self.datatype.name(),
+                    block,
+                    n_rows,
+                    max_rows
+                ));
+            }
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlockChunk;
+
+    #[test]
+    fn test_parse_row_count_check() {
+        let check: RowCountCheck = "logs:1::15000000".parse().unwrap();
+        assert_eq!(check.datatype, Datatype::Logs);
+        assert_eq!(check.min_rows, Some(1));
+        assert_eq!(check.max_rows, None);
+        assert_eq!(check.from_block, Some(15000000));
+        assert!("logs:5:1".parse::<RowCountCheck>().is_err());
+        assert!("logs".parse::<RowCountCheck>().is_err());
+    }
+
+    #[test]
+    fn test_row_count_check_violations() {
+        let df = df!("block_number" => [10u32, 10, 10, 12]).unwrap();
+        let partition = Partition {
+            block_numbers: Some(vec![BlockChunk::Range(10, 12)]),
+            ..Default::default()
+        };
+        let check: RowCountCheck = "transactions:1:2".parse().unwrap();
+        let violations = check.violations(&df, &partition).unwrap();
+        assert_eq!(
+            violations,
+            vec![
+                "transactions block 10 has 3 rows, expected at most 2",
+                "transactions block 11 has 0 rows, expected at least 1",
+            ]
+        );
+        let check: RowCountCheck = "transactions:1:2:12".parse().unwrap();
+        assert!(check.violations(&df, &partition).unwrap().is_empty());
+    }
+}
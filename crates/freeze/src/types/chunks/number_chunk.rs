@@ -8,7 +8,7 @@ pub enum NumberChunk {
     /// Vec of block numbers
     Numbers(Vec<u64>),
 
-    /// Range of block numbers
+    /// Range of block numbers, (start, end) with both bounds inclusive
     Range(u64, u64),
 }
 
@@ -60,18 +60,47 @@ impl NumberChunk {
                 })
                 .collect(),
             NumberChunk::Range(start_block, end_block) => {
-                let chunks = range_to_chunks(start_block, &(end_block + 1), log_request_size);
-                chunks
-                    .iter()
+                RangeBatches::new(*start_block, *end_block, *log_request_size)
                     .map(|(start, end)| FilterBlockOption::Range {
-                        from_block: Some((*start).into()),
-                        to_block: Some((*end).into()),
+                        from_block: Some(start.into()),
+                        to_block: Some(end.into()),
                     })
                     .collect()
             }
         }
     }
 
+    /// inclusive (start, end) bounds of chunk, without materializing its block numbers
+    pub fn bounds(&self) -> Option<(u64, u64)> {
+        match self {
+            NumberChunk::Numbers(_) => self.min_value().zip(self.max_value()),
+            NumberChunk::Range(start, end) => Some((*start, *end)),
+        }
+    }
+
+    /// iterate over block numbers of chunk without allocating a Vec
+    pub fn iter(&self) -> NumberChunkIter<'_> {
+        match self {
+            NumberChunk::Numbers(numbers) => NumberChunkIter::Numbers(numbers.iter()),
+            NumberChunk::Range(start, end) => NumberChunkIter::Range(*start..=*end),
+        }
+    }
+
+    /// lazily split chunk into consecutive chunks of at most batch_size blocks
+    pub fn batches(&self, batch_size: u64) -> Box<dyn Iterator<Item = NumberChunk> + '_> {
+        match self {
+            NumberChunk::Numbers(numbers) => Box::new(
+                numbers
+                    .chunks(batch_size.max(1) as usize)
+                    .map(|chunk| NumberChunk::Numbers(chunk.to_vec())),
+            ),
+            NumberChunk::Range(start, end) => Box::new(
+                RangeBatches::new(*start, *end, batch_size)
+                    .map(|(start, end)| NumberChunk::Range(start, end)),
+            ),
+        }
+    }
+
     /// align boundaries of chunk to clean boundaries
     pub fn align(self, chunk_size: u64) -> Option<NumberChunk> {
         match self {
@@ -89,18 +118,97 @@ impl NumberChunk {
     }
 }
 
+/// iterator over the block numbers of a NumberChunk
+#[derive(Debug, Clone)]
+pub enum NumberChunkIter<'a> {
+    /// iterator over explicit block numbers
+    Numbers(std::slice::Iter<'a, u64>),
+    /// iterator over a range of block numbers
+    Range(std::ops::RangeInclusive<u64>),
+}
+
+impl Iterator for NumberChunkIter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        match self {
+            NumberChunkIter::Numbers(numbers) => numbers.next().copied(),
+            NumberChunkIter::Range(range) => range.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            NumberChunkIter::Numbers(numbers) => numbers.size_hint(),
+            NumberChunkIter::Range(range) => range.size_hint(),
+        }
+    }
+}
+
+/// iterator splitting an inclusive range into inclusive (start, end) batches
+#[derive(Debug, Clone)]
+pub struct RangeBatches {
+    next_start: Option<u64>,
+    end: u64,
+    batch_size: u64,
+}
+
+impl RangeBatches {
+    /// split start..=end into batches of at most batch_size blocks
+    pub fn new(start: u64, end: u64, batch_size: u64) -> RangeBatches {
+        let next_start = if start <= end { Some(start) } else { None };
+        RangeBatches { next_start, end, batch_size: batch_size.max(1) }
+    }
+}
+
+impl Iterator for RangeBatches {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<(u64, u64)> {
+        let start = self.next_start?;
+        let batch_end = start.saturating_add(self.batch_size - 1).min(self.end);
+        self.next_start = if batch_end < self.end { Some(batch_end + 1) } else { None };
+        Some((start, batch_end))
+    }
+}
+
 pub(crate) fn range_to_chunks(start: &u64, end: &u64, chunk_size: &u64) -> Vec<(u64, u64)> {
-    let mut chunks: Vec<(u64, u64)> = Vec::new();
-    let mut chunk_start = *start;
-    loop {
-        let chunk_end: u64 = chunk_start + chunk_size - 1;
-        let chunk_end = if chunk_end > *end { *end } else { chunk_end };
-        chunks.push((chunk_start, chunk_end));
-        if chunk_end == *end {
-            break
-        } else {
-            chunk_start += chunk_size;
+    RangeBatches::new(*start, *end, *chunk_size).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_batches() {
+        let batches: Vec<_> = RangeBatches::new(0, 9, 4).collect();
+        assert_eq!(batches, vec![(0, 3), (4, 7), (8, 9)]);
+        let batches: Vec<_> = RangeBatches::new(5, 5, 4).collect();
+        assert_eq!(batches, vec![(5, 5)]);
+        let batches: Vec<_> = RangeBatches::new(u64::MAX - 1, u64::MAX, 10).collect();
+        assert_eq!(batches, vec![(u64::MAX - 1, u64::MAX)]);
+    }
+
+    #[test]
+    fn test_number_chunk_iter() {
+        let chunk = NumberChunk::Range(10, 14);
+        assert_eq!(chunk.iter().collect::<Vec<_>>(), chunk.values());
+        assert_eq!(chunk.bounds(), Some((10, 14)));
+        let batches: Vec<_> = chunk.batches(2).map(|batch| batch.bounds()).collect();
+        assert_eq!(batches, vec![Some((10, 11)), Some((12, 13)), Some((14, 14))]);
+    }
+
+    #[test]
+    fn test_log_filter_options_are_inclusive() {
+        let options = NumberChunk::Range(0, 9).to_log_filter_options(&5);
+        assert_eq!(options.len(), 2);
+        match &options[1] {
+            FilterBlockOption::Range { from_block, to_block } => {
+                assert_eq!(*from_block, Some(5u64.into()));
+                assert_eq!(*to_block, Some(9u64.into()));
+            }
+            _ => panic!("expected range"),
         }
     }
-    chunks
 }
@@ -18,8 +18,8 @@ impl Subchunk for BlockChunk {
                 .collect(),
             BlockChunk::Range(start_block, end_block) => {
                 range_to_chunks(start_block, end_block, chunk_size)
-                    .iter()
-                    .map(|(start, end)| BlockChunk::Range(*start, *end))
+                    .into_iter()
+                    .map(|(start, end)| BlockChunk::Range(start, end))
                     .collect()
             }
         }
@@ -32,22 +32,108 @@ impl Subchunk for BlockChunk {
     }
 }
 
+/// disjoint block ranges are subchunked separately, so that no subchunk spans the gap between
+/// two ranges and each subchunk is labeled by its own range
 impl Subchunk for Vec<BlockChunk> {
     fn subchunk_by_size(&self, chunk_size: &u64) -> Vec<BlockChunk> {
-        to_single_chunk(self).subchunk_by_size(chunk_size)
+        match disjoint_ranges(self) {
+            Some(ranges) => ranges
+                .into_iter()
+                .flat_map(|(start, end)| BlockChunk::Range(start, end).subchunk_by_size(chunk_size))
+                .collect(),
+            None => to_single_chunk(self).subchunk_by_size(chunk_size),
+        }
     }
 
     fn subchunk_by_count(&self, n_chunks: &u64) -> Vec<BlockChunk> {
-        to_single_chunk(self).subchunk_by_count(n_chunks)
+        match disjoint_ranges(self) {
+            Some(ranges) => {
+                let total_blocks: u64 = ranges.iter().map(|(start, end)| end - start + 1).sum();
+                self.subchunk_by_size(&total_blocks.div_ceil(*n_chunks).max(1))
+            }
+            None => to_single_chunk(self).subchunk_by_count(n_chunks),
+        }
+    }
+}
+
+/// sorted ranges with overlapping and adjacent ranges merged, if there are several ranges and
+/// every chunk is a range
+fn disjoint_ranges(chunks: &[BlockChunk]) -> Option<Vec<(u64, u64)>> {
+    let mut ranges = Vec::with_capacity(chunks.len());
+    for chunk in chunks.iter() {
+        match chunk {
+            BlockChunk::Range(start, end) => ranges.push((*start, *end)),
+            BlockChunk::Numbers(_) => return None,
+        }
+    }
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges.into_iter() {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = (*last_end).max(end)
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    if merged.len() > 1 {
+        Some(merged)
+    } else {
+        None
     }
 }
 
+/// merge chunks into one, contiguous ranges are merged without materializing block numbers
 fn to_single_chunk(chunks: &[BlockChunk]) -> BlockChunk {
     match (chunks.len(), chunks.first()) {
         (1, Some(chunk)) => chunk.clone(),
-        _ => {
-            let numbers = chunks.iter().flat_map(|x| x.values()).collect();
-            BlockChunk::Numbers(numbers)
-        }
+        _ => match contiguous_range(chunks) {
+            Some((start, end)) => BlockChunk::Range(start, end),
+            None => BlockChunk::Numbers(chunks.iter().flat_map(|x| x.iter()).collect()),
+        },
+    }
+}
+
+fn contiguous_range(chunks: &[BlockChunk]) -> Option<(u64, u64)> {
+    let mut range: Option<(u64, u64)> = None;
+    for chunk in chunks.iter() {
+        let (start, end) = match chunk {
+            BlockChunk::Range(start, end) => (*start, *end),
+            BlockChunk::Numbers(_) => return None,
+        };
+        range = match range {
+            None => Some((start, end)),
+            Some((range_start, range_end)) if range_end.checked_add(1) == Some(start) => {
+                Some((range_start, end))
+            }
+            Some(_) => return None,
+        };
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(chunks: Vec<BlockChunk>) -> Vec<(u64, u64)> {
+        chunks
+            .into_iter()
+            .map(|chunk| match chunk {
+                BlockChunk::Range(start, end) => (start, end),
+                BlockChunk::Numbers(_) => panic!("expected range"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subchunk_disjoint_ranges() {
+        let chunks = vec![BlockChunk::Range(1500, 1599), BlockChunk::Range(100, 249)];
+        assert_eq!(
+            ranges(chunks.subchunk_by_size(&100)),
+            vec![(100, 199), (200, 249), (1500, 1599)]
+        );
+        let contiguous = vec![BlockChunk::Range(0, 99), BlockChunk::Range(100, 199)];
+        assert_eq!(ranges(contiguous.subchunk_by_size(&200)), vec![(0, 199)]);
     }
 }
@@ -0,0 +1,254 @@
+use crate::{
+    dataframes, files, BlockChunk, CollectError, DatasetQuality, Datatype, FileOutput, Partition,
+    Query,
+};
+use polars::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+type R<T> = ::core::result::Result<T, CollectError>;
+
+/// writes the rows of blocks that exceed a byte budget to dedicated single-block files while a
+/// partition is being collected, so that a chunk containing e.g. calldata bombs never holds
+/// those blocks in memory
+///
+/// blocks are measured one response at a time, blocks that only exceed the budget across several
+/// responses are split out of the chunk after collection instead
+pub struct BlockFlush {
+    max_block_bytes: u64,
+    partition: Partition,
+    query: Arc<Query>,
+    sink: FileOutput,
+    paths: HashMap<Datatype, PathBuf>,
+    flushed: Mutex<Vec<FlushedBlock>>,
+}
+
+/// single-block file written by a BlockFlush
+pub struct FlushedBlock {
+    /// datatype of the file
+    pub datatype: Datatype,
+    /// block number of the rows of the file
+    pub block: u64,
+    /// path of the chunk file the block was split out of
+    pub chunk_path: PathBuf,
+    /// path of the written file
+    pub path: PathBuf,
+    /// number of rows of the file
+    pub n_rows: u64,
+    /// row count check violations of the block
+    pub violations: Vec<String>,
+    /// data quality statistics of the rows of the file
+    pub quality: DatasetQuality,
+}
+
+impl BlockFlush {
+    /// create flush for the outputs of a partition, None if the sink has no block byte budget
+    pub fn new(
+        partition: &Partition,
+        query: Arc<Query>,
+        sink: &FileOutput,
+        paths: &HashMap<Datatype, PathBuf>,
+    ) -> Option<BlockFlush> {
+        Some(BlockFlush {
+            max_block_bytes: sink.max_block_bytes?,
+            partition: partition.clone(),
+            query,
+            sink: sink.clone(),
+            paths: paths.clone(),
+            flushed: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// write the oversized blocks of the dataframes of one response, returning the other rows
+    pub fn flush(
+        &self,
+        dfs: HashMap<Datatype, DataFrame>,
+        chain_id: u64,
+    ) -> R<HashMap<Datatype, DataFrame>> {
+        let mut kept = HashMap::new();
+        for (datatype, df) in dfs.into_iter() {
+            let chunk_path = match self.paths.get(&datatype) {
+                Some(chunk_path) => chunk_path,
+                None => {
+                    kept.insert(datatype, df);
+                    continue
+                }
+            };
+            let (blocks, rest) = take_oversized_blocks(df, self.max_block_bytes)?;
+            for (block, df) in blocks.into_iter() {
+                self.write_block(datatype, block, df, chunk_path, chain_id)?;
+            }
+            kept.insert(datatype, rest);
+        }
+        Ok(kept)
+    }
+
+    fn write_block(
+        &self,
+        datatype: Datatype,
+        block: u64,
+        mut df: DataFrame,
+        chunk_path: &Path,
+        chain_id: u64,
+    ) -> R<()> {
+        let block_partition = block_partition(&self.partition, block);
+        let mut violations = Vec::new();
+        for check in self.query.row_count_checks.iter().filter(|c| c.datatype == datatype) {
+            violations.extend(check.violations(&df, &block_partition)?);
+        }
+        if self.query.strict_checks && !violations.is_empty() {
+            let message = format!(
+                "{} row count check violations, first: {}",
+                violations.len(),
+                violations[0]
+            );
+            return Err(CollectError::CollectError(message))
+        }
+        let quality = DatasetQuality::from_df(&df, &datatype, chain_id)?;
+
+        let path = split_path(&self.sink, &self.query, chunk_path, &block_partition, datatype)?;
+        dataframes::redact_columns(&mut df, &self.sink)?;
+        dataframes::pseudonymize_columns(&mut df, &self.sink)?;
+        dataframes::df_to_file(&mut df, &path, &self.sink)
+            .map_err(|_| CollectError::CollectError("error writing file".to_string()))?;
+
+        let flushed = FlushedBlock {
+            datatype,
+            block,
+            chunk_path: chunk_path.to_path_buf(),
+            path,
+            n_rows: df.height() as u64,
+            violations,
+            quality,
+        };
+        self.flushed
+            .lock()
+            .map_err(|_| CollectError::CollectError("could not lock flushed blocks".to_string()))?
+            .push(flushed);
+        Ok(())
+    }
+
+    /// take the blocks written so far
+    pub fn take_flushed(&self) -> R<Vec<FlushedBlock>> {
+        let mut flushed = self
+            .flushed
+            .lock()
+            .map_err(|_| CollectError::CollectError("could not lock flushed blocks".to_string()))?;
+        Ok(std::mem::take(&mut *flushed))
+    }
+}
+
+/// partition of a single block of a partition
+pub(crate) fn block_partition(partition: &Partition, block: u64) -> Partition {
+    Partition {
+        label: None,
+        block_numbers: Some(vec![BlockChunk::Numbers(vec![block])]),
+        ..partition.clone()
+    }
+}
+
+/// path of the file of a block split out of a chunk file, split files share the version of the
+/// chunk file they are split out of
+pub(crate) fn split_path(
+    sink: &FileOutput,
+    query: &Query,
+    chunk_path: &Path,
+    block_partition: &Partition,
+    datatype: Datatype,
+) -> R<PathBuf> {
+    let block_path = sink.get_path(query, block_partition, datatype)?;
+    Ok(files::versioned_path(&block_path, files::split_version(chunk_path).1))
+}
+
+/// move the rows of blocks exceeding max_block_bytes out of df, returning the dataframe of each
+/// oversized block and the remaining rows
+pub(crate) fn take_oversized_blocks(
+    df: DataFrame,
+    max_block_bytes: u64,
+) -> R<(Vec<(u64, DataFrame)>, DataFrame)> {
+    if df.height() == 0 || df.estimated_size() as u64 <= max_block_bytes {
+        return Ok((Vec::new(), df))
+    }
+    let block_numbers = match df.column("block_number") {
+        Ok(column) => column.cast(&DataType::UInt64)?,
+        Err(_) => return Ok((Vec::new(), df)),
+    };
+    let mut bytes_per_block: BTreeMap<u64, u64> = BTreeMap::new();
+    for (block, row_bytes) in block_numbers.u64()?.into_iter().zip(row_sizes(&df)?) {
+        if let Some(block) = block {
+            *bytes_per_block.entry(block).or_default() += row_bytes;
+        }
+    }
+    let oversized: Vec<u64> = bytes_per_block
+        .into_iter()
+        .filter(|(_, n_bytes)| *n_bytes > max_block_bytes)
+        .map(|(block, _)| block)
+        .collect();
+    if oversized.is_empty() {
+        return Ok((Vec::new(), df))
+    }
+
+    let mut blocks = Vec::new();
+    for block in oversized.iter() {
+        let mask = block_numbers.u64()?.equal(*block);
+        blocks.push((*block, df.filter(&mask)?));
+    }
+    let rest: BooleanChunked = block_numbers
+        .u64()?
+        .into_iter()
+        .map(|block| !block.is_some_and(|block| oversized.contains(&block)))
+        .collect();
+    Ok((blocks, df.filter(&rest)?))
+}
+
+/// estimated bytes of each row of df, counting the actual length of binary and string values so
+/// that a single block of huge values is measured by its own rows
+fn row_sizes(df: &DataFrame) -> R<Vec<u64>> {
+    let mut sizes = vec![0u64; df.height()];
+    for column in df.get_columns().iter() {
+        match column.dtype() {
+            DataType::Binary => {
+                for (size, value) in sizes.iter_mut().zip(column.binary()?.into_iter()) {
+                    *size += value.map_or(0, |value| value.len() as u64);
+                }
+            }
+            DataType::String => {
+                for (size, value) in sizes.iter_mut().zip(column.str()?.into_iter()) {
+                    *size += value.map_or(0, |value| value.len() as u64);
+                }
+            }
+            _ => {
+                let row_bytes = (column.estimated_size() / df.height().max(1)) as u64;
+                sizes.iter_mut().for_each(|size| *size += row_bytes);
+            }
+        }
+    }
+    Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_oversized_blocks() {
+        let df = df!(
+            "block_number" => [1u32, 1, 2, 3],
+            "input" => [vec![0u8; 10], vec![0u8; 10], vec![0u8; 5000], vec![0u8; 10]],
+        )
+        .unwrap();
+        let (blocks, rest) = take_oversized_blocks(df.clone(), 1000).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].0, 2);
+        assert_eq!(blocks[0].1.height(), 1);
+        assert_eq!(rest.height(), 3);
+
+        // nothing is taken when every block is within the budget
+        let (blocks, rest) = take_oversized_blocks(df, 100_000).unwrap();
+        assert!(blocks.is_empty());
+        assert_eq!(rest.height(), 4);
+    }
+}
@@ -1,5 +1,9 @@
-use super::collect_generic::{fetch_partition, join_partition_handles};
-use crate::{CollectError, Datatype, Params, Partition, Query, Source, ToDataFrames};
+use super::collect_generic::{create_dfs_blocking, fetch_partition, join_partition_handles};
+use crate::{
+    dataframes::SortableDataFrame, BlockFlush, CollectError, Datatype, Params, Partition, Query,
+    Source, ToDataFrames,
+};
+use alloy::primitives::Address;
 use polars::prelude::*;
 use std::collections::HashMap;
 use tokio::sync::mpsc;
@@ -22,6 +26,22 @@ pub trait CollectByBlock: 'static + Send + Default + ToDataFrames {
         Err(CollectError::CollectError("CollectByBlock not implemented".to_string()))
     }
 
+    /// contract call made by a request, datasets that implement this and multicall_response
+    /// batch their requests into Multicall3 calls when query.multicall is set
+    fn multicall_request(_request: &Params) -> R<(Address, Vec<u8>)> {
+        Err(CollectError::CollectError("multicall not implemented".to_string()))
+    }
+
+    /// convert the output of the contract call of a request, None if the call reverted
+    fn multicall_response(_request: Params, _output: Option<Vec<u8>>) -> R<Self::Response> {
+        Err(CollectError::CollectError("multicall not implemented".to_string()))
+    }
+
+    /// whether requests can be batched into Multicall3 calls
+    fn can_multicall() -> bool {
+        false
+    }
+
     /// collect data into DataFrame
     async fn collect_by_block(
         partition: Partition,
@@ -29,6 +49,12 @@ pub trait CollectByBlock: 'static + Send + Default + ToDataFrames {
         query: Arc<Query>,
         inner_request_size: Option<u64>,
     ) -> R<HashMap<Datatype, DataFrame>> {
+        if let (Some(batch_size), true) = (query.multicall, Self::can_multicall()) {
+            return crate::rpc::multicall::collect_by_multicall::<Self>(
+                partition, source, query, batch_size,
+            )
+            .await
+        }
         let (sender, receiver) = mpsc::channel(1);
         let chain_id = source.chain_id;
         let handles = fetch_partition(
@@ -40,9 +66,14 @@ pub trait CollectByBlock: 'static + Send + Default + ToDataFrames {
             sender,
         )
         .await?;
+        if let Some(flush) = query.block_flush.clone() {
+            let dfs = Self::transform_channel_flushing(receiver, &query, &flush, chain_id).await?;
+            join_partition_handles(handles).await?;
+            return Ok(dfs)
+        }
         let columns = Self::transform_channel(receiver, &query).await?;
         join_partition_handles(handles).await?;
-        columns.create_dfs(&query.schemas, chain_id)
+        create_dfs_blocking(columns, query, chain_id).await
     }
 
     /// convert block-derived data to dataframe
@@ -60,6 +91,43 @@ pub trait CollectByBlock: 'static + Send + Default + ToDataFrames {
         Ok(columns)
     }
 
+    /// convert block-derived data to dataframes one response at a time, so that blocks exceeding
+    /// the block byte budget are written by flush as soon as they are collected
+    async fn transform_channel_flushing(
+        mut receiver: mpsc::Receiver<R<Self::Response>>,
+        query: &Arc<Query>,
+        flush: &BlockFlush,
+        chain_id: u64,
+    ) -> R<HashMap<Datatype, DataFrame>> {
+        // categoricals of separately converted responses can only be stacked with shared categories
+        let _string_cache = StringCacheHolder::hold();
+        let mut dfs = Self::default().create_dfs(&query.schemas, chain_id)?;
+        while let Some(message) = receiver.recv().await {
+            let mut columns = Self::default();
+            Self::transform(message?, &mut columns, query)?;
+            let response_dfs = columns.create_dfs(&query.schemas, chain_id)?;
+            for (datatype, df) in flush.flush(response_dfs, chain_id)?.into_iter() {
+                match dfs.get_mut(&datatype) {
+                    Some(stacked) => {
+                        stacked.vstack_mut(&df)?;
+                    }
+                    None => {
+                        dfs.insert(datatype, df);
+                    }
+                }
+            }
+        }
+        let mut sorted = HashMap::new();
+        for (datatype, mut df) in dfs.into_iter() {
+            df.align_chunks();
+            let schema = query.schemas.get(&datatype).ok_or_else(|| {
+                CollectError::CollectError(format!("schema not provided: {}", datatype.name()))
+            })?;
+            sorted.insert(datatype, Ok(df).sort_by_schema(schema)?);
+        }
+        Ok(sorted)
+    }
+
     /// whether data can be collected by block
     fn can_collect_by_block() -> bool {
         std::any::type_name::<Self::Response>() != "()"
@@ -1,4 +1,4 @@
-use super::collect_generic::{fetch_partition, join_partition_handles};
+use super::collect_generic::{create_dfs_blocking, fetch_partition, join_partition_handles};
 use crate::{CollectError, Datatype, Params, Partition, Query, Source, ToDataFrames};
 use polars::prelude::*;
 use std::collections::HashMap;
@@ -42,7 +42,7 @@ pub trait CollectByTransaction: 'static + Send + Default + ToDataFrames {
         .await?;
         let columns = Self::transform_channel(receiver, &query).await?;
         join_partition_handles(handles).await?;
-        columns.create_dfs(&query.schemas, chain_id)
+        create_dfs_blocking(columns, query, chain_id).await
     }
 
     /// convert transaction-derived data to dataframe
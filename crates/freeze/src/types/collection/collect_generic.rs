@@ -19,6 +19,138 @@ pub async fn collect_partition(
     }
 }
 
+/// collect single partition once for several queries that differ only in their decoders,
+/// returning the dataframes of each query in order
+///
+/// each request is extracted once and its response is transformed with every query, so that
+/// e.g. the traces of a block are fetched once no matter how many functions are decoded
+pub async fn collect_partition_variants(
+    datatype: MetaDatatype,
+    partition: Partition,
+    queries: Vec<Arc<Query>>,
+    source: Arc<Source>,
+) -> Result<Vec<HashMap<Datatype, DataFrame>>, CollectError> {
+    let query = queries.first().ok_or(err("no queries to collect"))?;
+    let datatype = match datatype {
+        MetaDatatype::Scalar(datatype) => datatype,
+        MetaDatatype::Multi(_) => {
+            return Err(err("only decoded datatypes can be collected with several decoders"))
+        }
+    };
+    let inner_request_size =
+        if datatype.use_block_ranges() { Some(source.inner_request_size) } else { None };
+    match (datatype, &query.time_dimension) {
+        (Datatype::DecodedCalls, TimeDimension::Blocks) => {
+            collect_variants(
+                <DecodedCalls as CollectByBlock>::extract,
+                <DecodedCalls as CollectByBlock>::transform,
+                partition,
+                source,
+                queries,
+                inner_request_size,
+            )
+            .await
+        }
+        (Datatype::DecodedCalls, TimeDimension::Transactions) => {
+            collect_variants(
+                <DecodedCalls as CollectByTransaction>::extract,
+                <DecodedCalls as CollectByTransaction>::transform,
+                partition,
+                source,
+                queries,
+                inner_request_size,
+            )
+            .await
+        }
+        (Datatype::DecodedLogs, TimeDimension::Blocks) => {
+            collect_variants(
+                <DecodedLogs as CollectByBlock>::extract,
+                <DecodedLogs as CollectByBlock>::transform,
+                partition,
+                source,
+                queries,
+                inner_request_size,
+            )
+            .await
+        }
+        (Datatype::DecodedLogs, TimeDimension::Transactions) => {
+            collect_variants(
+                <DecodedLogs as CollectByTransaction>::extract,
+                <DecodedLogs as CollectByTransaction>::transform,
+                partition,
+                source,
+                queries,
+                inner_request_size,
+            )
+            .await
+        }
+        _ => Err(err("only decoded datatypes can be collected with several decoders")),
+    }
+}
+
+/// extract each request of partition with the first query and transform its response into the
+/// columns of every query
+async fn collect_variants<F, Fut, T, G, C>(
+    f_request: F,
+    f_transform: G,
+    partition: Partition,
+    source: Arc<Source>,
+    queries: Vec<Arc<Query>>,
+    inner_request_size: Option<u64>,
+) -> Result<Vec<HashMap<Datatype, DataFrame>>, CollectError>
+where
+    F: Copy
+        + Send
+        + for<'a> Fn(Params, Arc<Source>, Arc<Query>) -> Fut
+        + std::marker::Sync
+        + 'static,
+    Fut: Future<Output = Result<T, CollectError>> + Send + 'static,
+    T: Clone + Send + 'static,
+    G: Fn(T, &mut C, &Arc<Query>) -> Result<(), CollectError>,
+    C: ToDataFrames + Default + Send + 'static,
+{
+    let query = queries.first().ok_or(err("no queries to collect"))?.clone();
+    let (sender, mut receiver) = mpsc::channel(1);
+    let chain_id = source.chain_id;
+    let handles =
+        fetch_partition(f_request, partition, source, inner_request_size, query, sender).await?;
+    let mut columns: Vec<C> = queries.iter().map(|_| C::default()).collect();
+    while let Some(message) = receiver.recv().await {
+        let response = message?;
+        for (query, columns) in queries.iter().zip(columns.iter_mut()) {
+            f_transform(response.clone(), columns, query)?;
+        }
+    }
+    join_partition_handles(handles).await?;
+    let mut dfs = Vec::new();
+    for (columns, query) in columns.into_iter().zip(queries) {
+        dfs.push(create_dfs_blocking(columns, query, chain_id).await?);
+    }
+    Ok(dfs)
+}
+
+/// join handles of the fetch tasks of a partition
+///
+/// tasks still running when the handles are dropped are aborted, so that a collection that is
+/// cancelled or fails stops sending requests instead of leaving its tasks running detached
+#[derive(Default)]
+pub struct PartitionHandles(Vec<task::JoinHandle<Result<(), CollectError>>>);
+
+impl PartitionHandles {
+    /// add the handle of a fetch task
+    pub fn push(&mut self, handle: task::JoinHandle<Result<(), CollectError>>) {
+        self.0.push(handle)
+    }
+}
+
+impl Drop for PartitionHandles {
+    fn drop(&mut self) {
+        for handle in self.0.iter() {
+            handle.abort();
+        }
+    }
+}
+
 /// fetch data for a given partition
 pub async fn fetch_partition<F, Fut, T>(
     f_request: F,
@@ -27,7 +159,7 @@ pub async fn fetch_partition<F, Fut, T>(
     inner_request_size: Option<u64>,
     query: Arc<Query>,
     sender: mpsc::Sender<Result<T, CollectError>>,
-) -> Result<Vec<tokio::task::JoinHandle<Result<(), CollectError>>>, CollectError>
+) -> Result<PartitionHandles, CollectError>
 where
     F: Copy
         + Send
@@ -37,7 +169,7 @@ where
     Fut: Future<Output = Result<T, CollectError>> + Send + 'static,
     T: Send + 'static,
 {
-    let mut handles = Vec::new();
+    let mut handles = PartitionHandles::default();
     for rpc_params in partition.param_sets(inner_request_size)?.into_iter() {
         let sender = sender.clone();
         let source = source.clone();
@@ -55,12 +187,28 @@ where
     Ok(handles)
 }
 
+/// convert columns to dataframes on a blocking thread, so that concurrent chunks are built in
+/// parallel without stalling the async runtime that drives rpc requests, the columns of each
+/// chunk are in turn converted in parallel on the rayon thread pool
+pub(crate) async fn create_dfs_blocking<T>(
+    columns: T,
+    query: Arc<Query>,
+    chain_id: u64,
+) -> Result<HashMap<Datatype, DataFrame>, CollectError>
+where
+    T: ToDataFrames + Send + 'static,
+{
+    task::spawn_blocking(move || columns.create_dfs(&query.schemas, chain_id))
+        .await
+        .map_err(CollectError::TaskFailed)?
+}
+
+/// wait for every fetch task of a partition, the remaining tasks are aborted on the first error
 pub(crate) async fn join_partition_handles(
-    handles: Vec<tokio::task::JoinHandle<Result<(), CollectError>>>,
+    mut handles: PartitionHandles,
 ) -> Result<(), CollectError> {
-    let results: Vec<_> = futures::future::join_all(handles).await;
-    for result in results {
-        match result {
+    for handle in handles.0.iter_mut() {
+        match handle.await {
             Ok(Ok(())) => continue,
             Ok(Err(e)) => return Err(e),
             Err(join_err) => return Err(CollectError::TaskFailed(join_err)),
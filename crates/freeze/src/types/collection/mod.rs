@@ -1,3 +1,5 @@
+/// flush oversized blocks during collection
+pub mod block_flush;
 /// collect by block
 pub mod collect_by_block;
 /// collect by transaction
@@ -5,6 +7,7 @@ pub mod collect_by_transaction;
 /// generic collection functions
 pub mod collect_generic;
 
+pub use block_flush::{BlockFlush, FlushedBlock};
 pub use collect_by_block::CollectByBlock;
 pub use collect_by_transaction::CollectByTransaction;
-pub use collect_generic::collect_partition;
+pub use collect_generic::{collect_partition, collect_partition_variants};
@@ -30,6 +30,14 @@ pub trait ColumnData: Default + crate::Dataset {
         Self::default_blocks()
     }
 
+    /// columns identifying each row of dataset, the default sort if no key is declared
+    fn base_unique_key() -> Vec<String> {
+        match Self::unique_key() {
+            Some(key) => key.iter().map(|x| x.to_string()).collect(),
+            None => Self::base_default_sort(),
+        }
+    }
+
     /// default sort for dataset
     fn base_default_sort() -> Vec<String> {
         match Self::default_sort() {
@@ -54,6 +62,39 @@ pub trait ColumnData: Default + crate::Dataset {
     fn base_arg_aliases() -> HashMap<Dim, Dim> {
         Self::arg_aliases().unwrap_or_default()
     }
+
+    /// description of column, falling back to descriptions of columns shared by datasets
+    fn base_column_description(column: &str) -> Option<&'static str> {
+        Self::column_descriptions()
+            .get(column)
+            .copied()
+            .or_else(|| common_column_description(column))
+    }
+}
+
+/// descriptions of columns that have the same meaning in every dataset
+fn common_column_description(column: &str) -> Option<&'static str> {
+    let description = match column {
+        "block_number" => "number of the block",
+        "block_hash" => "hash of the block",
+        "transaction_index" => "index of the transaction within its block",
+        "transaction_hash" => "hash of the transaction",
+        "log_index" => "index of the log within its block",
+        "from_address" => "address that sent the transaction or call",
+        "to_address" => "address that received the transaction or call",
+        "address" => "address of the account or contract",
+        "value" => "amount of wei transferred",
+        "input" => "call data of the transaction or call",
+        "output" => "data returned by the call",
+        "gas_used" => "amount of gas used",
+        "gas_limit" => "maximum amount of gas allowed",
+        "timestamp" => "unix timestamp of the block",
+        "success" => "whether the transaction or call succeeded",
+        "error" => "error message of a failed call",
+        "chain_id" => "id of the chain the data was collected from",
+        _ => return None,
+    };
+    Some(description)
 }
 
 /// converts to dataframes
@@ -83,6 +124,11 @@ pub trait Dataset: Sync + Send {
         None
     }
 
+    /// columns identifying each row, for datasets whose default sort does not
+    fn unique_key() -> Option<Vec<&'static str>> {
+        None
+    }
+
     /// optional parameters for dataset
     fn optional_parameters() -> Vec<Dim> {
         vec![]
@@ -107,4 +153,14 @@ pub trait Dataset: Sync + Send {
     fn arg_aliases() -> Option<HashMap<Dim, Dim>> {
         None
     }
+
+    /// description of dataset
+    fn description() -> Option<&'static str> {
+        None
+    }
+
+    /// descriptions of dataset-specific columns
+    fn column_descriptions() -> HashMap<&'static str, &'static str> {
+        HashMap::new()
+    }
 }
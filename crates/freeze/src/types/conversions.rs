@@ -13,6 +13,23 @@ pub fn bytes_to_u32(value: Bytes) -> Result<u32, CollectError> {
     }
 }
 
+/// chronological ordering key, block_number * 1e9 + item_index
+///
+/// item_index is the position of the item among the items of its block, e.g. the log index of
+/// logs, so the key is monotone as long as a block has fewer than 1e9 items
+pub fn global_index(block_number: u64, item_index: u64) -> u64 {
+    block_number * 1_000_000_000 + item_index
+}
+
+/// nearest-rank value at percentile `p` in [0, 1] of sorted values
+pub fn percentile<T: Copy>(sorted: &[T], p: f64) -> Option<T> {
+    if sorted.is_empty() {
+        return None
+    }
+    let index = ((sorted.len() - 1) as f64 * p.clamp(0.0, 1.0)).round() as usize;
+    Some(sorted[index])
+}
+
 /// Converts data to Vec<u8>
 pub trait ToVecU8 {
     /// Convert to Vec<u8>
@@ -81,3 +98,22 @@ impl ToVecHex for Vec<Option<Vec<u8>>> {
         self.iter().map(|opt| opt.as_ref().map(|v| prefix_hex::encode(v.clone()))).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_percentile() {
+        let values = vec![1, 2, 3, 4, 10];
+        assert_eq!(percentile(&values, 0.0), Some(1));
+        assert_eq!(percentile(&values, 0.5), Some(3));
+        assert_eq!(percentile(&values, 1.0), Some(10));
+        assert_eq!(percentile::<u64>(&[], 0.5), None);
+
+        let latencies: Vec<Duration> = (1..=10).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 0.5), Some(Duration::from_millis(6)));
+        assert_eq!(percentile(&latencies, 0.9), Some(Duration::from_millis(9)));
+    }
+}
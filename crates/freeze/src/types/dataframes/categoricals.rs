@@ -0,0 +1,62 @@
+use polars::prelude::*;
+
+use crate::types::{CollectError, Table};
+
+pub(crate) trait CategoricalDataFrame {
+    fn cast_categoricals(self, schema: &Table) -> Self;
+}
+
+impl CategoricalDataFrame for Result<DataFrame, CollectError> {
+    fn cast_categoricals(self, schema: &Table) -> Self {
+        let mut df = self?;
+        for name in schema.categorical_columns.iter() {
+            let series = match df.column(name) {
+                Ok(series) if series.dtype() == &DataType::String => series,
+                _ => continue,
+            };
+            let categorical = series.cast(&DataType::Categorical(None, Default::default()))?;
+            df.with_column(categorical)?;
+        }
+        Ok(df)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColumnEncoding, ColumnType, Datatype, U256Type};
+
+    #[test]
+    fn test_categorical_parquet_dtype() {
+        let cols = Some(vec!["block_number".to_string(), "action_type".to_string()]);
+        let mut schema = Datatype::Traces
+            .table_schema(
+                &[U256Type::Binary],
+                &ColumnEncoding::Hex,
+                &None,
+                &None,
+                &cols,
+                None,
+                None,
+            )
+            .unwrap();
+        schema.set_categorical_columns(&["action_type", "block_number", "action_call_type"]);
+        assert_eq!(schema.column_type("action_type"), Some(ColumnType::Categorical));
+        assert_eq!(schema.column_type("block_number"), Some(ColumnType::UInt32));
+        assert_eq!(schema.categorical_columns, vec!["action_type"]);
+
+        let df = df!(
+            "block_number" => [1u32, 1, 2],
+            "action_type" => ["call", "create", "call"],
+        )
+        .map_err(CollectError::PolarsError);
+        let mut df = df.cast_categoricals(&schema).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ethereum__traces__1_to_2.parquet");
+        ParquetWriter::new(std::fs::File::create(&path).unwrap()).finish(&mut df).unwrap();
+        let written = ParquetReader::new(std::fs::File::open(&path).unwrap()).finish().unwrap();
+        let dtype = written.column("action_type").unwrap().dtype();
+        assert!(matches!(dtype, DataType::Categorical(_, _)), "{:?}", dtype);
+    }
+}
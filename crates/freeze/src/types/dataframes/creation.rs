@@ -89,7 +89,12 @@ macro_rules! with_series_u256 {
 
             // decimal128
             if $schema.u256_types.contains(&U256Type::Decimal128) {
-                return Err(CollectError::CollectError("DECIMAL128 not implemented".to_string()))
+                let name = $name.to_string() + U256Type::Decimal128.suffix().as_str();
+                let name = name.as_str();
+
+                let converted: Vec<Option<i128>> =
+                    $value.iter().map(|v| u256_to_decimal128(*v)).collect();
+                $all_series.push(decimal128_series(name, converted));
             }
         }
     };
@@ -170,7 +175,12 @@ macro_rules! with_series_option_u256 {
 
             // decimal128
             if $schema.u256_types.contains(&U256Type::Decimal128) {
-                return Err(CollectError::CollectError("DECIMAL128 not implemented".to_string()))
+                let name = $name.to_string() + U256Type::Decimal128.suffix().as_str();
+                let name = name.as_str();
+
+                let converted: Vec<Option<i128>> =
+                    $value.iter().map(|v| v.and_then(u256_to_decimal128)).collect();
+                $all_series.push(decimal128_series(name, converted));
             }
         }
     };
@@ -1,4 +1,11 @@
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+};
 
 use polars::prelude::*;
 
@@ -10,17 +17,117 @@ pub(crate) fn df_to_file(
     filename: &Path,
     file_output: &FileOutput,
 ) -> Result<(), FileError> {
-    let tmp_filename = filename.with_extension("_tmp");
+    let tmp_filename = tmp_path(df, filename, file_output);
     let result = match filename.extension().and_then(|ex| ex.to_str()) {
         Some("parquet") => df_to_parquet(df, &tmp_filename, file_output),
         Some("csv") => df_to_csv(df, &tmp_filename),
         Some("json") => df_to_json(df, &tmp_filename),
-        _ => return Err(FileError::FileWriteError),
+        _ => Err(FileError::FileWriteError),
     };
-    match result {
-        Ok(()) => std::fs::rename(tmp_filename, filename).map_err(|_e| FileError::FileWriteError),
-        Err(_e) => Err(FileError::FileWriteError),
+    let result = match result {
+        Ok(()) => move_file(&tmp_filename, filename),
+        Err(_e) => {
+            let _ = std::fs::remove_file(&tmp_filename);
+            Err(FileError::FileWriteError)
+        }
+    };
+    release_tmp_bytes(&tmp_filename);
+    result
+}
+
+/// bytes reserved in each tmp dir by the files this process is staging there, keyed by path
+static TMP_RESERVATIONS: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+/// number of staged files created by this process, used to give each one a unique name
+static N_TMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// whether a full tmp dir has already been reported
+static WARNED_TMP_DIR_FULL: AtomicBool = AtomicBool::new(false);
+
+/// path where a dataframe is written before being moved to its output path
+///
+/// files are staged in the configured tmp dir, unless the dataframe would exceed the size cap of
+/// the tmp dir, in which case they are staged next to their output path. the size of a staged
+/// file is reserved before it is written, so that concurrent writers cannot overshoot the cap
+fn tmp_path(df: &DataFrame, filename: &Path, file_output: &FileOutput) -> PathBuf {
+    let local_tmp = unique_tmp_path(filename);
+    let tmp_dir = match &file_output.tmp_dir {
+        Some(tmp_dir) => tmp_dir,
+        None => return local_tmp,
+    };
+    let tmp_filename = match local_tmp.file_name() {
+        Some(name) => tmp_dir.join(name),
+        None => return local_tmp,
+    };
+    let mut reservations = match TMP_RESERVATIONS.lock() {
+        Ok(reservations) => reservations,
+        Err(_) => return local_tmp,
+    };
+    let n_bytes = df.estimated_size() as u64;
+    if let Some(max_bytes) = file_output.max_tmp_bytes {
+        if tmp_dir_usage(tmp_dir, &reservations) + n_bytes > max_bytes {
+            if !WARNED_TMP_DIR_FULL.swap(true, Ordering::Relaxed) {
+                eprintln!(
+                    "tmp dir {} would exceed {} bytes, staging files next to their output paths",
+                    tmp_dir.display(),
+                    max_bytes
+                );
+            }
+            return local_tmp
+        }
     }
+    reservations.insert(tmp_filename.clone(), n_bytes);
+    tmp_filename
+}
+
+/// staging path next to filename, unique across processes and concurrent writers
+fn unique_tmp_path(filename: &Path) -> PathBuf {
+    let n = N_TMP_FILES.fetch_add(1, Ordering::Relaxed);
+    filename.with_extension(format!("{}.{}._tmp", std::process::id(), n))
+}
+
+/// release the reservation of a staged file once it has been moved or removed
+fn release_tmp_bytes(tmp_filename: &Path) {
+    if let Ok(mut reservations) = TMP_RESERVATIONS.lock() {
+        reservations.remove(tmp_filename);
+    }
+}
+
+/// total size of staged files in tmp dir, counting files being staged by this process at their
+/// reserved size
+fn tmp_dir_usage(tmp_dir: &Path, reservations: &BTreeMap<PathBuf, u64>) -> u64 {
+    let on_disk: u64 = match std::fs::read_dir(tmp_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().and_then(|ex| ex.to_str()) == Some("_tmp"))
+            .filter(|entry| !reservations.contains_key(&entry.path()))
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum(),
+        Err(_) => 0,
+    };
+    let reserved: u64 = reservations
+        .iter()
+        .filter(|(path, _)| path.parent() == Some(tmp_dir))
+        .map(|(_, n_bytes)| n_bytes)
+        .sum();
+    on_disk + reserved
+}
+
+/// move staged file to output path, copying when tmp dir is on a different filesystem
+fn move_file(tmp_filename: &Path, filename: &Path) -> Result<(), FileError> {
+    if std::fs::rename(tmp_filename, filename).is_ok() {
+        return Ok(())
+    }
+    let local_tmp = unique_tmp_path(filename);
+    let result = std::fs::copy(tmp_filename, &local_tmp)
+        .and_then(|_| std::fs::rename(&local_tmp, filename))
+        .map_err(|_e| FileError::FileWriteError);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&local_tmp);
+    }
+    let _ = std::fs::remove_file(tmp_filename);
+    result
 }
 
 /// write polars dataframe to parquet file
@@ -60,3 +167,77 @@ fn df_to_json(df: &mut DataFrame, filename: &Path) -> Result<(), FileError> {
         _ => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileFormat;
+
+    fn file_output(tmp_dir: &Path, max_tmp_bytes: u64) -> FileOutput {
+        FileOutput {
+            output_dir: tmp_dir.to_path_buf(),
+            prefix: "ethereum".to_string(),
+            suffix: None,
+            subdirs: vec![],
+            overwrite: false,
+            version_outputs: false,
+            tmp_dir: Some(tmp_dir.to_path_buf()),
+            max_tmp_bytes: Some(max_tmp_bytes),
+            max_block_bytes: None,
+            format: FileFormat::Parquet,
+            row_group_size: None,
+            parquet_statistics: false,
+            parquet_compression: ParquetCompression::Uncompressed,
+            hashed_columns: vec![],
+            hash_key: None,
+            dropped_columns: vec![],
+            masked_columns: vec![],
+        }
+    }
+
+    #[test]
+    fn test_unique_tmp_paths() {
+        let filename = Path::new("out/ethereum__blocks__0_to_9.parquet");
+        let (a, b) = (unique_tmp_path(filename), unique_tmp_path(filename));
+        assert_ne!(a, b);
+        assert_eq!(a.extension().and_then(|ex| ex.to_str()), Some("_tmp"));
+        assert!(a.to_string_lossy().contains(&std::process::id().to_string()));
+    }
+
+    #[test]
+    fn test_tmp_dir_reservations() {
+        let root = tempfile::tempdir().unwrap();
+        let tmp_dir = root.path().join("tmp");
+        let output_dir = root.path().join("output");
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let df = df!("block_number" => vec![1u64; 100]).unwrap();
+        let n_bytes = df.estimated_size() as u64;
+        let file_output = file_output(&tmp_dir, 2 * n_bytes - 1);
+
+        // the first file reserves its size in the tmp dir, leaving no room for a second one
+        let filename = output_dir.join("a.parquet");
+        let first = tmp_path(&df, &filename, &file_output);
+        assert_eq!(first.parent(), Some(tmp_dir.as_path()));
+        let second = tmp_path(&df, &output_dir.join("b.parquet"), &file_output);
+        assert_eq!(second.parent(), Some(output_dir.as_path()));
+
+        // once the first file is moved, its reservation is released
+        release_tmp_bytes(&first);
+        let third = tmp_path(&df, &output_dir.join("c.parquet"), &file_output);
+        assert_eq!(third.parent(), Some(tmp_dir.as_path()));
+        release_tmp_bytes(&third);
+    }
+
+    #[test]
+    fn test_df_to_file_through_tmp_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let tmp_dir = root.path().join("tmp");
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+        let mut df = df!("block_number" => [1u64, 2, 3]).unwrap();
+        let filename = root.path().join("ethereum__blocks__1_to_3.parquet");
+        df_to_file(&mut df, &filename, &file_output(&tmp_dir, u64::MAX)).unwrap();
+        assert!(filename.exists());
+        assert_eq!(std::fs::read_dir(&tmp_dir).unwrap().count(), 0);
+    }
+}
@@ -1,4 +1,7 @@
+mod categoricals;
 mod export;
+mod nulls;
+mod privacy;
 mod read;
 mod sort;
 mod u256s;
@@ -6,7 +9,10 @@ mod u256s;
 #[macro_use]
 mod creation;
 
+pub(crate) use categoricals::CategoricalDataFrame;
 pub(crate) use export::*;
+pub(crate) use nulls::GasNullDataFrame;
+pub(crate) use privacy::*;
 pub use read::*;
 pub(crate) use sort::SortableDataFrame;
 pub use u256s::*;
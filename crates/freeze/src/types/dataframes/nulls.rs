@@ -0,0 +1,127 @@
+use polars::prelude::*;
+
+use crate::types::{CollectError, Datatype, GasNullPolicy, Table};
+
+pub(crate) trait GasNullDataFrame {
+    fn fill_gas_nulls(self, schema: &Table) -> Self;
+}
+
+impl GasNullDataFrame for Result<DataFrame, CollectError> {
+    fn fill_gas_nulls(self, schema: &Table) -> Self {
+        match (self, schema.gas_null_policy) {
+            (Ok(df), GasNullPolicy::Zero) | (Ok(df), GasNullPolicy::Sentinel) => {
+                fill_gas_columns(df, schema.datatype, schema.gas_null_policy)
+            }
+            (df, _) => df,
+        }
+    }
+}
+
+/// nullable gas columns of each dataset, e.g. gas prices of transaction types without them or
+/// the gas of trace actions without gas
+fn gas_columns(datatype: Datatype) -> &'static [&'static str] {
+    match datatype {
+        Datatype::Blocks | Datatype::GasStats => &["base_fee_per_gas"],
+        Datatype::Transactions => &[
+            "gas_used",
+            "gas_price",
+            "max_priority_fee_per_gas",
+            "max_fee_per_gas",
+            "l1_gas_used",
+            "l1_gas_price",
+            "gas_used_for_l1",
+        ],
+        Datatype::BlobTransactions => &["max_fee_per_blob_gas", "blob_gas_price"],
+        Datatype::Receipts => &["blob_gas_used", "blob_gas_price"],
+        Datatype::Traces | Datatype::TraceCalls => &["action_gas", "result_gas_used"],
+        Datatype::TraceGas => &["gas_used", "exclusive_gas_used"],
+        Datatype::GasPercentiles => &["effective_gas_price"],
+        Datatype::Mempool => &["gas_price", "max_fee_per_gas", "max_priority_fee_per_gas"],
+        _ => &[],
+    }
+}
+
+fn fill_gas_columns(
+    mut df: DataFrame,
+    datatype: Datatype,
+    policy: GasNullPolicy,
+) -> Result<DataFrame, CollectError> {
+    for name in gas_columns(datatype).iter() {
+        let series = match df.column(name) {
+            Ok(series) if series.null_count() > 0 => series,
+            _ => continue,
+        };
+        if let Some(filled) = fill_nulls(series, policy)? {
+            df.with_column(filled)?;
+        }
+    }
+    Ok(df)
+}
+
+/// sentinels are the maximum value of integer columns and NaN for float columns
+fn fill_nulls(series: &Series, policy: GasNullPolicy) -> Result<Option<Series>, CollectError> {
+    let sentinel = policy == GasNullPolicy::Sentinel;
+    let filled = match series.dtype() {
+        DataType::UInt32 => {
+            let value = if sentinel { u32::MAX } else { 0 };
+            series.u32()?.fill_null_with_values(value)?.into_series()
+        }
+        DataType::UInt64 => {
+            let value = if sentinel { u64::MAX } else { 0 };
+            series.u64()?.fill_null_with_values(value)?.into_series()
+        }
+        DataType::Int32 => {
+            let value = if sentinel { i32::MAX } else { 0 };
+            series.i32()?.fill_null_with_values(value)?.into_series()
+        }
+        DataType::Int64 => {
+            let value = if sentinel { i64::MAX } else { 0 };
+            series.i64()?.fill_null_with_values(value)?.into_series()
+        }
+        DataType::Float32 => {
+            let value = if sentinel { f32::NAN } else { 0.0 };
+            series.f32()?.fill_null_with_values(value)?.into_series()
+        }
+        DataType::Float64 => {
+            let value = if sentinel { f64::NAN } else { 0.0 };
+            series.f64()?.fill_null_with_values(value)?.into_series()
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_gas_columns() {
+        let df = df!(
+            "gas_limit" => [Some(21000u64), None],
+            "gas_price" => [Some(7u64), None],
+            "max_fee_per_gas" => [None, Some(9u64)],
+            "effective_gas_price" => [Some(7u64), None],
+        )
+        .unwrap();
+
+        // only the gas columns listed for transactions are filled
+        let filled = fill_gas_columns(df.clone(), Datatype::Transactions, GasNullPolicy::Zero);
+        let filled = filled.unwrap();
+        assert_eq!(filled.column("gas_price").unwrap().null_count(), 0);
+        assert_eq!(filled.column("max_fee_per_gas").unwrap().u64().unwrap().get(0), Some(0));
+        assert_eq!(filled.column("gas_limit").unwrap().null_count(), 1);
+        assert_eq!(filled.column("effective_gas_price").unwrap().null_count(), 1);
+
+        let filled =
+            fill_gas_columns(df.clone(), Datatype::GasPercentiles, GasNullPolicy::Sentinel);
+        let filled = filled.unwrap();
+        let effective_gas_price = filled.column("effective_gas_price").unwrap();
+        assert_eq!(effective_gas_price.u64().unwrap().get(1), Some(u64::MAX));
+        assert_eq!(filled.column("gas_price").unwrap().null_count(), 1);
+
+        // datasets without nullable gas columns are unchanged
+        let filled = fill_gas_columns(df.clone(), Datatype::Logs, GasNullPolicy::Zero).unwrap();
+        assert!(filled.equals_missing(&df));
+    }
+}
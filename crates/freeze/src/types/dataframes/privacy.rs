@@ -0,0 +1,131 @@
+use crate::{err, CollectError, FileOutput};
+use hmac::{Hmac, Mac};
+use polars::prelude::*;
+use sha3::Keccak256;
+
+/// number of bytes kept from each hash, so that pseudonyms have the width of an address
+const PSEUDONYM_SIZE: usize = 20;
+
+/// replace values of configured columns with keyed hashes before writing
+///
+/// the same input value always maps to the same pseudonym under a given key, so hashed columns
+/// remain joinable across datasets without revealing the original addresses
+pub(crate) fn pseudonymize_columns(
+    df: &mut DataFrame,
+    file_output: &FileOutput,
+) -> Result<(), CollectError> {
+    let key = match &file_output.hash_key {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+    for name in file_output.hashed_columns.iter() {
+        let series = match df.column(name) {
+            Ok(series) => series,
+            Err(_) => continue,
+        };
+        let hashed = match series.dtype() {
+            DataType::Binary => {
+                let values: Vec<Option<Vec<u8>>> = series
+                    .binary()?
+                    .into_iter()
+                    .map(|value| value.map(|value| pseudonymize(key, value)))
+                    .collect();
+                Series::new(name.as_str(), values)
+            }
+            DataType::String => {
+                let values = series
+                    .str()?
+                    .into_iter()
+                    .map(|value| value.map(|value| pseudonymize_hex(key, value)).transpose())
+                    .collect::<Result<Vec<Option<String>>, CollectError>>()?;
+                Series::new(name.as_str(), values)
+            }
+            _ => {
+                return Err(err(format!("cannot hash column {}, not binary or hex", name).as_str()))
+            }
+        };
+        df.with_column(hashed)?;
+    }
+    Ok(())
+}
+
+/// drop or mask configured columns before writing
+///
+/// masked columns keep their name and type but every value is replaced with null, so that the
+/// schema of redacted files matches the schema of unredacted files
+pub(crate) fn redact_columns(
+    df: &mut DataFrame,
+    file_output: &FileOutput,
+) -> Result<(), CollectError> {
+    for name in file_output.masked_columns.iter() {
+        if let Ok(series) = df.column(name) {
+            let masked = Series::full_null(name.as_str(), series.len(), series.dtype());
+            df.with_column(masked)?;
+        }
+    }
+    for name in file_output.dropped_columns.iter() {
+        if df.column(name).is_ok() {
+            let _ = df.drop_in_place(name)?;
+        }
+    }
+    Ok(())
+}
+
+fn pseudonymize(key: &[u8], value: &[u8]) -> Vec<u8> {
+    hmac_keccak256(key, value)[..PSEUDONYM_SIZE].to_vec()
+}
+
+fn pseudonymize_hex(key: &[u8], value: &str) -> Result<String, CollectError> {
+    let bytes: Vec<u8> = prefix_hex::decode(value)
+        .map_err(|_| err(format!("could not decode hex value {}", value).as_str()))?;
+    Ok(prefix_hex::encode(pseudonymize(key, &bytes)))
+}
+
+/// hmac (rfc 2104) using keccak256 as the hash function
+fn hmac_keccak256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    // hmac accepts keys of any length, hashing keys longer than the block size
+    let mut mac = Hmac::<Keccak256>::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hmac_hex(key: &[u8], message: &[u8]) -> String {
+        prefix_hex::encode(hmac_keccak256(key, message).to_vec())
+    }
+
+    #[test]
+    fn test_hmac_keccak256() {
+        // inputs of the rfc 4231 test cases, with keccak256 as the hash function
+        assert_eq!(
+            hmac_hex(&[0x0b; 20], b"Hi There"),
+            "0x9663d10c73ee294054dc9faf95647cb99731d12210ff7075fb3d3395abfb9821"
+        );
+        assert_eq!(
+            hmac_hex(b"Jefe", b"what do ya want for nothing?"),
+            "0xaa9aed448c7abc8b5e326ffa6a01cdedf7b4b831881468c044ba8dd4566369a1"
+        );
+
+        // keys up to the 136 byte block size are padded, longer keys are hashed first
+        let message = b"Test Using Larger Than Block-Size Key - Hash Key First";
+        assert_eq!(
+            hmac_hex(&[0xaa; 131], message),
+            "0xb4d0cdee7ec2ba81a88b86918958312300a15622377929a054a9ce3ae1fac2b6"
+        );
+        assert_eq!(
+            hmac_hex(&[0xaa; 136], message),
+            "0xdf574cb347f5842a548fd8f1d2404f2e3e8b6a30e90b4f899b64597dd92efc3e"
+        );
+        assert_eq!(
+            hmac_hex(&[0xaa; 137], message),
+            "0x729e0d9f4bb07a089c0093c353f9613ef18430bc2eddb683982cf65ef5429c73"
+        );
+        assert_eq!(
+            hmac_hex(&[0xaa; 200], message),
+            "0xd05663862adc5817c97516e95dfe6e204195a7fb44857e2b66ce23d8175c65c7"
+        );
+    }
+}
@@ -1,5 +1,9 @@
 use crate::ParseError;
 use polars::prelude::*;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 /// read single binary column of parquet file as Vec<u8>
 pub fn read_binary_column(path: &str, column: &str) -> Result<Vec<Vec<u8>>, ParseError> {
@@ -29,3 +33,110 @@ pub fn read_binary_column(path: &str, column: &str) -> Result<Vec<Vec<u8>>, Pars
         })
         .collect()
 }
+
+/// find parquet files in an output directory, grouped by datatype name
+///
+/// datatype names are taken from cryo's file naming scheme `{network}__{datatype}__...`, only
+/// the latest version of files written with `--version-outputs` is included
+pub fn find_dataset_files(output_dir: &Path) -> Result<HashMap<String, Vec<PathBuf>>, ParseError> {
+    let mut files: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    let entries = std::fs::read_dir(output_dir)
+        .map_err(|_e| ParseError::ParseError("could not read output directory".to_string()))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|_e| ParseError::ParseError("could not read directory entry".to_string()))?
+            .path();
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if name.starts_with('.') {
+            continue;
+        } else if path.is_dir() {
+            for (datatype, paths) in find_dataset_files(&path)?.into_iter() {
+                files.entry(datatype).or_default().extend(paths);
+            }
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("parquet") {
+            if let Some(datatype) = name.split("__").nth(1) {
+                files.entry(datatype.to_string()).or_default().push(path.clone());
+            }
+        }
+    }
+    for paths in files.values_mut() {
+        let mut latest: HashMap<PathBuf, (u32, PathBuf)> = HashMap::new();
+        for path in paths.drain(..) {
+            let (base, version) = crate::files::split_version(&path);
+            match latest.get(&base) {
+                Some((latest_version, _)) if *latest_version >= version => {}
+                _ => {
+                    latest.insert(base, (version, path));
+                }
+            }
+        }
+        paths.extend(latest.into_values().map(|(_, path)| path));
+        paths.sort();
+    }
+    Ok(files)
+}
+
+/// block range of a file written by cryo, parsed from the `{start}_to_{end}` label of its name
+pub fn file_block_range(path: &Path) -> Option<(u64, u64)> {
+    let stem = path.file_stem()?.to_str()?;
+    stem.split("__").find_map(|piece| {
+        let (start, end) = piece.split_once("_to_")?;
+        Some((start.parse().ok()?, end.parse().ok()?))
+    })
+}
+
+/// lazily scan a dataset of an output directory, optionally restricted to a block range
+///
+/// only files overlapping the block range are scanned, and rows outside the range are filtered
+/// out. further column predicates can be applied to the result with `LazyFrame::filter`
+pub fn scan_dataset(
+    output_dir: &Path,
+    datatype: &str,
+    blocks: Option<(u64, u64)>,
+) -> Result<LazyFrame, ParseError> {
+    let paths = find_dataset_files(output_dir)?.remove(datatype).unwrap_or_default();
+    let paths: Vec<PathBuf> = match blocks {
+        Some((start, end)) => paths
+            .into_iter()
+            .filter(|path| match file_block_range(path) {
+                Some((file_start, file_end)) => file_start <= end && file_end >= start,
+                None => true,
+            })
+            .collect(),
+        None => paths,
+    };
+    if paths.is_empty() {
+        return Err(ParseError::ParseError(format!("no {} files found", datatype)))
+    }
+    let lf = LazyFrame::scan_parquet_files(paths.into(), ScanArgsParquet::default())
+        .map_err(|_e| ParseError::ParseError("could not scan parquet files".to_string()))?;
+    match blocks {
+        Some((start, end)) => {
+            let schema = lf
+                .schema()
+                .map_err(|_e| ParseError::ParseError("could not read file schema".to_string()))?;
+            if schema.contains("block_number") {
+                let in_range =
+                    col("block_number").gt_eq(lit(start)).and(col("block_number").lt_eq(lit(end)));
+                Ok(lf.filter(in_range))
+            } else {
+                Ok(lf)
+            }
+        }
+        None => Ok(lf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_block_range() {
+        let path = Path::new("/data/ethereum__blocks__00001000_to_00001999.parquet");
+        assert_eq!(file_block_range(path), Some((1000, 1999)));
+        let path = Path::new("/data/ethereum__blocks__00001000_to_00001999__v2.parquet");
+        assert_eq!(file_block_range(path), Some((1000, 1999)));
+        assert_eq!(file_block_range(Path::new("/data/ethereum__blocks__latest.parquet")), None);
+    }
+}
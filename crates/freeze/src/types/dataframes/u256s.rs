@@ -2,6 +2,35 @@ use crate::*;
 use alloy::primitives::{I256, U256};
 use polars::prelude::*;
 
+/// number of decimal digits of decimal128 columns, the most that fit in an i128
+const DECIMAL128_PRECISION: usize = 38;
+
+/// convert U256 to a decimal128 value, values with more than 38 digits become null
+pub fn u256_to_decimal128(value: U256) -> Option<i128> {
+    if value < U256::from(10).pow(U256::from(DECIMAL128_PRECISION)) {
+        Some(value.to::<u128>() as i128)
+    } else {
+        None
+    }
+}
+
+/// convert I256 to a decimal128 value, values with more than 38 digits become null
+pub fn i256_to_decimal128(value: I256) -> Option<i128> {
+    let magnitude = u256_to_decimal128(value.unsigned_abs())?;
+    if value.is_negative() {
+        Some(-magnitude)
+    } else {
+        Some(magnitude)
+    }
+}
+
+/// create decimal128 series with precision 38 and scale 0
+pub fn decimal128_series(name: &str, values: Vec<Option<i128>>) -> Series {
+    Int128Chunked::from_iter_options(name, values.into_iter())
+        .into_decimal_unchecked(Some(DECIMAL128_PRECISION), 0)
+        .into_series()
+}
+
 /// Converts a Vec of U256-like data into a polars Series
 pub trait ToU256Series {
     /// convert a Vec of U256-like data into a polars Series
@@ -54,7 +83,9 @@ impl ToU256Series for Vec<U256> {
                 Ok(Series::new(name, converted))
             }
             U256Type::Decimal128 => {
-                Err(CollectError::CollectError("DECIMAL128 not implemented".to_string()))
+                let converted: Vec<Option<i128>> =
+                    self.iter().map(|v| u256_to_decimal128(*v)).collect();
+                Ok(decimal128_series(name, converted))
             }
         }
     }
@@ -109,7 +140,9 @@ impl ToU256Series for Vec<Option<U256>> {
                 Ok(Series::new(name, converted))
             }
             U256Type::Decimal128 => {
-                Err(CollectError::CollectError("DECIMAL128 not implemented".to_string()))
+                let converted: Vec<Option<i128>> =
+                    self.iter().map(|v| v.and_then(u256_to_decimal128)).collect();
+                Ok(decimal128_series(name, converted))
             }
         }
     }
@@ -156,7 +189,9 @@ impl ToU256Series for Vec<I256> {
                 Ok(Series::new(name, converted))
             }
             U256Type::Decimal128 => {
-                Err(CollectError::CollectError("DECIMAL128 not implemented".to_string()))
+                let converted: Vec<Option<i128>> =
+                    self.iter().map(|v| i256_to_decimal128(*v)).collect();
+                Ok(decimal128_series(name, converted))
             }
         }
     }
@@ -211,8 +246,34 @@ impl ToU256Series for Vec<Option<I256>> {
                 Ok(Series::new(name, converted))
             }
             U256Type::Decimal128 => {
-                Err(CollectError::CollectError("DECIMAL128 not implemented".to_string()))
+                let converted: Vec<Option<i128>> =
+                    self.iter().map(|v| v.and_then(i256_to_decimal128)).collect();
+                Ok(decimal128_series(name, converted))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal128_conversion() {
+        let max = U256::from(10).pow(U256::from(38)) - U256::from(1);
+        assert_eq!(u256_to_decimal128(U256::from(12345)), Some(12345));
+        assert_eq!(u256_to_decimal128(max), Some(10i128.pow(38) - 1));
+        assert_eq!(u256_to_decimal128(max + U256::from(1)), None);
+        assert_eq!(i256_to_decimal128(I256::try_from(-7i64).unwrap()), Some(-7));
+
+        let series = vec![Some(U256::from(1)), None].to_u256_series(
+            "value".to_string(),
+            U256Type::Decimal128,
+            &ColumnEncoding::Binary,
+        );
+        let series = series.unwrap();
+        assert_eq!(series.name(), "value_d128");
+        assert_eq!(series.dtype(), &DataType::Decimal(Some(38), Some(0)));
+        assert_eq!(series.null_count(), 1);
+    }
+}
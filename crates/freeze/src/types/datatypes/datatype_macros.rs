@@ -41,6 +41,13 @@ macro_rules! define_datatypes {
                 }
             }
 
+            /// columns identifying each row of datatype
+            pub fn unique_key(&self) -> Vec<String> {
+                match *self {
+                    $(Datatype::$datatype => $datatype::base_unique_key(),)*
+                }
+            }
+
             /// default columns of datatype
             pub fn default_columns(&self) -> Vec<&'static str> {
                 match *self {
@@ -55,6 +62,20 @@ macro_rules! define_datatypes {
                 }
             }
 
+            /// description of datatype
+            pub fn description(&self) -> Option<&'static str> {
+                match *self {
+                    $(Datatype::$datatype => $datatype::description(),)*
+                }
+            }
+
+            /// description of column of datatype
+            pub fn column_description(&self, column: &str) -> Option<&'static str> {
+                match *self {
+                    $(Datatype::$datatype => $datatype::base_column_description(column),)*
+                }
+            }
+
             /// default column types of datatype
             pub fn column_types(&self) -> indexmap::IndexMap<&'static str, ColumnType> {
                 match *self {
@@ -135,6 +156,10 @@ macro_rules! define_datatypes {
                     MultiDatatype::GethStateDiffs => {
                         GethStateDiffs::collect_by_block(partition, source, query, None)
                     },
+                    MultiDatatype::LogDerivatives => {
+                        let inner_request_size = Some(source.inner_request_size);
+                        LogDerivatives::collect_by_block(partition, source, query, inner_request_size)
+                    },
                     MultiDatatype::StateDiffs => {
                         StateDiffs::collect_by_block(partition, source, query, None)
                     },
@@ -178,6 +203,9 @@ macro_rules! define_datatypes {
                         MultiDatatype::GethStateDiffs => {
                             GethStateDiffs::collect_by_transaction(partition, source, query, None)
                         },
+                        MultiDatatype::LogDerivatives => {
+                            LogDerivatives::collect_by_transaction(partition, source, query, None)
+                        },
                         MultiDatatype::StateDiffs => {
                             StateDiffs::collect_by_transaction(partition, source, query, inner_request_size)
                         },
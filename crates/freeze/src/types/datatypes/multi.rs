@@ -13,6 +13,9 @@ pub enum MultiDatatype {
     /// geth debug versions of balance diffs, code diffs, nonce diffs, and storage diffs
     GethStateDiffs,
 
+    /// logs and the transfer and approval datasets decoded from them
+    LogDerivatives,
+
     /// balance diffs, code diffs, nonce diffs, and storage diffs
     StateDiffs,
 
@@ -34,6 +37,13 @@ impl MultiDatatype {
                 Datatype::GethNonceDiffs,
                 Datatype::GethStorageDiffs,
             ],
+            MultiDatatype::LogDerivatives => vec![
+                Datatype::Erc1155Transfers,
+                Datatype::Erc20Approvals,
+                Datatype::Erc20Transfers,
+                Datatype::Erc721Transfers,
+                Datatype::Logs,
+            ],
             MultiDatatype::StateDiffs => vec![
                 Datatype::BalanceDiffs,
                 Datatype::CodeDiffs,
@@ -55,6 +65,7 @@ impl MultiDatatype {
             MultiDatatype::BlocksAndTransactions,
             MultiDatatype::CallTraceDerivatives,
             MultiDatatype::GethStateDiffs,
+            MultiDatatype::LogDerivatives,
             MultiDatatype::StateDiffs,
             MultiDatatype::StateReads,
         ]
@@ -4,14 +4,24 @@ use std::collections::HashMap;
 
 define_datatypes!(
     AddressAppearances,
+    AddressTransactions,
     BalanceDiffs,
     BalanceReads,
     Balances,
+    BeaconAttestations,
+    BeaconBlocks,
+    BeaconDeposits,
+    BlobTransactions,
     Blocks,
+    CallResults,
     CodeDiffs,
     CodeReads,
     Codes,
     Contracts,
+    CustomTraces,
+    DecodedCalls,
+    DecodedLogs,
+    Erc1155Transfers,
     Erc20Balances,
     Erc20Metadata,
     Erc20Supplies,
@@ -21,6 +31,9 @@ define_datatypes!(
     Erc721Transfers,
     EthCalls,
     FourByteCounts,
+    GasPercentiles,
+    GasStats,
+    GenesisBalances,
     GethCalls,
     GethCodeDiffs,
     GethBalanceDiffs,
@@ -29,16 +42,29 @@ define_datatypes!(
     GethOpcodes,
     JavascriptTraces,
     Logs,
+    Mempool,
     NativeTransfers,
     NonceDiffs,
     NonceReads,
     Nonces,
+    ProofStats,
+    Proofs,
+    ProxyUpgrades,
+    Receipts,
+    SafeExecutions,
+    SelectorCounts,
+    Selfdestructs,
     Slots,
     StorageDiffs,
     StorageReads,
+    TraceGas,
     Traces,
     TraceCalls,
+    TraceTrees,
     Transactions,
+    Uncles,
+    UserOperations,
+    ValidatorBalances,
     VmTraces,
 );
 
@@ -0,0 +1,231 @@
+use super::log_decoder::make_decoded_columns;
+use crate::{CollectError, ColumnEncoding, U256Type};
+use alloy::{
+    dyn_abi::{DynSolType, DynSolValue, JsonAbiExt},
+    json_abi::{Function, JsonAbi, Param},
+};
+use polars::prelude::*;
+
+/// container for function call decoding context
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionDecoder {
+    /// the raw function signature string ex: function transfer(address to, uint256 amount)
+    pub raw: String,
+    /// decoded abi type of function signature string
+    pub function: Function,
+}
+
+impl FunctionDecoder {
+    /// create a new FunctionDecoder from a function signature
+    /// ex: FunctionDecoder::new("function transfer(address to, uint256 amount)".to_string())
+    pub fn new(function_signature: String) -> Result<Self, String> {
+        match Function::parse(&function_signature) {
+            Ok(function) => Ok(Self { function, raw: function_signature }),
+            Err(e) => Err(format!(
+                "incorrectly formatted function {} (expect something like function \
+                 transfer(address to, uint256 amount) err: {}",
+                function_signature, e
+            )),
+        }
+    }
+
+    /// create a FunctionDecoder for each function of an abi json file, either a plain abi array
+    /// or a compiler artifact with an `abi` field
+    pub fn from_abi_file(path: &str) -> Result<Vec<Self>, String> {
+        Ok(read_abi_file(path)?
+            .functions()
+            .map(|function| Self { raw: function.full_signature(), function: function.clone() })
+            .collect())
+    }
+
+    /// 4-byte selector of the function
+    pub fn selector(&self) -> [u8; 4] {
+        self.function.selector().0
+    }
+
+    /// label distinguishing outputs of overloaded functions, ex: transfer_a9059cbb
+    pub fn label(&self) -> String {
+        format!("{}_{}", self.function.name, alloy::hex::encode(self.selector()))
+    }
+
+    /// get field names of function inputs, unnamed inputs are named by position
+    pub fn field_names(&self) -> Vec<String> {
+        param_names(&self.function.inputs)
+    }
+
+    /// get field names of function outputs, unnamed outputs are named by position
+    pub fn output_names(&self) -> Vec<String> {
+        param_names(&self.function.outputs)
+    }
+
+    /// encode call data from comma-separated argument values, ex: 0x6b17...1d0f,100
+    pub fn encode_call(&self, args: &str) -> Result<Vec<u8>, String> {
+        let types = self
+            .function
+            .inputs
+            .iter()
+            .map(|input| DynSolType::parse(&input.ty))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("unsupported input type of {}: {}", self.raw, e))?;
+        let values = match DynSolType::Tuple(types).coerce_str(&format!("({})", args)) {
+            Ok(DynSolValue::Tuple(values)) => values,
+            _ if self.function.inputs.is_empty() && args.trim().is_empty() => vec![],
+            _ => return Err(format!("invalid arguments ({}) for {}", args, self.raw)),
+        };
+        self.function
+            .abi_encode_input(&values)
+            .map_err(|e| format!("could not encode arguments ({}) for {}: {}", args, self.raw, e))
+    }
+
+    /// decode call input, returns None if the selector does not match or decoding fails
+    pub fn decode_input(&self, input: &[u8]) -> Option<Vec<DynSolValue>> {
+        match input.get(..4) {
+            Some(selector) if selector == self.selector() => {
+                self.function.abi_decode_input(&input[4..], true).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// decode call output, returns None if decoding fails
+    pub fn decode_output(&self, output: &[u8]) -> Option<Vec<DynSolValue>> {
+        self.function.abi_decode_output(output, true).ok()
+    }
+
+    /// convert decoded inputs into `input__{name}` series, creating empty typed series when
+    /// nothing was decoded
+    pub fn make_series(
+        &self,
+        input_cols: indexmap::IndexMap<String, Vec<DynSolValue>>,
+        chunk_len: usize,
+        u256_types: &[U256Type],
+        column_encoding: &ColumnEncoding,
+    ) -> Result<Vec<Series>, CollectError> {
+        let params = self
+            .field_names()
+            .into_iter()
+            .zip(self.function.inputs.iter().map(|input| input.ty.clone()))
+            .collect();
+        make_decoded_columns("input__", params, input_cols, chunk_len, u256_types, column_encoding)
+    }
+
+    /// convert decoded outputs into `output__{name}` series, rows without outputs (e.g. calls
+    /// that reverted) are null
+    pub fn make_output_series(
+        &self,
+        output_cols: indexmap::IndexMap<String, Vec<Option<DynSolValue>>>,
+        chunk_len: usize,
+        u256_types: &[U256Type],
+        column_encoding: &ColumnEncoding,
+    ) -> Result<Vec<Series>, CollectError> {
+        let params = self
+            .output_names()
+            .into_iter()
+            .zip(self.function.outputs.iter().map(|output| output.ty.clone()))
+            .collect();
+
+        // position of each row among the rows with outputs, which are the same for all outputs
+        let mut n_decoded: IdxSize = 0;
+        let rows: Vec<Option<IdxSize>> = match output_cols.values().next() {
+            Some(values) => values
+                .iter()
+                .map(|value| {
+                    value.as_ref().map(|_| {
+                        n_decoded += 1;
+                        n_decoded - 1
+                    })
+                })
+                .collect(),
+            None => vec![],
+        };
+        let decoded = match n_decoded {
+            0 => indexmap::IndexMap::new(),
+            _ => output_cols
+                .into_iter()
+                .map(|(name, values)| (name, values.into_iter().flatten().collect()))
+                .collect(),
+        };
+        let series = make_decoded_columns(
+            "output__",
+            params,
+            decoded,
+            n_decoded as usize,
+            u256_types,
+            column_encoding,
+        )?;
+        if n_decoded as usize == chunk_len {
+            return Ok(series)
+        }
+        let indices = IdxCa::from_iter_options("", rows.into_iter());
+        let mut cols = Vec::new();
+        for series in series.into_iter() {
+            cols.push(match n_decoded {
+                0 => Series::full_null(series.name(), chunk_len, series.dtype()),
+                _ => series.take(&indices)?,
+            });
+        }
+        Ok(cols)
+    }
+}
+
+fn param_names(params: &[Param]) -> Vec<String> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| match param.name.is_empty() {
+            true => format!("arg{}", i),
+            false => param.name.clone(),
+        })
+        .collect()
+}
+
+/// read an abi json file, either a plain abi array or a compiler artifact with an `abi` field
+pub(crate) fn read_abi_file(path: &str) -> Result<JsonAbi, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("could not read abi file {}: {}", path, e))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("invalid json in abi file {}: {}", path, e))?;
+    let value = match value.get("abi") {
+        Some(abi) => abi.clone(),
+        None => value,
+    };
+    serde_json::from_value(value).map_err(|e| format!("invalid abi in abi file {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_decode_input() {
+        let decoder =
+            FunctionDecoder::new("function transfer(address to, uint256 amount)".to_string())
+                .unwrap();
+        assert_eq!(decoder.label(), "transfer_a9059cbb");
+        let mut input = vec![0xa9, 0x05, 0x9c, 0xbb];
+        input.extend([0u8; 31]);
+        input.push(1);
+        input.extend([0u8; 31]);
+        input.push(2);
+        let decoded = decoder.decode_input(&input).unwrap();
+        assert_eq!(decoded[1], DynSolValue::Uint(U256::from(2), 256));
+        assert_eq!(decoder.decode_input(&input[4..]), None);
+        assert_eq!(decoder.encode_call("0x0000000000000000000000000000000000000001,2"), Ok(input));
+    }
+
+    #[test]
+    fn test_decode_output() {
+        let decoder = FunctionDecoder::new(
+            "function getReserves() view returns (uint112 reserve0, uint112, uint32)".to_string(),
+        )
+        .unwrap();
+        assert_eq!(decoder.output_names(), vec!["reserve0", "arg1", "arg2"]);
+        assert_eq!(decoder.encode_call(""), Ok(decoder.selector().to_vec()));
+        let mut output = vec![0u8; 96];
+        output[31] = 5;
+        let decoded = decoder.decode_output(&output).unwrap();
+        assert_eq!(decoded[0], DynSolValue::Uint(U256::from(5), 112));
+        assert_eq!(decoder.decode_output(&output[..64]), None);
+    }
+}
@@ -1,6 +1,7 @@
+use super::function_decoder::read_abi_file;
 use crate::{err, CollectError, ColumnEncoding, ToU256Series, U256Type};
 use alloy::{
-    dyn_abi::{DynSolValue, EventExt},
+    dyn_abi::{DynSolType, DynSolValue, EventExt},
     hex::ToHexExt,
     json_abi::Event,
     primitives::{I256, U256},
@@ -33,11 +34,67 @@ impl LogDecoder {
         }
     }
 
+    /// create a LogDecoder for each non-anonymous event of an abi json file
+    pub fn from_abi_file(path: &str) -> Result<Vec<Self>, String> {
+        Ok(read_abi_file(path)?
+            .events()
+            .filter(|event| !event.anonymous)
+            .map(|event| Self { raw: event.full_signature(), event: event.clone() })
+            .collect())
+    }
+
     /// get field names of event inputs
     pub fn field_names(&self) -> Vec<String> {
         self.event.inputs.iter().map(|i| i.name.clone()).collect()
     }
 
+    /// label distinguishing outputs of overloaded events, ex: Transfer_ddf252ad
+    pub fn label(&self) -> String {
+        format!("{}_{}", self.event.name, alloy::hex::encode(&self.event.selector()[..4]))
+    }
+
+    /// get argument names of event inputs, unnamed inputs are named by position
+    pub fn arg_names(&self) -> Vec<String> {
+        self.event
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| match input.name.is_empty() {
+                true => format!("arg{}", i),
+                false => input.name.clone(),
+            })
+            .collect()
+    }
+
+    /// decode the indexed and body values of a log in the order of the event inputs
+    pub fn decode_args(&self, log: &Log) -> Option<Vec<DynSolValue>> {
+        let decoded = self.event.decode_log(&log.inner.data, true).ok()?;
+        let mut indexed = decoded.indexed.into_iter();
+        let mut body = decoded.body.into_iter();
+        self.event
+            .inputs
+            .iter()
+            .map(|input| if input.indexed { indexed.next() } else { body.next() })
+            .collect()
+    }
+
+    /// convert decoded arguments into `arg__{name}` series, creating empty typed series when
+    /// nothing was decoded
+    pub fn make_arg_series(
+        &self,
+        arg_cols: indexmap::IndexMap<String, Vec<DynSolValue>>,
+        chunk_len: usize,
+        u256_types: &[U256Type],
+        column_encoding: &ColumnEncoding,
+    ) -> Result<Vec<Series>, CollectError> {
+        let params = self
+            .arg_names()
+            .into_iter()
+            .zip(self.event.inputs.iter().map(|input| input.ty.clone()))
+            .collect();
+        make_decoded_columns("arg__", params, arg_cols, chunk_len, u256_types, column_encoding)
+    }
+
     /// converts from a log type to an abi token type
     /// this function assumes all logs are of the same type and skips fields if they don't match the
     /// passed event definition
@@ -87,105 +144,241 @@ impl LogDecoder {
         u256_types: &[U256Type],
         column_encoding: &ColumnEncoding,
     ) -> Result<Vec<Series>, CollectError> {
-        // This is a smooth brain way of doing this, but I can't think of a better way right now
-        let mut ints: Vec<i64> = vec![];
-        let mut uints: Vec<u64> = vec![];
-        let mut u256s: Vec<U256> = vec![];
-        let mut i256s: Vec<I256> = vec![];
-        let mut bytes: Vec<Vec<u8>> = vec![];
-        let mut hexes: Vec<String> = vec![];
-        let mut bools: Vec<bool> = vec![];
-        let mut strings: Vec<String> = vec![];
-        // TODO: support array & tuple types
-
-        for token in data {
-            match token {
-                DynSolValue::Address(a) => match column_encoding {
-                    ColumnEncoding::Binary => bytes.push(a.to_vec()),
-                    ColumnEncoding::Hex => hexes.push(format!("{:?}", a)),
-                },
-                DynSolValue::FixedBytes(b, _) => match column_encoding {
-                    ColumnEncoding::Binary => bytes.push(b.to_vec()),
-                    ColumnEncoding::Hex => hexes.push(b.encode_hex()),
-                },
-                DynSolValue::Bytes(b) => match column_encoding {
-                    ColumnEncoding::Binary => bytes.push(b),
-                    ColumnEncoding::Hex => hexes.push(b.encode_hex()),
-                },
-                DynSolValue::Uint(i, size) => {
-                    if size <= 64 {
-                        uints.push(i.wrapping_to::<u64>())
-                    } else {
-                        u256s.push(i)
-                    }
+        make_decoded_series(
+            format!("event__{}", name),
+            data,
+            chunk_len,
+            u256_types,
+            column_encoding,
+        )
+    }
+}
+
+/// convert decoded abi values of named parameters into `{prefix}{name}` series, creating empty
+/// typed series from the abi types when nothing was decoded
+pub(crate) fn make_decoded_columns(
+    prefix: &str,
+    params: Vec<(String, String)>,
+    decoded: indexmap::IndexMap<String, Vec<DynSolValue>>,
+    chunk_len: usize,
+    u256_types: &[U256Type],
+    column_encoding: &ColumnEncoding,
+) -> Result<Vec<Series>, CollectError> {
+    let mut cols = Vec::new();
+    if decoded.is_empty() {
+        for (name, ty) in params.into_iter() {
+            let name = format!("{}{}", prefix, name);
+            let ty = DynSolType::parse(&ty)
+                .map_err(|_| err(format!("invalid type of parameter {}", name).as_str()))?;
+            cols.extend(empty_decoded_series(name, ty, u256_types, column_encoding)?);
+        }
+    } else {
+        for (name, data) in decoded.into_iter() {
+            cols.extend(make_decoded_series(
+                format!("{}{}", prefix, name),
+                data,
+                chunk_len,
+                u256_types,
+                column_encoding,
+            )?);
+        }
+    }
+    Ok(cols)
+}
+
+fn empty_decoded_series(
+    name: String,
+    ty: DynSolType,
+    u256_types: &[U256Type],
+    column_encoding: &ColumnEncoding,
+) -> Result<Vec<Series>, CollectError> {
+    let binary = |name: &str| match column_encoding {
+        ColumnEncoding::Binary => Series::new(name, Vec::<Vec<u8>>::new()),
+        ColumnEncoding::Hex => Series::new(name, Vec::<String>::new()),
+    };
+    let series = match ty {
+        DynSolType::Address | DynSolType::Bytes | DynSolType::FixedBytes(_) => vec![binary(&name)],
+        DynSolType::Int(bits) if bits <= 64 => vec![Series::new(&name, Vec::<i64>::new())],
+        DynSolType::Uint(bits) if bits <= 64 => vec![Series::new(&name, Vec::<u64>::new())],
+        DynSolType::Int(_) => u256_types
+            .iter()
+            .map(|t| Vec::<I256>::new().to_u256_series(name.clone(), t.clone(), column_encoding))
+            .collect::<Result<_, _>>()?,
+        DynSolType::Uint(_) => u256_types
+            .iter()
+            .map(|t| Vec::<U256>::new().to_u256_series(name.clone(), t.clone(), column_encoding))
+            .collect::<Result<_, _>>()?,
+        DynSolType::Bool => vec![Series::new(&name, Vec::<bool>::new())],
+        // arrays, tuples, and functions are stored as json strings, see value_to_json
+        _ => vec![Series::new(&name, Vec::<String>::new())],
+    };
+    Ok(series)
+}
+
+/// convert a decoded abi value into json, used for arrays, tuples, and functions
+///
+/// integers become decimal strings to keep their precision, bytes and addresses become hex
+fn value_to_json(value: &DynSolValue) -> serde_json::Value {
+    match value {
+        DynSolValue::Address(a) => format!("{:?}", a).into(),
+        DynSolValue::FixedBytes(b, size) => alloy::hex::encode_prefixed(&b[..*size]).into(),
+        DynSolValue::Bytes(b) => alloy::hex::encode_prefixed(b).into(),
+        DynSolValue::Uint(i, _) => i.to_string().into(),
+        DynSolValue::Int(i, _) => i.to_string().into(),
+        DynSolValue::Bool(b) => (*b).into(),
+        DynSolValue::String(s) => s.clone().into(),
+        DynSolValue::Function(f) => alloy::hex::encode_prefixed(f).into(),
+        DynSolValue::Array(values) |
+        DynSolValue::FixedArray(values) |
+        DynSolValue::Tuple(values) => values.iter().map(value_to_json).collect::<Vec<_>>().into(),
+    }
+}
+
+/// convert decoded abi values of a single parameter into series named `name`
+/// data should never be mixed type, otherwise this will return inconsistent results
+pub(crate) fn make_decoded_series(
+    name: String,
+    data: Vec<DynSolValue>,
+    chunk_len: usize,
+    u256_types: &[U256Type],
+    column_encoding: &ColumnEncoding,
+) -> Result<Vec<Series>, CollectError> {
+    // This is a smooth brain way of doing this, but I can't think of a better way right now
+    let mut ints: Vec<i64> = vec![];
+    let mut uints: Vec<u64> = vec![];
+    let mut u256s: Vec<U256> = vec![];
+    let mut i256s: Vec<I256> = vec![];
+    let mut bytes: Vec<Vec<u8>> = vec![];
+    let mut hexes: Vec<String> = vec![];
+    let mut bools: Vec<bool> = vec![];
+    let mut strings: Vec<String> = vec![];
+    let mut jsons: Vec<String> = vec![];
+
+    for token in data {
+        match token {
+            DynSolValue::Address(a) => match column_encoding {
+                ColumnEncoding::Binary => bytes.push(a.to_vec()),
+                ColumnEncoding::Hex => hexes.push(format!("{:?}", a)),
+            },
+            DynSolValue::FixedBytes(b, _) => match column_encoding {
+                ColumnEncoding::Binary => bytes.push(b.to_vec()),
+                ColumnEncoding::Hex => hexes.push(b.encode_hex()),
+            },
+            DynSolValue::Bytes(b) => match column_encoding {
+                ColumnEncoding::Binary => bytes.push(b),
+                ColumnEncoding::Hex => hexes.push(b.encode_hex()),
+            },
+            DynSolValue::Uint(i, size) => {
+                if size <= 64 {
+                    uints.push(i.wrapping_to::<u64>())
+                } else {
+                    u256s.push(i)
                 }
-                DynSolValue::Int(i, size) => {
-                    if size <= 64 {
-                        ints.push(i.unchecked_into());
-                    } else {
-                        i256s.push(i);
-                    }
+            }
+            DynSolValue::Int(i, size) => {
+                if size <= 64 {
+                    ints.push(i.unchecked_into());
+                } else {
+                    i256s.push(i);
                 }
-                DynSolValue::Bool(b) => bools.push(b),
-                DynSolValue::String(s) => strings.push(s),
-                DynSolValue::Array(_) | DynSolValue::FixedArray(_) => {}
-                DynSolValue::Tuple(_) => {}
-                DynSolValue::Function(_) => {}
             }
+            DynSolValue::Bool(b) => bools.push(b),
+            DynSolValue::String(s) => strings.push(s),
+            DynSolValue::Array(_) |
+            DynSolValue::FixedArray(_) |
+            DynSolValue::Tuple(_) |
+            DynSolValue::Function(_) => jsons.push(value_to_json(&token).to_string()),
         }
-        let mixed_length_err = format!("could not parse column {}, mixed type", name);
-        let mixed_length_err = mixed_length_err.as_str();
-
-        // check each vector, see if it contains any values, if it does, check if it's the same
-        // length as the input data and map to a series
-        let name = format!("event__{}", name);
-        if !ints.is_empty() {
-            Ok(vec![Series::new(name.as_str(), ints)])
-        } else if !i256s.is_empty() {
-            let mut series_vec = Vec::new();
-            for u256_type in u256_types.iter() {
-                series_vec.push(i256s.to_u256_series(
-                    name.clone(),
-                    u256_type.clone(),
-                    column_encoding,
-                )?)
-            }
-            Ok(series_vec)
-        } else if !u256s.is_empty() {
-            let mut series_vec: Vec<Series> = Vec::new();
-            for u256_type in u256_types.iter() {
-                series_vec.push(u256s.to_u256_series(
-                    name.clone(),
-                    u256_type.clone(),
-                    column_encoding,
-                )?)
-            }
-            Ok(series_vec)
-        } else if !uints.is_empty() {
-            Ok(vec![Series::new(name.as_str(), uints)])
-        } else if !bytes.is_empty() {
-            if bytes.len() != chunk_len {
-                return Err(err(mixed_length_err))
-            }
-            Ok(vec![Series::new(name.as_str(), bytes)])
-        } else if !hexes.is_empty() {
-            if hexes.len() != chunk_len {
-                return Err(err(mixed_length_err))
-            }
-            Ok(vec![Series::new(name.as_str(), hexes)])
-        } else if !bools.is_empty() {
-            if bools.len() != chunk_len {
-                return Err(err(mixed_length_err))
-            }
-            Ok(vec![Series::new(name.as_str(), bools)])
-        } else if !strings.is_empty() {
-            if strings.len() != chunk_len {
-                return Err(err(mixed_length_err))
-            }
-            Ok(vec![Series::new(name.as_str(), strings)])
-        } else {
-            // case where no data was passed
-            Ok(vec![Series::new(name.as_str(), vec![None::<u64>; chunk_len])])
+    }
+    let mixed_length_err = format!("could not parse column {}, mixed type", name);
+    let mixed_length_err = mixed_length_err.as_str();
+
+    // check each vector, see if it contains any values, if it does, check if it's the same
+    // length as the input data and map to a series
+    if !ints.is_empty() {
+        Ok(vec![Series::new(name.as_str(), ints)])
+    } else if !i256s.is_empty() {
+        let mut series_vec = Vec::new();
+        for u256_type in u256_types.iter() {
+            series_vec.push(i256s.to_u256_series(
+                name.clone(),
+                u256_type.clone(),
+                column_encoding,
+            )?)
+        }
+        Ok(series_vec)
+    } else if !u256s.is_empty() {
+        let mut series_vec: Vec<Series> = Vec::new();
+        for u256_type in u256_types.iter() {
+            series_vec.push(u256s.to_u256_series(
+                name.clone(),
+                u256_type.clone(),
+                column_encoding,
+            )?)
+        }
+        Ok(series_vec)
+    } else if !uints.is_empty() {
+        Ok(vec![Series::new(name.as_str(), uints)])
+    } else if !bytes.is_empty() {
+        if bytes.len() != chunk_len {
+            return Err(err(mixed_length_err))
+        }
+        Ok(vec![Series::new(name.as_str(), bytes)])
+    } else if !hexes.is_empty() {
+        if hexes.len() != chunk_len {
+            return Err(err(mixed_length_err))
+        }
+        Ok(vec![Series::new(name.as_str(), hexes)])
+    } else if !bools.is_empty() {
+        if bools.len() != chunk_len {
+            return Err(err(mixed_length_err))
+        }
+        Ok(vec![Series::new(name.as_str(), bools)])
+    } else if !strings.is_empty() {
+        if strings.len() != chunk_len {
+            return Err(err(mixed_length_err))
         }
+        Ok(vec![Series::new(name.as_str(), strings)])
+    } else if !jsons.is_empty() {
+        if jsons.len() != chunk_len {
+            return Err(err(mixed_length_err))
+        }
+        Ok(vec![Series::new(name.as_str(), jsons)])
+    } else {
+        // case where no data was passed
+        Ok(vec![Series::new(name.as_str(), vec![None::<u64>; chunk_len])])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    #[test]
+    fn test_array_and_tuple_series() {
+        let path = DynSolValue::Array(vec![
+            DynSolValue::Address(Address::repeat_byte(1)),
+            DynSolValue::Address(Address::repeat_byte(2)),
+        ]);
+        let pair = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(7), 256),
+            DynSolValue::Bool(true),
+        ]);
+        let series = make_decoded_series(
+            "arg__path".to_string(),
+            vec![path, pair],
+            2,
+            &[U256Type::String],
+            &ColumnEncoding::Hex,
+        )
+        .unwrap();
+        let values: Vec<Option<&str>> = series[0].str().unwrap().into_iter().collect();
+        assert_eq!(values[1], Some(r#"["7",true]"#));
+        assert!(values[0].unwrap().starts_with(r#"["0x0101"#));
+
+        let ty = DynSolType::parse("address[]").unwrap();
+        let empty = empty_decoded_series("input__path".to_string(), ty, &[], &ColumnEncoding::Hex);
+        assert_eq!(empty.unwrap()[0].dtype(), &DataType::String);
     }
 }
@@ -1,3 +1,9 @@
+/// function decoder
+pub mod function_decoder;
 /// log decoder
 pub mod log_decoder;
+/// trace decoder
+pub mod trace_decoder;
+pub use function_decoder::*;
 pub use log_decoder::*;
+pub use trace_decoder::*;
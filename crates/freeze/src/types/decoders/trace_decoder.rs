@@ -0,0 +1,308 @@
+use crate::{CollectError, ColumnEncoding};
+use alloy::rpc::types::trace::geth::{
+    GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType, GethDebugTracingOptions,
+};
+use polars::prelude::*;
+
+/// tracer of custom_traces and the mapping of its json output to columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceDecoder {
+    /// the raw tracer, a built-in tracer name or javascript code
+    pub raw: String,
+    /// tracer passed to debug_traceBlockByNumber
+    pub tracer: GethDebugTracerType,
+    /// config of the tracer
+    pub tracer_config: Option<serde_json::Value>,
+    /// json pointer to an array within each trace whose elements each become a row, if
+    /// None each trace is a single row
+    pub rows: Option<String>,
+    /// output columns, in order
+    pub columns: Vec<TraceColumn>,
+}
+
+/// column extracted from each row of custom_traces
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceColumn {
+    /// column name
+    pub name: String,
+    /// json pointer to the value within the row
+    pub path: String,
+    /// type of the column
+    pub column_type: TraceColumnType,
+}
+
+/// type that json values are converted to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceColumnType {
+    /// utf8 string, non-string values are stored as json
+    String,
+    /// unsigned integer, from json numbers, decimal strings, or hex strings
+    UInt64,
+    /// signed integer, from json numbers or decimal strings
+    Int64,
+    /// float, from json numbers or decimal strings
+    Float64,
+    /// boolean
+    Boolean,
+    /// bytes, from 0x-prefixed hex strings
+    Binary,
+    /// any value, stored as a json string
+    Json,
+}
+
+impl TraceColumnType {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "string" => Ok(TraceColumnType::String),
+            "uint64" => Ok(TraceColumnType::UInt64),
+            "int64" => Ok(TraceColumnType::Int64),
+            "float64" => Ok(TraceColumnType::Float64),
+            "bool" | "boolean" => Ok(TraceColumnType::Boolean),
+            "binary" => Ok(TraceColumnType::Binary),
+            "json" => Ok(TraceColumnType::Json),
+            _ => Err(format!(
+                "invalid trace column type {} (expect string, uint64, int64, float64, bool, \
+                 binary, or json)",
+                name
+            )),
+        }
+    }
+}
+
+impl TraceDecoder {
+    /// create a new TraceDecoder from a tracer and a json schema mapping
+    ///
+    /// tracer is the name of a built-in geth tracer or javascript tracer code, the mapping has
+    /// the form `{"rows": "/calls", "columns": {"to": "/to", "gas": {"path": "/gas", "type":
+    /// "uint64"}}}`, where paths are json pointers and rows is optional
+    pub fn new(
+        tracer: String,
+        tracer_config: Option<serde_json::Value>,
+        mapping: &serde_json::Value,
+    ) -> Result<Self, String> {
+        let tracer_type = match serde_json::from_value::<GethDebugBuiltInTracerType>(
+            serde_json::Value::String(tracer.clone()),
+        ) {
+            Ok(built_in) => GethDebugTracerType::BuiltInTracer(built_in),
+            Err(_) => GethDebugTracerType::JsTracer(tracer.clone()),
+        };
+        let rows = match mapping.get("rows") {
+            Some(serde_json::Value::String(rows)) => Some(rows.clone()),
+            Some(_) => return Err("trace schema rows must be a json pointer string".to_string()),
+            None => None,
+        };
+        let columns = match mapping.get("columns") {
+            Some(serde_json::Value::Object(columns)) => columns,
+            _ => return Err("trace schema requires a columns object".to_string()),
+        };
+        let columns = columns
+            .iter()
+            .map(|(name, column)| parse_trace_column(name, column))
+            .collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            return Err("trace schema requires at least one column".to_string())
+        }
+        Ok(Self { raw: tracer, tracer: tracer_type, tracer_config, rows, columns })
+    }
+
+    /// tracing options of debug_traceBlockByNumber and debug_traceTransaction
+    pub fn tracing_options(&self) -> GethDebugTracingOptions {
+        GethDebugTracingOptions {
+            tracer: Some(self.tracer.clone()),
+            tracer_config: GethDebugTracerConfig(
+                self.tracer_config.clone().unwrap_or(serde_json::Value::Null),
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// split a trace into its rows
+    pub fn rows<'a>(&self, trace: &'a serde_json::Value) -> Vec<&'a serde_json::Value> {
+        match &self.rows {
+            Some(pointer) => match trace.pointer(pointer) {
+                Some(serde_json::Value::Array(rows)) => rows.iter().collect(),
+                _ => vec![],
+            },
+            None => vec![trace],
+        }
+    }
+
+    /// values of each column within a row, null where the path is missing
+    pub fn extract(&self, row: &serde_json::Value) -> Vec<serde_json::Value> {
+        self.columns
+            .iter()
+            .map(|column| row.pointer(&column.path).cloned().unwrap_or(serde_json::Value::Null))
+            .collect()
+    }
+
+    /// convert extracted values into typed series, creating empty series when nothing was
+    /// extracted
+    pub fn make_series(
+        &self,
+        trace_cols: indexmap::IndexMap<String, Vec<serde_json::Value>>,
+        chunk_len: usize,
+        column_encoding: &ColumnEncoding,
+    ) -> Result<Vec<Series>, CollectError> {
+        let mut trace_cols = trace_cols;
+        let mut series = Vec::new();
+        for column in self.columns.iter() {
+            let values = trace_cols.swap_remove(&column.name).unwrap_or_default();
+            if values.len() != chunk_len {
+                return Err(CollectError::CollectError(format!(
+                    "trace column {} has {} values for {} rows",
+                    column.name,
+                    values.len(),
+                    chunk_len
+                )))
+            }
+            series.push(make_trace_series(column, values, column_encoding));
+        }
+        Ok(series)
+    }
+}
+
+fn parse_trace_column(name: &str, column: &serde_json::Value) -> Result<TraceColumn, String> {
+    let (path, column_type) = match column {
+        serde_json::Value::String(path) => (path.clone(), TraceColumnType::String),
+        serde_json::Value::Object(column) => {
+            let path = column
+                .get("path")
+                .and_then(|path| path.as_str())
+                .ok_or(format!("trace column {} requires a path", name))?;
+            let column_type = match column.get("type").and_then(|ty| ty.as_str()) {
+                Some(ty) => TraceColumnType::parse(ty)?,
+                None => TraceColumnType::String,
+            };
+            (path.to_string(), column_type)
+        }
+        _ => return Err(format!("trace column {} must be a path or an object", name)),
+    };
+    Ok(TraceColumn { name: name.to_string(), path, column_type })
+}
+
+fn make_trace_series(
+    column: &TraceColumn,
+    values: Vec<serde_json::Value>,
+    column_encoding: &ColumnEncoding,
+) -> Series {
+    let name = column.name.as_str();
+    let values = values.into_iter().map(|value| if value.is_null() { None } else { Some(value) });
+    match column.column_type {
+        TraceColumnType::String => Series::new(
+            name,
+            values
+                .map(|value| {
+                    value.map(|value| match value {
+                        serde_json::Value::String(s) => s,
+                        value => value.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        ),
+        TraceColumnType::UInt64 => {
+            Series::new(name, values.map(|value| value.and_then(to_u64)).collect::<Vec<_>>())
+        }
+        TraceColumnType::Int64 => Series::new(
+            name,
+            values
+                .map(|value| {
+                    value.and_then(|value| match value {
+                        serde_json::Value::String(s) => s.parse::<i64>().ok(),
+                        value => value.as_i64(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        ),
+        TraceColumnType::Float64 => Series::new(
+            name,
+            values
+                .map(|value| {
+                    value.and_then(|value| match value {
+                        serde_json::Value::String(s) => s.parse::<f64>().ok(),
+                        value => value.as_f64(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        ),
+        TraceColumnType::Boolean => Series::new(
+            name,
+            values.map(|value| value.and_then(|value| value.as_bool())).collect::<Vec<_>>(),
+        ),
+        TraceColumnType::Binary => {
+            let bytes = values.map(|value| {
+                value.and_then(|value| {
+                    value.as_str().and_then(|s| prefix_hex::decode::<Vec<u8>>(s).ok())
+                })
+            });
+            match column_encoding {
+                ColumnEncoding::Binary => Series::new(name, bytes.collect::<Vec<_>>()),
+                ColumnEncoding::Hex => Series::new(
+                    name,
+                    bytes.map(|bytes| bytes.map(prefix_hex::encode)).collect::<Vec<_>>(),
+                ),
+            }
+        }
+        TraceColumnType::Json => Series::new(
+            name,
+            values.map(|value| value.map(|value| value.to_string())).collect::<Vec<_>>(),
+        ),
+    }
+}
+
+fn to_u64(value: serde_json::Value) -> Option<u64> {
+    match value {
+        serde_json::Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse::<u64>().ok(),
+        },
+        value => value.as_u64(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_decoder() {
+        let mapping = serde_json::json!({
+            "rows": "/calls",
+            "columns": {
+                "to": {"path": "/to", "type": "binary"},
+                "gas": {"path": "/gas", "type": "uint64"},
+                "kind": "/type",
+            }
+        });
+        let decoder = TraceDecoder::new("callTracer".to_string(), None, &mapping).unwrap();
+        assert_eq!(
+            decoder.tracer,
+            GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer)
+        );
+        let trace = serde_json::json!({
+            "calls": [{"to": "0x01", "gas": "0x10", "type": "CALL"}, {"type": "STATICCALL"}]
+        });
+        let rows = decoder.rows(&trace);
+        assert_eq!(rows.len(), 2);
+        let values: Vec<_> =
+            decoder.columns.iter().map(|c| c.name.as_str()).zip(decoder.extract(rows[1])).collect();
+        assert!(values.contains(&("to", serde_json::Value::Null)));
+        assert!(values.contains(&("kind", serde_json::json!("STATICCALL"))));
+
+        let mut trace_cols: indexmap::IndexMap<String, Vec<serde_json::Value>> = Default::default();
+        for row in rows {
+            for (column, value) in decoder.columns.iter().zip(decoder.extract(row)) {
+                trace_cols.entry(column.name.clone()).or_default().push(value);
+            }
+        }
+        let series = decoder.make_series(trace_cols, 2, &ColumnEncoding::Hex).unwrap();
+        let df = DataFrame::new(series).unwrap();
+        let gas: Vec<Option<u64>> = df.column("gas").unwrap().u64().unwrap().into_iter().collect();
+        assert_eq!(gas, vec![Some(16), None]);
+        let to: Vec<Option<&str>> = df.column("to").unwrap().str().unwrap().into_iter().collect();
+        assert_eq!(to, vec![Some("0x01"), None]);
+
+        let js = TraceDecoder::new("{result: function() {}}".to_string(), None, &mapping);
+        assert!(matches!(js.unwrap().tracer, GethDebugTracerType::JsTracer(_)));
+        assert!(TraceDecoder::new("callTracer".to_string(), None, &serde_json::json!({})).is_err());
+    }
+}
@@ -85,6 +85,10 @@ pub enum CollectError {
     /// Generic RPC Error
     #[error("RPC call error")]
     RPCError(String),
+
+    /// Collection was cancelled by its cancellation token
+    #[error("Collection cancelled")]
+    Cancelled,
 }
 
 /// Error related to parsing
@@ -1,6 +1,7 @@
 use crate::CollectError;
 use indicatif::ProgressBar;
 use std::{path::PathBuf, sync::Arc, time::SystemTime};
+use tokio_util::sync::CancellationToken;
 
 /// configuration of execution environment
 #[derive(Clone)]
@@ -25,6 +26,8 @@ pub struct ExecutionEnv {
     pub t_end: Option<SystemTime>,
     /// report directory
     pub report_dir: Option<PathBuf>,
+    /// token used to abort in-flight collection
+    pub cancel: Option<CancellationToken>,
 }
 
 impl ExecutionEnv {
@@ -67,6 +70,7 @@ pub struct ExecutionEnvBuilder {
     t_start: SystemTime,
     t_end: Option<SystemTime>,
     report_dir: Option<PathBuf>,
+    cancel: Option<CancellationToken>,
 }
 
 impl Default for ExecutionEnvBuilder {
@@ -82,6 +86,7 @@ impl Default for ExecutionEnvBuilder {
             t_start: SystemTime::now(),
             t_end: None,
             report_dir: None,
+            cancel: None,
         }
     }
 }
@@ -134,6 +139,13 @@ impl ExecutionEnvBuilder {
         self
     }
 
+    /// cancellation token, partitions still in flight when it is cancelled are reported as
+    /// errored while completed partitions are kept
+    pub fn cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
     /// build final output
     pub fn build(self) -> ExecutionEnv {
         ExecutionEnv {
@@ -147,6 +159,7 @@ impl ExecutionEnvBuilder {
             t_start: self.t_start,
             t_end: self.t_end,
             report_dir: self.report_dir,
+            cancel: self.cancel,
         }
     }
 }
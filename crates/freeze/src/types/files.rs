@@ -1,5 +1,8 @@
 use crate::{CollectError, Datatype, MetaDatatype, ParseError, Partition, Query};
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
 
 /// Options for file output
 #[derive(Clone, Debug)]
@@ -14,6 +17,14 @@ pub struct FileOutput {
     pub subdirs: Vec<SubDir>,
     /// Whether to overwrite existing files or skip them
     pub overwrite: bool,
+    /// Whether overwrites write a new numbered version instead of replacing existing files
+    pub version_outputs: bool,
+    /// Directory where files are staged before being moved to their output path
+    pub tmp_dir: Option<PathBuf>,
+    /// Maximum total bytes of files staged in `tmp_dir`
+    pub max_tmp_bytes: Option<u64>,
+    /// Blocks whose rows exceed this many bytes are written to dedicated single-block files
+    pub max_block_bytes: Option<u64>,
     /// File format to use for output files
     pub format: FileFormat,
     /// Number of rows per parquet row group
@@ -22,6 +33,14 @@ pub struct FileOutput {
     pub parquet_statistics: bool,
     /// Parquet compression options
     pub parquet_compression: polars::prelude::ParquetCompression,
+    /// Columns to replace with keyed hashes before writing
+    pub hashed_columns: Vec<String>,
+    /// Key used to hash `hashed_columns`
+    pub hash_key: Option<Vec<u8>>,
+    /// Columns to remove from every dataset before writing
+    pub dropped_columns: Vec<String>,
+    /// Columns whose values are replaced with nulls before writing
+    pub masked_columns: Vec<String>,
 }
 
 /// Possible item to use as subdirectory
@@ -59,52 +78,236 @@ impl FileOutput {
         Ok(paths)
     }
 
-    /// get output file path
+    /// get output file path, creating its directory
     pub fn get_path(
         &self,
         query: &Query,
         partition: &Partition,
         datatype: Datatype,
     ) -> Result<PathBuf, CollectError> {
+        let path = self.output_path(query, partition, datatype)?;
+        if let Some(output_dir) = path.parent() {
+            std::fs::create_dir_all(output_dir)
+                .map_err(|_| ParseError::ParseError("could not create dir".to_string()))?;
+        }
+        Ok(path)
+    }
+
+    /// get output file path without touching the filesystem
+    pub fn output_path(
+        &self,
+        query: &Query,
+        partition: &Partition,
+        datatype: Datatype,
+    ) -> Result<PathBuf, CollectError> {
+        let label = sanitize_path_component(&partition.label(&query.partitioned_by)?);
+        let prefix = sanitize_path_component(&self.prefix);
         let filename = if let Some(suffix) = self.suffix.clone() {
             format!(
                 "{}__{}__{}__{}.{}",
-                self.prefix.clone(),
+                prefix,
                 datatype.name(),
-                suffix,
-                partition.label(&query.partitioned_by)?,
+                sanitize_path_component(&suffix),
+                label,
                 self.format.as_str(),
             )
         } else {
-            format!(
-                "{}__{}__{}.{}",
-                self.prefix.clone(),
-                datatype.name(),
-                partition.label(&query.partitioned_by)?,
-                self.format.as_str(),
-            )
+            format!("{}__{}__{}.{}", prefix, datatype.name(), label, self.format.as_str())
         };
         let filename = std::path::Path::new(&filename).to_path_buf();
         let mut output_dir = std::path::Path::new(&self.output_dir).to_path_buf();
         for subdir in self.subdirs.iter() {
             let subdir_str: String = match subdir {
-                SubDir::Network => self.prefix.clone(),
+                SubDir::Network => prefix.clone(),
                 SubDir::Datatype => match &self.suffix {
-                    Some(suffix) => datatype.name() + "__" + suffix.as_str(),
+                    Some(suffix) => datatype.name() + "__" + &sanitize_path_component(suffix),
                     None => datatype.name(),
                 },
-                SubDir::Custom(subdir_str) => subdir_str.to_string(),
+                SubDir::Custom(subdir_str) => sanitize_path_component(subdir_str),
             };
             output_dir = output_dir.join(std::path::Path::new(&subdir_str));
         }
 
-        std::fs::create_dir_all(output_dir.clone())
-            .map_err(|_| ParseError::ParseError("could not create dir".to_string()))?;
-
         Ok(output_dir.join(filename))
     }
 }
 
+/// names that windows reserves for devices, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// make a user-provided piece of a file or directory name valid on every platform
+///
+/// replaces path separators, characters reserved on windows, and control characters with `_`,
+/// strips trailing dots and spaces, escapes a leading dot with `_` so that the result is neither
+/// hidden nor skipped when reading outputs, and suffixes device names such as `CON` with `_`
+pub fn sanitize_path_component(component: &str) -> String {
+    let mut sanitized: String = component
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    sanitized.truncate(sanitized.trim_end_matches(['.', ' ']).len());
+    if sanitized.starts_with('.') {
+        sanitized.replace_range(..1, "_");
+    }
+    let stem = sanitized.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|name| name.eq_ignore_ascii_case(stem)) {
+        let stem_len = stem.len();
+        sanitized.insert(stem_len, '_');
+    }
+    sanitized
+}
+
+/// split a versioned path `{stem}__v{N}.{ext}` into its unversioned path and version number
+///
+/// unversioned paths are version 1
+pub fn split_version(path: &Path) -> (PathBuf, u32) {
+    let (stem, extension) = match (
+        path.file_stem().and_then(|stem| stem.to_str()),
+        path.extension().and_then(|ext| ext.to_str()),
+    ) {
+        (Some(stem), Some(extension)) => (stem, extension),
+        _ => return (path.to_path_buf(), 1),
+    };
+    if let Some((base, version)) = stem.rsplit_once("__v") {
+        if let Ok(version) = version.parse::<u32>() {
+            if version > 1 {
+                return (path.with_file_name(format!("{}.{}", base, extension)), version)
+            }
+        }
+    }
+    (path.to_path_buf(), 1)
+}
+
+/// path of a given version of an unversioned path
+pub fn versioned_path(path: &Path, version: u32) -> PathBuf {
+    match (path.file_stem().and_then(|s| s.to_str()), path.extension().and_then(|e| e.to_str())) {
+        (Some(stem), Some(extension)) if version > 1 => {
+            path.with_file_name(format!("{}__v{}.{}", stem, version, extension))
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// first version of an unversioned path that does not exist yet
+pub fn next_version_path(path: &Path) -> PathBuf {
+    let mut version = 1;
+    while versioned_path(path, version).exists() {
+        version += 1;
+    }
+    versioned_path(path, version)
+}
+
+/// latest existing version of an unversioned path, the unversioned path if none exists
+pub fn latest_version_path(path: &Path) -> PathBuf {
+    let mut version = 1;
+    while versioned_path(path, version + 1).exists() {
+        version += 1;
+    }
+    versioned_path(path, version)
+}
+
+/// record the latest version of each written file in `{output_dir}/.cryo/versions.json`
+///
+/// the manifest maps each unversioned file path, relative to the output directory, to the
+/// number of its latest version
+pub fn update_version_manifest(output_dir: &Path, paths: &[PathBuf]) -> Result<(), CollectError> {
+    let manifest_path = output_dir.join(".cryo").join("versions.json");
+    let mut manifest: BTreeMap<String, u32> = match std::fs::read_to_string(&manifest_path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|_| CollectError::CollectError("could not parse versions.json".to_string()))?,
+        Err(_) => BTreeMap::new(),
+    };
+    for path in paths.iter().filter(|path| path.exists()) {
+        let (base, version) = split_version(path);
+        let key = base.strip_prefix(output_dir).unwrap_or(&base).to_string_lossy().to_string();
+        let latest = manifest.entry(key).or_insert(version);
+        *latest = std::cmp::max(*latest, version);
+    }
+    if let Some(parent) = manifest_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|_| CollectError::CollectError("could not create .cryo dir".to_string()))?;
+    }
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|_| CollectError::CollectError("could not serialize versions".to_string()))?;
+    std::fs::write(manifest_path, json)
+        .map_err(|_| CollectError::CollectError("could not write versions.json".to_string()))
+}
+
+/// load `{output_dir}/.cryo/splits.json`, which maps each chunk file, relative to the output
+/// directory, to the single-block files holding the oversized blocks moved out of it
+pub fn load_split_manifest(
+    output_dir: &Path,
+) -> Result<BTreeMap<String, Vec<String>>, CollectError> {
+    match std::fs::read_to_string(output_dir.join(".cryo").join("splits.json")) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|_| CollectError::CollectError("could not parse splits.json".to_string())),
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+/// single-block files that were split out of a chunk file according to a split manifest
+pub fn split_paths(
+    output_dir: &Path,
+    manifest: &BTreeMap<String, Vec<String>>,
+    path: &Path,
+) -> Vec<PathBuf> {
+    let key = path.strip_prefix(output_dir).unwrap_or(path).to_string_lossy().to_string();
+    match manifest.get(&key) {
+        Some(splits) => splits.iter().map(|split| output_dir.join(split)).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// record the single-block files split out of each written chunk file in splits.json
+///
+/// files split out of an earlier write of a chunk that were not written again are deleted, so
+/// that a rewritten chunk does not leave stale copies of its blocks behind
+pub fn update_split_manifest(
+    output_dir: &Path,
+    splits: &BTreeMap<PathBuf, Vec<PathBuf>>,
+) -> Result<(), CollectError> {
+    let mut manifest = load_split_manifest(output_dir)?;
+    let relative =
+        |path: &Path| path.strip_prefix(output_dir).unwrap_or(path).to_string_lossy().to_string();
+    let mut changed = false;
+    for (path, split_paths) in splits.iter() {
+        let key = relative(path);
+        let new: Vec<String> = split_paths.iter().map(|split| relative(split)).collect();
+        let old = match new.is_empty() {
+            true => manifest.remove(&key),
+            false => manifest.insert(key, new.clone()),
+        };
+        for stale in old.iter().flatten().filter(|split| !new.contains(split)) {
+            let stale = output_dir.join(stale);
+            if stale.exists() {
+                std::fs::remove_file(stale).map_err(|_| {
+                    CollectError::CollectError("could not remove stale split file".to_string())
+                })?;
+            }
+        }
+        changed |= old.is_some() || !new.is_empty();
+    }
+    if !changed {
+        return Ok(())
+    }
+    let manifest_path = output_dir.join(".cryo").join("splits.json");
+    if let Some(parent) = manifest_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|_| CollectError::CollectError("could not create .cryo dir".to_string()))?;
+    }
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|_| CollectError::CollectError("could not serialize splits".to_string()))?;
+    std::fs::write(manifest_path, json)
+        .map_err(|_| CollectError::CollectError("could not write splits.json".to_string()))
+}
+
 /// File format
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum FileFormat {
@@ -145,3 +348,54 @@ impl ColumnEncoding {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versioned_paths() {
+        let path = PathBuf::from("/data/ethereum__blocks__00000000_to_00000999.parquet");
+        let v3 = versioned_path(&path, 3);
+        assert_eq!(v3, PathBuf::from("/data/ethereum__blocks__00000000_to_00000999__v3.parquet"));
+        assert_eq!(split_version(&v3), (path.clone(), 3));
+        assert_eq!(split_version(&path), (path.clone(), 1));
+        assert_eq!(versioned_path(&path, 1), path);
+    }
+
+    #[test]
+    fn test_sanitize_path_component() {
+        assert_eq!(sanitize_path_component("ethereum"), "ethereum");
+        assert_eq!(sanitize_path_component("run:1/2?"), "run_1_2_");
+        assert_eq!(sanitize_path_component("a\\b\tc"), "a_b_c");
+        assert_eq!(sanitize_path_component("label. "), "label");
+        assert_eq!(sanitize_path_component("con"), "con_");
+        assert_eq!(sanitize_path_component("NUL.txt"), "NUL_.txt");
+        assert_eq!(sanitize_path_component("console"), "console");
+        assert_eq!(sanitize_path_component(".hidden"), "_hidden");
+        assert_eq!(sanitize_path_component("../up"), "_._up");
+    }
+
+    #[test]
+    fn test_split_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        let chunk = dir.join("ethereum__transactions__00001000_to_00001999.parquet");
+        let split_a = dir.join("ethereum__transactions__00001500_to_00001500.parquet");
+        let split_b = dir.join("ethereum__transactions__00001600_to_00001600.parquet");
+        std::fs::write(&split_a, "").unwrap();
+        std::fs::write(&split_b, "").unwrap();
+
+        let splits = [(chunk.clone(), vec![split_a.clone(), split_b.clone()])].into();
+        update_split_manifest(dir, &splits).unwrap();
+        let manifest = load_split_manifest(dir).unwrap();
+        assert_eq!(split_paths(dir, &manifest, &chunk), vec![split_a.clone(), split_b.clone()]);
+
+        // rewriting the chunk removes files of blocks that are no longer split out
+        let splits = [(chunk.clone(), vec![split_a.clone()])].into();
+        update_split_manifest(dir, &splits).unwrap();
+        let manifest = load_split_manifest(dir).unwrap();
+        assert_eq!(split_paths(dir, &manifest, &chunk), vec![split_a.clone()]);
+        assert!(split_a.exists() && !split_b.exists());
+    }
+}
@@ -0,0 +1,315 @@
+/// recorded rpc fixtures and a mock transport that replays them
+///
+/// allows datasets to be tested deterministically without a live node, e.g.
+/// `RpcFixtures::load(path)?.into_source(1)` gives a Source that answers every recorded
+/// request. fixtures can be captured from a live node using `RecordingTransport`
+use crate::{CollectError, Source, SourceLabels};
+use alloy::{
+    providers::ProviderBuilder,
+    rpc::{
+        client::RpcClient,
+        json_rpc::{
+            ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload,
+            SerializedRequest,
+        },
+    },
+    transports::{BoxTransport, TransportError, TransportFut},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{value::RawValue, Value};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+/// a single recorded rpc call
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcFixture {
+    /// rpc method
+    pub method: String,
+    /// rpc params
+    pub params: Value,
+    /// result returned by the node
+    pub result: Value,
+}
+
+/// collection of recorded rpc calls, keyed by method and params
+#[derive(Clone, Debug, Default)]
+pub struct RpcFixtures {
+    calls: HashMap<String, RpcFixture>,
+}
+
+impl RpcFixtures {
+    /// load fixtures from a json file containing a list of recorded calls
+    pub fn load(path: &Path) -> Result<RpcFixtures, CollectError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| CollectError::CollectError("could not read fixture file".to_string()))?;
+        let fixtures: Vec<RpcFixture> = serde_json::from_str(&contents)
+            .map_err(|_| CollectError::CollectError("could not parse fixture file".to_string()))?;
+        let mut output = RpcFixtures::default();
+        for fixture in fixtures.into_iter() {
+            output.insert(fixture.method, fixture.params, fixture.result);
+        }
+        Ok(output)
+    }
+
+    /// save fixtures to a json file, sorted so that recordings of the same range are identical
+    pub fn save(&self, path: &Path) -> Result<(), CollectError> {
+        let mut keys: Vec<&String> = self.calls.keys().collect();
+        keys.sort();
+        let fixtures: Vec<&RpcFixture> = keys.into_iter().map(|key| &self.calls[key]).collect();
+        let json = serde_json::to_string_pretty(&fixtures)
+            .map_err(|_| CollectError::CollectError("could not serialize fixtures".to_string()))?;
+        std::fs::write(path, json)
+            .map_err(|_| CollectError::CollectError("could not write fixture file".to_string()))
+    }
+
+    /// add a recorded call
+    pub fn insert(&mut self, method: String, params: Value, result: Value) {
+        let key = fixture_key(&method, &params);
+        self.calls.insert(key, RpcFixture { method, params, result });
+    }
+
+    /// get the recorded result of a call
+    pub fn get(&self, method: &str, params: &Value) -> Option<&Value> {
+        self.calls.get(&fixture_key(method, params)).map(|fixture| &fixture.result)
+    }
+
+    /// number of recorded calls
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// whether there are no recorded calls
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// create a Source whose provider replays these fixtures
+    pub fn into_source(self, chain_id: u64) -> Source {
+        MockTransport::new(self).into_source(chain_id)
+    }
+}
+
+/// hex strings are compared case-insensitively, since addresses may be checksummed
+fn fixture_key(method: &str, params: &Value) -> String {
+    format!("{} {}", method, params).to_lowercase()
+}
+
+/// transport answering requests from recorded fixtures instead of a node
+///
+/// clones share the log of requested methods, so that tests can count the requests a Source made
+#[derive(Clone, Debug)]
+pub struct MockTransport {
+    fixtures: Arc<RpcFixtures>,
+    requested: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockTransport {
+    /// create transport replaying fixtures
+    pub fn new(fixtures: RpcFixtures) -> MockTransport {
+        MockTransport { fixtures: Arc::new(fixtures), requested: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// create a Source whose provider uses this transport
+    pub fn into_source(self, chain_id: u64) -> Source {
+        let client = RpcClient::new(self, true).boxed();
+        Source {
+            provider: ProviderBuilder::default().on_client(client),
+            chain_id,
+            inner_request_size: 1,
+            max_concurrent_chunks: Some(1),
+            rpc_url: "mock".to_string(),
+            semaphore: Arc::new(None),
+            rate_limiter: Arc::new(None),
+            shared_rate_limiter: None,
+            request_jitter: None,
+            throttled_until: Arc::new(Mutex::new(None)),
+            block_receipts_support: Arc::new(Mutex::new(None)),
+            labels: SourceLabels::default(),
+        }
+    }
+
+    /// methods of the requests answered so far, in request order
+    pub fn requested_methods(&self) -> Vec<String> {
+        self.requested.lock().map(|requested| requested.clone()).unwrap_or_default()
+    }
+
+    fn respond(&self, request: &SerializedRequest) -> Result<Response, TransportError> {
+        let params = parse_params(request)?;
+        if let Ok(mut requested) = self.requested.lock() {
+            requested.push(request.method().to_string());
+        }
+        let payload = match self.fixtures.get(request.method(), &params) {
+            Some(result) => {
+                let result =
+                    RawValue::from_string(result.to_string()).map_err(TransportError::ser_err)?;
+                ResponsePayload::Success(result)
+            }
+            None => ResponsePayload::Failure(ErrorPayload {
+                code: -32601,
+                message: format!("no fixture for {} {}", request.method(), params).into(),
+                data: None,
+            }),
+        };
+        Ok(Response { id: request.id().clone(), payload })
+    }
+}
+
+impl tower::Service<RequestPacket> for MockTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let transport = self.clone();
+        Box::pin(async move {
+            match request {
+                RequestPacket::Single(request) => {
+                    transport.respond(&request).map(ResponsePacket::Single)
+                }
+                RequestPacket::Batch(requests) => requests
+                    .iter()
+                    .map(|request| transport.respond(request))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(ResponsePacket::Batch),
+            }
+        })
+    }
+}
+
+/// transport forwarding requests to a node and recording every successful response
+///
+/// recorded fixtures are written to the output path once the last clone of the transport is
+/// dropped, so that partial recordings are kept even if collection fails
+#[derive(Clone)]
+pub struct RecordingTransport {
+    inner: BoxTransport,
+    recorder: Arc<FixtureRecorder>,
+}
+
+struct FixtureRecorder {
+    path: PathBuf,
+    fixtures: Mutex<RpcFixtures>,
+}
+
+impl Drop for FixtureRecorder {
+    fn drop(&mut self) {
+        if let Ok(fixtures) = self.fixtures.lock() {
+            if let Err(e) = fixtures.save(&self.path) {
+                eprintln!("could not save fixtures to {}: {}", self.path.display(), e)
+            }
+        }
+    }
+}
+
+impl RecordingTransport {
+    /// wrap a transport, recording its responses into a fixture file at path
+    pub fn new(inner: BoxTransport, path: PathBuf) -> RecordingTransport {
+        let recorder = FixtureRecorder { path, fixtures: Mutex::new(RpcFixtures::default()) };
+        RecordingTransport { inner, recorder: Arc::new(recorder) }
+    }
+}
+
+fn parse_params(request: &SerializedRequest) -> Result<Value, TransportError> {
+    match request.params() {
+        Some(params) => serde_json::from_str(params.get()).map_err(TransportError::ser_err),
+        None => Ok(Value::Null),
+    }
+}
+
+impl tower::Service<RequestPacket> for RecordingTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let requests: Vec<&SerializedRequest> = match &request {
+            RequestPacket::Single(request) => vec![request],
+            RequestPacket::Batch(requests) => requests.iter().collect(),
+        };
+        let mut calls = HashMap::new();
+        for request in requests.into_iter() {
+            match parse_params(request) {
+                Ok(params) => {
+                    calls.insert(request.id().clone(), (request.method().to_string(), params));
+                }
+                Err(e) => return Box::pin(async move { Err(e) }),
+            }
+        }
+
+        let future = self.inner.call(request);
+        let recorder = self.recorder.clone();
+        Box::pin(async move {
+            let response = future.await?;
+            let responses: Vec<&Response> = match &response {
+                ResponsePacket::Single(response) => vec![response],
+                ResponsePacket::Batch(responses) => responses.iter().collect(),
+            };
+            for response in responses.into_iter() {
+                if let (ResponsePayload::Success(result), Some((method, params))) =
+                    (&response.payload, calls.remove(&response.id))
+                {
+                    let result: Value =
+                        serde_json::from_str(result.get()).map_err(TransportError::ser_err)?;
+                    if let Ok(mut fixtures) = recorder.fixtures.lock() {
+                        fixtures.insert(method, params, result);
+                    }
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{Address, U256},
+        providers::Provider,
+    };
+
+    #[tokio::test]
+    async fn test_mock_source_replays_fixture() {
+        let mut fixtures = RpcFixtures::default();
+        let address = "0x0000000000000000000000000000000000000001";
+        fixtures.insert(
+            "eth_getBalance".to_string(),
+            serde_json::json!([address, "0x1"]),
+            serde_json::json!("0x10"),
+        );
+        let source = fixtures.into_source(1);
+        let address: Address = address.parse().unwrap();
+        let balance = source.get_balance(address, 1).await.unwrap();
+        assert_eq!(balance, U256::from(16));
+        assert!(source.get_balance(address, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recording_transport_saves_fixtures() {
+        let mut fixtures = RpcFixtures::default();
+        fixtures.insert("eth_chainId".to_string(), Value::Null, serde_json::json!("0x1"));
+        let inner = BoxTransport::new(MockTransport::new(fixtures));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixtures.json");
+        let transport = RecordingTransport::new(inner, path.clone());
+        let client = RpcClient::new(transport, true).boxed();
+        let provider = ProviderBuilder::default().on_client(client);
+        assert_eq!(provider.get_chain_id().await.unwrap(), 1);
+        drop(provider);
+
+        let recorded = RpcFixtures::load(&path).unwrap();
+        assert_eq!(recorded.get("eth_chainId", &Value::Null), Some(&serde_json::json!("0x1")));
+    }
+}
@@ -0,0 +1,131 @@
+/// network upgrades that introduce columns, and the columns they introduce
+///
+/// columns introduced by a fork are null in blocks before the fork activates, so nulls in
+/// these columns before activation are expected rather than a sign of missing provider data.
+/// some columns are also null after activation for transaction types that predate the fork.
+///
+/// activation blocks are only known for ethereum mainnet (chain 1). on other chains, nulls in
+/// fork columns are only expected when explained by the transaction type, every other null is
+/// reported as unexpected
+use crate::Datatype;
+
+/// network upgrade that changes which columns of a dataset are populated
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Fork {
+    /// receipts contain a status code instead of a post-state root (eip-658)
+    Byzantium,
+    /// blocks contain a base fee and type 2 transactions contain fee caps (eip-1559)
+    London,
+    /// blocks contain a withdrawals root (eip-4895)
+    Shanghai,
+    /// blocks and receipts contain blob gas fields (eip-4844)
+    Cancun,
+}
+
+impl Fork {
+    /// first block of fork on chain, None if the activation block of chain is not known
+    pub fn activation_block(&self, chain_id: u64) -> Option<u64> {
+        match (chain_id, self) {
+            (1, Fork::Byzantium) => Some(4_370_000),
+            (1, Fork::London) => Some(12_965_000),
+            (1, Fork::Shanghai) => Some(17_034_870),
+            (1, Fork::Cancun) => Some(19_426_587),
+            _ => None,
+        }
+    }
+
+    /// whether fork is active at block on chain, None if the activation block is not known
+    pub fn is_active(&self, chain_id: u64, block_number: u64) -> Option<bool> {
+        self.activation_block(chain_id).map(|activation| block_number >= activation)
+    }
+}
+
+/// columns of datatype that are null in blocks before the given fork, and for transactions of
+/// the types listed by typed_columns
+pub fn fork_columns(datatype: &Datatype) -> Vec<(&'static str, Fork)> {
+    match datatype {
+        Datatype::Blocks => {
+            vec![("base_fee_per_gas", Fork::London), ("withdrawals_root", Fork::Shanghai)]
+        }
+        Datatype::Transactions => vec![
+            ("success", Fork::Byzantium),
+            ("max_fee_per_gas", Fork::London),
+            ("max_priority_fee_per_gas", Fork::London),
+        ],
+        Datatype::Receipts => vec![
+            ("success", Fork::Byzantium),
+            ("blob_gas_used", Fork::Cancun),
+            ("blob_gas_price", Fork::Cancun),
+        ],
+        _ => vec![],
+    }
+}
+
+/// columns of datatype that are only populated for transactions of at least the given eip-2718
+/// type, e.g. legacy and eip-2930 transactions have no fee caps after london either
+pub fn typed_columns(datatype: &Datatype) -> Vec<(&'static str, u32)> {
+    match datatype {
+        Datatype::Transactions => vec![("max_fee_per_gas", 2), ("max_priority_fee_per_gas", 2)],
+        _ => vec![],
+    }
+}
+
+/// whether a null value in column of datatype at block is explained by fork activation or by the
+/// type of the transaction of the row, if the dataset has a transaction_type column
+pub fn is_expected_null(
+    datatype: &Datatype,
+    column: &str,
+    chain_id: u64,
+    block_number: u64,
+    transaction_type: Option<u32>,
+) -> bool {
+    let before_fork = fork_columns(datatype)
+        .into_iter()
+        .filter(|(name, _)| *name == column)
+        .any(|(_, fork)| fork.is_active(chain_id, block_number) == Some(false));
+    let untyped = transaction_type.is_some_and(|transaction_type| {
+        typed_columns(datatype)
+            .into_iter()
+            .any(|(name, min_type)| name == column && transaction_type < min_type)
+    });
+    before_fork || untyped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_expected_null() {
+        assert!(is_expected_null(&Datatype::Blocks, "base_fee_per_gas", 1, 12_964_999, None));
+        assert!(!is_expected_null(&Datatype::Blocks, "base_fee_per_gas", 1, 12_965_000, None));
+        assert!(!is_expected_null(&Datatype::Blocks, "base_fee_per_gas", 10, 0, None));
+        assert!(!is_expected_null(&Datatype::Blocks, "gas_used", 1, 0, None));
+        assert!(is_expected_null(&Datatype::Transactions, "success", 1, 4_369_999, Some(0)));
+        assert!(!is_expected_null(&Datatype::Transactions, "success", 1, 4_370_000, Some(0)));
+    }
+
+    #[test]
+    fn test_fee_caps_expected_null_by_transaction_type() {
+        let datatype = Datatype::Transactions;
+        for transaction_type in [0, 1] {
+            assert!(is_expected_null(
+                &datatype,
+                "max_fee_per_gas",
+                1,
+                20_000_000,
+                Some(transaction_type)
+            ));
+            assert!(is_expected_null(
+                &datatype,
+                "max_priority_fee_per_gas",
+                10,
+                0,
+                Some(transaction_type)
+            ));
+        }
+        assert!(!is_expected_null(&datatype, "max_fee_per_gas", 1, 20_000_000, Some(2)));
+        assert!(!is_expected_null(&datatype, "max_fee_per_gas", 1, 20_000_000, None));
+        assert!(is_expected_null(&datatype, "max_fee_per_gas", 1, 12_964_999, Some(2)));
+    }
+}
@@ -28,7 +28,7 @@ pub mod execution;
 
 /// report generation
 pub mod reports;
-pub use reports::CRYO_VERSION;
+pub use reports::{redact_rpc_url, CRYO_VERSION};
 
 /// type specifications for dataframes
 #[macro_use]
@@ -38,28 +38,52 @@ pub mod dataframes;
 #[allow(missing_docs)]
 pub mod signatures;
 
+/// chain families with extra columns
+pub mod chains;
+/// expectations on rows collected per block
+pub mod checks;
 /// error specifications
 pub mod errors;
 /// type specifications for output data formats
 pub mod files;
+/// recorded rpc fixtures and mock transport
+pub mod fixtures;
+/// network upgrades that change which columns are populated
+pub mod forks;
+/// data quality statistics of collected datasets
+pub mod quality;
 /// queries
 pub mod queries;
+/// requests-per-second budgets shared between processes
+pub mod rate_limits;
+/// export of data schemas
+pub mod schema_export;
 /// type specifications for data schemas
 pub mod schemas;
 /// types related to summaries
 pub mod summaries;
 
+pub use chains::{chain_columns, ChainProfile};
+pub use checks::RowCountCheck;
 pub use chunks::{
     AddressChunk, BlockChunk, CallDataChunk, Chunk, ChunkData, ChunkStats, SlotChunk, Subchunk,
     TopicChunk, TransactionChunk,
 };
-pub use conversions::{bytes_to_u32, ToVecHex, ToVecU8};
+pub use conversions::{bytes_to_u32, global_index, percentile, ToVecHex, ToVecU8};
 pub use dataframes::*;
 pub use datatypes::*;
 pub use files::{ColumnEncoding, FileFormat, FileOutput, SubDir};
+pub use fixtures::{MockTransport, RecordingTransport, RpcFixture, RpcFixtures};
+pub use forks::{fork_columns, is_expected_null, typed_columns, Fork};
+pub use quality::DatasetQuality;
 pub use queries::{Query, QueryLabels, TimeDimension};
-pub use schemas::{ColumnType, SchemaFunctions, Schemas, Table, U256Type};
-pub use sources::{Fetcher, RateLimiter, Source, SourceLabels};
+pub use rate_limits::SharedRateLimiter;
+pub use schema_export::SchemaFormat;
+pub use schemas::{
+    ColumnType, GasNullPolicy, SchemaFunctions, Schemas, Table, U256Type, CATEGORICAL_COLUMNS,
+};
+pub(crate) use sources::is_execution_error;
+pub use sources::{Fetcher, RateLimiter, Source, SourceLabels, ThrottleLayer};
 // pub(crate) use summaries::FreezeSummaryAgg;
 // pub use summaries::{FreezeChunkSummary, FreezeSummary};
 pub use summaries::{print_all_datasets, print_dataset_info, FreezeSummary};
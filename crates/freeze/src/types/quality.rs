@@ -0,0 +1,270 @@
+/// data quality statistics of collected datasets
+///
+/// statistics are computed on every collected chunk before it is written and merged across
+/// chunks, so that null spikes caused by a degraded rpc provider show up in the run report.
+/// nulls in columns that did not exist before a fork are counted separately as expected nulls
+use crate::{forks, CollectError, Datatype};
+use polars::prelude::*;
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+};
+
+/// number of bits of a value hash that select a register of a distinct count sketch
+const SKETCH_PRECISION: u32 = 10;
+
+/// number of registers of a distinct count sketch
+const SKETCH_REGISTERS: usize = 1 << SKETCH_PRECISION;
+
+/// data quality statistics of a dataset
+#[derive(Clone, Debug, Default)]
+pub struct DatasetQuality {
+    /// rows collected
+    pub n_rows: u64,
+    /// lowest block number collected
+    pub min_block: Option<u64>,
+    /// highest block number collected
+    pub max_block: Option<u64>,
+    /// statistics of each column
+    pub columns: BTreeMap<String, ColumnQuality>,
+}
+
+/// data quality statistics of a column
+#[derive(Clone, Debug)]
+pub struct ColumnQuality {
+    /// null values collected
+    pub n_nulls: u64,
+    /// null values in blocks before the fork that introduced the column
+    pub n_expected_nulls: u64,
+    /// hyperloglog registers estimating the number of distinct non-null values
+    registers: Vec<u8>,
+}
+
+impl Default for ColumnQuality {
+    fn default() -> ColumnQuality {
+        ColumnQuality { n_nulls: 0, n_expected_nulls: 0, registers: vec![0; SKETCH_REGISTERS] }
+    }
+}
+
+impl DatasetQuality {
+    /// compute statistics of a dataframe collected for datatype on chain
+    pub fn from_df(
+        df: &DataFrame,
+        datatype: &Datatype,
+        chain_id: u64,
+    ) -> Result<DatasetQuality, CollectError> {
+        let mut quality = DatasetQuality { n_rows: df.height() as u64, ..Default::default() };
+        let blocks = match df.column("block_number") {
+            Ok(column) => Some(column.cast(&DataType::UInt64)?),
+            Err(_) => None,
+        };
+        if let Some(blocks) = &blocks {
+            quality.min_block = blocks.u64()?.min();
+            quality.max_block = blocks.u64()?.max();
+        }
+        let transaction_types = match df.column("transaction_type") {
+            Ok(column) => Some(column.cast(&DataType::UInt32)?),
+            Err(_) => None,
+        };
+        let fork_columns = forks::fork_columns(datatype);
+        for column in df.get_columns() {
+            let mut column_quality =
+                ColumnQuality { n_nulls: column.null_count() as u64, ..Default::default() };
+            let is_fork_column = fork_columns.iter().any(|(name, _)| *name == column.name());
+            let has_fork_nulls = is_fork_column && column.null_count() > 0;
+            if let Some(blocks) = blocks.as_ref().filter(|_| has_fork_nulls) {
+                let nulls = column.is_null();
+                let transaction_types: Vec<Option<u32>> = match &transaction_types {
+                    Some(transaction_types) => transaction_types.u32()?.into_iter().collect(),
+                    None => vec![None; df.height()],
+                };
+                column_quality.n_expected_nulls = blocks
+                    .u64()?
+                    .into_iter()
+                    .zip(&nulls)
+                    .zip(transaction_types)
+                    .filter(|((block, is_null), transaction_type)| {
+                        *is_null == Some(true) &&
+                            block.is_some_and(|block| {
+                                forks::is_expected_null(
+                                    datatype,
+                                    column.name(),
+                                    chain_id,
+                                    block,
+                                    *transaction_type,
+                                )
+                            })
+                    })
+                    .count() as u64;
+            }
+            for hash in hash_values(column)? {
+                column_quality.insert(hash);
+            }
+            quality.columns.insert(column.name().to_string(), column_quality);
+        }
+        Ok(quality)
+    }
+
+    /// combine with the statistics of another chunk of the same dataset
+    pub fn merge(&mut self, other: DatasetQuality) {
+        self.n_rows += other.n_rows;
+        self.min_block = match (self.min_block, other.min_block) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max_block = match (self.max_block, other.max_block) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        for (name, column) in other.columns.into_iter() {
+            self.columns.entry(name).or_default().merge(column);
+        }
+    }
+
+    /// summarize statistics as json, with null rates and distinct count estimates per column
+    pub fn to_json(&self) -> serde_json::Value {
+        let columns: serde_json::Map<String, serde_json::Value> = self
+            .columns
+            .iter()
+            .map(|(name, column)| {
+                let null_rate = match self.n_rows {
+                    0 => 0.0,
+                    n_rows => column.n_nulls as f64 / n_rows as f64,
+                };
+                let value = serde_json::json!({
+                    "n_nulls": column.n_nulls,
+                    "n_expected_nulls": column.n_expected_nulls,
+                    "null_rate": null_rate,
+                    "n_distinct_estimate": column.n_distinct_estimate(),
+                });
+                (name.clone(), value)
+            })
+            .collect();
+        serde_json::json!({
+            "n_rows": self.n_rows,
+            "min_block": self.min_block,
+            "max_block": self.max_block,
+            "columns": columns,
+        })
+    }
+}
+
+impl ColumnQuality {
+    fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - SKETCH_PRECISION)) as usize;
+        let rank = ((hash << SKETCH_PRECISION).leading_zeros() + 1).min(64 - SKETCH_PRECISION + 1);
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    fn merge(&mut self, other: ColumnQuality) {
+        self.n_nulls += other.n_nulls;
+        self.n_expected_nulls += other.n_expected_nulls;
+        for (register, other_register) in self.registers.iter_mut().zip(other.registers) {
+            *register = (*register).max(other_register);
+        }
+    }
+
+    /// estimated number of distinct non-null values, within a few percent
+    pub fn n_distinct_estimate(&self) -> u64 {
+        let m = SKETCH_REGISTERS as f64;
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = 0.7213 / (1.0 + 1.079 / m) * m * m / sum;
+        let n_empty = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && n_empty > 0 {
+            // linear counting is more accurate for small cardinalities
+            (m * (m / n_empty as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+/// hash the non-null values of a column
+fn hash_values(column: &Series) -> Result<Vec<u64>, CollectError> {
+    fn hash<T: Hash>(value: T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+    let hashes = match column.dtype() {
+        DataType::Binary => column.binary()?.into_iter().flatten().map(hash).collect(),
+        DataType::String => column.str()?.into_iter().flatten().map(hash).collect(),
+        DataType::Boolean => column.bool()?.into_iter().flatten().map(hash).collect(),
+        DataType::Float32 | DataType::Float64 => column
+            .cast(&DataType::Float64)?
+            .f64()?
+            .into_iter()
+            .flatten()
+            .map(|value| hash(value.to_bits()))
+            .collect(),
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
+            column.cast(&DataType::UInt64)?.u64()?.into_iter().flatten().map(hash).collect()
+        }
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
+            column.cast(&DataType::Int64)?.i64()?.into_iter().flatten().map(hash).collect()
+        }
+        _ => column.cast(&DataType::String)?.str()?.into_iter().flatten().map(hash).collect(),
+    };
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_quality() {
+        let df = df!(
+            "block_number" => [10u32, 11, 12, 12],
+            "value" => [Some(1u64), None, Some(1), None],
+        )
+        .unwrap();
+        let mut quality = DatasetQuality::from_df(&df, &Datatype::Logs, 1).unwrap();
+        assert_eq!((quality.min_block, quality.max_block), (Some(10), Some(12)));
+        assert_eq!(quality.columns["value"].n_nulls, 2);
+        assert_eq!(quality.columns["value"].n_distinct_estimate(), 1);
+        assert_eq!(quality.columns["block_number"].n_distinct_estimate(), 3);
+
+        let df = df!("block_number" => [20u32], "value" => [Some(2u64)]).unwrap();
+        quality.merge(DatasetQuality::from_df(&df, &Datatype::Logs, 1).unwrap());
+        assert_eq!((quality.n_rows, quality.max_block), (5, Some(20)));
+        assert_eq!(quality.columns["value"].n_distinct_estimate(), 2);
+    }
+
+    #[test]
+    fn test_expected_nulls() {
+        let df = df!(
+            "block_number" => [12_964_999u32, 12_965_000, 12_965_001],
+            "base_fee_per_gas" => [None, Some(7u64), None],
+        )
+        .unwrap();
+        let quality = DatasetQuality::from_df(&df, &Datatype::Blocks, 1).unwrap();
+        assert_eq!(quality.columns["base_fee_per_gas"].n_nulls, 2);
+        assert_eq!(quality.columns["base_fee_per_gas"].n_expected_nulls, 1);
+    }
+
+    #[test]
+    fn test_expected_nulls_of_legacy_transactions() {
+        let df = df!(
+            "block_number" => [20_000_000u32, 20_000_000, 20_000_001],
+            "transaction_type" => [0u32, 2, 2],
+            "max_fee_per_gas" => [None, Some(7u64), None],
+        )
+        .unwrap();
+        let quality = DatasetQuality::from_df(&df, &Datatype::Transactions, 1).unwrap();
+        assert_eq!(quality.columns["max_fee_per_gas"].n_nulls, 2);
+        assert_eq!(quality.columns["max_fee_per_gas"].n_expected_nulls, 1);
+    }
+
+    #[test]
+    fn test_distinct_count_estimate() {
+        let mut column = ColumnQuality::default();
+        for value in 0..100_000u64 {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            column.insert(hasher.finish());
+        }
+        let estimate = column.n_distinct_estimate() as f64;
+        assert!((estimate - 100_000.0).abs() < 10_000.0);
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{CollectError, Datatype, Dim, MetaDatatype, Partition, Table};
-use std::collections::{HashMap, HashSet};
+use crate::{
+    BlockFlush, CollectError, Datatype, Dim, MetaDatatype, Partition, RowCountCheck, Table,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 /// Query
 #[derive(Clone)]
@@ -18,6 +23,30 @@ pub struct Query {
     pub exclude_failed: bool,
     /// Javascript tracer
     pub js_tracer: Option<String>,
+    /// Genesis spec file
+    pub genesis_file: Option<String>,
+    /// Beacon node api url
+    pub beacon_url: Option<String>,
+    /// Whether trace_calls uses debug_traceCall instead of trace_call
+    pub debug_trace_calls: bool,
+    /// Whether address_appearances includes the addresses of state diffs
+    pub state_diff_appearances: bool,
+    /// Validator indices or pubkeys of validator_balances, required by validator_balances
+    pub validators: Option<Vec<String>>,
+    /// Percentiles in [0, 100] computed by gas_percentiles
+    pub gas_percentiles: Vec<f64>,
+    /// Expected rows per block, evaluated on each chunk
+    pub row_count_checks: Vec<RowCountCheck>,
+    /// Whether chunks violating row count checks fail instead of being reported
+    pub strict_checks: bool,
+    /// Calls per Multicall3 call when batching the contract calls of datasets that support it
+    pub multicall: Option<usize>,
+    /// Seconds of pending transactions written to each mempool file
+    pub mempool_window: u64,
+    /// Seconds after which mempool streaming stops, streams until cancelled if None
+    pub mempool_duration: Option<u64>,
+    /// Writes oversized blocks to their own files while collecting, set by freeze per partition
+    pub block_flush: Option<Arc<BlockFlush>>,
     /// Labels (these are non-functional)
     pub labels: QueryLabels,
 }
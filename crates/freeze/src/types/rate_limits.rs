@@ -0,0 +1,123 @@
+/// requests-per-second budgets shared between processes
+///
+/// every cryo process using the same rpc url and state directory reserves request slots from a
+/// small state file, so that concurrent processes on one machine together stay within a single
+/// requests-per-second budget
+use crate::CollectError;
+use alloy::primitives::keccak256;
+use std::{
+    fs::{OpenOptions, TryLockError},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// first delay between attempts to lock the state file, doubled after each failed attempt
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+
+/// longest delay between attempts to lock the state file
+const MAX_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// rate limiter coordinated through a state file guarded by an os file lock
+///
+/// the lock is released by the os when its holder exits, so a process dying while holding it
+/// cannot block the other processes
+#[derive(Clone, Debug)]
+pub struct SharedRateLimiter {
+    state_path: PathBuf,
+    interval: Duration,
+}
+
+impl SharedRateLimiter {
+    /// create limiter whose budget is shared by all processes using `rpc_url` and `state_dir`
+    pub fn new(
+        state_dir: &Path,
+        rpc_url: &str,
+        requests_per_second: u32,
+    ) -> Result<SharedRateLimiter, CollectError> {
+        if requests_per_second == 0 {
+            return Err(CollectError::CollectError(
+                "requests per second must be positive".to_string(),
+            ))
+        }
+        std::fs::create_dir_all(state_dir).map_err(|_| {
+            CollectError::CollectError("could not create rate limit state dir".to_string())
+        })?;
+        let name = prefix_hex::encode(keccak256(rpc_url.as_bytes())[..8].to_vec());
+        Ok(SharedRateLimiter {
+            state_path: state_dir.join(format!("{}.state", name)),
+            interval: Duration::from_secs(1) / requests_per_second,
+        })
+    }
+
+    /// wait until this process may send its next request
+    pub async fn until_ready(&self) {
+        let mut retry_interval = LOCK_RETRY_INTERVAL;
+        let slot = loop {
+            let limiter = self.clone();
+            match tokio::task::spawn_blocking(move || limiter.reserve_slot()).await {
+                Ok(Ok(Some(slot))) => break slot,
+                Ok(Ok(None)) => {
+                    tokio::time::sleep(retry_interval).await;
+                    retry_interval = std::cmp::min(retry_interval * 2, MAX_LOCK_RETRY_INTERVAL);
+                }
+                // coordination is best effort, do not block requests on filesystem errors
+                Ok(Err(_)) | Err(_) => return,
+            }
+        };
+        if let Ok(wait) = slot.duration_since(SystemTime::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// reserve the next free request slot, or None if another process holds the lock
+    fn reserve_slot(&self) -> Result<Option<SystemTime>, std::io::Error> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.state_path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => return Err(e),
+        };
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let now = SystemTime::now();
+        let next_free = contents
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(|nanos| UNIX_EPOCH + Duration::from_nanos(nanos))
+            .unwrap_or(now);
+        let slot = std::cmp::max(now, next_free);
+        let next_free = (slot + self.interval).duration_since(UNIX_EPOCH).unwrap_or_default();
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all((next_free.as_nanos() as u64).to_string().as_bytes())?;
+        file.unlock()?;
+        Ok(Some(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shared_rate_limiter_spaces_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = SharedRateLimiter::new(dir.path(), "http://localhost:8545", 20).unwrap();
+        let second = SharedRateLimiter::new(dir.path(), "http://localhost:8545", 20).unwrap();
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            first.until_ready().await;
+            second.until_ready().await;
+        }
+        // 6 requests at 20 per second need at least 5 intervals of 50ms
+        assert!(start.elapsed() >= Duration::from_millis(250));
+    }
+}
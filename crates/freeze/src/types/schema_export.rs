@@ -0,0 +1,337 @@
+/// export of table schemas as sql ddl, arrow schemas, or json schema
+use crate::{ColumnEncoding, ColumnType, ParseError, Table, U256Type};
+use serde_json::{json, Value};
+
+/// format of an exported schema
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SchemaFormat {
+    /// postgres CREATE TABLE statement
+    Postgres,
+    /// clickhouse CREATE TABLE statement
+    ClickHouse,
+    /// duckdb CREATE TABLE statement
+    DuckDb,
+    /// arrow schema as json
+    Arrow,
+    /// json schema of a row
+    JsonSchema,
+}
+
+impl std::str::FromStr for SchemaFormat {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<SchemaFormat, ParseError> {
+        match s {
+            "postgres" | "postgresql" => Ok(SchemaFormat::Postgres),
+            "clickhouse" => Ok(SchemaFormat::ClickHouse),
+            "duckdb" => Ok(SchemaFormat::DuckDb),
+            "arrow" => Ok(SchemaFormat::Arrow),
+            "json_schema" | "jsonschema" => Ok(SchemaFormat::JsonSchema),
+            _ => Err(ParseError::ParseError(format!(
+                "invalid schema format: {}, options: postgres clickhouse duckdb arrow json_schema",
+                s
+            ))),
+        }
+    }
+}
+
+impl Table {
+    /// columns as written to output files, with u256 columns expanded into one column per
+    /// u256 type
+    pub fn output_columns(&self) -> Vec<(String, ColumnType)> {
+        let mut output = Vec::new();
+        for name in self.columns() {
+            match self.column_type(name) {
+                Some(ColumnType::UInt256) => {
+                    for u256_type in self.u256_types.iter() {
+                        let ctype = match (u256_type, &self.binary_type) {
+                            (U256Type::Binary, ColumnEncoding::Hex) => ColumnType::Hex,
+                            _ => u256_type.to_columntype(),
+                        };
+                        output.push((name.to_string() + u256_type.suffix().as_str(), ctype));
+                    }
+                }
+                Some(ctype) => output.push((name.to_string(), ctype)),
+                None => {}
+            }
+        }
+        output
+    }
+
+    /// description of an output column, u256 columns are described by their source column
+    pub fn column_description(&self, name: &str) -> Option<&'static str> {
+        self.datatype.column_description(name).or_else(|| {
+            self.columns()
+                .into_iter()
+                .filter(|column| self.column_type(column) == Some(ColumnType::UInt256))
+                .find(|column| name.starts_with(column))
+                .and_then(|column| self.datatype.column_description(column))
+        })
+    }
+
+    /// render schema of table in given format
+    pub fn export_schema(&self, format: SchemaFormat) -> String {
+        let table_name = self.datatype.name();
+        let columns = self.output_columns();
+        match format {
+            SchemaFormat::Postgres | SchemaFormat::ClickHouse | SchemaFormat::DuckDb => {
+                let lines: Vec<String> = columns
+                    .iter()
+                    .map(|(name, ctype)| {
+                        let line = format!("    {} {}", name, sql_type(*ctype, format));
+                        match (format, self.column_description(name)) {
+                            (SchemaFormat::ClickHouse, Some(description)) => {
+                                format!("{} COMMENT '{}'", line, description.replace('\'', "\\'"))
+                            }
+                            _ => line,
+                        }
+                    })
+                    .collect();
+                let engine = match format {
+                    SchemaFormat::ClickHouse => {
+                        // every column is Nullable, which MergeTree does not allow in sort keys
+                        let sort_keys: Vec<String> = self
+                            .sort_columns
+                            .iter()
+                            .flatten()
+                            .filter(|column| columns.iter().any(|(name, _)| name == *column))
+                            .map(|column| format!("assumeNotNull({})", column))
+                            .collect();
+                        let order_by = match sort_keys.is_empty() {
+                            false => format!("({})", sort_keys.join(", ")),
+                            true => "tuple()".to_string(),
+                        };
+                        format!("\nENGINE = MergeTree\nORDER BY {}", order_by)
+                    }
+                    _ => "".to_string(),
+                };
+                format!("CREATE TABLE {} (\n{}\n){};", table_name, lines.join(",\n"), engine)
+            }
+            SchemaFormat::Arrow => {
+                let fields: Vec<Value> = columns
+                    .iter()
+                    .map(|(name, ctype)| {
+                        let mut field =
+                            json!({"name": name, "type": arrow_type(*ctype), "nullable": true});
+                        if let Some(description) = self.column_description(name) {
+                            field["metadata"] = json!({ "description": description });
+                        }
+                        field
+                    })
+                    .collect();
+                let mut schema = json!({ "fields": fields });
+                if let Some(description) = self.datatype.description() {
+                    schema["metadata"] = json!({ "description": description });
+                }
+                serde_json::to_string_pretty(&schema).unwrap_or_default()
+            }
+            SchemaFormat::JsonSchema => {
+                let properties: serde_json::Map<String, Value> = columns
+                    .iter()
+                    .map(|(name, ctype)| {
+                        let mut property = json_schema_type(*ctype);
+                        if let Some(description) = self.column_description(name) {
+                            property["description"] = json!(description);
+                        }
+                        (name.clone(), property)
+                    })
+                    .collect();
+                let mut schema = json!({
+                    "$schema": "https://json-schema.org/draft/2020-12/schema",
+                    "title": table_name,
+                    "type": "object",
+                    "properties": properties,
+                });
+                if let Some(description) = self.datatype.description() {
+                    schema["description"] = json!(description);
+                }
+                serde_json::to_string_pretty(&schema).unwrap_or_default()
+            }
+        }
+    }
+}
+
+fn sql_type(ctype: ColumnType, format: SchemaFormat) -> &'static str {
+    match format {
+        SchemaFormat::Postgres => match ctype {
+            ColumnType::Boolean => "BOOLEAN",
+            ColumnType::UInt32 => "BIGINT",
+            ColumnType::UInt64 => "NUMERIC(20, 0)",
+            ColumnType::UInt256 => "NUMERIC(78, 0)",
+            ColumnType::Int32 => "INTEGER",
+            ColumnType::Int64 => "BIGINT",
+            ColumnType::Float32 => "REAL",
+            ColumnType::Float64 => "DOUBLE PRECISION",
+            ColumnType::Decimal128 => "NUMERIC(38, 0)",
+            ColumnType::String | ColumnType::Categorical | ColumnType::Hex => "TEXT",
+            ColumnType::Binary => "BYTEA",
+        },
+        SchemaFormat::ClickHouse => match ctype {
+            ColumnType::Boolean => "Nullable(Bool)",
+            ColumnType::UInt32 => "Nullable(UInt32)",
+            ColumnType::UInt64 => "Nullable(UInt64)",
+            ColumnType::UInt256 => "Nullable(UInt256)",
+            ColumnType::Int32 => "Nullable(Int32)",
+            ColumnType::Int64 => "Nullable(Int64)",
+            ColumnType::Float32 => "Nullable(Float32)",
+            ColumnType::Float64 => "Nullable(Float64)",
+            ColumnType::Decimal128 => "Nullable(Decimal(38, 0))",
+            ColumnType::String | ColumnType::Hex | ColumnType::Binary => "Nullable(String)",
+            ColumnType::Categorical => "LowCardinality(Nullable(String))",
+        },
+        _ => match ctype {
+            ColumnType::Boolean => "BOOLEAN",
+            ColumnType::UInt32 => "UINTEGER",
+            ColumnType::UInt64 => "UBIGINT",
+            ColumnType::UInt256 => "HUGEINT",
+            ColumnType::Int32 => "INTEGER",
+            ColumnType::Int64 => "BIGINT",
+            ColumnType::Float32 => "FLOAT",
+            ColumnType::Float64 => "DOUBLE",
+            ColumnType::Decimal128 => "DECIMAL(38, 0)",
+            ColumnType::String | ColumnType::Categorical | ColumnType::Hex => "VARCHAR",
+            ColumnType::Binary => "BLOB",
+        },
+    }
+}
+
+fn arrow_type(ctype: ColumnType) -> &'static str {
+    match ctype {
+        ColumnType::Boolean => "bool",
+        ColumnType::UInt32 => "uint32",
+        ColumnType::UInt64 => "uint64",
+        ColumnType::UInt256 => "binary",
+        ColumnType::Int32 => "int32",
+        ColumnType::Int64 => "int64",
+        ColumnType::Float32 => "float32",
+        ColumnType::Float64 => "float64",
+        ColumnType::Decimal128 => "decimal128(38, 0)",
+        ColumnType::String | ColumnType::Hex => "large_utf8",
+        ColumnType::Categorical => "dictionary(uint32, large_utf8)",
+        ColumnType::Binary => "large_binary",
+    }
+}
+
+fn json_schema_type(ctype: ColumnType) -> Value {
+    match ctype {
+        ColumnType::Boolean => json!({"type": ["boolean", "null"]}),
+        ColumnType::UInt32 | ColumnType::UInt64 => {
+            json!({"type": ["integer", "null"], "minimum": 0})
+        }
+        ColumnType::Int32 | ColumnType::Int64 => json!({"type": ["integer", "null"]}),
+        ColumnType::Float32 | ColumnType::Float64 => json!({"type": ["number", "null"]}),
+        ColumnType::UInt256 |
+        ColumnType::Decimal128 |
+        ColumnType::String |
+        ColumnType::Categorical => {
+            json!({"type": ["string", "null"]})
+        }
+        ColumnType::Hex | ColumnType::Binary => {
+            json!({"type": ["string", "null"], "pattern": "^0x[0-9a-f]*$"})
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Datatype;
+
+    fn get_table() -> Table {
+        let cols = Some(vec!["block_number".to_string(), "block_hash".to_string()]);
+        Datatype::Blocks
+            .table_schema(
+                &[U256Type::Binary, U256Type::F64],
+                &ColumnEncoding::Binary,
+                &None,
+                &None,
+                &cols,
+                Some(vec!["block_number".to_string()]),
+                None,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_export_postgres() {
+        let ddl = get_table().export_schema(SchemaFormat::Postgres);
+        assert_eq!(
+            ddl,
+            "CREATE TABLE blocks (\n    block_number BIGINT,\n    block_hash BYTEA\n);"
+        );
+    }
+
+    #[test]
+    fn test_export_clickhouse_order_by() {
+        let ddl = get_table().export_schema(SchemaFormat::ClickHouse);
+        assert!(ddl.ends_with("ENGINE = MergeTree\nORDER BY (assumeNotNull(block_number));"));
+    }
+
+    #[test]
+    fn test_export_categorical() {
+        let cols = Some(vec!["action_type".to_string()]);
+        let mut table = Datatype::Traces
+            .table_schema(
+                &[U256Type::Binary],
+                &ColumnEncoding::Hex,
+                &None,
+                &None,
+                &cols,
+                None,
+                None,
+            )
+            .unwrap();
+        table.set_categorical_columns(&["action_type"]);
+        let ddl = table.export_schema(SchemaFormat::ClickHouse);
+        assert!(ddl.contains("action_type LowCardinality(Nullable(String))"));
+        let arrow = table.export_schema(SchemaFormat::Arrow);
+        assert!(arrow.contains("\"dictionary(uint32, large_utf8)\""));
+    }
+
+    #[test]
+    fn test_export_json_schema_descriptions() {
+        let schema = get_table().export_schema(SchemaFormat::JsonSchema);
+        let schema: Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(schema["description"], "block headers, one row per block");
+        assert_eq!(schema["properties"]["block_number"]["description"], "number of the block");
+    }
+
+    #[test]
+    fn test_described_datasets_describe_every_column() {
+        let datatypes = [
+            Datatype::Balances,
+            Datatype::Blocks,
+            Datatype::Contracts,
+            Datatype::Erc20Transfers,
+            Datatype::GenesisBalances,
+            Datatype::Logs,
+            Datatype::NativeTransfers,
+            Datatype::Receipts,
+            Datatype::Traces,
+            Datatype::Transactions,
+        ];
+        for datatype in datatypes.iter() {
+            let columns =
+                Some(datatype.column_types().keys().map(|column| column.to_string()).collect());
+            let table = datatype
+                .table_schema(
+                    &[U256Type::Binary],
+                    &ColumnEncoding::Binary,
+                    &None,
+                    &None,
+                    &columns,
+                    None,
+                    None,
+                )
+                .unwrap();
+            for (name, _) in table.output_columns() {
+                assert!(
+                    table.column_description(&name).is_some(),
+                    "{} column {} has no description",
+                    datatype.name(),
+                    name
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
 /// types and functions related to schemas
 use std::collections::HashMap;
 
-use crate::{err, CollectError, ColumnEncoding, Datatype, LogDecoder};
+use crate::{
+    err, CollectError, ColumnEncoding, Datatype, FunctionDecoder, LogDecoder, ParseError,
+    TraceDecoder,
+};
+use alloy::primitives::B256;
 use indexmap::{IndexMap, IndexSet};
 use thiserror::Error;
 
@@ -39,6 +43,22 @@ pub struct Table {
 
     /// log decoder for table
     pub log_decoder: Option<LogDecoder>,
+
+    /// topic0 of every event decoded along with log_decoder, so that the logs of all of them are
+    /// fetched with a single request
+    pub decoded_events: Vec<B256>,
+
+    /// function decoder for table
+    pub function_decoder: Option<FunctionDecoder>,
+
+    /// tracer and column mapping for table
+    pub trace_decoder: Option<TraceDecoder>,
+
+    /// representation of missing values in gas columns
+    pub gas_null_policy: GasNullPolicy,
+
+    /// string columns stored as dictionary-encoded categoricals
+    pub categorical_columns: Vec<String>,
 }
 
 impl Table {
@@ -56,6 +76,45 @@ impl Table {
     pub fn columns(&self) -> Vec<&str> {
         self.columns.keys().map(|x| x.as_str()).collect()
     }
+
+    /// store the given string columns of Table as categoricals, other columns are ignored
+    pub fn set_categorical_columns(&mut self, columns: &[&str]) {
+        for column in columns.iter() {
+            if self.column_type(column) == Some(ColumnType::String) {
+                self.columns.insert(column.to_string(), ColumnType::Categorical);
+                self.categorical_columns.push(column.to_string());
+            }
+        }
+    }
+}
+
+/// enum-like string columns stored as categoricals when categorical output is enabled
+pub const CATEGORICAL_COLUMNS: [&str; 3] =
+    ["action_type", "action_call_type", "action_reward_type"];
+
+/// representation of missing values in gas columns, e.g. gas of traces without a gas field
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GasNullPolicy {
+    /// keep missing values as nulls
+    #[default]
+    Null,
+    /// replace missing values with zero
+    Zero,
+    /// replace missing values with the maximum integer value, or NaN for floats
+    Sentinel,
+}
+
+impl std::str::FromStr for GasNullPolicy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<GasNullPolicy, ParseError> {
+        match s {
+            "null" => Ok(GasNullPolicy::Null),
+            "zero" => Ok(GasNullPolicy::Zero),
+            "sentinel" => Ok(GasNullPolicy::Sentinel),
+            _ => Err(ParseError::ParseError(format!("invalid gas null policy: {}", s))),
+        }
+    }
 }
 
 /// representation of a U256 datum
@@ -128,6 +187,8 @@ pub enum ColumnType {
     Decimal128,
     /// String column type
     String,
+    /// String column type stored as a dictionary-encoded categorical
+    Categorical,
     /// Binary column type
     Binary,
     /// Hex column type
@@ -148,6 +209,7 @@ impl ColumnType {
             ColumnType::Float64 => "float64",
             ColumnType::Decimal128 => "decimal128",
             ColumnType::String => "string",
+            ColumnType::Categorical => "categorical",
             ColumnType::Binary => "binary",
             ColumnType::Hex => "hex",
         }
@@ -201,6 +263,11 @@ impl Datatype {
             u256_types: u256_types.to_owned(),
             binary_type: binary_column_format.clone(),
             log_decoder,
+            decoded_events: Vec::new(),
+            function_decoder: None,
+            trace_decoder: None,
+            gas_null_policy: GasNullPolicy::default(),
+            categorical_columns: Vec::new(),
         };
         Ok(schema)
     }
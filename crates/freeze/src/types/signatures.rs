@@ -24,3 +24,124 @@ sol! {
         function tokenURI(uint256 tokenId) external view returns (string memory);
     }
 }
+
+sol! {
+    contract ERC1155 {
+        event TransferSingle(
+            address indexed operator,
+            address indexed from,
+            address indexed to,
+            uint256 id,
+            uint256 value
+        );
+        event TransferBatch(
+            address indexed operator,
+            address indexed from,
+            address indexed to,
+            uint256[] ids,
+            uint256[] values
+        );
+    }
+}
+
+sol! {
+    contract EntryPoint {
+        event UserOperationEvent(
+            bytes32 indexed userOpHash,
+            address indexed sender,
+            address indexed paymaster,
+            uint256 nonce,
+            bool success,
+            uint256 actualGasCost,
+            uint256 actualGasUsed
+        );
+
+        struct PackedUserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            bytes32 accountGasLimits;
+            uint256 preVerificationGas;
+            bytes32 gasFees;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function handleOps(PackedUserOperation[] calldata ops, address beneficiary) external;
+    }
+}
+
+sol! {
+    contract EntryPointV06 {
+        struct UserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            uint256 callGasLimit;
+            uint256 verificationGasLimit;
+            uint256 preVerificationGas;
+            uint256 maxFeePerGas;
+            uint256 maxPriorityFeePerGas;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function handleOps(UserOperation[] calldata ops, address beneficiary) external;
+    }
+}
+
+sol! {
+    contract Safe {
+        event ExecutionSuccess(bytes32 txHash, uint256 payment);
+        event ExecutionFailure(bytes32 txHash, uint256 payment);
+
+        function execTransaction(
+            address to,
+            uint256 value,
+            bytes calldata data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            bytes memory signatures
+        ) external payable returns (bool success);
+    }
+}
+
+sol! {
+    contract DepositContract {
+        event DepositEvent(
+            bytes pubkey,
+            bytes withdrawal_credentials,
+            bytes amount,
+            bytes signature,
+            bytes index
+        );
+    }
+}
+
+sol! {
+    contract Multicall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls)
+            external
+            payable
+            returns (Call3Result[] memory returnData);
+
+        function getEthBalance(address addr) external view returns (uint256 balance);
+    }
+}
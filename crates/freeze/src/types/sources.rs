@@ -1,4 +1,9 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
 
 use alloy::{
     eips::BlockNumberOrTag,
@@ -7,22 +12,32 @@ use alloy::{
         ext::{DebugApi, TraceApi},
         Provider, ProviderBuilder, RootProvider,
     },
-    rpc::types::{
-        trace::{
-            common::TraceResult,
-            geth::{
-                AccountState, CallConfig, CallFrame, DefaultFrame, DiffMode,
-                GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingOptions,
-                GethTrace, PreStateConfig, PreStateFrame,
-            },
-            parity::{
-                LocalizedTransactionTrace, TraceResults, TraceResultsWithTransactionHash, TraceType,
+    pubsub::Subscription,
+    rpc::{
+        client::{BatchRequest, ClientBuilder, Waiter},
+        json_rpc::{RequestPacket, ResponsePacket},
+        types::{
+            trace::{
+                common::TraceResult,
+                geth::{
+                    AccountState, CallConfig, CallFrame, DefaultFrame, DiffMode,
+                    GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingCallOptions,
+                    GethDebugTracingOptions, GethTrace, PreStateConfig, PreStateFrame,
+                },
+                parity::{
+                    LocalizedTransactionTrace, TraceResults, TraceResultsWithTransactionHash,
+                    TraceType,
+                },
             },
+            Block, BlockTransactions, BlockTransactionsKind, EIP1186AccountProofResponse, Filter,
+            Log, Transaction, TransactionInput, TransactionReceipt, TransactionRequest,
         },
-        Block, BlockTransactions, BlockTransactionsKind, Filter, Log, Transaction,
-        TransactionInput, TransactionReceipt, TransactionRequest,
     },
-    transports::{http::reqwest::Url, BoxTransport, RpcError, TransportErrorKind},
+    transports::{
+        http::{reqwest, reqwest::Url},
+        layers::{RateLimitRetryPolicy, RetryPolicy},
+        BoxTransport, RpcError, TransportError, TransportErrorKind, TransportFut,
+    },
 };
 use governor::{
     clock::DefaultClock,
@@ -33,8 +48,9 @@ use tokio::{
     sync::{AcquireError, Semaphore, SemaphorePermit},
     task,
 };
+use tower::{Layer, Service};
 
-use crate::CollectError;
+use crate::{CollectError, SharedRateLimiter};
 
 /// RateLimiter based on governor crate
 pub type RateLimiter = governor::RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
@@ -56,6 +72,14 @@ pub struct Source {
     pub semaphore: Arc<Option<Semaphore>>,
     /// rate limiter for controlling request rate
     pub rate_limiter: Arc<Option<RateLimiter>>,
+    /// rate limiter whose budget is shared with other processes on this machine
+    pub shared_rate_limiter: Option<SharedRateLimiter>,
+    /// maximum random delay added before each request
+    pub request_jitter: Option<Duration>,
+    /// pause all requests until this time, set when the provider signals rate limiting
+    pub throttled_until: Arc<Mutex<Option<Instant>>>,
+    /// whether provider supports `eth_getBlockReceipts`, probed by the first request for it
+    pub block_receipts_support: Arc<Mutex<Option<bool>>>,
     /// Labels (these are non-functional)
     pub labels: SourceLabels,
 }
@@ -67,22 +91,164 @@ impl Source {
         &self,
         block: &Block<Transaction>,
     ) -> Result<Vec<TransactionReceipt>> {
-        let block_number = block.header.number;
-        if let Ok(Some(receipts)) = self.get_block_receipts(block_number).await {
+        if let Some(receipts) = self.try_block_receipts(block.header.number).await {
             return Ok(receipts);
         }
 
         self.get_tx_receipts(block.transactions.clone()).await
     }
 
+    /// Returns all receipts for a block number.
+    /// Tries to use `eth_getBlockReceipts` first, and falls back to fetching the block's
+    /// transactions and requesting their receipts with `eth_getTransactionReceipt`
+    pub async fn get_receipts_by_block_number(
+        &self,
+        block_num: u64,
+    ) -> Result<Vec<TransactionReceipt>> {
+        if let Some(receipts) = self.try_block_receipts(block_num).await {
+            return Ok(receipts);
+        }
+
+        let block = self
+            .get_block(block_num, BlockTransactionsKind::Full)
+            .await?
+            .ok_or(CollectError::CollectError("block not found".to_string()))?;
+        self.get_tx_receipts(block.transactions).await
+    }
+
+    /// Returns receipts of block using `eth_getBlockReceipts` if the provider supports it
+    ///
+    /// support is probed by the first requests: once the provider reports that the method does
+    /// not exist, every later request of this source skips `eth_getBlockReceipts` and the caller
+    /// falls back to per-tx receipts. other errors, e.g. timeouts or rate limits, only fall back
+    /// for the current block
+    async fn try_block_receipts(&self, block_num: u64) -> Option<Vec<TransactionReceipt>> {
+        let support = self.block_receipts_support.lock().ok().and_then(|support| *support);
+        if support == Some(false) {
+            return None
+        }
+        let result = self.get_block_receipts(block_num).await;
+        self.record_block_receipts_support(support, &result);
+        result.ok().flatten()
+    }
+
+    /// Returns all receipts of a block as raw json
+    ///
+    /// uses `eth_getBlockReceipts` like `try_block_receipts`, and falls back to requesting the
+    /// receipts of tx_hashes with `eth_getTransactionReceipt`
+    pub async fn get_receipts_json_by_block_number(
+        &self,
+        block_num: u64,
+        tx_hashes: &[TxHash],
+    ) -> Result<Vec<serde_json::Value>> {
+        let support = self.block_receipts_support.lock().ok().and_then(|support| *support);
+        if support != Some(false) {
+            let result = self.get_block_receipts_json(block_num).await;
+            self.record_block_receipts_support(support, &result);
+            if let Ok(Some(receipts)) = result {
+                return Ok(receipts)
+            }
+        }
+
+        let mut tasks = Vec::new();
+        for tx_hash in tx_hashes.iter().copied() {
+            let source = self.clone();
+            let task: task::JoinHandle<std::result::Result<serde_json::Value, CollectError>> =
+                task::spawn(async move {
+                    match source.get_transaction_receipt_json(tx_hash).await? {
+                        Some(receipt) => Ok(receipt),
+                        None => {
+                            Err(CollectError::CollectError("could not find tx receipt".to_string()))
+                        }
+                    }
+                });
+            tasks.push(task);
+        }
+        let mut receipts = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(receipt) => receipts.push(receipt?),
+                Err(e) => return Err(CollectError::TaskFailed(e)),
+            }
+        }
+        Ok(receipts)
+    }
+
+    /// record whether the provider supports `eth_getBlockReceipts` from the result of a request
+    fn record_block_receipts_support<T>(&self, support: Option<bool>, result: &Result<Option<T>>) {
+        let probed = match result {
+            Ok(Some(_)) => Some(true),
+            Err(e) if support.is_none() && is_method_not_found(e) => Some(false),
+            _ => None,
+        };
+        if let (Some(probed), Ok(mut support)) = (probed, self.block_receipts_support.lock()) {
+            support.get_or_insert(probed);
+        }
+    }
+
+    /// Returns rpc method used to collect receipts, once support for `eth_getBlockReceipts` has
+    /// been probed
+    pub fn receipts_method(&self) -> Option<&'static str> {
+        match self.block_receipts_support.lock().ok().and_then(|support| *support) {
+            Some(true) => Some("eth_getBlockReceipts"),
+            Some(false) => Some("eth_getTransactionReceipt"),
+            None => None,
+        }
+    }
+
     /// Returns all receipts for vector of transactions using `eth_getTransactionReceipt`
+    ///
+    /// requests are sent as json-rpc batches of `RECEIPT_BATCH_SIZE`, and individually if the
+    /// provider rejects a batch
     pub async fn get_tx_receipts(
         &self,
         transactions: BlockTransactions<Transaction>,
+    ) -> Result<Vec<TransactionReceipt>> {
+        let tx_hashes: Vec<TxHash> = transactions.hashes().collect();
+        let mut receipts = Vec::with_capacity(tx_hashes.len());
+        for batch in tx_hashes.chunks(RECEIPT_BATCH_SIZE) {
+            match self.get_tx_receipts_batch(batch).await {
+                Ok(batch_receipts) => receipts.extend(batch_receipts),
+                Err(_) => receipts.extend(self.get_tx_receipts_individually(batch).await?),
+            }
+        }
+
+        Ok(receipts)
+    }
+
+    /// Returns receipts of transactions using a single json-rpc batch request
+    ///
+    /// every call of the batch counts against the rate limits, not only the request carrying it
+    async fn get_tx_receipts_batch(&self, tx_hashes: &[TxHash]) -> Result<Vec<TransactionReceipt>> {
+        let _permit = self.permit_request().await;
+        for _ in 1..tx_hashes.len() {
+            self.wait_for_rate_limits().await;
+        }
+        let mut batch = BatchRequest::new(self.provider.client());
+        let mut waiters: Vec<Waiter<Option<TransactionReceipt>>> = Vec::new();
+        for tx_hash in tx_hashes.iter() {
+            waiters.push(self.map_err(batch.add_call("eth_getTransactionReceipt", &(*tx_hash,)))?);
+        }
+        self.map_err(batch.send().await)?;
+        let mut receipts = Vec::with_capacity(waiters.len());
+        for waiter in waiters.into_iter() {
+            match self.map_err(waiter.await)? {
+                Some(receipt) => receipts.push(receipt),
+                None => {
+                    return Err(CollectError::CollectError("could not find tx receipt".to_string()))
+                }
+            }
+        }
+        Ok(receipts)
+    }
+
+    /// Returns receipts of transactions using concurrent `eth_getTransactionReceipt` requests
+    async fn get_tx_receipts_individually(
+        &self,
+        tx_hashes: &[TxHash],
     ) -> Result<Vec<TransactionReceipt>> {
         let mut tasks = Vec::new();
-        for tx in transactions.as_transactions().unwrap() {
-            let tx_hash = *tx.inner.tx_hash();
+        for tx_hash in tx_hashes.iter().copied() {
             let source = self.clone();
             let task: task::JoinHandle<std::result::Result<TransactionReceipt, CollectError>> =
                 task::spawn(async move {
@@ -112,6 +278,51 @@ const DEFAULT_MAX_RETRIES: u32 = 5;
 const DEFAULT_INTIAL_BACKOFF: u64 = 5;
 const DEFAULT_MAX_CONCURRENT_CHUNKS: u64 = 4;
 const DEFAULT_MAX_CONCURRENT_REQUESTS: u64 = 100;
+/// transaction receipts requested per json-rpc batch when falling back from eth_getBlockReceipts
+const RECEIPT_BATCH_SIZE: usize = 100;
+/// timeout of each http request to a rest api, such as a beacon node
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// http client for rest api requests, shared by all sources of the process
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(HTTP_REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new())
+    })
+}
+
+/// whether error is the provider reporting that the requested rpc method does not exist
+fn is_method_not_found(error: &CollectError) -> bool {
+    match error {
+        CollectError::ProviderError(RpcError::ErrorResp(payload)) => {
+            let message = payload.message.to_lowercase();
+            payload.code == -32601 ||
+                (message.contains("method") &&
+                    (message.contains("not found") ||
+                        message.contains("not supported") ||
+                        message.contains("does not exist") ||
+                        message.contains("not available")))
+        }
+        _ => false,
+    }
+}
+
+/// whether error is the execution of a call failing (e.g. a revert) rather than the request
+pub(crate) fn is_execution_error(error: &CollectError) -> bool {
+    match error {
+        CollectError::ProviderError(RpcError::ErrorResp(payload)) => {
+            let message = payload.message.to_lowercase();
+            payload.code == 3 ||
+                message.contains("revert") ||
+                message.contains("invalid opcode") ||
+                message.contains("out of gas")
+        }
+        _ => false,
+    }
+}
 
 /// builder
 impl Source {
@@ -128,7 +339,13 @@ impl Source {
         let rate_limiter = None;
         let semaphore = None;
 
-        let provider = ProviderBuilder::new().on_http(parsed_rpc_url);
+        let throttled_until = Arc::new(Mutex::new(None));
+        let throttle = ThrottleLayer::new(
+            throttled_until.clone(),
+            Duration::from_millis(DEFAULT_INTIAL_BACKOFF),
+        );
+        let client = ClientBuilder::default().layer(throttle).http(parsed_rpc_url);
+        let provider = ProviderBuilder::new().on_client(client);
 
         let source = Source {
             provider: provider.boxed(),
@@ -143,7 +360,11 @@ impl Source {
                 initial_backoff: Some(DEFAULT_INTIAL_BACKOFF),
             },
             rate_limiter: rate_limiter.into(),
+            shared_rate_limiter: None,
+            request_jitter: None,
             semaphore: semaphore.into(),
+            throttled_until,
+            block_receipts_support: Arc::new(Mutex::new(None)),
         };
 
         Ok(source)
@@ -223,12 +444,39 @@ pub struct Fetcher<P> {
 
 type Result<T> = ::core::result::Result<T, CollectError>;
 
+/// pending transactions of `txpool_content`, keyed by sender and nonce
+#[derive(serde::Deserialize)]
+struct TxpoolContent {
+    pending: HashMap<Address, HashMap<String, Transaction>>,
+}
+
 // impl<P: JsonRpcClient> Fetcher<P> {
 impl Source {
     /// Returns an array (possibly empty) of logs that match the filter
+    ///
+    /// when the provider rejects a query for returning too many results, the block range of the
+    /// query is bisected and each half is requested separately
     pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
-        let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_logs(filter).await)
+        let mut logs = Vec::new();
+        let mut pending = vec![filter.clone()];
+        while let Some(filter) = pending.pop() {
+            let result = {
+                let _permit = self.permit_request().await;
+                self.provider.get_logs(&filter).await
+            };
+            match result {
+                Ok(new_logs) => logs.extend(new_logs),
+                Err(e) if is_log_limit_error(&e) => match bisect_log_filter(&filter) {
+                    Some((first, second)) => {
+                        pending.push(second);
+                        pending.push(first);
+                    }
+                    None => return self.map_err(Err(e)),
+                },
+                Err(e) => return self.map_err(Err(e)),
+            }
+        }
+        Ok(logs)
     }
 
     /// Replays all transactions in a block returning the requested traces for each transaction
@@ -238,7 +486,7 @@ impl Source {
         trace_types: Vec<TraceType>,
     ) -> Result<Vec<TraceResultsWithTransactionHash>> {
         let _permit = self.permit_request().await;
-        Self::map_err(
+        self.map_err(
             self.provider.trace_replay_block_transactions(block.into(), &trace_types).await,
         )
     }
@@ -298,7 +546,7 @@ impl Source {
         trace_types: Vec<TraceType>,
     ) -> Result<TraceResults> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.trace_replay_transaction(tx_hash, &trace_types).await)
+        self.map_err(self.provider.trace_replay_transaction(tx_hash, &trace_types).await)
     }
 
     /// Get state diff traces of transaction
@@ -329,7 +577,7 @@ impl Source {
     /// Gets the transaction with transaction_hash
     pub async fn get_transaction_by_hash(&self, tx_hash: TxHash) -> Result<Option<Transaction>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_transaction_by_hash(tx_hash).await)
+        self.map_err(self.provider.get_transaction_by_hash(tx_hash).await)
     }
 
     /// Gets the transaction receipt with transaction_hash
@@ -338,7 +586,7 @@ impl Source {
         tx_hash: TxHash,
     ) -> Result<Option<TransactionReceipt>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_transaction_receipt(tx_hash).await)
+        self.map_err(self.provider.get_transaction_receipt(tx_hash).await)
     }
 
     /// Gets the block at `block_num` (transaction hashes only)
@@ -348,7 +596,7 @@ impl Source {
         kind: BlockTransactionsKind,
     ) -> Result<Option<Block>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_block(block_num.into(), kind).await)
+        self.map_err(self.provider.get_block(block_num.into(), kind).await)
     }
 
     /// Gets the block with `block_hash` (transaction hashes only)
@@ -358,19 +606,115 @@ impl Source {
         kind: BlockTransactionsKind,
     ) -> Result<Option<Block>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_block(block_hash.into(), kind).await)
+        self.map_err(self.provider.get_block(block_hash.into(), kind).await)
+    }
+
+    /// Gets the uncle at `index` of block `block_num`, using `eth_getUncleByBlockNumberAndIndex`
+    pub async fn get_uncle(&self, block_num: u64, index: u64) -> Result<Option<Block>> {
+        let _permit = self.permit_request().await;
+        self.map_err(self.provider.get_uncle(block_num.into(), index).await)
     }
 
     /// Returns all receipts for a block.
     /// Note that this uses the `eth_getBlockReceipts` method which is not supported by all nodes.
-    /// Consider using `FetcherExt::get_tx_receipts_in_block` which takes a block, and falls back to
+    /// Consider using `Source::get_tx_receipts_in_block` which takes a block, and falls back to
     /// `eth_getTransactionReceipt` if `eth_getBlockReceipts` is not supported.
     pub async fn get_block_receipts(
         &self,
         block_num: u64,
     ) -> Result<Option<Vec<TransactionReceipt>>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_block_receipts(block_num.into()).await)
+        self.map_err(self.provider.get_block_receipts(block_num.into()).await)
+    }
+
+    /// Gets the block at `block_num` as raw json, keeping fields of transaction types that are
+    /// unknown to ethereum such as l2 system transactions
+    pub async fn get_block_json(
+        &self,
+        block_num: u64,
+        full_transactions: bool,
+    ) -> Result<Option<serde_json::Value>> {
+        let _permit = self.permit_request().await;
+        self.map_err(
+            self.provider
+                .raw_request(
+                    "eth_getBlockByNumber".into(),
+                    (BlockNumberOrTag::Number(block_num), full_transactions),
+                )
+                .await,
+        )
+    }
+
+    /// Returns all receipts of a block as raw json, using `eth_getBlockReceipts`
+    pub async fn get_block_receipts_json(
+        &self,
+        block_num: u64,
+    ) -> Result<Option<Vec<serde_json::Value>>> {
+        let _permit = self.permit_request().await;
+        self.map_err(
+            self.provider
+                .raw_request("eth_getBlockReceipts".into(), (BlockNumberOrTag::Number(block_num),))
+                .await,
+        )
+    }
+
+    /// Gets the transaction with transaction_hash as raw json
+    pub async fn get_transaction_by_hash_json(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<serde_json::Value>> {
+        let _permit = self.permit_request().await;
+        self.map_err(self.provider.raw_request("eth_getTransactionByHash".into(), (tx_hash,)).await)
+    }
+
+    /// Gets the transaction receipt with transaction_hash as raw json
+    pub async fn get_transaction_receipt_json(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<serde_json::Value>> {
+        let _permit = self.permit_request().await;
+        self.map_err(
+            self.provider.raw_request("eth_getTransactionReceipt".into(), (tx_hash,)).await,
+        )
+    }
+
+    /// Returns all accounts of the state at a block, using geth's `debug_dumpBlock`
+    pub async fn geth_debug_dump_block(&self, block_num: u64) -> Result<serde_json::Value> {
+        let _permit = self.permit_request().await;
+        self.map_err(
+            self.provider
+                .raw_request("debug_dumpBlock".into(), (BlockNumberOrTag::Number(block_num),))
+                .await,
+        )
+    }
+
+    /// Returns page of transactions of address after block, using otterscan's
+    /// `ots_searchTransactionsAfter`
+    pub async fn ots_search_transactions_after(
+        &self,
+        address: Address,
+        block_num: u64,
+        page_size: u64,
+    ) -> Result<serde_json::Value> {
+        let _permit = self.permit_request().await;
+        self.map_err(
+            self.provider
+                .raw_request("ots_searchTransactionsAfter".into(), (address, block_num, page_size))
+                .await,
+        )
+    }
+
+    /// Returns pending transactions of the transaction pool, using geth's `txpool_content`
+    pub async fn txpool_pending_transactions(&self) -> Result<Vec<Transaction>> {
+        let _permit = self.permit_request().await;
+        let content: TxpoolContent =
+            self.map_err(self.provider.raw_request("txpool_content".into(), ()).await)?;
+        Ok(content.pending.into_values().flat_map(|txs| txs.into_values()).collect())
+    }
+
+    /// Subscribes to bodies of new pending transactions, requires a ws or ipc rpc url
+    pub async fn subscribe_pending_transactions(&self) -> Result<Subscription<Transaction>> {
+        self.map_err(self.provider.subscribe_full_pending_transactions().await)
     }
 
     /// Returns traces created at given block
@@ -379,7 +723,7 @@ impl Source {
         block_num: BlockNumber,
     ) -> Result<Vec<LocalizedTransactionTrace>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.trace_block(block_num.into()).await)
+        self.map_err(self.provider.trace_block(block_num.into()).await)
     }
 
     /// Returns all traces of a given transaction
@@ -388,7 +732,7 @@ impl Source {
         tx_hash: TxHash,
     ) -> Result<Vec<LocalizedTransactionTrace>> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.trace_transaction(tx_hash).await)
+        self.map_err(self.provider.trace_transaction(tx_hash).await)
     }
 
     /// Deprecated
@@ -398,7 +742,7 @@ impl Source {
         block_number: BlockNumber,
     ) -> Result<Bytes> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.call(&transaction).block(block_number.into()).await)
+        self.map_err(self.provider.call(&transaction).block(block_number.into()).await)
     }
 
     /// Returns traces for given call data
@@ -410,11 +754,11 @@ impl Source {
     ) -> Result<TraceResults> {
         let _permit = self.permit_request().await;
         if let Some(bn) = block_number {
-            return Self::map_err(
+            return self.map_err(
                 self.provider.trace_call(&transaction, &trace_type).block_id(bn.into()).await,
             );
         }
-        Self::map_err(self.provider.trace_call(&transaction, &trace_type).await)
+        self.map_err(self.provider.trace_call(&transaction, &trace_type).await)
     }
 
     /// Get nonce of address
@@ -424,7 +768,7 @@ impl Source {
         block_number: BlockNumber,
     ) -> Result<u64> {
         let _permit = self.permit_request().await;
-        Self::map_err(
+        self.map_err(
             self.provider.get_transaction_count(address).block_id(block_number.into()).await,
         )
     }
@@ -432,13 +776,13 @@ impl Source {
     /// Get code at address
     pub async fn get_balance(&self, address: Address, block_number: BlockNumber) -> Result<U256> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_balance(address).block_id(block_number.into()).await)
+        self.map_err(self.provider.get_balance(address).block_id(block_number.into()).await)
     }
 
     /// Get code at address
     pub async fn get_code(&self, address: Address, block_number: BlockNumber) -> Result<Bytes> {
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.get_code_at(address).block_id(block_number.into()).await)
+        self.map_err(self.provider.get_code_at(address).block_id(block_number.into()).await)
     }
 
     /// Get stored data at given location
@@ -449,14 +793,25 @@ impl Source {
         block_number: BlockNumber,
     ) -> Result<U256> {
         let _permit = self.permit_request().await;
-        Self::map_err(
+        self.map_err(
             self.provider.get_storage_at(address, slot).block_id(block_number.into()).await,
         )
     }
 
+    /// Get account proof and storage proofs of slots at block
+    pub async fn get_proof(
+        &self,
+        address: Address,
+        slots: Vec<B256>,
+        block_number: BlockNumber,
+    ) -> Result<EIP1186AccountProofResponse> {
+        let _permit = self.permit_request().await;
+        self.map_err(self.provider.get_proof(address, slots).block_id(block_number.into()).await)
+    }
+
     /// Get the block number
     pub async fn get_block_number(&self) -> Result<u64> {
-        Self::map_err(self.provider.get_block_number().await)
+        self.map_err(self.provider.get_block_number().await)
     }
 
     // extra helpers below
@@ -495,7 +850,7 @@ impl Source {
             ..Default::default()
         };
         let _permit = self.permit_request().await;
-        Self::map_err(self.provider.call(&transaction).block(block_number.into()).await)
+        self.map_err(self.provider.call(&transaction).block(block_number.into()).await)
     }
 
     /// Return output data of a contract call
@@ -513,14 +868,45 @@ impl Source {
         };
         let _permit = self.permit_request().await;
         if block_number.is_some() {
-            Self::map_err(
+            self.map_err(
                 self.provider
                     .trace_call(&transaction, &trace_type)
                     .block_id(block_number.unwrap().into())
                     .await,
             )
         } else {
-            Self::map_err(self.provider.trace_call(&transaction, &trace_type).await)
+            self.map_err(self.provider.trace_call(&transaction, &trace_type).await)
+        }
+    }
+
+    /// get geth debug call trace of a call at a block
+    pub async fn geth_debug_trace_call(
+        &self,
+        address: Address,
+        call_data: Vec<u8>,
+        block_number: BlockNumber,
+    ) -> Result<CallFrame> {
+        let transaction = TransactionRequest {
+            to: Some(address.into()),
+            input: TransactionInput::new(call_data.into()),
+            ..Default::default()
+        };
+        let tracer = GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer);
+        let options = GethDebugTracingCallOptions {
+            tracing_options: GethDebugTracingOptions::default()
+                .with_tracer(tracer)
+                .with_call_config(CallConfig::default()),
+            ..Default::default()
+        };
+        let trace = {
+            let _permit = self.permit_request().await;
+            self.map_err(
+                self.provider.debug_trace_call(transaction, block_number.into(), options).await,
+            )?
+        };
+        match trace {
+            GethTrace::CallTracer(frame) => Ok(frame),
+            _ => Err(CollectError::CollectError("invalid trace result".to_string())),
         }
     }
 
@@ -533,7 +919,7 @@ impl Source {
     ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<TraceResult<GethTrace, String>>)> {
         let traces = {
             let _permit = self.permit_request().await;
-            Self::map_err(
+            self.map_err(
                 self.provider
                     .debug_trace_block_by_number(
                         BlockNumberOrTag::Number(block_number.into()),
@@ -565,6 +951,38 @@ impl Source {
         Ok((Some(block_number), txs, traces))
     }
 
+    /// get geth debug block traces of any tracer as json
+    pub async fn geth_debug_trace_block_json(
+        &self,
+        block_number: u32,
+        include_transaction_hashes: bool,
+        options: GethDebugTracingOptions,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<serde_json::Value>)> {
+        let (block, txs, traces) =
+            self.geth_debug_trace_block(block_number, options, include_transaction_hashes).await?;
+
+        let mut values = Vec::new();
+        for trace in traces.into_iter() {
+            match trace {
+                TraceResult::Success { result, tx_hash } => {
+                    values.push(serde_json::to_value(result).map_err(|e| {
+                        CollectError::CollectError(format!(
+                            "invalid trace result in tx {:?}: {}",
+                            tx_hash, e
+                        ))
+                    })?)
+                }
+                TraceResult::Error { error, tx_hash } => {
+                    return Err(CollectError::CollectError(format!(
+                        "invalid trace result in tx {:?}: {}",
+                        tx_hash, error
+                    )))
+                }
+            }
+        }
+        Ok((block, txs, values))
+    }
+
     /// get geth debug block call traces
     pub async fn geth_debug_trace_block_javascript_traces(
         &self,
@@ -813,10 +1231,7 @@ impl Source {
 
         let trace = {
             let _permit = self.permit_request().await;
-            self.provider
-                .debug_trace_transaction(ethers_tx, options)
-                .await
-                .map_err(CollectError::ProviderError)?
+            self.map_err(self.provider.debug_trace_transaction(ethers_tx, options).await)?
         };
         let traces = vec![trace];
 
@@ -836,6 +1251,24 @@ impl Source {
         Ok((block_number, vec![Some(transaction_hash)], traces))
     }
 
+    /// get geth debug transaction traces of any tracer as json
+    pub async fn geth_debug_trace_transaction_json(
+        &self,
+        transaction_hash: Vec<u8>,
+        include_block_number: bool,
+        options: GethDebugTracingOptions,
+    ) -> Result<(Option<u32>, Vec<Option<Vec<u8>>>, Vec<serde_json::Value>)> {
+        let (block, txs, traces) = self
+            .geth_debug_trace_transaction(transaction_hash, options, include_block_number)
+            .await?;
+        let traces = traces
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| CollectError::CollectError(format!("invalid trace result: {}", e)))?;
+        Ok((block, txs, traces))
+    }
+
     /// get geth debug block javascript traces
     pub async fn geth_debug_trace_transaction_javascript_traces(
         &self,
@@ -983,6 +1416,66 @@ impl Source {
         Ok((block, txs, diffs))
     }
 
+    /// Returns json of an http get request to a rest api such as a beacon node, None if the
+    /// resource is not found
+    ///
+    /// requests count against the concurrency and rate limits of this source, time out after
+    /// `HTTP_REQUEST_TIMEOUT`, and are retried with exponential backoff when the server is
+    /// unreachable, unavailable, or rate limiting
+    pub async fn get_http_json(&self, url: &str) -> Result<Option<serde_json::Value>> {
+        let max_retries = self.labels.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let initial_backoff = self.labels.initial_backoff.unwrap_or(DEFAULT_INTIAL_BACKOFF);
+        let mut attempt = 0;
+        loop {
+            let backoff =
+                Duration::from_millis(initial_backoff.saturating_mul(1 << attempt.min(16)));
+            let response = {
+                let _permit = self.permit_request().await;
+                http_client().get(url).send().await
+            };
+            let failure = match response {
+                Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+                    return Ok(None)
+                }
+                Ok(response) if response.status().is_success() => {
+                    let text = response
+                        .text()
+                        .await
+                        .map_err(|_| err(format!("could not read {}", url).as_str()))?;
+                    return serde_json::from_str(&text)
+                        .map(Some)
+                        .map_err(|_| err(format!("invalid json from {}", url).as_str()))
+                }
+                Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                    let retry_after = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.parse::<u64>().ok())
+                        .map(Duration::from_secs);
+                    self.throttle_for(retry_after.unwrap_or(backoff));
+                    format!("request failed with {}: {}", response.status(), url)
+                }
+                Ok(response) if response.status().is_server_error() => {
+                    format!("request failed with {}: {}", response.status(), url)
+                }
+                Ok(response) => {
+                    let message = format!("request failed with {}: {}", response.status(), url);
+                    return Err(err(message.as_str()))
+                }
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                    format!("could not reach {}: {}", url, e)
+                }
+                Err(e) => return Err(err(format!("could not reach {}: {}", url, e).as_str())),
+            };
+            if attempt >= max_retries {
+                return Err(err(failure.as_str()))
+            }
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
     async fn permit_request(
         &self,
     ) -> Option<::core::result::Result<SemaphorePermit<'_>, AcquireError>> {
@@ -990,20 +1483,200 @@ impl Source {
             Some(semaphore) => Some(semaphore.acquire().await),
             _ => None,
         };
+        self.wait_for_rate_limits().await;
+        let throttled_until = self.throttled_until.lock().ok().and_then(|until| *until);
+        if let Some(until) = throttled_until {
+            tokio::time::sleep_until(until.into()).await;
+        }
+        if let Some(max_jitter) = self.request_jitter {
+            tokio::time::sleep(max_jitter.mul_f64(rand::random::<f64>())).await;
+        }
+        permit
+    }
+
+    /// wait until the requests-per-second limits of this source allow one more call
+    async fn wait_for_rate_limits(&self) {
         if let Some(limiter) = &*self.rate_limiter {
             limiter.until_ready().await;
         }
-        permit
+        if let Some(limiter) = &self.shared_rate_limiter {
+            limiter.until_ready().await;
+        }
     }
 
-    fn map_err<T>(res: ::core::result::Result<T, RpcError<TransportErrorKind>>) -> Result<T> {
+    fn map_err<T>(
+        &self,
+        res: ::core::result::Result<T, RpcError<TransportErrorKind>>,
+    ) -> Result<T> {
         res.map_err(CollectError::ProviderError)
     }
 }
 
+/// transport layer that pauses all requests of a source as soon as one response is rate limited,
+/// honoring the backoff requested by the provider if one is given
+///
+/// the layer goes below the retry layer of the client, so that every attempt of the retry layer
+/// is observed and waits for the pause, instead of the pause starting once retries are exhausted
+#[derive(Clone, Debug)]
+pub struct ThrottleLayer {
+    throttled_until: Arc<Mutex<Option<Instant>>>,
+    initial_backoff: Duration,
+}
+
+impl ThrottleLayer {
+    /// create layer pausing requests through throttled_until, the pause of a source
+    pub fn new(throttled_until: Arc<Mutex<Option<Instant>>>, initial_backoff: Duration) -> Self {
+        ThrottleLayer { throttled_until, initial_backoff }
+    }
+
+    /// wait until the current pause ends
+    async fn wait(&self) {
+        let throttled_until = self.throttled_until.lock().ok().and_then(|until| *until);
+        if let Some(until) = throttled_until {
+            tokio::time::sleep_until(until.into()).await;
+        }
+    }
+
+    /// pause all requests for the backoff of error, if error is rate limiting
+    fn observe(&self, error: &TransportError) {
+        if !is_rate_limit_error(error) {
+            return
+        }
+        let backoff =
+            RateLimitRetryPolicy::default().backoff_hint(error).unwrap_or(self.initial_backoff);
+        let until = Instant::now() + backoff;
+        if let Ok(mut throttled_until) = self.throttled_until.lock() {
+            if throttled_until.is_none_or(|current| current < until) {
+                *throttled_until = Some(until);
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for ThrottleLayer {
+    type Service = ThrottleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ThrottleService { inner, layer: self.clone() }
+    }
+}
+
+/// transport service of ThrottleLayer
+#[derive(Clone, Debug)]
+pub struct ThrottleService<S> {
+    inner: S,
+    layer: ThrottleLayer,
+}
+
+impl<S> Service<RequestPacket> for ThrottleService<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<::core::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let layer = self.layer.clone();
+        Box::pin(async move {
+            layer.wait().await;
+            let response = inner.call(request).await;
+            match &response {
+                Ok(packet) => {
+                    if let Some(payload) = packet.as_error() {
+                        layer.observe(&RpcError::ErrorResp(payload.clone()));
+                    }
+                }
+                Err(error) => layer.observe(error),
+            }
+            response
+        })
+    }
+}
+
 use crate::err;
 use std::collections::BTreeMap;
 
+/// fragments of the error messages providers return when a log query has too many results
+const LOG_LIMIT_ERRORS: [&str; 8] = [
+    "query returned more than",
+    "log response size exceeded",
+    "response size exceeded",
+    "too many results",
+    "block range is too wide",
+    "block range too large",
+    "exceed maximum block range",
+    "exceeds max results",
+];
+
+fn is_log_limit_error(error: &RpcError<TransportErrorKind>) -> bool {
+    let message = error.to_string().to_lowercase();
+    LOG_LIMIT_ERRORS.iter().any(|fragment| message.contains(fragment))
+}
+
+/// codes that providers use for rate limiting in json rpc error responses
+///
+/// infura also uses -32005 when a log query returns more than 10000 results, which is told apart
+/// by its message and handled by bisecting the query instead
+const RATE_LIMIT_CODES: [i64; 5] = [429, -32005, -32007, -32012, -32016];
+
+/// fragments of the error messages providers return when rate limiting
+const RATE_LIMIT_ERRORS: [&str; 6] = [
+    "rate limit",
+    "rate exceeded",
+    "too many requests",
+    "credits limited",
+    "request limit",
+    "daily request count exceeded",
+];
+
+/// whether error is the provider rate limiting, unlike the retry policy of alloy this excludes
+/// unavailable servers, null responses, and missing headers, which should not pause all requests
+fn is_rate_limit_error(error: &RpcError<TransportErrorKind>) -> bool {
+    match error {
+        RpcError::Transport(TransportErrorKind::HttpError(http_error)) => {
+            http_error.is_rate_limit_err()
+        }
+        RpcError::Transport(TransportErrorKind::Custom(custom)) => {
+            custom.to_string().contains("429 Too Many Requests")
+        }
+        RpcError::ErrorResp(payload) => {
+            let message = payload.message.to_lowercase();
+            if LOG_LIMIT_ERRORS.iter().any(|fragment| message.contains(fragment)) {
+                return false
+            }
+            RATE_LIMIT_CODES.contains(&payload.code) ||
+                RATE_LIMIT_ERRORS.iter().any(|fragment| message.contains(fragment))
+        }
+        _ => false,
+    }
+}
+
+/// split the block range of a log filter into two halves, None if it spans a single block
+fn bisect_log_filter(filter: &Filter) -> Option<(Filter, Filter)> {
+    let (start, end) = (filter.get_from_block()?, filter.get_to_block()?);
+    if start >= end {
+        return None
+    }
+    let mid = start + (end - start) / 2;
+    let first = filter.clone().from_block(start).to_block(mid);
+    let second = filter.clone().from_block(mid + 1).to_block(end);
+    Some((first, second))
+}
+
 fn parse_geth_diff_object(map: serde_json::Map<String, serde_json::Value>) -> Result<DiffMode> {
     let pre: BTreeMap<Address, AccountState> = serde_json::from_value(map["pre"].clone())
         .map_err(|_| err("cannot deserialize pre diff"))?;
@@ -1012,3 +1685,110 @@ fn parse_geth_diff_object(map: serde_json::Map<String, serde_json::Value>) -> Re
 
     Ok(DiffMode { pre, post })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisect_log_filter() {
+        let filter = Filter::new().from_block(100).to_block(199);
+        let (first, second) = bisect_log_filter(&filter).unwrap();
+        assert_eq!((first.get_from_block(), first.get_to_block()), (Some(100), Some(149)));
+        assert_eq!((second.get_from_block(), second.get_to_block()), (Some(150), Some(199)));
+        assert!(bisect_log_filter(&Filter::new().from_block(100).to_block(100)).is_none());
+    }
+
+    #[test]
+    fn test_is_method_not_found() {
+        let error = |code, message: &'static str| {
+            let payload =
+                alloy::rpc::json_rpc::ErrorPayload { code, message: message.into(), data: None };
+            CollectError::ProviderError(RpcError::ErrorResp(payload))
+        };
+        assert!(is_method_not_found(&error(-32601, "the method does not exist")));
+        assert!(is_method_not_found(&error(-32000, "method eth_getBlockReceipts not supported")));
+        assert!(!is_method_not_found(&error(429, "too many requests")));
+        assert!(!is_method_not_found(&error(-32000, "request timed out")));
+        assert!(is_execution_error(&error(3, "execution reverted")));
+        assert!(is_execution_error(&error(-32000, "execution reverted: not owner")));
+        assert!(!is_execution_error(&error(429, "too many requests")));
+    }
+
+    #[test]
+    fn test_is_rate_limit_error() {
+        let error = |code, message: &'static str| {
+            let payload =
+                alloy::rpc::json_rpc::ErrorPayload { code, message: message.into(), data: None };
+            RpcError::ErrorResp(payload)
+        };
+        assert!(is_rate_limit_error(&error(429, "too many requests")));
+        assert!(is_rate_limit_error(&error(-32005, "project limit exceeded")));
+        assert!(!is_rate_limit_error(&error(-32005, "query returned more than 10000 results")));
+        assert!(is_log_limit_error(&error(-32005, "query returned more than 10000 results")));
+        assert!(is_rate_limit_error(&error(
+            -32000,
+            "daily request count exceeded, request rate limited"
+        )));
+        assert!(!is_rate_limit_error(&error(-32000, "header not found")));
+        assert!(!is_rate_limit_error(&error(3, "execution reverted")));
+        assert!(!is_rate_limit_error(&RpcError::NullResp));
+        assert!(!is_rate_limit_error(&TransportErrorKind::http_error(503, String::new())));
+        assert!(is_rate_limit_error(&TransportErrorKind::http_error(429, String::new())));
+    }
+
+    /// transport answering every request with a rate limit error
+    #[derive(Clone)]
+    struct RateLimitedTransport;
+
+    impl Service<RequestPacket> for RateLimitedTransport {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<::core::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: RequestPacket) -> Self::Future {
+            use alloy::rpc::json_rpc::{ErrorPayload, Response, ResponsePayload};
+            Box::pin(async move {
+                match request {
+                    RequestPacket::Single(request) => {
+                        let payload = ErrorPayload {
+                            code: 429,
+                            message: "too many requests".into(),
+                            data: None,
+                        };
+                        let payload = ResponsePayload::Failure(payload);
+                        Ok(ResponsePacket::Single(Response { id: request.id().clone(), payload }))
+                    }
+                    RequestPacket::Batch(_) => Err(TransportErrorKind::custom_str("no batches")),
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_throttle_layer_pauses_between_retries() {
+        use alloy::{rpc::client::ClientBuilder, transports::layers::RetryBackoffLayer};
+
+        let throttled_until = Arc::new(Mutex::new(None));
+        let throttle = ThrottleLayer::new(throttled_until.clone(), Duration::from_millis(300));
+        let client = ClientBuilder::default()
+            .layer(RetryBackoffLayer::new(1, 1, 10000))
+            .layer(throttle)
+            .transport(RateLimitedTransport, true);
+        let provider = ProviderBuilder::new().on_client(client);
+
+        // the retry waits for the pause set by the rate limited first attempt, not only its own
+        // 1ms backoff
+        let start = Instant::now();
+        assert!(provider.get_chain_id().await.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert!(throttled_until.lock().unwrap().is_some());
+    }
+}
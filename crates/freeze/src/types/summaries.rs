@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use chrono::{DateTime, Local};
 use colored::Colorize;
 use thousands::Separable;
 
 use crate::{
-    chunks::chunk_ops::ValueToString, ChunkData, ChunkStats, CollectError, ColumnType, Datatype,
-    Dim, ExecutionEnv, FileOutput, MetaDatatype, MultiDatatype, Partition, Query, Source, Table,
+    chunks::chunk_ops::ValueToString, ChunkData, ChunkStats, CollectError, ColumnType,
+    DatasetQuality, Datatype, Dim, ExecutionEnv, FileOutput, MetaDatatype, MultiDatatype,
+    Partition, Query, Source, Table,
 };
 use std::path::PathBuf;
 
@@ -28,6 +29,12 @@ pub struct FreezeSummary {
     pub errored: Vec<(Option<Partition>, CollectError)>,
     /// rows written
     pub n_rows: u64,
+    /// violations of row count checks in written chunks
+    pub check_violations: Vec<String>,
+    /// data quality statistics of each written dataset
+    pub quality: BTreeMap<String, DatasetQuality>,
+    /// single-block files of oversized blocks split out of each written chunk file
+    pub split_files: BTreeMap<PathBuf, Vec<PathBuf>>,
 }
 
 /// print all datasets
@@ -64,6 +71,9 @@ pub fn print_all_datasets() {
 /// print info about datasets
 pub fn print_dataset_info(datatype: Datatype, schema: &Table) {
     print_header(datatype.name());
+    if let Some(description) = datatype.description() {
+        println!("{}\n", description);
+    }
 
     match (datatype.can_collect_by_block(), datatype.can_collect_by_transaction()) {
         (true, true) => print_bullet_key("can collect by block or by transaction"),
@@ -108,6 +118,19 @@ pub fn print_dataset_info(datatype: Datatype, schema: &Table) {
     println!();
     println!();
     print_schema(&datatype, schema);
+
+    let descriptions: Vec<(&str, &str)> = datatype
+        .column_types()
+        .keys()
+        .filter_map(|column| datatype.column_description(column).map(|d| (*column, d)))
+        .collect();
+    if !descriptions.is_empty() {
+        println!();
+        print_header("column descriptions");
+        for (column, description) in descriptions.into_iter() {
+            print_bullet(column, description);
+        }
+    }
 }
 
 pub(crate) fn print_header<A: AsRef<str>>(header: A) {
@@ -439,6 +462,19 @@ pub(crate) fn print_cryo_conclusion(
         println!();
     }
 
+    if !freeze_summary.check_violations.is_empty() {
+        print_header_error("check summary");
+        println!("({} row count check violations)", freeze_summary.check_violations.len());
+        for violation in freeze_summary.check_violations.iter().take(10) {
+            println!("- {}", violation);
+        }
+        if freeze_summary.check_violations.len() > 10 {
+            println!("...")
+        }
+        println!();
+        println!();
+    }
+
     let duration = match t_end.duration_since(env.t_start) {
         Ok(duration) => duration,
         Err(_e) => {
@@ -77,3 +77,36 @@ if typing.TYPE_CHECKING:
         js_tracer: str | None
         verbose: bool
         event_signature: str | None
+        hash_columns: typing.Sequence[str] | None
+        drop_columns: typing.Sequence[str] | None
+        mask_columns: typing.Sequence[str] | None
+        record_fixtures: str | None
+        replay_fixtures: str | None
+        version_outputs: bool
+        gas_nulls: str
+        tmp_dir: str | None
+        max_tmp_bytes: int | None
+        max_block_bytes: int | None
+        shared_rate_limit: bool
+        rate_limit_dir: str | None
+        categorical: bool
+        profile: str | None
+        genesis_file: str | None
+        check: typing.Sequence[str] | None
+        strict_checks: bool
+        beacon_url: str | None
+        gas_percentiles: typing.Sequence[float] | None
+        trace_address_columns: int | None
+        abi: typing.Sequence[str] | None
+        enqueue: str | None
+        call_signature: str | None
+        call_args: typing.Sequence[str] | None
+        multicall: int | None
+        mempool_window: int
+        mempool_duration: int | None
+        validators: typing.Sequence[str] | None
+        tracer: str | None
+        tracer_config: str | None
+        trace_schema: str | None
+        debug_trace_calls: bool
+        state_diff_appearances: bool
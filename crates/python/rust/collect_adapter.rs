@@ -65,6 +65,39 @@ use cryo_freeze::collect;
         verbose = false,
         no_verbose = false,
         event_signature = None,
+        hash_columns = None,
+        drop_columns = None,
+        mask_columns = None,
+        record_fixtures = None,
+        replay_fixtures = None,
+        version_outputs = false,
+        gas_nulls = "null".to_string(),
+        tmp_dir = None,
+        max_tmp_bytes = None,
+        max_block_bytes = None,
+        shared_rate_limit = false,
+        rate_limit_dir = None,
+        categorical = false,
+        profile = None,
+        genesis_file = None,
+        check = None,
+        strict_checks = false,
+        beacon_url = None,
+        gas_percentiles = None,
+        trace_address_columns = None,
+        abi = None,
+        enqueue = None,
+        call_signature = None,
+        call_args = None,
+        multicall = None,
+        mempool_window = 60,
+        mempool_duration = None,
+        validators = None,
+        tracer = None,
+        tracer_config = None,
+        trace_schema = None,
+        debug_trace_calls = false,
+        state_diff_appearances = false,
     )
 )]
 #[allow(clippy::too_many_arguments)]
@@ -127,6 +160,39 @@ pub fn _collect(
     verbose: bool,
     no_verbose: bool,
     event_signature: Option<String>,
+    hash_columns: Option<Vec<String>>,
+    drop_columns: Option<Vec<String>>,
+    mask_columns: Option<Vec<String>>,
+    record_fixtures: Option<String>,
+    replay_fixtures: Option<String>,
+    version_outputs: bool,
+    gas_nulls: String,
+    tmp_dir: Option<String>,
+    max_tmp_bytes: Option<u64>,
+    max_block_bytes: Option<u64>,
+    shared_rate_limit: bool,
+    rate_limit_dir: Option<String>,
+    categorical: bool,
+    profile: Option<String>,
+    genesis_file: Option<String>,
+    check: Option<Vec<String>>,
+    strict_checks: bool,
+    beacon_url: Option<String>,
+    gas_percentiles: Option<Vec<f64>>,
+    trace_address_columns: Option<usize>,
+    abi: Option<Vec<String>>,
+    enqueue: Option<String>,
+    call_signature: Option<String>,
+    call_args: Option<Vec<String>>,
+    multicall: Option<usize>,
+    mempool_window: u64,
+    mempool_duration: Option<u64>,
+    validators: Option<Vec<String>>,
+    tracer: Option<String>,
+    tracer_config: Option<String>,
+    trace_schema: Option<String>,
+    debug_trace_calls: bool,
+    state_diff_appearances: bool,
 ) -> PyResult<&PyAny> {
     if let Some(command) = command {
         pyo3_asyncio::tokio::future_into_py(py, async move {
@@ -193,6 +259,39 @@ pub fn _collect(
             verbose,
             no_verbose,
             event_signature,
+            hash_columns,
+            drop_columns,
+            mask_columns,
+            record_fixtures,
+            replay_fixtures,
+            version_outputs,
+            gas_nulls,
+            tmp_dir,
+            max_tmp_bytes,
+            max_block_bytes,
+            shared_rate_limit,
+            rate_limit_dir,
+            categorical,
+            profile,
+            genesis_file,
+            check,
+            strict_checks,
+            beacon_url,
+            gas_percentiles,
+            trace_address_columns,
+            abi,
+            enqueue,
+            call_signature,
+            call_args,
+            multicall,
+            mempool_window,
+            mempool_duration,
+            validators,
+            tracer,
+            tracer_config,
+            trace_schema,
+            debug_trace_calls,
+            state_diff_appearances,
         };
         pyo3_asyncio::tokio::future_into_py(py, async move {
             match run_collect(args).await {
@@ -50,7 +50,14 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
             };
             let field_name_str = format!("{}", quote!(#name));
             quote! {
-                #macro_name!(cols, #field_name_str, self.#name, schema);
+                {
+                    let value = self.#name;
+                    Box::new(move || {
+                        let mut cols = Vec::new();
+                        #macro_name!(cols, #field_name_str, value, schema);
+                        cols
+                    })
+                }
             }
         })
         .collect();
@@ -93,7 +100,7 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
                             U256Type::F64 => cols.push(Series::new(full_name, Vec::<f64>::new())),
                             U256Type::U32 => cols.push(Series::new(full_name, Vec::<u32>::new())),
                             U256Type::U64 => cols.push(Series::new(full_name, Vec::<u64>::new())),
-                            U256Type::Decimal128 => cols.push(Series::new(full_name, Vec::<Vec<u8>>::new())),
+                            U256Type::Decimal128 => cols.push(decimal128_series(full_name, Vec::new())),
                         }
                     }
                 }
@@ -171,6 +178,72 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
         quote! {}
     };
 
+    let has_input_cols = field_names_and_types.iter().any(|(name, _)| name == "input_cols");
+    let input_code = if has_input_cols {
+        quote! {
+            if let Some(decoder) = schema.function_decoder.clone() {
+                let u256_types: Vec<_> = schema.u256_types.clone().into_iter().collect();
+                cols.extend(decoder.make_series(
+                    self.input_cols,
+                    self.n_rows as usize,
+                    &u256_types,
+                    &schema.binary_type,
+                )?);
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let has_arg_cols = field_names_and_types.iter().any(|(name, _)| name == "arg_cols");
+    let arg_code = if has_arg_cols {
+        quote! {
+            if let Some(decoder) = schema.log_decoder.clone() {
+                let u256_types: Vec<_> = schema.u256_types.clone().into_iter().collect();
+                cols.extend(decoder.make_arg_series(
+                    self.arg_cols,
+                    self.n_rows as usize,
+                    &u256_types,
+                    &schema.binary_type,
+                )?);
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let has_output_cols = field_names_and_types.iter().any(|(name, _)| name == "output_cols");
+    let output_code = if has_output_cols {
+        quote! {
+            if let Some(decoder) = schema.function_decoder.clone() {
+                let u256_types: Vec<_> = schema.u256_types.clone().into_iter().collect();
+                cols.extend(decoder.make_output_series(
+                    self.output_cols,
+                    self.n_rows as usize,
+                    &u256_types,
+                    &schema.binary_type,
+                )?);
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let has_trace_cols = field_names_and_types.iter().any(|(name, _)| name == "trace_cols");
+    let trace_code = if has_trace_cols {
+        quote! {
+            if let Some(decoder) = schema.trace_decoder.clone() {
+                cols.extend(decoder.make_series(
+                    self.trace_cols,
+                    self.n_rows as usize,
+                    &schema.binary_type,
+                )?);
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     fn map_type_to_column_type(ty: &syn::Type) -> Option<proc_macro2::TokenStream> {
         match quote!(#ty).to_string().as_str() {
             "Vec < bool >" => Some(quote! { ColumnType::Boolean }),
@@ -208,7 +281,13 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
         if let Some(column_type) = map_type_to_column_type(ty) {
             let field_name_str = format!("{}", quote!(#name));
             column_types.push(quote! { (#field_name_str, #column_type) });
-        } else if name != "n_rows" && name != "event_cols" {
+        } else if name != "n_rows" &&
+            name != "event_cols" &&
+            name != "input_cols" &&
+            name != "output_cols" &&
+            name != "trace_cols" &&
+            name != "arg_cols"
+        {
             println!("invalid column type for {name} in table {}", datatype_str);
         }
     }
@@ -230,9 +309,16 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
                     panic!("improper datatypes for single schema")
                 };
                 let schema = schemas.get(&datatype).expect("schema not provided");
+                // columns are converted in parallel, in field order
+                let field_tasks: Vec<Box<dyn FnOnce() -> Vec<Series> + Send + '_>> = vec![
+                    #(#field_processing),*
+                ];
+                let field_cols: Vec<Vec<Series>> = {
+                    use rayon::prelude::*;
+                    field_tasks.into_par_iter().map(|task| task()).collect()
+                };
                 let mut cols = Vec::with_capacity(schema.columns().len());
-
-                #(#field_processing)*
+                cols.extend(field_cols.into_iter().flatten());
 
                 if self.chain_id.len() == 0 {
                     with_series!(cols, "chain_id", vec![chain_id; self.n_rows as usize], schema);
@@ -242,7 +328,15 @@ pub fn to_df(attrs: TokenStream, input: TokenStream) -> TokenStream {
 
                 #event_code
 
-                let df = DataFrame::new(cols).map_err(CollectError::PolarsError).sort_by_schema(schema)?;
+                #input_code
+
+                #arg_code
+
+                #output_code
+
+                #trace_code
+
+                let df = DataFrame::new(cols).map_err(CollectError::PolarsError).fill_gas_nulls(schema).cast_categoricals(schema).sort_by_schema(schema)?;
                 let mut output = std::collections::HashMap::new();
                 output.insert(datatype, df);
                 Ok(output)